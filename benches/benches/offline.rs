@@ -0,0 +1,37 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use sherpa_rs::transducer::{TransducerConfig, TransducerRecognizer};
+use sherpa_rs_benches::{audio, engine_params, require, SAMPLE_RATE};
+
+fn offline_transcribe(c: &mut Criterion) {
+    let Some(paths) = require(&[
+        "SHERPA_RS_BENCH_OFFLINE_ENCODER",
+        "SHERPA_RS_BENCH_OFFLINE_DECODER",
+        "SHERPA_RS_BENCH_OFFLINE_JOINER",
+        "SHERPA_RS_BENCH_OFFLINE_TOKENS",
+    ]) else {
+        return;
+    };
+    let samples = audio();
+    let mut group = c.benchmark_group("offline_transcribe");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(samples.len() as u64));
+    for (provider, threads, label) in engine_params() {
+        let mut recognizer = TransducerRecognizer::new(TransducerConfig {
+            encoder: paths[0].clone(),
+            decoder: paths[1].clone(),
+            joiner: paths[2].clone(),
+            tokens: paths[3].clone(),
+            num_threads: threads,
+            provider: Some(provider),
+            ..Default::default()
+        })
+        .expect("create offline recognizer");
+        group.bench_function(BenchmarkId::from_parameter(label), |b| {
+            b.iter(|| recognizer.transcribe(SAMPLE_RATE, &samples))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, offline_transcribe);
+criterion_main!(benches);
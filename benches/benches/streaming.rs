@@ -0,0 +1,54 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use sherpa_rs::online::{OnlineModel, OnlineRecognizer, OnlineRecognizerConfig};
+use sherpa_rs_benches::{audio, engine_params, require, SAMPLE_RATE};
+
+/// Samples per `accept_waveform` call, 100 ms as from a live source
+const CHUNK: usize = 1600;
+
+fn streaming_decode(c: &mut Criterion) {
+    let Some(paths) = require(&[
+        "SHERPA_RS_TEST_ENCODER",
+        "SHERPA_RS_TEST_DECODER",
+        "SHERPA_RS_TEST_JOINER",
+        "SHERPA_RS_TEST_TOKENS",
+    ]) else {
+        return;
+    };
+    let samples = audio();
+    let mut group = c.benchmark_group("streaming_decode");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(samples.len() as u64));
+    for (provider, threads, label) in engine_params() {
+        let mut recognizer = OnlineRecognizer::new(OnlineRecognizerConfig {
+            model: OnlineModel::Transducer {
+                encoder: paths[0].clone(),
+                decoder: paths[1].clone(),
+                joiner: paths[2].clone(),
+            },
+            tokens: paths[3].clone(),
+            num_threads: threads,
+            provider: Some(provider),
+            enable_endpoint: false,
+            ..Default::default()
+        })
+        .expect("create online recognizer");
+        group.bench_function(BenchmarkId::from_parameter(label), |b| {
+            b.iter(|| {
+                let stream = recognizer.create_stream().expect("create stream");
+                for chunk in samples.chunks(CHUNK) {
+                    stream.accept_waveform(SAMPLE_RATE, chunk).unwrap();
+                    while recognizer.is_stream_ready(&stream) {
+                        recognizer.decode_stream(&stream);
+                    }
+                }
+                let text = recognizer.stream_result(&stream);
+                recognizer.destroy_stream(stream);
+                text
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, streaming_decode);
+criterion_main!(benches);
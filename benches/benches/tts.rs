@@ -0,0 +1,41 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use sherpa_rs::tts::{VitsTts, VitsTtsConfig};
+use sherpa_rs::OnnxConfig;
+use sherpa_rs_benches::{engine_params, env, require};
+
+const TEXT: &str =
+    "The quick brown fox jumps over the lazy dog. How vexingly quick daft zebras jump!";
+
+fn tts_generate(c: &mut Criterion) {
+    let Some(paths) = require(&[
+        "SHERPA_RS_BENCH_VITS_MODEL",
+        "SHERPA_RS_BENCH_VITS_LEXICON",
+        "SHERPA_RS_BENCH_VITS_TOKENS",
+    ]) else {
+        return;
+    };
+    let mut group = c.benchmark_group("tts_generate");
+    group.sample_size(10);
+    for (provider, threads, label) in engine_params() {
+        let mut tts = VitsTts::new(VitsTtsConfig {
+            model: paths[0].clone(),
+            lexicon: paths[1].clone(),
+            tokens: paths[2].clone(),
+            data_dir: env("SHERPA_RS_BENCH_VITS_DATA_DIR").unwrap_or_default(),
+            length_scale: 1.0,
+            onnx_config: OnnxConfig {
+                provider,
+                num_threads: threads,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        group.bench_function(BenchmarkId::from_parameter(label), |b| {
+            b.iter(|| tts.create(TEXT, 0, 1.0).expect("generate speech"))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, tts_generate);
+criterion_main!(benches);
@@ -0,0 +1,65 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use sherpa_rs::energy_vad::{EnergyVad, EnergyVadConfig};
+use sherpa_rs::silero_vad::{SileroVad, SileroVadConfig};
+use sherpa_rs::VoiceActivityDetector;
+use sherpa_rs_benches::{audio, engine_params, env};
+
+/// Samples per `accept_waveform` call, 100 ms as from a live source
+const CHUNK: usize = 1600;
+
+fn run<V: VoiceActivityDetector>(vad: &mut V, samples: &[f32]) -> usize {
+    let mut segments = 0;
+    for chunk in samples.chunks(CHUNK) {
+        vad.accept_waveform(chunk.to_vec());
+        while vad.pop_segment().is_some() {
+            segments += 1;
+        }
+    }
+    vad.flush();
+    while vad.pop_segment().is_some() {
+        segments += 1;
+    }
+    segments
+}
+
+fn vad(c: &mut Criterion) {
+    let samples = audio();
+    let mut group = c.benchmark_group("vad");
+    group.throughput(Throughput::Elements(samples.len() as u64));
+
+    group.bench_function("energy", |b| {
+        b.iter_batched(
+            || EnergyVad::new(EnergyVadConfig::default()).unwrap(),
+            |mut vad| run(&mut vad, &samples),
+            BatchSize::PerIteration,
+        )
+    });
+
+    if let Some(model) = env("SHERPA_RS_BENCH_SILERO_MODEL") {
+        for (provider, threads, label) in engine_params() {
+            group.bench_function(BenchmarkId::new("silero", label), |b| {
+                // A fresh detector per run, so no state carries over, but its
+                // model load stays out of the measurement
+                b.iter_batched(
+                    || {
+                        let config = SileroVadConfig {
+                            model: model.clone(),
+                            provider: Some(provider.clone()),
+                            num_threads: Some(threads),
+                            ..Default::default()
+                        };
+                        SileroVad::new(config, 60.0).expect("create Silero VAD")
+                    },
+                    |mut vad| run(&mut vad, &samples),
+                    BatchSize::PerIteration,
+                )
+            });
+        }
+    } else {
+        eprintln!("skipping Silero: set SHERPA_RS_BENCH_SILERO_MODEL");
+    }
+    group.finish();
+}
+
+criterion_group!(benches, vad);
+criterion_main!(benches);
@@ -0,0 +1,80 @@
+//! Shared setup for the sherpa-rs benchmarks.
+//!
+//! Models are read from environment variables; benchmarks whose models aren't set are
+//! skipped. Run with `cargo bench -p sherpa-rs-benches`.
+//!
+//! - `SHERPA_RS_TEST_ENCODER`, `_DECODER`, `_JOINER`, `_TOKENS`: streaming transducer
+//! - `SHERPA_RS_BENCH_OFFLINE_ENCODER`, `_DECODER`, `_JOINER`, `_TOKENS`: offline transducer
+//! - `SHERPA_RS_BENCH_VITS_MODEL`, `_LEXICON`, `_TOKENS`, optionally `_DATA_DIR`: VITS TTS
+//! - `SHERPA_RS_BENCH_SILERO_MODEL`: Silero VAD; the energy VAD always runs
+//! - `SHERPA_RS_BENCH_WAV`: 16 kHz mono input audio, defaults to 10 s of synthetic audio
+//! - `SHERPA_RS_BENCH_PROVIDERS`: comma-separated providers, defaults to `cpu`
+//! - `SHERPA_RS_BENCH_THREADS`: comma-separated thread counts, defaults to `1,2,4`
+
+pub const SAMPLE_RATE: u32 = 16000;
+
+/// Value of an environment variable, `None` when unset or empty
+pub fn env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+/// Values of all the variables, or `None` with a note when one is missing
+pub fn require(names: &[&str]) -> Option<Vec<String>> {
+    let values: Option<Vec<String>> = names.iter().map(|name| env(name)).collect();
+    if values.is_none() {
+        eprintln!("skipping: set {}", names.join(", "));
+    }
+    values
+}
+
+pub fn providers() -> Vec<String> {
+    env("SHERPA_RS_BENCH_PROVIDERS")
+        .unwrap_or_else(|| "cpu".into())
+        .split(',')
+        .map(|p| p.trim().to_string())
+        .collect()
+}
+
+pub fn thread_counts() -> Vec<i32> {
+    env("SHERPA_RS_BENCH_THREADS")
+        .unwrap_or_else(|| "1,2,4".into())
+        .split(',')
+        .map(|n| {
+            n.trim()
+                .parse()
+                .expect("SHERPA_RS_BENCH_THREADS holds integers")
+        })
+        .collect()
+}
+
+/// Every provider and thread count combination, with a label for `BenchmarkId`
+pub fn engine_params() -> Vec<(String, i32, String)> {
+    let mut params = Vec::new();
+    for provider in providers() {
+        for threads in thread_counts() {
+            let label = format!("{provider}/{threads}t");
+            params.push((provider.clone(), threads, label));
+        }
+    }
+    params
+}
+
+/// Input audio at 16 kHz: `SHERPA_RS_BENCH_WAV`, or tone bursts separated by silence
+pub fn audio() -> Vec<f32> {
+    if let Some(path) = env("SHERPA_RS_BENCH_WAV") {
+        let (samples, sample_rate) = sherpa_rs::read_audio_file(&path).expect("read bench audio");
+        assert_eq!(sample_rate, SAMPLE_RATE, "bench audio must be 16 kHz");
+        return samples;
+    }
+    // 1 s of tone and 1 s of silence, five times
+    (0..10 * SAMPLE_RATE as usize)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            if (i / SAMPLE_RATE as usize).is_multiple_of(2) {
+                0.3 * (t * 220.0 * std::f32::consts::TAU).sin()
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
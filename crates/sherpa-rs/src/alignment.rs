@@ -0,0 +1,157 @@
+use crate::tokens::Tokens;
+use eyre::Result;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordTiming {
+    pub word: String,
+    /// Start time in seconds
+    pub start: f32,
+    /// End time in seconds: the start of the next word, or an estimate for the last word
+    pub end: f32,
+}
+
+/// Time span of one recognized token
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenTiming {
+    pub token: String,
+    /// Start time in seconds
+    pub start: f32,
+    /// End time in seconds: the start of the next token, or an estimate for the last token
+    pub end: f32,
+}
+
+/// How a model's tokens mark word boundaries.
+/// CJK characters are always words of their own, whatever the style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenStyle {
+    /// SentencePiece: a leading `▁` starts a word (zipformer, SenseVoice, NeMo, ...)
+    SentencePiece,
+    /// A trailing `@@` continues the word into the next token (Paraformer)
+    ContinuationMarker,
+    /// A leading space starts a word (Whisper, Moonshine)
+    Whitespace,
+}
+
+impl TokenStyle {
+    /// Guess the style from a list of token symbols, e.g. a whole vocabulary
+    pub fn detect<S: AsRef<str>>(symbols: &[S]) -> Self {
+        let symbols = || symbols.iter().map(|s| s.as_ref());
+        if symbols().any(|s| s.contains('▁')) {
+            TokenStyle::SentencePiece
+        } else if symbols().any(|s| s.len() > 2 && s.ends_with("@@")) {
+            TokenStyle::ContinuationMarker
+        } else {
+            TokenStyle::Whitespace
+        }
+    }
+
+    /// Guess the style from a model's `tokens.txt`
+    pub fn from_tokens_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Tokens::from_file(path)?.style())
+    }
+}
+
+pub(crate) fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30ff}' // Hiragana, Katakana
+        | '\u{3400}'..='\u{4dbf}'
+        | '\u{4e00}'..='\u{9fff}'
+        | '\u{f900}'..='\u{faff}')
+}
+
+/// Merge subword tokens and their start times into words.
+///
+/// `timestamps` holds the start of each token, as in `OfflineRecognizerResult`.
+/// Tokens without a timestamp are ignored.
+pub fn align_words<S: AsRef<str>>(
+    tokens: &[S],
+    timestamps: &[f32],
+    style: TokenStyle,
+) -> Vec<WordTiming> {
+    let mut words: Vec<WordTiming> = Vec::new();
+    // Start of the last token of each word
+    let mut last_token = Vec::new();
+    // The next token starts a new word
+    let mut boundary = true;
+    // The previous token asked to be joined with this one
+    let mut joined = false;
+
+    for (token, &start) in tokens.iter().zip(timestamps) {
+        let token = token.as_ref();
+        let (text, starts_word, continues) = match style {
+            TokenStyle::SentencePiece => {
+                (token.trim_start_matches('▁'), token.starts_with('▁'), false)
+            }
+            TokenStyle::ContinuationMarker => match token.strip_suffix("@@") {
+                Some(text) => (text, true, true),
+                None => (token, true, false),
+            },
+            TokenStyle::Whitespace => (
+                token.trim_start(),
+                token.starts_with(char::is_whitespace),
+                false,
+            ),
+        };
+        let cjk = !text.is_empty() && text.chars().all(is_cjk);
+        let new_word = cjk || (!joined && (boundary || starts_word));
+        joined = continues;
+        if text.is_empty() {
+            // A lone `▁`
+            boundary = true;
+            continue;
+        }
+        boundary = cjk;
+
+        match words.last_mut() {
+            Some(word) if !new_word => {
+                word.word.push_str(text);
+                *last_token.last_mut().unwrap() = start;
+            }
+            _ => {
+                words.push(WordTiming {
+                    word: text.to_string(),
+                    start,
+                    end: start,
+                });
+                last_token.push(start);
+            }
+        }
+    }
+
+    // The last word lasts about as long as a typical token after its last one
+    let typical_gap = typical_gap(timestamps);
+    let next_starts: Vec<f32> = words.iter().skip(1).map(|w| w.start).collect();
+    for (i, word) in words.iter_mut().enumerate() {
+        word.end = next_starts
+            .get(i)
+            .copied()
+            .unwrap_or(last_token[i] + typical_gap);
+    }
+    words
+}
+
+/// Pair tokens with their start times and give each an end time.
+/// Tokens without a timestamp are ignored.
+pub fn align_tokens<S: AsRef<str>>(tokens: &[S], timestamps: &[f32]) -> Vec<TokenTiming> {
+    let typical_gap = typical_gap(timestamps);
+    let count = tokens.len().min(timestamps.len());
+    (0..count)
+        .map(|i| TokenTiming {
+            token: tokens[i].as_ref().to_string(),
+            start: timestamps[i],
+            end: timestamps
+                .get(i + 1)
+                .filter(|_| i + 1 < count)
+                .copied()
+                .unwrap_or(timestamps[i] + typical_gap),
+        })
+        .collect()
+}
+
+/// Median spacing of consecutive timestamps
+fn typical_gap(timestamps: &[f32]) -> f32 {
+    let mut gaps: Vec<f32> = timestamps.windows(2).map(|w| w[1] - w[0]).collect();
+    gaps.sort_by(f32::total_cmp);
+    gaps.get(gaps.len() / 2).copied().unwrap_or(0.0)
+}
@@ -5,10 +5,58 @@ use crate::{
     utils::{cstr_to_string, cstring_from_str},
 };
 
+/// Coarse content of an audio segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentClass {
+    Speech,
+    Music,
+    Noise,
+    Silence,
+}
+
+/// Segments quieter than this (dBFS) are silence without running the model
+const SILENCE_THRESHOLD_DB: f32 = -60.0;
+
+/// AudioSet labels counted as speech besides the ones mentioning speech
+const SPEECH_LABELS: &[&str] = &[
+    "Conversation",
+    "Narration, monologue",
+    "Babbling",
+    "Whispering",
+    "Chatter",
+    "Shout",
+    "Yell",
+];
+
+/// AudioSet labels counted as music besides the ones mentioning music
+const MUSIC_LABELS: &[&str] = &[
+    "Musical instrument",
+    "Singing",
+    "Choir",
+    "Orchestra",
+    "Guitar",
+    "Electric guitar",
+    "Acoustic guitar",
+    "Bass guitar",
+    "Piano",
+    "Keyboard (musical)",
+    "Synthesizer",
+    "Drum",
+    "Drum kit",
+    "Percussion",
+    "Violin, fiddle",
+    "Cello",
+    "Brass instrument",
+    "Trumpet",
+    "Saxophone",
+    "Flute",
+];
+
 #[derive(Debug, Default, Clone)]
 pub struct AudioTagConfig {
     pub model: String,
     pub labels: String,
+    /// Number of events returned by `compute`. 0 uses the sherpa-onnx default of 5.
     pub top_k: i32,
     pub ced: Option<String>,
     pub debug: bool,
@@ -37,7 +85,7 @@ impl AudioTag {
                 },
                 ced: ced.as_ptr(),
                 num_threads: config.num_threads.unwrap_or(1),
-                debug: config.debug.into(),
+                debug: crate::native_debug(config.debug),
                 provider: provider.as_ptr(),
             },
             labels: labels.as_ptr(),
@@ -55,6 +103,65 @@ impl AudioTag {
     }
 
     pub fn compute(&mut self, samples: Vec<f32>, sample_rate: u32) -> Vec<String> {
+        self.events(&samples, sample_rate)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Top `top_k` events with their probabilities, most likely first
+    pub fn compute_with_probs(&mut self, samples: &[f32], sample_rate: u32) -> Vec<(String, f32)> {
+        self.events(samples, sample_rate)
+    }
+
+    /// Whether a segment is mostly speech, music, other sounds or silence.
+    /// Meant to skip non-speech regions before ASR; uses the top `top_k` events.
+    pub fn classify_segment(&mut self, samples: &[f32], sample_rate: u32) -> ContentClass {
+        let energy = samples.iter().map(|s| s * s).sum::<f32>() / samples.len().max(1) as f32;
+        if 10.0 * energy.max(1e-10).log10() < SILENCE_THRESHOLD_DB {
+            return ContentClass::Silence;
+        }
+
+        let (mut speech, mut music, mut silence, mut other) = (0.0, 0.0, 0.0, 0.0);
+        for (name, prob) in self.events(samples, sample_rate) {
+            let lower = name.to_lowercase();
+            if lower == "silence" {
+                silence += prob;
+            } else if lower.contains("speech")
+                || lower.contains("speaking")
+                || SPEECH_LABELS.contains(&name.as_str())
+            {
+                speech += prob;
+            } else if lower.contains("music") || MUSIC_LABELS.contains(&name.as_str()) {
+                music += prob;
+            } else {
+                other += prob;
+            }
+        }
+
+        let scores = [
+            (ContentClass::Speech, speech),
+            (ContentClass::Music, music),
+            (ContentClass::Silence, silence),
+            (ContentClass::Noise, other),
+        ];
+        scores
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .filter(|(_, score)| *score > 0.0)
+            .map_or(ContentClass::Noise, |(class, _)| class)
+    }
+
+    /// Top events with their probabilities. Buffers longer than a native call takes
+    /// are logged and give no events.
+    fn events(&mut self, samples: &[f32], sample_rate: u32) -> Vec<(String, f32)> {
+        let len = match crate::utils::native_len(samples.len()) {
+            Ok(len) => len,
+            Err(error) => {
+                tracing::warn!("audio tagging skipped: {}", error);
+                return Vec::new();
+            }
+        };
         let mut events = Vec::new();
         unsafe {
             let stream = sherpa_rs_sys::SherpaOnnxAudioTaggingCreateOfflineStream(self.audio_tag);
@@ -62,7 +169,7 @@ impl AudioTag {
                 stream,
                 sample_rate as i32,
                 samples.as_ptr(),
-                samples.len() as i32,
+                len,
             );
 
             let results = sherpa_rs_sys::SherpaOnnxAudioTaggingCompute(
@@ -71,10 +178,15 @@ impl AudioTag {
                 self.config.top_k,
             );
 
-            for i in 0..self.config.top_k {
-                let event = *results.add(i.try_into().unwrap());
-                let event_name = cstr_to_string((*event).name as _);
-                events.push(event_name);
+            if !results.is_null() {
+                // The array is null terminated
+                let mut i = 0;
+                while !(*results.add(i)).is_null() {
+                    let event = *results.add(i);
+                    events.push((cstr_to_string((*event).name as _), (*event).prob));
+                    i += 1;
+                }
+                sherpa_rs_sys::SherpaOnnxAudioTaggingFreeResults(results);
             }
 
             sherpa_rs_sys::SherpaOnnxDestroyOfflineStream(stream);
@@ -83,6 +195,127 @@ impl AudioTag {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct StreamingTagConfig {
+    /// Seconds of audio tagged at a time
+    pub window: f32,
+    /// Seconds between the starts of consecutive windows. Smaller than `window` overlaps them.
+    pub hop: f32,
+    /// Events less likely than this are left out
+    pub min_prob: f32,
+}
+
+impl Default for StreamingTagConfig {
+    fn default() -> Self {
+        Self {
+            window: 2.0,
+            hop: 1.0,
+            min_prob: 0.3,
+        }
+    }
+}
+
+/// Sound event found in one window of a live feed
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagEvent {
+    pub name: String,
+    pub prob: f32,
+    /// Start of the window in seconds, counted from the start of the feed
+    pub start: f32,
+    /// End of the window in seconds
+    pub end: f32,
+}
+
+/// Audio tagging over a continuous feed, for always-on acoustic monitoring.
+///
+/// Audio is tagged in sliding windows; each window reports its top events as
+/// `TagEvent`s, so a sound lasting several windows is reported once per window.
+pub struct StreamingAudioTag {
+    tagger: AudioTag,
+    config: StreamingTagConfig,
+    sample_rate: u32,
+    window_len: usize,
+    hop_len: usize,
+    /// Audio from `offset` on
+    buffer: Vec<f32>,
+    /// Samples dropped from the front of the buffer
+    offset: usize,
+    /// End of the last tagged window, in samples
+    tagged_until: usize,
+}
+
+impl StreamingAudioTag {
+    pub fn new(tagger: AudioTag, sample_rate: u32, config: StreamingTagConfig) -> Result<Self> {
+        if !(config.window > 0.0 && config.hop > 0.0) {
+            bail!("window and hop must be positive");
+        }
+        if config.hop > config.window {
+            bail!("hop must not exceed window, or audio between windows is skipped");
+        }
+        let window_len = ((config.window * sample_rate as f32) as usize).max(1);
+        let hop_len = ((config.hop * sample_rate as f32) as usize).clamp(1, window_len);
+        Ok(Self {
+            tagger,
+            config,
+            sample_rate,
+            window_len,
+            hop_len,
+            buffer: Vec::new(),
+            offset: 0,
+            tagged_until: 0,
+        })
+    }
+
+    /// Feed the next chunk of audio at the sample rate given to `new`.
+    /// Returns the events of every window completed by it.
+    pub fn accept_waveform(&mut self, samples: &[f32]) -> Vec<TagEvent> {
+        self.buffer.extend_from_slice(samples);
+        let mut events = Vec::new();
+        while self.buffer.len() >= self.window_len {
+            events.extend(self.tag(self.window_len));
+            self.buffer.drain(..self.hop_len);
+            self.offset += self.hop_len;
+        }
+        events
+    }
+
+    /// Tag the audio after the last full window, e.g. at the end of the feed,
+    /// and start over at time 0
+    pub fn flush(&mut self) -> Vec<TagEvent> {
+        let end = self.offset + self.buffer.len();
+        let events = if end > self.tagged_until && !self.buffer.is_empty() {
+            self.tag(self.buffer.len())
+        } else {
+            Vec::new()
+        };
+        self.buffer.clear();
+        self.offset = 0;
+        self.tagged_until = 0;
+        events
+    }
+
+    pub fn into_inner(self) -> AudioTag {
+        self.tagger
+    }
+
+    fn tag(&mut self, len: usize) -> Vec<TagEvent> {
+        let start = self.offset as f32 / self.sample_rate as f32;
+        let end = (self.offset + len) as f32 / self.sample_rate as f32;
+        self.tagged_until = self.offset + len;
+        self.tagger
+            .events(&self.buffer[..len], self.sample_rate)
+            .into_iter()
+            .filter(|(_, prob)| *prob >= self.config.min_prob)
+            .map(|(name, prob)| TagEvent {
+                name,
+                prob,
+                start,
+                end,
+            })
+            .collect()
+    }
+}
+
 unsafe impl Send for AudioTag {}
 unsafe impl Sync for AudioTag {}
 
@@ -0,0 +1,112 @@
+use crate::alignment::{is_cjk, WordTiming};
+
+#[derive(Debug, Clone)]
+pub struct CaptionConfig {
+    /// Longest line in characters. A single longer word gets a line of its own.
+    pub max_chars_per_line: usize,
+    pub max_lines: usize,
+    /// Shortest time a caption stays on screen, in seconds. Captions are extended up to
+    /// the start of the next one.
+    pub min_duration: f32,
+    /// End captions at the end of a sentence once they're at least half a line long,
+    /// instead of running on into the next sentence
+    pub prefer_sentence_breaks: bool,
+}
+
+impl Default for CaptionConfig {
+    fn default() -> Self {
+        Self {
+            max_chars_per_line: 42,
+            max_lines: 2,
+            min_duration: 1.0,
+            prefer_sentence_breaks: true,
+        }
+    }
+}
+
+/// One caption ready for display
+#[derive(Debug, Clone, PartialEq)]
+pub struct Caption {
+    /// Start time in seconds
+    pub start: f32,
+    /// End time in seconds
+    pub end: f32,
+    pub lines: Vec<String>,
+}
+
+impl Caption {
+    /// Lines joined with newlines
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+}
+
+/// Lay out timestamped words, e.g. from `OfflineRecognizerResult::words`, into captions
+pub fn format_captions(words: &[WordTiming], config: &CaptionConfig) -> Vec<Caption> {
+    let max_chars = config.max_chars_per_line.max(1);
+    let max_lines = config.max_lines.max(1);
+    let mut captions: Vec<Caption> = Vec::new();
+    let mut current: Option<Caption> = None;
+
+    for word in words {
+        let text = word.word.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let caption = current.get_or_insert_with(|| Caption {
+            start: word.start,
+            end: word.end,
+            lines: vec![String::new()],
+        });
+        let line = caption.lines.last().unwrap();
+        let joined_len =
+            line.chars().count() + usize::from(needs_space(line, text)) + text.chars().count();
+        if !line.is_empty() && joined_len > max_chars {
+            if caption.lines.len() == max_lines {
+                captions.extend(current.take());
+                current = Some(Caption {
+                    start: word.start,
+                    end: word.end,
+                    lines: vec![String::new()],
+                });
+            } else {
+                caption.lines.push(String::new());
+            }
+        }
+
+        let caption = current.as_mut().unwrap();
+        let line = caption.lines.last_mut().unwrap();
+        if needs_space(line, text) {
+            line.push(' ');
+        }
+        line.push_str(text);
+        caption.end = word.end;
+
+        let len: usize = caption.lines.iter().map(|l| l.chars().count()).sum();
+        if config.prefer_sentence_breaks && ends_sentence(text) && len >= max_chars / 2 {
+            captions.extend(current.take());
+        }
+    }
+    captions.extend(current);
+
+    for i in 0..captions.len() {
+        let limit = captions.get(i + 1).map_or(f32::INFINITY, |next| next.start);
+        let caption = &mut captions[i];
+        let min_end = caption.start + config.min_duration;
+        if caption.end < min_end {
+            caption.end = min_end.min(limit).max(caption.end);
+        }
+    }
+    captions
+}
+
+fn needs_space(line: &str, word: &str) -> bool {
+    match (line.chars().last(), word.chars().next()) {
+        (Some(a), Some(b)) => !is_cjk(a) && !is_cjk(b),
+        _ => false,
+    }
+}
+
+fn ends_sentence(word: &str) -> bool {
+    word.ends_with(['.', '?', '!', '。', '？', '！'])
+}
@@ -0,0 +1,73 @@
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::Sample;
+use eyre::{bail, Result};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// Captures the default input device as mono f32 chunks.
+/// Capture stops when the microphone is dropped.
+pub struct Microphone {
+    _stream: cpal::Stream,
+    sample_rate: u32,
+}
+
+impl Microphone {
+    pub fn open() -> Result<(Self, Receiver<Vec<f32>>)> {
+        let host = cpal::default_host();
+        let Some(device) = host.default_input_device() else {
+            bail!("No input device available");
+        };
+        let config = device.default_input_config()?;
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels() as usize;
+        let (tx, rx) = mpsc::channel();
+
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::F32 => build_stream::<f32>(&device, &config.into(), channels, tx)?,
+            cpal::SampleFormat::I16 => build_stream::<i16>(&device, &config.into(), channels, tx)?,
+            cpal::SampleFormat::U16 => build_stream::<u16>(&device, &config.into(), channels, tx)?,
+            cpal::SampleFormat::I32 => build_stream::<i32>(&device, &config.into(), channels, tx)?,
+            format => bail!("Unsupported sample format {format}"),
+        };
+        stream.play()?;
+
+        Ok((
+            Self {
+                _stream: stream,
+                sample_rate,
+            },
+            rx,
+        ))
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    channels: usize,
+    tx: Sender<Vec<f32>>,
+) -> Result<cpal::Stream>
+where
+    T: cpal::SizedSample,
+    f32: cpal::FromSample<T>,
+{
+    let stream = device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            // Downmix to mono
+            let samples = data
+                .chunks(channels)
+                .map(|frame| {
+                    frame.iter().map(|s| f32::from_sample(*s)).sum::<f32>() / channels as f32
+                })
+                .collect();
+            let _ = tx.send(samples);
+        },
+        |err| tracing::error!("audio capture error: {}", err),
+        None,
+    )?;
+    Ok(stream)
+}
@@ -0,0 +1,187 @@
+use crate::{OfflineRecognizerResult, Transcriber, VoiceActivityDetector};
+use eyre::{bail, Result};
+
+#[derive(Debug, Clone)]
+pub struct ChunkedConfig {
+    /// Sample rate of the audio, the VAD and the recognizer
+    pub sample_rate: u32,
+    /// Seconds of new audio between partial results while speech goes on.
+    /// 0 disables partial results.
+    pub partial_interval: f32,
+    /// Partial results decode at most this many seconds of the speech in progress,
+    /// so their cost stays bounded during long utterances
+    pub max_partial_window: f32,
+    /// Audio from before the VAD detected speech included in partial results, in seconds
+    pub pre_roll: f32,
+}
+
+impl Default for ChunkedConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 16000,
+            partial_interval: 0.5,
+            max_partial_window: 10.0,
+            pre_roll: 0.2,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ChunkedEvent {
+    /// Hypothesis for the speech in progress, replaced by later partials and the final result
+    Partial { start: f32, text: String },
+    /// Result of a complete speech segment
+    Final {
+        /// Start time in seconds, relative to the first sample fed
+        start: f32,
+        /// Duration in seconds
+        duration: f32,
+        result: OfflineRecognizerResult,
+    },
+}
+
+/// Pseudo-streaming recognition with an offline model such as SenseVoice.
+///
+/// The VAD cuts the live audio into speech segments and each one is recognized as it
+/// ends. While speech goes on, the audio since it started is re-decoded every
+/// `partial_interval` seconds for partial results, so latency is about one interval
+/// plus the decode time, without a streaming model.
+pub struct ChunkedTranscriber<T: Transcriber, V: VoiceActivityDetector> {
+    recognizer: T,
+    vad: V,
+    config: ChunkedConfig,
+    /// Recent audio, for partial results
+    history: Vec<f32>,
+    /// Position of the first sample of `history`
+    history_start: usize,
+    /// Samples fed so far
+    position: usize,
+    /// Start of the speech in progress
+    speech_start: Option<usize>,
+    /// Position at the last partial result
+    last_partial: usize,
+}
+
+impl<T: Transcriber, V: VoiceActivityDetector> ChunkedTranscriber<T, V> {
+    /// `vad` must run at `config.sample_rate`
+    pub fn new(recognizer: T, vad: V, config: ChunkedConfig) -> Result<Self> {
+        if config.sample_rate == 0 {
+            bail!("sample_rate must be positive");
+        }
+        if config.partial_interval < 0.0 || config.max_partial_window <= 0.0 {
+            bail!("partial_interval must not be negative and max_partial_window must be positive");
+        }
+        Ok(Self {
+            recognizer,
+            vad,
+            config,
+            history: Vec::new(),
+            history_start: 0,
+            position: 0,
+            speech_start: None,
+            last_partial: 0,
+        })
+    }
+
+    /// Feed the next chunk of audio and return the results it produced
+    pub fn accept_waveform(&mut self, samples: &[f32]) -> Vec<ChunkedEvent> {
+        self.vad.accept_waveform(samples.to_vec());
+        self.history.extend_from_slice(samples);
+        let chunk_start = self.position;
+        self.position += samples.len();
+
+        let mut events = self.drain_segments();
+        match (self.vad.is_speech(), self.speech_start) {
+            (true, None) => {
+                let start = chunk_start
+                    .saturating_sub(self.seconds_to_samples(self.config.pre_roll))
+                    .max(self.history_start);
+                self.speech_start = Some(start);
+                self.last_partial = chunk_start;
+            }
+            (false, Some(_)) => self.speech_start = None,
+            _ => {}
+        }
+
+        let interval = self.seconds_to_samples(self.config.partial_interval);
+        if let Some(start) = self.speech_start {
+            if interval > 0 && self.position - self.last_partial >= interval {
+                self.last_partial = self.position;
+                events.extend(self.partial(start));
+            }
+        }
+
+        // Keep the speech in progress, or the pre-roll for the next one
+        let keep_from = match self.speech_start {
+            Some(start) => start.max(self.window_start()),
+            None => self
+                .position
+                .saturating_sub(self.seconds_to_samples(self.config.pre_roll)),
+        };
+        if keep_from > self.history_start {
+            self.history.drain(..keep_from - self.history_start);
+            self.history_start = keep_from;
+        }
+        events
+    }
+
+    /// Signal end of input and return the results of the speech still pending
+    pub fn finish(&mut self) -> Vec<ChunkedEvent> {
+        self.vad.flush();
+        self.speech_start = None;
+        self.drain_segments()
+    }
+
+    pub fn into_inner(self) -> (T, V) {
+        (self.recognizer, self.vad)
+    }
+
+    fn seconds_to_samples(&self, seconds: f32) -> usize {
+        (seconds * self.config.sample_rate as f32) as usize
+    }
+
+    /// Oldest sample a partial result decodes
+    fn window_start(&self) -> usize {
+        let window = self.seconds_to_samples(self.config.max_partial_window);
+        self.position.saturating_sub(window)
+    }
+
+    fn partial(&mut self, speech_start: usize) -> Option<ChunkedEvent> {
+        let from = speech_start
+            .max(self.window_start())
+            .max(self.history_start);
+        let samples = &self.history[from - self.history_start..];
+        let result = self.recognizer.transcribe(self.config.sample_rate, samples);
+        let text = result.text.trim();
+        (!text.is_empty()).then(|| ChunkedEvent::Partial {
+            start: from as f32 / self.config.sample_rate as f32,
+            text: text.to_string(),
+        })
+    }
+
+    fn drain_segments(&mut self) -> Vec<ChunkedEvent> {
+        let sample_rate = self.config.sample_rate as f32;
+        let mut events = Vec::new();
+        while let Some(segment) = self.vad.pop_segment() {
+            let start = segment.start.max(0) as usize;
+            let end = start + segment.samples.len();
+            // Speech still going on belongs to the next segment
+            if let Some(speech_start) = &mut self.speech_start {
+                *speech_start = (*speech_start).max(end);
+            }
+            let mut result = self
+                .recognizer
+                .transcribe(self.config.sample_rate, &segment.samples);
+            result.text = result.text.trim().to_string();
+            if result.text.is_empty() {
+                continue;
+            }
+            events.push(ChunkedEvent::Final {
+                start: start as f32 / sample_rate,
+                duration: segment.samples.len() as f32 / sample_rate,
+                result,
+            });
+        }
+        events
+    }
+}
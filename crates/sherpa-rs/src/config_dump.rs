@@ -0,0 +1,240 @@
+use crate::utils::cstr_to_string;
+use std::ffi::c_char;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigValue {
+    /// `None` for null pointers
+    Str(Option<String>),
+    Int(i32),
+    Float(f32),
+}
+
+/// Flattened copy of the native config a model was created with.
+/// Every field is listed by its dotted path, including the ones left zeroed.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigDump {
+    fields: Vec<(String, ConfigValue)>,
+    /// Names of the fields holding file or directory paths
+    path_fields: Vec<String>,
+}
+
+impl ConfigDump {
+    pub fn fields(&self) -> &[(String, ConfigValue)] {
+        &self.fields
+    }
+
+    /// Non-empty values of the fields holding model files, lexicons, rule FSTs and other
+    /// paths. Comma-separated lists are split.
+    pub fn paths(&self) -> Vec<&str> {
+        self.fields
+            .iter()
+            .filter(|(name, _)| self.path_fields.contains(name))
+            .filter_map(|(_, value)| match value {
+                ConfigValue::Str(Some(paths)) => Some(paths.split(',')),
+                _ => None,
+            })
+            .flatten()
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .collect()
+    }
+
+    pub fn get(&self, path: &str) -> Option<&ConfigValue> {
+        self.fields
+            .iter()
+            .find(|(name, _)| name == path)
+            .map(|(_, value)| value)
+    }
+
+    /// Fields nested into JSON objects by their path
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut root = serde_json::Map::new();
+        for (path, value) in &self.fields {
+            let mut parts: Vec<&str> = path.split('.').collect();
+            let leaf = parts.pop().unwrap_or_default();
+            let mut object = &mut root;
+            for part in parts {
+                object = object
+                    .entry(part)
+                    .or_insert_with(|| serde_json::Value::Object(Default::default()))
+                    .as_object_mut()
+                    .expect("config paths never collide with leaf fields");
+            }
+            let value = match value {
+                ConfigValue::Str(s) => s.clone().into(),
+                ConfigValue::Int(v) => (*v).into(),
+                ConfigValue::Float(v) => (*v).into(),
+            };
+            object.insert(leaf.to_string(), value);
+        }
+        serde_json::Value::Object(root)
+    }
+
+    fn push(&mut self, path: &str, value: ConfigValue) {
+        // stringify! puts spaces around the dots
+        self.fields.push((path.replace(' ', ""), value));
+    }
+
+    fn str(&mut self, path: &str, ptr: *const c_char) {
+        let value = if ptr.is_null() {
+            None
+        } else {
+            Some(unsafe { cstr_to_string(ptr) })
+        };
+        self.push(path, ConfigValue::Str(value));
+    }
+
+    fn path(&mut self, path: &str, ptr: *const c_char) {
+        self.str(path, ptr);
+        self.path_fields.push(path.replace(' ', ""));
+    }
+
+    fn int(&mut self, path: &str, value: i32) {
+        self.push(path, ConfigValue::Int(value));
+    }
+
+    fn float(&mut self, path: &str, value: f32) {
+        self.push(path, ConfigValue::Float(value));
+    }
+}
+
+/// Recognizers that keep the native config they were created with
+pub trait NativeConfig {
+    /// Native config the recognizer was created with
+    fn config_dump(&self) -> &ConfigDump;
+
+    /// `config_dump` nested into a JSON object, e.g. to log what a service runs with.
+    ///
+    /// Holds what this crate passed to sherpa-onnx, including the values it picked for
+    /// options left unset (threads, provider, decoding method). Defaults sherpa-onnx
+    /// resolves internally, e.g. for a zeroed field, aren't visible here.
+    fn effective_config_json(&self) -> String {
+        self.config_dump().to_json().to_string()
+    }
+}
+
+impl fmt::Display for ConfigDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (path, value) in &self.fields {
+            match value {
+                ConfigValue::Str(Some(s)) => writeln!(f, "{} = {:?}", path, s)?,
+                ConfigValue::Str(None) => writeln!(f, "{} = <null>", path)?,
+                ConfigValue::Int(v) => writeln!(f, "{} = {}", path, v)?,
+                ConfigValue::Float(v) => writeln!(f, "{} = {}", path, v)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+macro_rules! dump_fields {
+    ($dump:ident, $config:ident; $($kind:ident $($field:ident).+;)*) => {
+        $( $dump.$kind(stringify!($($field).+), $config.$($field).+); )*
+    };
+}
+
+pub(crate) fn offline_recognizer(
+    config: &sherpa_rs_sys::SherpaOnnxOfflineRecognizerConfig,
+) -> ConfigDump {
+    let mut dump = ConfigDump::default();
+    dump_fields!(dump, config;
+        int feat_config.sample_rate;
+        int feat_config.feature_dim;
+        path model_config.transducer.encoder;
+        path model_config.transducer.decoder;
+        path model_config.transducer.joiner;
+        path model_config.paraformer.model;
+        path model_config.nemo_ctc.model;
+        path model_config.whisper.encoder;
+        path model_config.whisper.decoder;
+        str model_config.whisper.language;
+        str model_config.whisper.task;
+        int model_config.whisper.tail_paddings;
+        path model_config.tdnn.model;
+        path model_config.tokens;
+        int model_config.num_threads;
+        int model_config.debug;
+        str model_config.provider;
+        str model_config.model_type;
+        str model_config.modeling_unit;
+        path model_config.bpe_vocab;
+        path model_config.telespeech_ctc;
+        path model_config.sense_voice.model;
+        str model_config.sense_voice.language;
+        int model_config.sense_voice.use_itn;
+        path model_config.moonshine.preprocessor;
+        path model_config.moonshine.encoder;
+        path model_config.moonshine.uncached_decoder;
+        path model_config.moonshine.cached_decoder;
+        path model_config.fire_red_asr.encoder;
+        path model_config.fire_red_asr.decoder;
+        path model_config.dolphin.model;
+        path model_config.zipformer_ctc.model;
+        path model_config.canary.encoder;
+        path model_config.canary.decoder;
+        str model_config.canary.src_lang;
+        str model_config.canary.tgt_lang;
+        int model_config.canary.use_pnc;
+        path lm_config.model;
+        float lm_config.scale;
+        str decoding_method;
+        int max_active_paths;
+        path hotwords_file;
+        float hotwords_score;
+        path rule_fsts;
+        path rule_fars;
+        float blank_penalty;
+        path hr.dict_dir;
+        path hr.lexicon;
+        path hr.rule_fsts;
+    );
+    if config.model_config.debug != 0 {
+        tracing::debug!("offline recognizer config:\n{}", dump);
+    }
+    dump
+}
+
+pub(crate) fn online_recognizer(
+    config: &sherpa_rs_sys::SherpaOnnxOnlineRecognizerConfig,
+) -> ConfigDump {
+    let mut dump = ConfigDump::default();
+    dump_fields!(dump, config;
+        int feat_config.sample_rate;
+        int feat_config.feature_dim;
+        path model_config.transducer.encoder;
+        path model_config.transducer.decoder;
+        path model_config.transducer.joiner;
+        path model_config.paraformer.encoder;
+        path model_config.paraformer.decoder;
+        path model_config.zipformer2_ctc.model;
+        path model_config.nemo_ctc.model;
+        path model_config.tokens;
+        int model_config.num_threads;
+        str model_config.provider;
+        int model_config.debug;
+        str model_config.model_type;
+        str model_config.modeling_unit;
+        path model_config.bpe_vocab;
+        str decoding_method;
+        int max_active_paths;
+        int enable_endpoint;
+        float rule1_min_trailing_silence;
+        float rule2_min_trailing_silence;
+        float rule3_min_utterance_length;
+        path hotwords_file;
+        float hotwords_score;
+        path ctc_fst_decoder_config.graph;
+        int ctc_fst_decoder_config.max_active;
+        path rule_fsts;
+        path rule_fars;
+        float blank_penalty;
+        path hr.dict_dir;
+        path hr.lexicon;
+        path hr.rule_fsts;
+    );
+    if config.model_config.debug != 0 {
+        tracing::debug!("online recognizer config:\n{}", dump);
+    }
+    dump
+}
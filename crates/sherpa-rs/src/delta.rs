@@ -0,0 +1,85 @@
+use crate::alignment::is_cjk;
+
+/// Change between two consecutive partial results, in words
+#[derive(Debug, Clone, PartialEq)]
+pub enum WordDelta {
+    /// Words added after the ones already shown
+    Append(Vec<String>),
+    /// Words from `from_index` on changed: drop them and show `words` instead.
+    /// `words` is empty when the hypothesis got shorter.
+    Revise {
+        from_index: usize,
+        words: Vec<String>,
+    },
+}
+
+/// Turns the stream of partial results of an utterance into word-level updates,
+/// e.g. from `SessionEvent::Partial`, so a UI can patch what it shows instead of
+/// re-rendering the whole string.
+///
+/// Words are split at whitespace; characters of scripts written without spaces,
+/// e.g. Chinese and Japanese, count as one word each.
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptDiff {
+    words: Vec<String>,
+}
+
+impl TranscriptDiff {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compare `text` with the previous partial. `None` when the words didn't change.
+    pub fn update(&mut self, text: &str) -> Option<WordDelta> {
+        let words = split_words(text);
+        let delta = diff_words(&self.words, &words);
+        self.words = words;
+        delta
+    }
+
+    /// Words of the latest partial
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    /// Start the next utterance, e.g. after a final result. Indices restart at 0.
+    pub fn reset(&mut self) {
+        self.words.clear();
+    }
+}
+
+/// Delta turning `old` into `new`, `None` when they're equal
+pub fn diff_words(old: &[String], new: &[String]) -> Option<WordDelta> {
+    let common = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    if common == old.len() && common == new.len() {
+        None
+    } else if common == old.len() {
+        Some(WordDelta::Append(new[common..].to_vec()))
+    } else {
+        Some(WordDelta::Revise {
+            from_index: common,
+            words: new[common..].to_vec(),
+        })
+    }
+}
+
+fn split_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for word in text.split_whitespace() {
+        let mut spaced = String::new();
+        for c in word.chars() {
+            if !is_cjk(c) {
+                spaced.push(c);
+                continue;
+            }
+            if !spaced.is_empty() {
+                words.push(std::mem::take(&mut spaced));
+            }
+            words.push(c.to_string());
+        }
+        if !spaced.is_empty() {
+            words.push(spaced);
+        }
+    }
+    words
+}
@@ -0,0 +1,118 @@
+use crate::{get_default_provider, utils::cstring_from_str};
+use eyre::{bail, Result};
+
+#[derive(Debug, Default, Clone)]
+pub struct DenoiserConfig {
+    /// Path to a GTCRN speech enhancement model
+    pub model: String,
+    pub debug: bool,
+    pub num_threads: Option<i32>,
+    pub provider: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DenoisedAudio {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DenoiseError {
+    #[error("Denoiser expects {expected} Hz audio, got {actual} Hz")]
+    SampleRateMismatch { expected: u32, actual: u32 },
+
+    #[error("Denoising failed")]
+    Failed,
+
+    #[error(transparent)]
+    TooManySamples(#[from] crate::TooManySamples),
+}
+
+pub struct Denoiser {
+    denoiser: *const sherpa_rs_sys::SherpaOnnxOfflineSpeechDenoiser,
+    sample_rate: u32,
+}
+
+impl Denoiser {
+    pub fn new(config: DenoiserConfig) -> Result<Self> {
+        let model = cstring_from_str(&config.model);
+        let provider = cstring_from_str(&config.provider.unwrap_or(get_default_provider()));
+
+        let sherpa_config = sherpa_rs_sys::SherpaOnnxOfflineSpeechDenoiserConfig {
+            model: sherpa_rs_sys::SherpaOnnxOfflineSpeechDenoiserModelConfig {
+                gtcrn: sherpa_rs_sys::SherpaOnnxOfflineSpeechDenoiserGtcrnModelConfig {
+                    model: model.as_ptr(),
+                },
+                num_threads: config.num_threads.unwrap_or(1),
+                debug: crate::native_debug(config.debug),
+                provider: provider.as_ptr(),
+            },
+        };
+        let denoiser =
+            unsafe { sherpa_rs_sys::SherpaOnnxCreateOfflineSpeechDenoiser(&sherpa_config) };
+        if denoiser.is_null() {
+            bail!("Failed to create speech denoiser");
+        }
+        let sample_rate =
+            unsafe { sherpa_rs_sys::SherpaOnnxOfflineSpeechDenoiserGetSampleRate(denoiser) };
+
+        Ok(Self {
+            denoiser,
+            sample_rate: sample_rate as u32,
+        })
+    }
+
+    /// Sample rate the model expects its input in
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn denoise(
+        &mut self,
+        samples: &[f32],
+        sample_rate: u32,
+    ) -> Result<DenoisedAudio, DenoiseError> {
+        if sample_rate != self.sample_rate {
+            return Err(DenoiseError::SampleRateMismatch {
+                expected: self.sample_rate,
+                actual: sample_rate,
+            });
+        }
+        let len = crate::utils::native_len(samples.len())?;
+        unsafe {
+            let audio_ptr = sherpa_rs_sys::SherpaOnnxOfflineSpeechDenoiserRun(
+                self.denoiser,
+                samples.as_ptr(),
+                len,
+                sample_rate as i32,
+            );
+            if audio_ptr.is_null() {
+                return Err(DenoiseError::Failed);
+            }
+            let audio = audio_ptr.read();
+            let samples = if audio.samples.is_null() || audio.n <= 0 {
+                Vec::new()
+            } else {
+                std::slice::from_raw_parts(audio.samples, audio.n as usize).to_vec()
+            };
+            let denoised = DenoisedAudio {
+                samples,
+                sample_rate: audio.sample_rate as u32,
+            };
+            // Free
+            sherpa_rs_sys::SherpaOnnxDestroyDenoisedAudio(audio_ptr);
+            Ok(denoised)
+        }
+    }
+}
+
+unsafe impl Send for Denoiser {}
+unsafe impl Sync for Denoiser {}
+
+impl Drop for Denoiser {
+    fn drop(&mut self) {
+        unsafe {
+            sherpa_rs_sys::SherpaOnnxDestroyOfflineSpeechDenoiser(self.denoiser);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{
+    audio_tag::AudioTagConfig, denoise::DenoiserConfig, diarize::DiarizeConfig,
+    dolphin::DolphinConfig, keyword_spot::KeywordSpotConfig, language_id::SpokenLanguageIdConfig,
+    moonshine::MoonshineConfig, nemo_ctc::NemoCtcConfig, online::OnlineRecognizerConfig,
+    paraformer::ParaformerConfig, punctuate::PunctuationConfig, sense_voice::SenseVoiceConfig,
+    silero_vad::SileroVadConfig, speaker_id::ExtractorConfig, telespeech::TeleSpeechConfig,
+    ten_vad::TenVadConfig, transducer::TransducerConfig, whisper::WhisperConfig,
+    zipformer::ZipFormerConfig, OnnxConfig,
+};
+
+/// Preset for engines that must produce the same output on every run, e.g. for
+/// golden transcripts in CI.
+///
+/// The preset runs the engine on the CPU provider with one thread, so no GPU kernels
+/// or thread scheduling change the floating point results. Output can still differ
+/// between CPU architectures or ONNX Runtime builds, as they use different vectorized
+/// kernels; pin both to compare across machines.
+pub trait Deterministic: Sized {
+    /// This config with the preset applied, keeping every other field
+    fn deterministic(self) -> Self;
+}
+
+macro_rules! deterministic {
+    ($($config:ty { $($field:ident: $value:expr),* }),* $(,)?) => {
+        $(
+            impl Deterministic for $config {
+                fn deterministic(self) -> Self {
+                    Self {
+                        $($field: $value,)*
+                        ..self
+                    }
+                }
+            }
+        )*
+    };
+}
+
+deterministic! {
+    AudioTagConfig { provider: Some("cpu".into()), num_threads: Some(1) },
+    DenoiserConfig { provider: Some("cpu".into()), num_threads: Some(1) },
+    DiarizeConfig { provider: Some("cpu".into()) },
+    DolphinConfig { provider: Some("cpu".into()), num_threads: Some(1) },
+    ExtractorConfig { provider: Some("cpu".into()), num_threads: Some(1) },
+    KeywordSpotConfig { provider: Some("cpu".into()), num_threads: Some(1) },
+    MoonshineConfig { provider: Some("cpu".into()), num_threads: Some(1) },
+    NemoCtcConfig { provider: Some("cpu".into()), num_threads: Some(1) },
+    OnlineRecognizerConfig { provider: Some("cpu".into()), num_threads: 1 },
+    OnnxConfig { provider: "cpu".into(), num_threads: 1 },
+    ParaformerConfig { provider: Some("cpu".into()), num_threads: Some(1) },
+    PunctuationConfig { provider: Some("cpu".into()), num_threads: Some(1) },
+    SenseVoiceConfig { provider: Some("cpu".into()), num_threads: Some(1) },
+    SileroVadConfig { provider: Some("cpu".into()), num_threads: Some(1) },
+    SpokenLanguageIdConfig { provider: Some("cpu".into()), num_threads: Some(1) },
+    TeleSpeechConfig { provider: Some("cpu".into()), num_threads: Some(1) },
+    TenVadConfig { provider: Some("cpu".into()), num_threads: Some(1) },
+    TransducerConfig { provider: Some("cpu".into()), num_threads: 1 },
+    WhisperConfig { provider: Some("cpu".into()), num_threads: Some(1) },
+    ZipFormerConfig { provider: Some("cpu".into()), num_threads: Some(1) },
+}
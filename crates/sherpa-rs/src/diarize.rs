@@ -1,6 +1,6 @@
 use crate::{get_default_provider, utils::cstring_from_str};
 use eyre::{bail, Result};
-use std::{path::Path, ptr::null_mut};
+use std::{mem, path::Path, ptr::null_mut};
 
 #[derive(Debug)]
 pub struct Diarize {
@@ -18,9 +18,15 @@ type ProgressCallback = Box<dyn (Fn(i32, i32) -> i32) + Send + 'static>;
 
 #[derive(Debug, Clone)]
 pub struct DiarizeConfig {
+    /// Number of speakers, when it is known in advance.
+    /// `None` lets the clustering `threshold` decide how many speakers there are.
     pub num_clusters: Option<i32>,
+    /// Clustering threshold used when `num_clusters` is `None`.
+    /// Smaller values produce more speakers, larger values fewer.
     pub threshold: Option<f32>,
+    /// Speech segments shorter than this (in seconds) are discarded.
     pub min_duration_on: Option<f32>,
+    /// Gaps shorter than this (in seconds) between segments of the same speaker are merged.
     pub min_duration_off: Option<f32>,
     pub provider: Option<String>,
     pub debug: bool,
@@ -47,16 +53,12 @@ impl Diarize {
     ) -> Result<Self> {
         let provider = config.provider.unwrap_or(get_default_provider());
 
-        let debug = config.debug;
-        let debug = if debug { 1 } else { 0 };
+        let debug = crate::native_debug(config.debug);
 
         let embedding_model = embedding_model.as_ref().to_str().unwrap();
         let segmentation_model = segmentation_model.as_ref().to_str().unwrap();
 
-        let clustering_config = sherpa_rs_sys::SherpaOnnxFastClusteringConfig {
-            num_clusters: config.num_clusters.unwrap_or(4),
-            threshold: config.threshold.unwrap_or(0.5),
-        };
+        let clustering_config = clustering_config(config.num_clusters, config.threshold);
 
         let embedding_model = cstring_from_str(embedding_model);
         let provider = cstring_from_str(&provider.clone());
@@ -90,11 +92,27 @@ impl Diarize {
         Ok(Self { sd })
     }
 
+    /// Update the clustering parameters without reloading the models.
+    /// Useful when the same models serve different domains (e.g. meetings vs. phone calls).
+    pub fn set_clustering(&mut self, num_clusters: Option<i32>, threshold: Option<f32>) {
+        unsafe {
+            // Only the clustering config is used by sherpa-onnx here
+            let config = sherpa_rs_sys::SherpaOnnxOfflineSpeakerDiarizationConfig {
+                clustering: clustering_config(num_clusters, threshold),
+                ..mem::zeroed::<_>()
+            };
+            sherpa_rs_sys::SherpaOnnxOfflineSpeakerDiarizationSetConfig(self.sd, &config);
+        }
+    }
+
+    /// Speaker turns of 16 kHz mono `samples`, sorted by start time. Empty when no one
+    /// speaks. `progress_callback` gets the processed and total chunk counts.
     pub fn compute(
         &mut self,
         mut samples: Vec<f32>,
         progress_callback: Option<ProgressCallback>,
     ) -> Result<Vec<Segment>> {
+        let len = crate::utils::native_len(samples.len())?;
         let samples_ptr = samples.as_mut_ptr();
         let mut segments = Vec::new();
         unsafe {
@@ -108,7 +126,7 @@ impl Diarize {
             let result = sherpa_rs_sys::SherpaOnnxOfflineSpeakerDiarizationProcessWithCallback(
                 self.sd,
                 samples_ptr,
-                samples.len() as i32,
+                len,
                 if callback_box.is_some() {
                     Some(progress_callback_wrapper)
                 } else {
@@ -116,6 +134,9 @@ impl Diarize {
                 },
                 callback_ptr,
             );
+            if result.is_null() {
+                bail!("SherpaOnnxOfflineSpeakerDiarizationProcess failed");
+            }
 
             let num_segments =
                 sherpa_rs_sys::SherpaOnnxOfflineSpeakerDiarizationResultGetNumSegments(result);
@@ -128,19 +149,18 @@ impl Diarize {
                 ] = std::slice::from_raw_parts(segments_ptr, num_segments as usize);
 
                 for segment in segments_result {
-                    // Use segment here
-
                     segments.push(Segment {
                         start: segment.start,
                         end: segment.end,
                         speaker: segment.speaker,
                     });
                 }
-            } else {
-                bail!("No segments found or invalid pointer.");
             }
 
-            sherpa_rs_sys::SherpaOnnxOfflineSpeakerDiarizationDestroySegment(segments_ptr);
+            // No segments is a valid result, e.g. for silence
+            if !segments_ptr.is_null() {
+                sherpa_rs_sys::SherpaOnnxOfflineSpeakerDiarizationDestroySegment(segments_ptr);
+            }
             sherpa_rs_sys::SherpaOnnxOfflineSpeakerDiarizationDestroyResult(result);
 
             Ok(segments)
@@ -148,6 +168,17 @@ impl Diarize {
     }
 }
 
+fn clustering_config(
+    num_clusters: Option<i32>,
+    threshold: Option<f32>,
+) -> sherpa_rs_sys::SherpaOnnxFastClusteringConfig {
+    sherpa_rs_sys::SherpaOnnxFastClusteringConfig {
+        // -1 means unknown number of speakers, use threshold instead
+        num_clusters: num_clusters.unwrap_or(-1),
+        threshold: threshold.unwrap_or(0.5),
+    }
+}
+
 unsafe extern "C" fn progress_callback_wrapper(
     num_processed_chunk: i32,
     num_total_chunks: i32,
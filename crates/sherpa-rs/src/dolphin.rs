@@ -1,10 +1,16 @@
-use crate::{get_default_provider, utils::cstring_from_str};
+use crate::config_dump::{self, ConfigDump, NativeConfig};
+use crate::{
+    get_default_provider,
+    offline_stream::{self, OfflineStream},
+    utils::cstring_from_str,
+};
 use eyre::{bail, Result};
 use std::mem;
 
 #[derive(Debug)]
 pub struct DolphinRecognizer {
     recognizer: *const sherpa_rs_sys::SherpaOnnxOfflineRecognizer,
+    config_dump: ConfigDump,
 }
 
 pub type DolphinRecognizerResult = super::OfflineRecognizerResult;
@@ -35,7 +41,7 @@ impl Default for DolphinConfig {
 
 impl DolphinRecognizer {
     pub fn new(config: DolphinConfig) -> Result<Self> {
-        let debug = config.debug.into();
+        let debug = crate::native_debug(config.debug);
         let provider = config.provider.unwrap_or(get_default_provider());
 
         let provider_ptr = cstring_from_str(&provider);
@@ -92,33 +98,37 @@ impl DolphinRecognizer {
             }
         };
 
+        let config_dump = config_dump::offline_recognizer(&config);
         let recognizer = unsafe { sherpa_rs_sys::SherpaOnnxCreateOfflineRecognizer(&config) };
 
         if recognizer.is_null() {
             bail!("Failed to create recognizer");
         }
 
-        Ok(Self { recognizer })
+        Ok(Self {
+            recognizer,
+            config_dump,
+        })
+    }
+
+    pub fn create_stream(&self) -> Result<OfflineStream<'_>> {
+        unsafe { OfflineStream::new(self.recognizer) }
     }
 
     pub fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> DolphinRecognizerResult {
-        unsafe {
-            let stream = sherpa_rs_sys::SherpaOnnxCreateOfflineStream(self.recognizer);
-            sherpa_rs_sys::SherpaOnnxAcceptWaveformOffline(
-                stream,
-                sample_rate as i32,
-                samples.as_ptr(),
-                samples.len().try_into().unwrap(),
-            );
-            sherpa_rs_sys::SherpaOnnxDecodeOfflineStream(self.recognizer, stream);
-            let result_ptr = sherpa_rs_sys::SherpaOnnxGetOfflineStreamResult(stream);
-            let raw_result = result_ptr.read();
-            let result = DolphinRecognizerResult::new(&raw_result);
-            // Free
-            sherpa_rs_sys::SherpaOnnxDestroyOfflineRecognizerResult(result_ptr);
-            sherpa_rs_sys::SherpaOnnxDestroyOfflineStream(stream);
-            result
-        }
+        offline_stream::transcribe_once(self.create_stream(), sample_rate, samples)
+    }
+}
+
+impl NativeConfig for DolphinRecognizer {
+    fn config_dump(&self) -> &ConfigDump {
+        &self.config_dump
+    }
+}
+
+impl crate::Transcriber for DolphinRecognizer {
+    fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> crate::OfflineRecognizerResult {
+        DolphinRecognizer::transcribe(self, sample_rate, samples)
     }
 }
 
@@ -1,9 +1,28 @@
 use crate::utils::{cstr_to_string, cstring_from_str};
-use eyre::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
 
-#[derive(Debug, Clone)]
+/// Enrolled speakers, searched by voice. Save the enrollments with `save` and restore
+/// them with `load`.
+#[derive(Debug)]
 pub struct EmbeddingManager {
     pub(crate) manager: *const sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingManager,
+    dim: usize,
+    /// Copy of the native enrollments, which can't be read back
+    speakers: Vec<EnrolledSpeaker>,
+}
+
+/// Saved form of an `EmbeddingManager`
+#[derive(Debug, Serialize, Deserialize)]
+struct SpeakerDatabase {
+    dim: usize,
+    speakers: Vec<EnrolledSpeaker>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EnrolledSpeaker {
+    name: String,
+    embeddings: Vec<Vec<f32>>,
 }
 
 #[derive(Debug, Clone)]
@@ -12,26 +31,56 @@ pub struct SpeakerMatch {
     pub score: f32,
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum EmbeddingManagerError {
+    #[error("Embedding dimension mismatch: expected {expected}, got {actual}")]
+    DimensionMismatch { expected: usize, actual: usize },
+
+    #[error("Failed to register {0}")]
+    RegistrationFailed(String),
+
+    #[error("Failed to access speaker database: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid speaker database: {0}")]
+    InvalidDatabase(#[from] serde_json::Error),
+}
+
 impl EmbeddingManager {
     pub fn new(dimension: i32) -> Self {
         unsafe {
             let manager = sherpa_rs_sys::SherpaOnnxCreateSpeakerEmbeddingManager(dimension);
-            Self { manager }
+            Self {
+                manager,
+                dim: dimension.try_into().unwrap_or_default(),
+                speakers: Vec::new(),
+            }
         }
     }
 
-    pub fn search(&mut self, embedding: &[f32], threshold: f32) -> Option<String> {
+    /// Dimension every embedding passed to the manager must have
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    pub fn search(
+        &mut self,
+        embedding: &[f32],
+        threshold: f32,
+    ) -> Result<Option<String>, EmbeddingManagerError> {
+        self.check_dim(embedding)?;
         unsafe {
             let name = sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingManagerSearch(
                 self.manager,
-                embedding.to_owned().as_mut_ptr(),
+                embedding.as_ptr(),
                 threshold,
             );
             if name.is_null() {
-                return None;
+                return Ok(None);
             }
-            let name = cstr_to_string(name as _);
-            Some(name)
+            let result = cstr_to_string(name as _);
+            sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingManagerFreeSearch(name);
+            Ok(Some(result))
         }
     }
 
@@ -40,16 +89,17 @@ impl EmbeddingManager {
         embedding: &[f32],
         threshold: f32,
         n: i32,
-    ) -> Vec<SpeakerMatch> {
+    ) -> Result<Vec<SpeakerMatch>, EmbeddingManagerError> {
+        self.check_dim(embedding)?;
         unsafe {
             let result_ptr = sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingManagerGetBestMatches(
                 self.manager,
-                embedding.to_owned().as_mut_ptr(),
+                embedding.as_ptr(),
                 threshold,
                 n,
             );
             if result_ptr.is_null() {
-                return Vec::new();
+                return Ok(Vec::new());
             }
             let result = result_ptr.read();
 
@@ -62,11 +112,16 @@ impl EmbeddingManager {
                 matches.push(SpeakerMatch { name, score });
             }
             sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingManagerFreeBestMatches(result_ptr);
-            matches
+            Ok(matches)
         }
     }
 
-    pub fn add(&mut self, name: String, embedding: &mut [f32]) -> Result<()> {
+    pub fn add(
+        &mut self,
+        name: String,
+        embedding: &mut [f32],
+    ) -> Result<(), EmbeddingManagerError> {
+        self.check_dim(embedding)?;
         let name_c = cstring_from_str(&name.clone());
         unsafe {
             let status = sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingManagerAdd(
@@ -75,11 +130,129 @@ impl EmbeddingManager {
                 embedding.as_mut_ptr(),
             );
 
-            if status.is_negative() {
-                bail!("Failed to register {}", name);
+            if status == 0 {
+                return Err(EmbeddingManagerError::RegistrationFailed(name));
             }
-            Ok(())
         }
+        self.speakers.push(EnrolledSpeaker {
+            name,
+            embeddings: vec![embedding.to_vec()],
+        });
+        Ok(())
+    }
+
+    /// Register a speaker from several recordings, e.g. enrollment phrases.
+    /// Matches are scored against their average.
+    pub fn add_list(
+        &mut self,
+        name: String,
+        embeddings: &[Vec<f32>],
+    ) -> Result<(), EmbeddingManagerError> {
+        for embedding in embeddings {
+            self.check_dim(embedding)?;
+        }
+        if embeddings.is_empty() {
+            return Err(EmbeddingManagerError::RegistrationFailed(name));
+        }
+        let flattened: Vec<f32> = embeddings.concat();
+        let name_c = cstring_from_str(&name);
+        let status = unsafe {
+            sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingManagerAddListFlattened(
+                self.manager,
+                name_c.as_ptr(),
+                flattened.as_ptr(),
+                embeddings.len() as i32,
+            )
+        };
+        if status == 0 {
+            return Err(EmbeddingManagerError::RegistrationFailed(name));
+        }
+        self.speakers.push(EnrolledSpeaker {
+            name,
+            embeddings: embeddings.to_vec(),
+        });
+        Ok(())
+    }
+
+    /// Remove a speaker. Returns whether it was registered.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let name_c = cstring_from_str(name);
+        let removed = unsafe {
+            sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingManagerRemove(self.manager, name_c.as_ptr())
+        };
+        self.speakers.retain(|speaker| speaker.name != name);
+        removed != 0
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        let name_c = cstring_from_str(name);
+        unsafe {
+            sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingManagerContains(self.manager, name_c.as_ptr())
+                != 0
+        }
+    }
+
+    /// Whether `embedding` is the voice of the registered speaker `name`.
+    /// False for unknown speakers.
+    pub fn verify(
+        &self,
+        name: &str,
+        embedding: &[f32],
+        threshold: f32,
+    ) -> Result<bool, EmbeddingManagerError> {
+        self.check_dim(embedding)?;
+        let name_c = cstring_from_str(name);
+        let matched = unsafe {
+            sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingManagerVerify(
+                self.manager,
+                name_c.as_ptr(),
+                embedding.as_ptr(),
+                threshold,
+            )
+        };
+        Ok(matched != 0)
+    }
+
+    /// Names of the registered speakers, in registration order
+    pub fn speakers(&self) -> Vec<&str> {
+        self.speakers
+            .iter()
+            .map(|speaker| speaker.name.as_str())
+            .collect()
+    }
+
+    pub fn num_speakers(&self) -> usize {
+        self.speakers.len()
+    }
+
+    /// Write the registered speakers and their embeddings to `path` as JSON
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), EmbeddingManagerError> {
+        let database = SpeakerDatabase {
+            dim: self.dim,
+            speakers: self.speakers.clone(),
+        };
+        std::fs::write(path, serde_json::to_vec(&database)?)?;
+        Ok(())
+    }
+
+    /// Manager with the speakers saved by `save`
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, EmbeddingManagerError> {
+        let database: SpeakerDatabase = serde_json::from_slice(&std::fs::read(path)?)?;
+        let mut manager = Self::new(database.dim.try_into().unwrap_or(i32::MAX));
+        for speaker in database.speakers {
+            manager.add_list(speaker.name, &speaker.embeddings)?;
+        }
+        Ok(manager)
+    }
+
+    fn check_dim(&self, embedding: &[f32]) -> Result<(), EmbeddingManagerError> {
+        if embedding.len() != self.dim {
+            return Err(EmbeddingManagerError::DimensionMismatch {
+                expected: self.dim,
+                actual: embedding.len(),
+            });
+        }
+        Ok(())
     }
 }
 
@@ -0,0 +1,263 @@
+use crate::silero_vad::SpeechSegment;
+use eyre::{bail, Result};
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone)]
+pub struct EnergyVadConfig {
+    pub sample_rate: u32,
+    /// Analysis frame length in seconds
+    pub frame_duration: f32,
+    /// Frames quieter than this (dBFS) are never speech
+    pub threshold_db: f32,
+    /// Frames must also be this much louder than the tracked noise floor
+    pub noise_margin_db: f32,
+    /// Frames crossing zero more often than this (crossings per sample) look like noise
+    /// and need another 6 dB of energy to count as speech
+    pub max_zero_crossing_rate: f32,
+    pub min_silence_duration: f32,
+    pub min_speech_duration: f32,
+    /// Longer segments are split. 0 disables the limit.
+    pub max_speech_duration: f32,
+    /// Audio kept before and after each segment, in seconds
+    pub speech_pad_duration: f32,
+}
+
+impl Default for EnergyVadConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 16000,
+            frame_duration: 0.02,
+            threshold_db: -45.0,
+            noise_margin_db: 10.0,
+            max_zero_crossing_rate: 0.35,
+            min_silence_duration: 0.5,
+            min_speech_duration: 0.25,
+            max_speech_duration: 0.0,
+            speech_pad_duration: 0.1,
+        }
+    }
+}
+
+struct Current {
+    start: usize,
+    samples: Vec<f32>,
+    /// Length of `samples` up to the end of the last speech frame
+    speech_len: usize,
+    silence: usize,
+}
+
+/// Energy and zero-crossing voice activity detector in pure Rust.
+///
+/// Needs no model files, at the cost of being fooled by loud non-speech sounds.
+/// Good enough to gate a recognizer or trim silence before offline decoding.
+pub struct EnergyVad {
+    config: EnergyVadConfig,
+    frame_len: usize,
+    pad_len: usize,
+    /// Samples that don't fill a frame yet
+    pending: Vec<f32>,
+    /// Audio before the current segment, kept as padding
+    history: VecDeque<f32>,
+    /// Samples processed so far
+    position: usize,
+    noise_floor_db: Option<f32>,
+    current: Option<Current>,
+    segments: VecDeque<SpeechSegment>,
+}
+
+impl EnergyVad {
+    pub fn new(config: EnergyVadConfig) -> Result<Self> {
+        let frame_len = (config.frame_duration * config.sample_rate as f32) as usize;
+        if frame_len == 0 {
+            bail!("frame_duration is too short for the sample rate");
+        }
+        let pad_len = (config.speech_pad_duration.max(0.0) * config.sample_rate as f32) as usize;
+        Ok(Self {
+            config,
+            frame_len,
+            pad_len,
+            pending: Vec::new(),
+            history: VecDeque::new(),
+            position: 0,
+            noise_floor_db: None,
+            current: None,
+            segments: VecDeque::new(),
+        })
+    }
+
+    pub fn accept_waveform(&mut self, samples: &[f32]) {
+        self.pending.extend_from_slice(samples);
+        let frames = self.pending.len() / self.frame_len;
+        let rest = self.pending.split_off(frames * self.frame_len);
+        let pending = std::mem::replace(&mut self.pending, rest);
+        for frame in pending.chunks(self.frame_len) {
+            self.process_frame(frame);
+        }
+    }
+
+    /// Process buffered samples and close the segment in progress
+    pub fn flush(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        if !pending.is_empty() {
+            self.process_frame(&pending);
+        }
+        self.finish();
+    }
+
+    /// Whether the last frame was inside a speech segment
+    pub fn is_speech(&self) -> bool {
+        self.current.is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn pop(&mut self) -> Option<SpeechSegment> {
+        self.segments.pop_front()
+    }
+
+    /// Drop all state and start counting samples from zero again
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.history.clear();
+        self.position = 0;
+        self.noise_floor_db = None;
+        self.current = None;
+        self.segments.clear();
+    }
+
+    fn is_speech_frame(&mut self, frame: &[f32]) -> bool {
+        let energy = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+        let db = 10.0 * energy.max(1e-10).log10();
+        let crossings = frame
+            .windows(2)
+            .filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0))
+            .count();
+        let zcr = crossings as f32 / frame.len() as f32;
+
+        let floor = *self
+            .noise_floor_db
+            .get_or_insert(self.config.threshold_db - self.config.noise_margin_db);
+        let mut bar = self
+            .config
+            .threshold_db
+            .max(floor + self.config.noise_margin_db);
+        if zcr > self.config.max_zero_crossing_rate {
+            bar += 6.0;
+        }
+        let speech = db >= bar;
+
+        // The floor drops immediately and rises slowly, even more slowly during speech,
+        // so steady noise is eventually ignored while speech barely moves it
+        self.noise_floor_db = Some(if db < floor {
+            db
+        } else if speech {
+            floor + 0.01
+        } else {
+            (floor + 0.05).min(db)
+        });
+        speech
+    }
+
+    fn process_frame(&mut self, frame: &[f32]) {
+        let speech = self.is_speech_frame(frame);
+        let start = self.position;
+        self.position += frame.len();
+
+        match &mut self.current {
+            None if speech => {
+                let mut samples: Vec<f32> = self.history.drain(..).collect();
+                samples.extend_from_slice(frame);
+                self.current = Some(Current {
+                    start: start - (samples.len() - frame.len()),
+                    speech_len: samples.len(),
+                    samples,
+                    silence: 0,
+                });
+            }
+            None => {
+                self.history.extend(frame);
+                let excess = self.history.len().saturating_sub(self.pad_len);
+                self.history.drain(..excess);
+            }
+            Some(current) => {
+                current.samples.extend_from_slice(frame);
+                if speech {
+                    current.speech_len = current.samples.len();
+                    current.silence = 0;
+                } else {
+                    current.silence += frame.len();
+                }
+                let sample_rate = self.config.sample_rate as f32;
+                let max_len = (self.config.max_speech_duration * sample_rate) as usize;
+                let min_silence = (self.config.min_silence_duration * sample_rate) as usize;
+                if current.silence >= min_silence
+                    || (max_len > 0 && current.samples.len() >= max_len)
+                {
+                    self.finish();
+                }
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        let Some(mut current) = self.current.take() else {
+            return;
+        };
+        let end = (current.speech_len + self.pad_len).min(current.samples.len());
+        // Silence after the padding goes back to the history for the next segment
+        self.history.extend(current.samples.drain(end..));
+        let excess = self.history.len().saturating_sub(self.pad_len);
+        self.history.drain(..excess);
+
+        let min_speech =
+            (self.config.min_speech_duration * self.config.sample_rate as f32) as usize;
+        if current.speech_len >= min_speech {
+            self.segments.push_back(SpeechSegment {
+                start: current.start as i32,
+                samples: current.samples,
+            });
+        }
+    }
+}
+
+impl crate::VoiceActivityDetector for EnergyVad {
+    fn accept_waveform(&mut self, samples: Vec<f32>) {
+        EnergyVad::accept_waveform(self, &samples)
+    }
+
+    fn flush(&mut self) {
+        EnergyVad::flush(self)
+    }
+
+    fn pop_segment(&mut self) -> Option<SpeechSegment> {
+        self.pop()
+    }
+
+    fn is_speech(&mut self) -> bool {
+        EnergyVad::is_speech(self)
+    }
+}
+
+/// Cut leading and trailing silence, keeping everything from the start of the first
+/// speech segment to the end of the last one. Returns an empty slice when there's no speech.
+pub fn trim_silence(samples: &[f32], config: EnergyVadConfig) -> Result<&[f32]> {
+    let mut vad = EnergyVad::new(config)?;
+    vad.accept_waveform(samples);
+    vad.flush();
+
+    let mut range: Option<(usize, usize)> = None;
+    while let Some(segment) = vad.pop() {
+        let start = segment.start.max(0) as usize;
+        let end = start + segment.samples.len();
+        range = Some(match range {
+            Some((first, _)) => (first, end),
+            None => (start, end),
+        });
+    }
+    Ok(match range {
+        Some((start, end)) => &samples[start..end.min(samples.len())],
+        None => &[],
+    })
+}
@@ -0,0 +1,118 @@
+use crate::{
+    dolphin::{DolphinConfig, DolphinRecognizer},
+    moonshine::{MoonshineConfig, MoonshineRecognizer},
+    nemo_ctc::{NemoCtcConfig, NemoCtcRecognizer},
+    online::{OnlineRecognizer, OnlineRecognizerConfig},
+    paraformer::{ParaformerConfig, ParaformerRecognizer},
+    sense_voice::{SenseVoiceConfig, SenseVoiceRecognizer},
+    telespeech::{TeleSpeechConfig, TeleSpeechRecognizer},
+    transducer::{TransducerConfig, TransducerRecognizer},
+    whisper::{WhisperConfig, WhisperRecognizer},
+    zipformer::{ZipFormer, ZipFormerConfig},
+};
+use eyre::Result;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+
+/// Config of a recognizer that can be shared process-wide with `recognizer`.
+///
+/// Implemented for the recognizer configs of this crate. Configs with the same `key`
+/// share one recognizer.
+pub trait SharedConfig: Debug + 'static {
+    type Recognizer: Send + 'static;
+
+    fn load(self) -> Result<Self::Recognizer>;
+
+    /// Identity of the config, covering every field that matters. Defaults to the `Debug`
+    /// output; override it when that isn't canonical, e.g. for configs holding a `HashMap`,
+    /// whose iteration order differs between equal maps.
+    fn key(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+macro_rules! shared_config {
+    ($($config:ty => $recognizer:ty),* $(,)?) => {
+        $(
+            impl SharedConfig for $config {
+                type Recognizer = $recognizer;
+
+                fn load(self) -> Result<Self::Recognizer> {
+                    <$recognizer>::new(self)
+                }
+            }
+        )*
+    };
+}
+
+shared_config! {
+    DolphinConfig => DolphinRecognizer,
+    MoonshineConfig => MoonshineRecognizer,
+    NemoCtcConfig => NemoCtcRecognizer,
+    OnlineRecognizerConfig => OnlineRecognizer,
+    ParaformerConfig => ParaformerRecognizer,
+    SenseVoiceConfig => SenseVoiceRecognizer,
+    TeleSpeechConfig => TeleSpeechRecognizer,
+    TransducerConfig => TransducerRecognizer,
+    WhisperConfig => WhisperRecognizer,
+    ZipFormerConfig => ZipFormer,
+}
+
+type Key = (TypeId, String);
+
+#[derive(Default)]
+struct Slot {
+    /// `Arc<Mutex<C::Recognizer>>` once loaded
+    recognizer: OnceLock<Arc<dyn Any + Send + Sync>>,
+    /// Held while loading, so concurrent callers wait for one load instead of racing
+    loading: Mutex<()>,
+}
+
+/// The map is never left half-updated, so a panic elsewhere doesn't invalidate it
+fn slots() -> MutexGuard<'static, HashMap<Key, Arc<Slot>>> {
+    static SLOTS: OnceLock<Mutex<HashMap<Key, Arc<Slot>>>> = OnceLock::new();
+    SLOTS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Recognizer for `config`, loaded on the first call and shared by every later call
+/// with an equal config.
+///
+/// Loading one config doesn't block lookups of others. A failed load isn't remembered,
+/// so the next call tries again. Shared recognizers stay loaded until the process exits.
+pub fn recognizer<C: SharedConfig>(config: C) -> Result<Arc<Mutex<C::Recognizer>>> {
+    let key = (TypeId::of::<C>(), config.key());
+    let slot = slots().entry(key).or_default().clone();
+
+    if let Some(recognizer) = slot.recognizer.get() {
+        return Ok(downcast::<C>(recognizer));
+    }
+    // A loader that panicked left nothing behind, so the next caller just loads again
+    let _loading = slot.loading.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(recognizer) = slot.recognizer.get() {
+        return Ok(downcast::<C>(recognizer));
+    }
+    tracing::debug!("loading shared recognizer for {:?}", config);
+    let recognizer = Arc::new(Mutex::new(config.load()?));
+    let _ = slot.recognizer.set(recognizer.clone());
+    Ok(recognizer)
+}
+
+/// Whether `config` has a shared recognizer loaded
+pub fn is_loaded<C: SharedConfig>(config: &C) -> bool {
+    let key = (TypeId::of::<C>(), config.key());
+    slots()
+        .get(&key)
+        .is_some_and(|slot| slot.recognizer.get().is_some())
+}
+
+fn downcast<C: SharedConfig>(recognizer: &Arc<dyn Any + Send + Sync>) -> Arc<Mutex<C::Recognizer>> {
+    recognizer
+        .clone()
+        .downcast()
+        .expect("slots are keyed by config type")
+}
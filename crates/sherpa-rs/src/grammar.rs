@@ -0,0 +1,134 @@
+use crate::{transducer::TransducerRecognizer, DecodeOptions};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandMatch {
+    pub id: String,
+    /// Phrase of the grammar that matched best
+    pub phrase: String,
+    /// Similarity between the phrase and the recognized text, from 0.0 to 1.0
+    pub score: f32,
+}
+
+/// Closed set of spoken commands, e.g. for smart-home control.
+///
+/// The phrases bias decoding as hotwords, and the recognized text is then matched
+/// against them with a fuzzy score, so near misses ("turn of the light") still map
+/// to the right command while unrelated speech maps to none.
+#[derive(Debug, Clone)]
+pub struct CommandGrammar {
+    /// (command id, phrase as given, normalized phrase)
+    phrases: Vec<(String, String, String)>,
+    /// Minimum score of a match
+    pub threshold: f32,
+    /// Hotword boost of the phrases. `None` uses the recognizer's `hotwords_score`.
+    pub boost: Option<f32>,
+}
+
+impl Default for CommandGrammar {
+    fn default() -> Self {
+        Self {
+            phrases: Vec::new(),
+            threshold: 0.75,
+            boost: None,
+        }
+    }
+}
+
+impl CommandGrammar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a phrase for command `id`. A command may have several phrases.
+    pub fn add(&mut self, id: impl Into<String>, phrase: &str) -> &mut Self {
+        self.phrases
+            .push((id.into(), phrase.trim().to_string(), normalize(phrase)));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.phrases.is_empty()
+    }
+
+    /// Hotwords for the phrases, one per line in the hotwords file format.
+    /// Phrases must use the casing of the model's tokens (e.g. uppercase for older English models).
+    pub fn hotwords(&self) -> String {
+        let mut lines: Vec<String> = Vec::new();
+        for (_, phrase, _) in &self.phrases {
+            let line = match self.boost {
+                Some(boost) => format!("{} :{}", phrase, boost),
+                None => phrase.clone(),
+            };
+            if !lines.contains(&line) {
+                lines.push(line);
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Decoding options that bias the recognizer towards the phrases
+    pub fn decode_options(&self) -> DecodeOptions {
+        DecodeOptions {
+            hotwords: Some(self.hotwords()),
+        }
+    }
+
+    /// Best matching command for recognized `text`, if any scores above `threshold`
+    pub fn match_text(&self, text: &str) -> Option<CommandMatch> {
+        let text = normalize(text);
+        if text.is_empty() {
+            return None;
+        }
+        self.phrases
+            .iter()
+            .map(|(id, phrase, normalized)| CommandMatch {
+                id: id.clone(),
+                phrase: phrase.clone(),
+                score: similarity(&text, normalized),
+            })
+            .filter(|m| m.score >= self.threshold)
+            .max_by(|a, b| a.score.total_cmp(&b.score))
+    }
+
+    /// Decode `samples` biased towards the phrases and match the result.
+    /// The recognizer needs decoding method "modified_beam_search" for the bias to apply.
+    pub fn recognize(
+        &self,
+        recognizer: &mut TransducerRecognizer,
+        sample_rate: u32,
+        samples: &[f32],
+    ) -> Option<CommandMatch> {
+        let text = recognizer.transcribe_with_options(sample_rate, samples, &self.decode_options());
+        self.match_text(&text)
+    }
+}
+
+/// Lowercase words without punctuation, separated by single spaces
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 1 minus the character edit distance, relative to the longer string
+fn similarity(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    1.0 - row[b.len()] as f32 / longest as f32
+}
@@ -0,0 +1,86 @@
+use eyre::{bail, Result};
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenInfo {
+    pub text: String,
+    /// Start time in seconds
+    pub start: f32,
+    /// Probability of the token, when the decoder reports it (transducer beam search)
+    pub prob: Option<f32>,
+}
+
+/// Decoded hypothesis with per-token confidence
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hypothesis {
+    pub text: String,
+    pub tokens: Vec<TokenInfo>,
+    /// Sum of the token log probabilities, when available
+    pub score: Option<f32>,
+}
+
+impl Hypothesis {
+    /// Parse the JSON result produced by sherpa-onnx for a stream.
+    ///
+    /// Per-token log probabilities are only filled by transducer models
+    /// (`ys_log_probs` offline, `ys_probs` online); otherwise `prob` and `score` are `None`.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(json)?;
+        let Some(object) = value.as_object() else {
+            bail!("Result JSON is not an object");
+        };
+
+        let text = object
+            .get("text")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let strings = |key: &str| -> Vec<String> {
+            object
+                .get(key)
+                .and_then(Value::as_array)
+                .map(|a| {
+                    a.iter()
+                        .map(|v| v.as_str().unwrap_or_default().to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let floats = |key: &str| -> Option<Vec<f32>> {
+            object
+                .get(key)
+                .and_then(Value::as_array)
+                .map(|a| a.iter().map(|v| v.as_f64().unwrap_or(0.0) as f32).collect())
+        };
+
+        let symbols = strings("tokens");
+        let timestamps = floats("timestamps").unwrap_or_default();
+        let log_probs = floats("ys_log_probs")
+            .or_else(|| floats("ys_probs"))
+            .filter(|p| !p.is_empty() && p.len() == symbols.len());
+
+        let tokens = symbols
+            .into_iter()
+            .enumerate()
+            .map(|(i, text)| TokenInfo {
+                text,
+                start: timestamps.get(i).copied().unwrap_or(0.0),
+                prob: log_probs.as_ref().map(|p| p[i].exp()),
+            })
+            .collect();
+        let score = log_probs.map(|p| p.iter().sum());
+
+        Ok(Self {
+            text,
+            tokens,
+            score,
+        })
+    }
+
+    /// Tokens whose probability is below `threshold`, e.g. to highlight them for review
+    pub fn uncertain_tokens(&self, threshold: f32) -> impl Iterator<Item = &TokenInfo> {
+        self.tokens
+            .iter()
+            .filter(move |t| t.prob.is_some_and(|p| p < threshold))
+    }
+}
@@ -0,0 +1,624 @@
+/// Languages supported by the built-in inverse text normalizer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItnLanguage {
+    English,
+    Chinese,
+}
+
+/// Pure-Rust inverse text normalization, for when no rule FSTs are available.
+///
+/// Converts spelled-out numbers to digits, including ordinals, decimals, years,
+/// percentages, currencies and dates: "twenty twenty five" becomes "2025" and
+/// "january first" becomes "January 1". In English, lone numbers below ten are
+/// kept as words ("one question") unless they're part of an amount or a date.
+#[derive(Debug, Clone)]
+pub struct InverseTextNormalizer {
+    language: ItnLanguage,
+}
+
+impl InverseTextNormalizer {
+    pub fn new(language: ItnLanguage) -> Self {
+        Self { language }
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        match self.language {
+            ItnLanguage::English => english(text),
+            ItnLanguage::Chinese => chinese(text),
+        }
+    }
+
+    /// Turn the normalizer into a text hook for `PostProcessors` / `PostProcessed` / pipelines
+    pub fn into_post_processor(self) -> impl FnMut(String) -> String + Send + 'static {
+        move |text| self.apply(&text)
+    }
+}
+
+const ONES: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+const TEENS: [&str; 10] = [
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const TENS: [&str; 8] = [
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+const SCALES: [(&str, u64); 3] = [
+    ("thousand", 1_000),
+    ("million", 1_000_000),
+    ("billion", 1_000_000_000),
+];
+/// Ordinal words and the cardinal they stand for
+const ORDINALS: [(&str, &str); 31] = [
+    ("first", "one"),
+    ("second", "two"),
+    ("third", "three"),
+    ("fourth", "four"),
+    ("fifth", "five"),
+    ("sixth", "six"),
+    ("seventh", "seven"),
+    ("eighth", "eight"),
+    ("ninth", "nine"),
+    ("tenth", "ten"),
+    ("eleventh", "eleven"),
+    ("twelfth", "twelve"),
+    ("thirteenth", "thirteen"),
+    ("fourteenth", "fourteen"),
+    ("fifteenth", "fifteen"),
+    ("sixteenth", "sixteen"),
+    ("seventeenth", "seventeen"),
+    ("eighteenth", "eighteen"),
+    ("nineteenth", "nineteen"),
+    ("twentieth", "twenty"),
+    ("thirtieth", "thirty"),
+    ("fortieth", "forty"),
+    ("fiftieth", "fifty"),
+    ("sixtieth", "sixty"),
+    ("seventieth", "seventy"),
+    ("eightieth", "eighty"),
+    ("ninetieth", "ninety"),
+    ("hundredth", "hundred"),
+    ("thousandth", "thousand"),
+    ("millionth", "million"),
+    ("billionth", "billion"),
+];
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const CURRENCIES: [(&str, &str); 6] = [
+    ("dollar", "$"),
+    ("dollars", "$"),
+    ("euro", "€"),
+    ("euros", "€"),
+    ("pound", "£"),
+    ("pounds", "£"),
+];
+
+struct Token {
+    /// Lowercase word without trailing punctuation
+    word: String,
+    /// Word as written, without trailing punctuation
+    text: String,
+    /// Trailing punctuation
+    punct: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Number {
+    Cardinal(u64),
+    Ordinal(u64),
+    Decimal(String),
+    Year(u64),
+    /// Digit by digit, e.g. phone numbers
+    Digits(String),
+}
+
+impl Number {
+    fn text(&self) -> String {
+        match self {
+            Number::Cardinal(v) | Number::Year(v) => v.to_string(),
+            Number::Ordinal(v) => {
+                let suffix = match (v % 100, v % 10) {
+                    (11..=13, _) => "th",
+                    (_, 1) => "st",
+                    (_, 2) => "nd",
+                    (_, 3) => "rd",
+                    _ => "th",
+                };
+                format!("{}{}", v, suffix)
+            }
+            Number::Decimal(s) | Number::Digits(s) => s.clone(),
+        }
+    }
+
+    fn spelled_out(&self) -> bool {
+        matches!(self, Number::Cardinal(v) | Number::Ordinal(v) if *v < 10)
+    }
+}
+
+fn english(text: &str) -> String {
+    let tokens = tokenize(text);
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        match date(&tokens[i..]).or_else(|| number(&tokens[i..])) {
+            Some((text, consumed)) => {
+                out.push(text);
+                i += consumed;
+            }
+            None => {
+                out.push(format!("{}{}", tokens[i].text, tokens[i].punct));
+                i += 1;
+            }
+        }
+    }
+    out.join(" ")
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    for raw in text.split_whitespace() {
+        let core = raw.trim_end_matches(|c: char| c.is_ascii_punctuation());
+        let punct = &raw[core.len()..];
+        // "twenty-five" is two number words
+        let parts: Vec<&str> = core.split('-').collect();
+        let parts = if parts.len() > 1
+            && parts
+                .iter()
+                .all(|p| is_number_word(&p.to_lowercase()) || ordinal(&p.to_lowercase()).is_some())
+        {
+            parts
+        } else {
+            vec![core]
+        };
+        let last = parts.len() - 1;
+        for (i, part) in parts.into_iter().enumerate() {
+            tokens.push(Token {
+                word: part.to_lowercase(),
+                text: part.to_string(),
+                punct: if i == last {
+                    punct.to_string()
+                } else {
+                    String::new()
+                },
+            });
+        }
+    }
+    tokens
+}
+
+fn ones(word: &str) -> Option<u64> {
+    ONES.iter().position(|w| *w == word).map(|i| i as u64 + 1)
+}
+
+fn teens(word: &str) -> Option<u64> {
+    TEENS.iter().position(|w| *w == word).map(|i| i as u64 + 10)
+}
+
+fn tens(word: &str) -> Option<u64> {
+    TENS.iter()
+        .position(|w| *w == word)
+        .map(|i| (i as u64 + 2) * 10)
+}
+
+fn scale(word: &str) -> Option<u64> {
+    SCALES.iter().find(|(w, _)| *w == word).map(|(_, v)| *v)
+}
+
+fn ordinal(word: &str) -> Option<&'static str> {
+    ORDINALS.iter().find(|(w, _)| *w == word).map(|(_, c)| *c)
+}
+
+fn is_number_word(word: &str) -> bool {
+    word == "zero"
+        || word == "hundred"
+        || ones(word).is_some()
+        || teens(word).is_some()
+        || tens(word).is_some()
+        || scale(word).is_some()
+}
+
+fn digit(word: &str) -> Option<u64> {
+    match word {
+        "zero" | "oh" => Some(0),
+        _ => ones(word),
+    }
+}
+
+/// Number of leading tokens that can be part of one spoken number
+fn number_span(tokens: &[Token]) -> usize {
+    let mut len = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        let word = token.word.as_str();
+        let next_is_number = tokens
+            .get(i + 1)
+            .is_some_and(|t| is_number_word(&t.word) || ordinal(&t.word).is_some());
+        let accepted = if i == 0 {
+            is_number_word(word) || ordinal(word).is_some()
+        } else {
+            is_number_word(word)
+                || ordinal(word).is_some()
+                || (matches!(word, "and" | "point" | "oh") && next_is_number)
+        };
+        if !accepted {
+            break;
+        }
+        len += 1;
+        if !token.punct.is_empty() || ordinal(word).is_some() {
+            break;
+        }
+    }
+    len
+}
+
+fn parse_cardinal(words: &[&str]) -> Option<u64> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Last {
+        None,
+        Ones,
+        Teens,
+        Tens,
+        Hundred,
+        Scale,
+        And,
+    }
+
+    if words == ["zero"] {
+        return Some(0);
+    }
+    let mut total = 0;
+    let mut current = 0;
+    let mut last = Last::None;
+    let mut last_scale = u64::MAX;
+    for word in words {
+        if let Some(v) = ones(word) {
+            match last {
+                Last::Ones | Last::Teens => return None,
+                Last::Tens => {}
+                _ if current % 100 != 0 => return None,
+                _ => {}
+            }
+            current += v;
+            last = Last::Ones;
+        } else if let Some(v) = teens(word).or_else(|| tens(word)) {
+            if matches!(last, Last::Ones | Last::Teens | Last::Tens) || current % 100 != 0 {
+                return None;
+            }
+            current += v;
+            last = if v < 20 { Last::Teens } else { Last::Tens };
+        } else if *word == "hundred" {
+            if !matches!(last, Last::Ones | Last::Teens) || current >= 100 {
+                return None;
+            }
+            current *= 100;
+            last = Last::Hundred;
+        } else if let Some(scale) = scale(word) {
+            if current == 0 || scale >= last_scale {
+                return None;
+            }
+            total += current * scale;
+            current = 0;
+            last_scale = scale;
+            last = Last::Scale;
+        } else if *word == "and" {
+            if !matches!(last, Last::Hundred | Last::Scale) {
+                return None;
+            }
+            last = Last::And;
+        } else {
+            return None;
+        }
+    }
+    if matches!(last, Last::None | Last::And) {
+        return None;
+    }
+    Some(total + current)
+}
+
+/// "twenty twenty five", "nineteen oh five", "nineteen hundred"
+fn parse_year(words: &[&str]) -> Option<u64> {
+    (1..words.len()).find_map(|split| {
+        let (high, low) = words.split_at(split);
+        let high = parse_cardinal(high).filter(|v| (10..100).contains(v))?;
+        let low = match low {
+            ["hundred"] => 0,
+            ["oh", d] => ones(d)?,
+            _ => parse_cardinal(low).filter(|v| (10..100).contains(v))?,
+        };
+        Some(high * 100 + low)
+    })
+}
+
+fn interpret(words: &[&str]) -> Option<Number> {
+    let (last, rest) = words.split_last()?;
+    if let Some(cardinal) = ordinal(last) {
+        let mut words = rest.to_vec();
+        words.push(cardinal);
+        return parse_cardinal(&words).map(Number::Ordinal);
+    }
+    if let Some(point) = words.iter().position(|w| *w == "point") {
+        let whole = parse_cardinal(&words[..point])?;
+        let fraction = &words[point + 1..];
+        if fraction.is_empty() {
+            return None;
+        }
+        let fraction = fraction
+            .iter()
+            .map(|w| digit(w).map(|d| d.to_string()))
+            .collect::<Option<String>>()?;
+        return Some(Number::Decimal(format!("{}.{}", whole, fraction)));
+    }
+    if let Some(v) = parse_cardinal(words) {
+        return Some(Number::Cardinal(v));
+    }
+    if let Some(v) = parse_year(words) {
+        return Some(Number::Year(v));
+    }
+    if words.len() >= 3 {
+        let digits = words
+            .iter()
+            .map(|w| digit(w).map(|d| d.to_string()))
+            .collect::<Option<String>>()?;
+        return Some(Number::Digits(digits));
+    }
+    None
+}
+
+fn words(tokens: &[Token]) -> Vec<&str> {
+    tokens.iter().map(|t| t.word.as_str()).collect()
+}
+
+fn number(tokens: &[Token]) -> Option<(String, usize)> {
+    let span = number_span(tokens);
+    for len in (1..=span).rev() {
+        let Some(number) = interpret(&words(&tokens[..len])) else {
+            continue;
+        };
+        let punct = &tokens[len - 1].punct;
+        if punct.is_empty() {
+            if let Some((text, consumed)) = unit(&number, &tokens[len..]) {
+                return Some((text, len + consumed));
+            }
+        }
+        if number.spelled_out() {
+            return None;
+        }
+        return Some((format!("{}{}", number.text(), punct), len));
+    }
+    None
+}
+
+/// Percent and currency words following an amount
+fn unit(number: &Number, rest: &[Token]) -> Option<(String, usize)> {
+    let amount = match number {
+        Number::Cardinal(v) => v.to_string(),
+        Number::Decimal(s) => s.clone(),
+        _ => return None,
+    };
+    let first = rest.first()?;
+    if first.word == "percent" {
+        return Some((format!("{}%{}", amount, first.punct), 1));
+    }
+    let symbol = CURRENCIES
+        .iter()
+        .find(|(w, _)| *w == first.word)
+        .map(|(_, s)| *s)?;
+
+    // "five dollars and fifty cents"
+    if first.punct.is_empty() && matches!(number, Number::Cardinal(_)) {
+        if let Some(and) = rest.get(1).filter(|t| t.word == "and") {
+            let cents = &rest[2..];
+            let len = number_span(cents);
+            let unit = cents
+                .get(len)
+                .filter(|t| t.word == "cent" || t.word == "cents");
+            if let (true, Some(unit)) = (and.punct.is_empty(), unit) {
+                if let Some(cents) = parse_cardinal(&words(&cents[..len])).filter(|c| *c < 100) {
+                    let text = format!("{}{}.{:02}{}", symbol, amount, cents, unit.punct);
+                    return Some((text, len + 3));
+                }
+            }
+        }
+    }
+    Some((format!("{}{}{}", symbol, amount, first.punct), 1))
+}
+
+/// "<month> <day> [<year>]"
+fn date(tokens: &[Token]) -> Option<(String, usize)> {
+    let (first, rest) = tokens.split_first()?;
+    let month = MONTHS
+        .iter()
+        .position(|m| m.eq_ignore_ascii_case(&first.word))?;
+    if !first.punct.is_empty() {
+        return None;
+    }
+
+    let span = number_span(rest).min(2);
+    for len in (1..=span).rev() {
+        let day = match interpret(&words(&rest[..len])) {
+            Some(Number::Ordinal(day)) => day,
+            // "may five" is more likely a verb than a date
+            Some(Number::Cardinal(day)) if MONTHS[month] != "May" => day,
+            _ => continue,
+        };
+        if !(1..=31).contains(&day) {
+            continue;
+        }
+        let mut text = format!("{} {}", MONTHS[month], day);
+        let mut consumed = 1 + len;
+        let mut punct = rest[len - 1].punct.clone();
+        if punct.is_empty() {
+            let after = &rest[len..];
+            for year_len in (1..=number_span(after)).rev() {
+                if let Some(Number::Year(year) | Number::Cardinal(year)) =
+                    interpret(&words(&after[..year_len]))
+                {
+                    if (1000..10000).contains(&year) {
+                        text = format!("{}, {}", text, year);
+                        consumed += year_len;
+                        punct = after[year_len - 1].punct.clone();
+                        break;
+                    }
+                }
+            }
+        }
+        return Some((text + &punct, consumed));
+    }
+    None
+}
+
+const ZH_DIGITS: [(char, u64); 12] = [
+    ('零', 0),
+    ('〇', 0),
+    ('一', 1),
+    ('二', 2),
+    ('两', 2),
+    ('三', 3),
+    ('四', 4),
+    ('五', 5),
+    ('六', 6),
+    ('七', 7),
+    ('八', 8),
+    ('九', 9),
+];
+/// Characters after which a lone numeral is a number rather than part of a word
+const ZH_COUNTERS: &str = "年月日号元岁";
+
+fn zh_digit(c: char) -> Option<u64> {
+    ZH_DIGITS.iter().find(|(d, _)| *d == c).map(|(_, v)| *v)
+}
+
+fn zh_unit(c: char) -> Option<u64> {
+    match c {
+        '十' => Some(10),
+        '百' => Some(100),
+        '千' => Some(1_000),
+        '万' => Some(10_000),
+        '亿' => Some(100_000_000),
+        _ => None,
+    }
+}
+
+fn chinese(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i..].starts_with(&['百', '分', '之']) {
+            if let Some((number, consumed)) = zh_number(&chars[i + 3..], true) {
+                out.push_str(&number);
+                out.push('%');
+                i += 3 + consumed;
+                continue;
+            }
+        }
+        let ordinal = i > 0 && chars[i - 1] == '第';
+        if let Some((number, consumed)) = zh_number(&chars[i..], ordinal) {
+            out.push_str(&number);
+            i += consumed;
+            continue;
+        }
+        out.push(chars[i]);
+        i += 1;
+    }
+    out
+}
+
+fn zh_number(chars: &[char], force: bool) -> Option<(String, usize)> {
+    let len = chars
+        .iter()
+        .take_while(|c| zh_digit(**c).is_some() || zh_unit(**c).is_some())
+        .count();
+    // "万一", "千万" and friends are words
+    if len == 0 || matches!(chars[0], '百' | '千' | '万' | '亿') {
+        return None;
+    }
+    let run = &chars[..len];
+    let is_digits = |chars: &[char]| chars.iter().all(|c| zh_digit(*c).is_some());
+    let decimal =
+        chars.get(len) == Some(&'点') && chars.get(len + 1).is_some_and(|c| zh_digit(*c).is_some());
+    let counter = chars.get(len).is_some_and(|c| ZH_COUNTERS.contains(*c));
+    // Two bare digits ("一一") are more often part of a word than a number
+    let long_enough = if is_digits(run) { len >= 3 } else { len >= 2 };
+    if !(force || long_enough || decimal || counter) {
+        return None;
+    }
+
+    let mut text = if len >= 2 && is_digits(run) {
+        // Years and codes are read digit by digit
+        run.iter()
+            .map(|c| zh_digit(*c).unwrap().to_string())
+            .collect()
+    } else {
+        zh_value(run)?.to_string()
+    };
+    let mut consumed = len;
+    if decimal {
+        let fraction: String = chars[len + 1..]
+            .iter()
+            .map_while(|c| zh_digit(*c))
+            .map(|d| d.to_string())
+            .collect();
+        text.push('.');
+        text.push_str(&fraction);
+        consumed += 1 + fraction.len();
+    }
+    Some((text, consumed))
+}
+
+/// `None` for malformed runs and values past `u64`, e.g. "一亿亿亿亿"
+fn zh_value(run: &[char]) -> Option<u64> {
+    let mut total: u64 = 0;
+    let mut section: u64 = 0;
+    let mut number = 0;
+    for &c in run {
+        if let Some(d) = zh_digit(c) {
+            number = d;
+            continue;
+        }
+        match zh_unit(c)? {
+            unit @ (10 | 100 | 1_000) => {
+                // "十五" is fifteen
+                let n = if number == 0 && unit == 10 { 1 } else { number };
+                if n == 0 {
+                    return None;
+                }
+                section = section.checked_add(n * unit)?;
+            }
+            10_000 => {
+                let value = section.checked_add(number)?.checked_mul(10_000)?;
+                total = total.checked_add(value)?;
+                section = 0;
+            }
+            unit => {
+                total = total
+                    .checked_add(section)?
+                    .checked_add(number)?
+                    .checked_mul(unit)?;
+                section = 0;
+            }
+        }
+        number = 0;
+    }
+    total.checked_add(section)?.checked_add(number)
+}
@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+#[derive(Debug, Clone)]
+pub struct JitterBufferConfig {
+    pub sample_rate: u32,
+    /// Time audio is held back before release, in seconds
+    pub target_delay: f32,
+    /// Grow the delay when arrival times vary more than `target_delay` covers
+    pub adaptive: bool,
+    /// Upper bound of the adaptive delay, in seconds
+    pub max_delay: f32,
+}
+
+impl Default for JitterBufferConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 16000,
+            target_delay: 0.06,
+            adaptive: true,
+            max_delay: 0.5,
+        }
+    }
+}
+
+/// Reorders timestamped network audio and releases it at a steady pace.
+///
+/// Chunks are played out `delay` seconds after the time their timestamp says they
+/// were captured, measured from the first chunk's arrival. Chunks that arrive after
+/// their audio was released are dropped, audio already buffered from an overlapping
+/// chunk is kept over the new copy, and holes are filled with silence, so the
+/// recognizer gets one continuous stream in order. With `adaptive` set, the delay
+/// follows the RFC 3550 interarrival jitter estimate between `target_delay` and `max_delay`.
+pub struct JitterBuffer {
+    config: JitterBufferConfig,
+    /// Chunks waiting for release, by timestamp
+    chunks: BTreeMap<u64, Vec<f32>>,
+    /// Timestamp of the next sample to release
+    next: Option<u64>,
+    /// Arrival and timestamp of the first chunk, which anchor the playout clock
+    anchor: Option<(Instant, u64)>,
+    /// Arrival and timestamp of the previous chunk
+    last: Option<(Instant, u64)>,
+    /// Interarrival jitter in seconds
+    jitter: f32,
+    late: usize,
+}
+
+impl JitterBuffer {
+    pub fn new(config: JitterBufferConfig) -> Self {
+        Self {
+            config,
+            chunks: BTreeMap::new(),
+            next: None,
+            anchor: None,
+            last: None,
+            jitter: 0.0,
+            late: 0,
+        }
+    }
+
+    /// Current playout delay in seconds
+    pub fn delay(&self) -> f32 {
+        if self.config.adaptive {
+            // Three times the mean deviation covers nearly all arrivals
+            (3.0 * self.jitter)
+                .max(self.config.target_delay)
+                .min(self.config.max_delay)
+        } else {
+            self.config.target_delay
+        }
+    }
+
+    /// Seconds of interarrival jitter measured so far
+    pub fn jitter(&self) -> f32 {
+        self.jitter
+    }
+
+    /// Chunks dropped because they arrived after their audio was released
+    pub fn late_chunks(&self) -> usize {
+        self.late
+    }
+
+    /// Samples waiting for release
+    pub fn buffered(&self) -> usize {
+        self.chunks.values().map(Vec::len).sum()
+    }
+
+    /// Add a chunk that arrived at `arrival`. `timestamp` is the position of its first
+    /// sample in the sender's stream, in samples (e.g. an unwrapped RTP timestamp).
+    pub fn push(&mut self, timestamp: u64, samples: Vec<f32>, arrival: Instant) {
+        if samples.is_empty() {
+            return;
+        }
+        let sample_rate = self.config.sample_rate as f32;
+        self.anchor.get_or_insert((arrival, timestamp));
+        if let Some((last_arrival, last_timestamp)) = self.last {
+            // Difference between the arrival spacing and the capture spacing
+            let arrived = if arrival >= last_arrival {
+                (arrival - last_arrival).as_secs_f32()
+            } else {
+                -(last_arrival - arrival).as_secs_f32()
+            };
+            let captured = (timestamp as f64 - last_timestamp as f64) as f32 / sample_rate;
+            self.jitter += ((arrived - captured).abs() - self.jitter) / 16.0;
+        }
+        self.last = Some((arrival, timestamp));
+
+        let end = timestamp + samples.len() as u64;
+        match self.next {
+            Some(next) if end <= next => {
+                self.late += 1;
+                tracing::trace!("dropping late chunk at {}", timestamp);
+            }
+            // Partly released already: keep the rest
+            Some(next) if timestamp < next => {
+                self.insert(next, &samples[(next - timestamp) as usize..]);
+            }
+            _ => self.insert(timestamp, &samples),
+        }
+    }
+
+    /// Buffer the parts of a chunk that no buffered chunk covers yet
+    fn insert(&mut self, timestamp: u64, samples: &[f32]) {
+        let end = timestamp + samples.len() as u64;
+        let covered: Vec<(u64, u64)> = self
+            .chunks
+            .range(..end)
+            .map(|(&start, chunk)| (start, start + chunk.len() as u64))
+            .filter(|&(_, chunk_end)| chunk_end > timestamp)
+            .collect();
+        let mut from = timestamp;
+        for (start, chunk_end) in covered.into_iter().chain([(end, end)]) {
+            if start > from {
+                let range = (from - timestamp) as usize..(start.min(end) - timestamp) as usize;
+                self.chunks.insert(from, samples[range].to_vec());
+            }
+            from = from.max(chunk_end);
+        }
+    }
+
+    /// Release the audio due at `now`, in order, with silence for missing chunks
+    pub fn pop(&mut self, now: Instant) -> Vec<f32> {
+        let Some((anchor, anchor_timestamp)) = self.anchor else {
+            return Vec::new();
+        };
+        let elapsed = now.saturating_duration_since(anchor).as_secs_f32() - self.delay();
+        if elapsed < 0.0 {
+            return Vec::new();
+        }
+        let due = anchor_timestamp + (elapsed * self.config.sample_rate as f32) as u64;
+        self.release(due)
+    }
+
+    /// Release everything buffered, e.g. at the end of a call
+    pub fn flush(&mut self) -> Vec<f32> {
+        let Some((&last, samples)) = self.chunks.last_key_value() else {
+            return Vec::new();
+        };
+        let end = last + samples.len() as u64;
+        self.release(end)
+    }
+
+    /// Drop all audio and start a new stream
+    pub fn reset(&mut self) {
+        self.chunks.clear();
+        self.next = None;
+        self.anchor = None;
+        self.last = None;
+        self.jitter = 0.0;
+        self.late = 0;
+    }
+
+    /// Output up to timestamp `due`. Chunks starting before it are released whole.
+    fn release(&mut self, due: u64) -> Vec<f32> {
+        let mut output = Vec::new();
+        let mut next = match (self.next, self.chunks.first_key_value()) {
+            (Some(next), _) => next,
+            (None, Some((&first, _))) => first,
+            (None, None) => return output,
+        };
+        while next < due {
+            match self.chunks.first_key_value() {
+                Some((&timestamp, _)) if timestamp <= next => {
+                    let (_, samples) = self.chunks.pop_first().unwrap();
+                    // Overlaps an earlier chunk
+                    let skip = ((next - timestamp) as usize).min(samples.len());
+                    output.extend_from_slice(&samples[skip..]);
+                    next = next.max(timestamp + samples.len() as u64);
+                }
+                Some((&timestamp, _)) => {
+                    let gap = timestamp.min(due) - next;
+                    output.resize(output.len() + gap as usize, 0.0);
+                    next += gap;
+                }
+                None => {
+                    output.resize(output.len() + (due - next) as usize, 0.0);
+                    next = due;
+                }
+            }
+        }
+        self.next = Some(next);
+        output
+    }
+}
@@ -52,6 +52,16 @@ impl Default for KeywordSpotConfig {
     }
 }
 
+/// Keyword found in a stream
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeywordDetection {
+    pub keyword: String,
+    /// Start of the keyword in seconds, counted from the start of the stream
+    pub start: f32,
+    /// Time of the keyword's last token in seconds
+    pub end: f32,
+}
+
 pub struct KeywordSpot {
     spotter: *const sherpa_rs_sys::SherpaOnnxKeywordSpotter,
     stream: *const sherpa_rs_sys::SherpaOnnxOnlineStream,
@@ -91,7 +101,7 @@ impl KeywordSpot {
                     },
                     num_threads: config.num_threads.unwrap_or(1),
                     provider: provider.as_ptr(),
-                    debug: config.debug.into(),
+                    debug: crate::native_debug(config.debug),
                     tokens: tokens.as_ptr(),
 
                     paraformer: mem::zeroed::<_>(),
@@ -124,14 +134,8 @@ impl KeywordSpot {
         samples: Vec<f32>,
         sample_rate: u32,
     ) -> Result<Option<String>> {
-        // Create keyword spotting stream
+        self.feed(sample_rate, &samples);
         unsafe {
-            sherpa_rs_sys::SherpaOnnxOnlineStreamAcceptWaveform(
-                self.stream,
-                sample_rate as i32,
-                samples.as_ptr(),
-                samples.len() as i32,
-            );
             sherpa_rs_sys::SherpaOnnxOnlineStreamInputFinished(self.stream);
             while sherpa_rs_sys::SherpaOnnxIsKeywordStreamReady(self.spotter, self.stream) == 1 {
                 sherpa_rs_sys::SherpaOnnxDecodeKeywordStream(self.spotter, self.stream);
@@ -148,6 +152,65 @@ impl KeywordSpot {
             Ok(keyword)
         }
     }
+
+    /// Feed the next chunk of a continuous stream.
+    /// Returns the keyword when one is detected, after which spotting continues on the same stream.
+    /// When the chunk holds several keywords this is the last one, see `detect` for all of them.
+    pub fn accept_waveform(&mut self, sample_rate: u32, samples: &[f32]) -> Option<String> {
+        self.detect(sample_rate, samples)
+            .pop()
+            .map(|detection| detection.keyword)
+    }
+
+    /// Every keyword detected in the chunk with its time, in the order spoken
+    pub fn detect(&mut self, sample_rate: u32, samples: &[f32]) -> Vec<KeywordDetection> {
+        self.feed(sample_rate, samples);
+        unsafe {
+            let mut detections = Vec::new();
+            while sherpa_rs_sys::SherpaOnnxIsKeywordStreamReady(self.spotter, self.stream) == 1 {
+                sherpa_rs_sys::SherpaOnnxDecodeKeywordStream(self.spotter, self.stream);
+                let result_ptr =
+                    sherpa_rs_sys::SherpaOnnxGetKeywordResult(self.spotter, self.stream);
+                if result_ptr.is_null() {
+                    continue;
+                }
+                let result = &*result_ptr;
+                let keyword = cstr_to_string(result.keyword as _);
+                // Token times are relative to the start of the segment
+                let timestamps = if result.timestamps.is_null() || result.count <= 0 {
+                    &[][..]
+                } else {
+                    std::slice::from_raw_parts(result.timestamps, result.count as usize)
+                };
+                let start = result.start_time + timestamps.first().copied().unwrap_or(0.0);
+                let end = result.start_time + timestamps.last().copied().unwrap_or(0.0);
+                sherpa_rs_sys::SherpaOnnxDestroyKeywordResult(result_ptr);
+                if !keyword.is_empty() {
+                    // Must reset right after a detection
+                    sherpa_rs_sys::SherpaOnnxResetKeywordStream(self.spotter, self.stream);
+                    detections.push(KeywordDetection {
+                        keyword,
+                        start,
+                        end,
+                    });
+                }
+            }
+            detections
+        }
+    }
+
+    fn feed(&mut self, sample_rate: u32, samples: &[f32]) {
+        for chunk in crate::utils::native_chunks(samples) {
+            unsafe {
+                sherpa_rs_sys::SherpaOnnxOnlineStreamAcceptWaveform(
+                    self.stream,
+                    sample_rate as i32,
+                    chunk.as_ptr(),
+                    chunk.len() as i32,
+                );
+            }
+        }
+    }
 }
 
 unsafe impl Send for KeywordSpot {}
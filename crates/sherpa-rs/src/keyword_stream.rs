@@ -0,0 +1,115 @@
+use crate::keyword_spot::{KeywordDetection, KeywordSpot};
+use crate::shutdown::{self, Stopper};
+use futures_channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures_core::Stream;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Keyword detections as an async `Stream`, for voice assistant runtimes that
+/// `select!` over detections and other events.
+///
+/// Spotting runs on a background thread fed from `audio`, mono chunks at the sample
+/// rate given to `start`. The stream ends when the sender side of `audio` is dropped.
+pub struct KeywordStream {
+    detections: UnboundedReceiver<KeywordDetection>,
+    stop: Arc<AtomicBool>,
+    /// Registered with `shutdown_all`
+    _stopper: Arc<Stopper>,
+    /// Yields `None` when `shutdown_all` destroyed the spotter
+    worker: Option<JoinHandle<Option<KeywordSpot>>>,
+}
+
+impl KeywordStream {
+    pub fn start(spotter: KeywordSpot, sample_rate: u32, audio: Receiver<Vec<f32>>) -> Self {
+        let (tx, detections) = mpsc::unbounded();
+        let stop = Arc::new(AtomicBool::new(false));
+        let release = Arc::new(AtomicBool::new(false));
+        let worker = {
+            let stop = stop.clone();
+            let release = release.clone();
+            shutdown::spawn_worker(move || {
+                let spotter = run(spotter, sample_rate, audio, tx, stop);
+                (!release.load(Ordering::SeqCst)).then_some(spotter)
+            })
+        };
+        let stopper: Arc<Stopper> = {
+            let stop = stop.clone();
+            Arc::new(move || {
+                release.store(true, Ordering::SeqCst);
+                stop.store(true, Ordering::Relaxed);
+            })
+        };
+        shutdown::on_shutdown(&stopper);
+
+        Self {
+            detections,
+            stop,
+            _stopper: stopper,
+            worker: Some(worker),
+        }
+    }
+
+    /// Stop spotting and return the spotter, or `None` if `shutdown_all` already
+    /// destroyed it.
+    ///
+    /// A panic of the spotting worker is resumed here. Dropping the stream logs it instead.
+    pub fn stop(mut self) -> Option<KeywordSpot> {
+        let worker = self.shutdown()?;
+        match worker.join() {
+            Ok(spotter) => spotter,
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    }
+
+    fn shutdown(&mut self) -> Option<JoinHandle<Option<KeywordSpot>>> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.worker.take()
+    }
+}
+
+impl Stream for KeywordStream {
+    type Item = KeywordDetection;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.detections).poll_next(cx)
+    }
+}
+
+impl Drop for KeywordStream {
+    /// Doesn't wait for the worker, which stops at its next chunk or within 100ms
+    fn drop(&mut self) {
+        let Some(worker) = self.shutdown() else {
+            return;
+        };
+        if worker.is_finished() {
+            shutdown::join_logged(worker, "keyword stream worker");
+        }
+    }
+}
+
+fn run(
+    mut spotter: KeywordSpot,
+    sample_rate: u32,
+    audio: Receiver<Vec<f32>>,
+    detections: UnboundedSender<KeywordDetection>,
+    stop: Arc<AtomicBool>,
+) -> KeywordSpot {
+    while !stop.load(Ordering::Relaxed) {
+        let chunk = match audio.recv_timeout(Duration::from_millis(100)) {
+            Ok(chunk) => chunk,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        for detection in spotter.detect(sample_rate, &chunk) {
+            if detections.unbounded_send(detection).is_err() {
+                return spotter;
+            }
+        }
+    }
+    spotter
+}
@@ -2,7 +2,9 @@ use crate::{
     get_default_provider,
     utils::{cstr_to_string, cstring_from_str},
 };
+use crate::{OfflineRecognizerResult, Transcriber};
 use eyre::{bail, Result};
+use std::collections::HashMap;
 
 #[derive(Debug)]
 pub struct SpokenLanguageId {
@@ -20,7 +22,7 @@ pub struct SpokenLanguageIdConfig {
 
 impl SpokenLanguageId {
     pub fn new(config: SpokenLanguageIdConfig) -> Self {
-        let debug = config.debug.into();
+        let debug = crate::native_debug(config.debug);
 
         let decoder = cstring_from_str(&config.decoder);
         let encoder = cstring_from_str(&config.encoder);
@@ -43,7 +45,10 @@ impl SpokenLanguageId {
         Self { slid }
     }
 
+    /// Language code of the clip, e.g. `en` or `zh`. A few seconds of speech are enough.
+    /// Fails with `TooManySamples` for buffers longer than a native call takes.
     pub fn compute(&mut self, samples: Vec<f32>, sample_rate: u32) -> Result<String> {
+        let len = crate::utils::native_len(samples.len())?;
         unsafe {
             let stream =
                 sherpa_rs_sys::SherpaOnnxSpokenLanguageIdentificationCreateOfflineStream(self.slid);
@@ -51,20 +56,23 @@ impl SpokenLanguageId {
                 stream,
                 sample_rate as i32,
                 samples.as_ptr(),
-                samples.len().try_into().unwrap(),
+                len,
             );
             let language_result_ptr =
                 sherpa_rs_sys::SherpaOnnxSpokenLanguageIdentificationCompute(self.slid, stream);
-            if language_result_ptr.is_null() || (*language_result_ptr).lang.is_null() {
+            sherpa_rs_sys::SherpaOnnxDestroyOfflineStream(stream);
+            if language_result_ptr.is_null() {
                 bail!("language ptr is null");
             }
             let language_ptr = (*language_result_ptr).lang;
-            let language = cstr_to_string(language_ptr as _);
+            let language = (!language_ptr.is_null()).then(|| cstr_to_string(language_ptr as _));
             // Free
             sherpa_rs_sys::SherpaOnnxDestroySpokenLanguageIdentificationResult(language_result_ptr);
-            sherpa_rs_sys::SherpaOnnxDestroyOfflineStream(stream);
 
-            Ok(language)
+            match language {
+                Some(language) => Ok(language),
+                None => bail!("language ptr is null"),
+            }
         }
     }
 }
@@ -79,3 +87,77 @@ impl Drop for SpokenLanguageId {
         }
     }
 }
+
+/// Runs language identification on the start of each utterance and hands the audio
+/// to the recognizer registered for that language.
+///
+/// Implements `Transcriber`, so it can be used as the recognizer of a `Pipeline` with a VAD
+/// to route each utterance separately.
+pub struct LanguageRouter {
+    lid: SpokenLanguageId,
+    recognizers: HashMap<String, Box<dyn Transcriber + Send>>,
+    fallback: Option<Box<dyn Transcriber + Send>>,
+    detect_seconds: f32,
+}
+
+impl LanguageRouter {
+    pub fn new(lid: SpokenLanguageId) -> Self {
+        Self {
+            lid,
+            recognizers: HashMap::new(),
+            fallback: None,
+            detect_seconds: 3.0,
+        }
+    }
+
+    /// Use `recognizer` for audio detected as `lang` (e.g. "en", "de")
+    pub fn route<T: Transcriber + Send + 'static>(mut self, lang: &str, recognizer: T) -> Self {
+        self.recognizers
+            .insert(lang.to_string(), Box::new(recognizer));
+        self
+    }
+
+    /// Recognizer for languages without a route
+    pub fn fallback<T: Transcriber + Send + 'static>(mut self, recognizer: T) -> Self {
+        self.fallback = Some(Box::new(recognizer));
+        self
+    }
+
+    /// How much audio from the start of each utterance is used for detection. Defaults to 3 seconds.
+    pub fn detect_seconds(mut self, seconds: f32) -> Self {
+        self.detect_seconds = seconds;
+        self
+    }
+
+    pub fn detect(&mut self, sample_rate: u32, samples: &[f32]) -> Result<String> {
+        let len = ((self.detect_seconds * sample_rate as f32) as usize).min(samples.len());
+        self.lid.compute(samples[..len].to_vec(), sample_rate)
+    }
+
+    /// Detect the language and transcribe with the matching recognizer.
+    /// The detected language is set as the result `lang`.
+    pub fn transcribe_routed(
+        &mut self,
+        sample_rate: u32,
+        samples: &[f32],
+    ) -> Result<OfflineRecognizerResult> {
+        let lang = self.detect(sample_rate, samples)?;
+        let Some(recognizer) = self.recognizers.get_mut(&lang).or(self.fallback.as_mut()) else {
+            bail!("No recognizer for language {}", lang);
+        };
+        let mut result = recognizer.transcribe(sample_rate, samples);
+        result.lang = lang;
+        Ok(result)
+    }
+}
+
+impl Transcriber for LanguageRouter {
+    /// Routing failures are logged and yield an empty result
+    fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> OfflineRecognizerResult {
+        self.transcribe_routed(sample_rate, samples)
+            .unwrap_or_else(|error| {
+                tracing::warn!("language routing failed: {}", error);
+                OfflineRecognizerResult::default()
+            })
+    }
+}
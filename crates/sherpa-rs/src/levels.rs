@@ -0,0 +1,117 @@
+/// Samples at or above this magnitude count as clipped
+const CLIP_LEVEL: f32 = 0.999;
+/// Share of clipped samples above which the input counts as heavily clipped
+const MAX_CLIPPED: f32 = 0.01;
+
+/// Level statistics of a buffer of samples
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LevelReport {
+    /// Largest absolute sample
+    pub peak: f32,
+    pub rms: f32,
+    /// Share of samples at full scale, from 0 to 1
+    pub clipped: f32,
+    pub len: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum LevelIssue {
+    /// Usually i16 samples converted to f32 without dividing by 32768
+    #[error("samples reach {peak}, outside [-1, 1]; were they scaled from integers?")]
+    OutOfRange { peak: f32 },
+
+    #[error("all samples are zero")]
+    Silent,
+
+    #[error("{:.1}% of samples are clipped", clipped * 100.0)]
+    Clipped { clipped: f32 },
+}
+
+impl LevelReport {
+    pub fn measure(samples: &[f32]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut peak = 0.0f32;
+        let mut energy = 0.0f64;
+        let mut clipped = 0;
+        for sample in samples {
+            let level = sample.abs();
+            peak = peak.max(level);
+            energy += f64::from(sample * sample);
+            if level >= CLIP_LEVEL {
+                clipped += 1;
+            }
+        }
+        Self {
+            peak,
+            rms: (energy / samples.len() as f64).sqrt() as f32,
+            clipped: clipped as f32 / samples.len() as f32,
+            len: samples.len(),
+        }
+    }
+
+    /// Problems that suggest the audio won't recognize well, most severe first.
+    /// An empty buffer has none.
+    pub fn issues(&self) -> Vec<LevelIssue> {
+        let mut issues = Vec::new();
+        if self.len == 0 {
+            return issues;
+        }
+        if self.peak > 1.0 {
+            issues.push(LevelIssue::OutOfRange { peak: self.peak });
+        }
+        if self.peak == 0.0 {
+            issues.push(LevelIssue::Silent);
+        }
+        if self.clipped > MAX_CLIPPED {
+            issues.push(LevelIssue::Clipped {
+                clipped: self.clipped,
+            });
+        }
+        issues
+    }
+}
+
+/// What to do about input level problems
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LevelCheck {
+    #[default]
+    Off,
+    /// Log each issue as a warning
+    Warn,
+    /// Fail with the most severe issue
+    Error,
+}
+
+impl LevelCheck {
+    /// Check `samples` before recognition. Fails only in `Error` mode.
+    pub fn check(self, samples: &[f32]) -> Result<(), LevelIssue> {
+        self.check_issues(samples, false).map(|_| ())
+    }
+
+    /// Check one chunk of a stream, where silent chunks are expected.
+    /// Returns whether a warning was logged.
+    pub(crate) fn check_chunk(self, samples: &[f32]) -> Result<bool, LevelIssue> {
+        self.check_issues(samples, true)
+    }
+
+    fn check_issues(self, samples: &[f32], allow_silence: bool) -> Result<bool, LevelIssue> {
+        if self == LevelCheck::Off {
+            return Ok(false);
+        }
+        let mut warned = false;
+        let issues = LevelReport::measure(samples)
+            .issues()
+            .into_iter()
+            .filter(|issue| !(allow_silence && *issue == LevelIssue::Silent));
+        for issue in issues {
+            match self {
+                LevelCheck::Error => return Err(issue),
+                _ => tracing::warn!("input audio: {}", issue),
+            }
+            warned = true;
+        }
+        Ok(warned)
+    }
+}
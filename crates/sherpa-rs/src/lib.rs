@@ -1,18 +1,58 @@
+pub mod alignment;
 pub mod audio_tag;
+pub mod caption;
+pub mod chunked;
+pub mod config_dump;
+pub mod delta;
+pub mod denoise;
+pub mod deterministic;
 pub mod diarize;
 pub mod dolphin;
 pub mod embedding_manager;
+pub mod energy_vad;
+pub mod global;
+pub mod grammar;
+pub mod hypothesis;
+pub mod itn;
+pub mod jitter_buffer;
 pub mod keyword_spot;
 pub mod language_id;
+pub mod levels;
+pub mod locale;
+pub mod long_audio;
+pub mod meeting;
+pub mod memory;
 pub mod moonshine;
+pub mod nemo_ctc;
+pub mod offline_stream;
+pub mod online;
+mod online_config;
 pub mod paraformer;
+pub mod pipeline;
+pub mod post_process;
+pub mod preprocess;
 pub mod punctuate;
+pub mod realtime;
+pub mod recorder;
+pub mod registry;
+pub mod resample;
+pub mod retry;
+pub mod rtp;
 pub mod sense_voice;
+pub mod shutdown;
 pub mod silero_vad;
 pub mod speaker_id;
+pub mod streaming_diarize;
+pub mod subtitle;
+pub mod telephony;
+pub mod telespeech;
 pub mod ten_vad;
+pub mod timeline;
+pub mod tokens;
+pub mod transcript;
 pub mod transducer;
 pub mod transducer_online;
+pub mod wake_word;
 pub mod whisper;
 pub mod zipformer;
 pub mod zipformer_online;
@@ -22,11 +62,30 @@ pub mod utils;
 #[cfg(feature = "tts")]
 pub mod tts;
 
+#[cfg(feature = "capture")]
+pub mod capture;
+
+#[cfg(feature = "async")]
+pub mod keyword_stream;
+
+#[cfg(feature = "opus")]
+pub mod opus_input;
+
+#[cfg(feature = "playback")]
+pub mod playback;
+
+#[cfg(feature = "playback")]
+pub mod voice_agent;
+
 use std::ffi::CStr;
+use std::sync::atomic::{AtomicU8, Ordering};
 
 #[cfg(feature = "sys")]
 pub use sherpa_rs_sys;
 
+pub use config_dump::NativeConfig;
+pub use shutdown::shutdown_all;
+
 use eyre::{bail, Result};
 use utils::cstr_to_string;
 
@@ -85,24 +144,84 @@ pub fn write_audio_file(path: &str, samples: &[f32], sample_rate: u32) -> Result
     Ok(())
 }
 
+#[derive(Debug)]
 pub struct OnnxConfig {
     pub provider: String,
     pub debug: bool,
+    /// Threads of each ONNX Runtime session. sherpa-onnx uses this value for both
+    /// intra-op and inter-op threads; the two can't be set separately.
     pub num_threads: i32,
 }
 
-#[derive(Debug, Clone)]
+/// Verbosity of sherpa-onnx's native logging, applied to engines created afterwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NativeLogLevel {
+    /// Follow each config's `debug` flag
+    #[default]
+    PerConfig,
+    /// Errors only, even for configs with `debug` set. Silences the config and model
+    /// metadata printed while sessions are created.
+    Error,
+    /// Debug output for every engine
+    Debug,
+}
+
+static NATIVE_LOG_LEVEL: AtomicU8 = AtomicU8::new(0);
+
+/// Set the native log verbosity for the whole process.
+///
+/// sherpa-onnx only distinguishes debug output from errors, which are always printed.
+/// ONNX Runtime's own severity is fixed by sherpa-onnx and can't be changed here.
+pub fn set_native_log_level(level: NativeLogLevel) {
+    NATIVE_LOG_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn native_log_level() -> NativeLogLevel {
+    match NATIVE_LOG_LEVEL.load(Ordering::Relaxed) {
+        1 => NativeLogLevel::Error,
+        2 => NativeLogLevel::Debug,
+        _ => NativeLogLevel::PerConfig,
+    }
+}
+
+/// Native `debug` value for a config's flag under the process-wide log level
+pub(crate) fn native_debug(debug: bool) -> i32 {
+    match native_log_level() {
+        NativeLogLevel::PerConfig => debug.into(),
+        NativeLogLevel::Error => 0,
+        NativeLogLevel::Debug => 1,
+    }
+}
+
+/// Splits the available cores evenly between `sessions` models running in parallel,
+/// for use as their `num_threads`. Always at least 1.
+pub fn threads_per_session(sessions: usize) -> i32 {
+    let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+    (cores / sessions.max(1)).max(1) as i32
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct OfflineRecognizerResult {
+    /// Spoken language, when the model reports it
     pub lang: String,
     pub text: String,
     pub timestamps: Vec<f32>,
     pub tokens: Vec<String>,
+    /// Set when Whisper runs with task "translate": `text` is an English translation
+    /// and `lang` is the source language.
+    pub is_translation: bool,
+    /// Emotion tag, e.g. `<|HAPPY|>`, when the model reports it (SenseVoice)
+    pub emotion: String,
+    /// Audio event tag, e.g. `<|Speech|>`, when the model reports it (SenseVoice)
+    pub event: String,
 }
 
 impl OfflineRecognizerResult {
     fn new(result: &sherpa_rs_sys::SherpaOnnxOfflineRecognizerResult) -> Self {
         let lang = unsafe { cstr_to_string(result.lang) };
         let text = unsafe { cstr_to_string(result.text) };
+        let emotion = unsafe { cstr_to_string(result.emotion) };
+        let event = unsafe { cstr_to_string(result.event) };
         let count = result.count.try_into().unwrap();
         let timestamps = if result.timestamps.is_null() {
             Vec::new()
@@ -124,8 +243,215 @@ impl OfflineRecognizerResult {
             text,
             timestamps,
             tokens,
+            is_translation: false,
+            emotion,
+            event,
+        }
+    }
+
+    /// Word-level timings merged from `tokens` and `timestamps`.
+    /// Empty when the model doesn't report timestamps.
+    pub fn words(&self, style: alignment::TokenStyle) -> Vec<alignment::WordTiming> {
+        alignment::align_words(&self.tokens, &self.timestamps, style)
+    }
+
+    /// Start and end time of each token.
+    /// Empty when the model doesn't report timestamps.
+    pub fn token_timings(&self) -> Vec<alignment::TokenTiming> {
+        alignment::align_tokens(&self.tokens, &self.timestamps)
+    }
+}
+
+/// Full result of an online stream, as returned by `get_result_full`
+#[derive(Debug, Clone, Default)]
+pub struct OnlineRecognizerResult {
+    pub text: String,
+    pub tokens: Vec<String>,
+    /// Start time of each token in seconds, counted from the last reset
+    pub timestamps: Vec<f32>,
+    /// Log probability of each token. Only transducer models report them, and only with
+    /// `modified_beam_search`; empty otherwise.
+    pub ys_probs: Vec<f32>,
+    /// The result serialized by sherpa-onnx
+    pub json: String,
+    /// Tag of the stream the result came from, see `SafeOnlineStream::set_tag`
+    pub tag: Option<String>,
+}
+
+impl OnlineRecognizerResult {
+    /// Read the current result of `stream`, freeing the native copy
+    pub(crate) unsafe fn fetch(
+        recognizer: *const sherpa_rs_sys::SherpaOnnxOnlineRecognizer,
+        stream: *const sherpa_rs_sys::SherpaOnnxOnlineStream,
+    ) -> Self {
+        let result_ptr = sherpa_rs_sys::SherpaOnnxGetOnlineStreamResult(recognizer, stream);
+        if result_ptr.is_null() {
+            return Self::default();
+        }
+        let result = Self::new(&*result_ptr);
+        sherpa_rs_sys::SherpaOnnxDestroyOnlineRecognizerResult(result_ptr);
+        result
+    }
+
+    unsafe fn new(result: &sherpa_rs_sys::SherpaOnnxOnlineRecognizerResult) -> Self {
+        let count: usize = result.count.max(0).try_into().unwrap();
+        let tokens = if result.tokens_arr.is_null() {
+            Vec::new()
+        } else {
+            std::slice::from_raw_parts(result.tokens_arr, count)
+                .iter()
+                .map(|token| cstr_to_string(*token as _))
+                .collect()
+        };
+        let timestamps = if result.timestamps.is_null() {
+            Vec::new()
+        } else {
+            std::slice::from_raw_parts(result.timestamps, count).to_vec()
+        };
+        let json = cstr_to_string(result.json as _);
+        Self {
+            text: cstr_to_string(result.text as _),
+            ys_probs: Self::parse_ys_probs(&json, tokens.len()),
+            tokens,
+            timestamps,
+            json,
+            tag: None,
+        }
+    }
+
+    /// `ys_probs` of the JSON result, which the C struct doesn't carry. Empty unless
+    /// there is one per token.
+    fn parse_ys_probs(json: &str, count: usize) -> Vec<f32> {
+        let probs: Vec<f32> = serde_json::from_str::<serde_json::Value>(json)
+            .ok()
+            .and_then(|value| {
+                let probs = value.get("ys_probs")?.as_array()?;
+                probs.iter().map(|p| p.as_f64().map(|p| p as f32)).collect()
+            })
+            .unwrap_or_default();
+        if probs.len() == count {
+            probs
+        } else {
+            Vec::new()
         }
     }
+
+    /// Word-level timings merged from `tokens` and `timestamps`
+    pub fn words(&self, style: alignment::TokenStyle) -> Vec<alignment::WordTiming> {
+        alignment::align_words(&self.tokens, &self.timestamps, style)
+    }
+
+    /// Tokens with their probabilities, parsed from `json`
+    pub fn hypothesis(&self) -> Result<hypothesis::Hypothesis> {
+        hypothesis::Hypothesis::from_json(&self.json)
+    }
+}
+
+/// Per-call decoding overrides.
+///
+/// Only settings the C API allows per stream are here; blank penalty and decoding method
+/// are fixed when the recognizer is created.
+#[derive(Debug, Clone, Default)]
+pub struct DecodeOptions {
+    /// Hotwords for this call, one per line in the hotwords file format.
+    /// Replaces the recognizer's hotwords file and needs decoding method "modified_beam_search".
+    pub hotwords: Option<String>,
+}
+
+/// Common interface of the offline recognizers
+pub trait Transcriber {
+    fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> OfflineRecognizerResult;
+}
+
+/// Outcome of `poll_result` on an online recognizer
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodePoll {
+    /// A decode step ran and more frames are ready; poll again
+    Pending,
+    /// Decoding caught up with the audio; holds the current hypothesis
+    Ready(String),
+}
+
+/// Replayable state of an online stream's current utterance
+#[derive(Debug, Clone, Default)]
+pub struct OnlineStreamSnapshot {
+    pub sample_rate: u32,
+    /// Audio fed since the last reset
+    pub samples: Vec<f32>,
+    /// Hypothesis at the time of the snapshot
+    pub partial: String,
+}
+
+/// Usage counters of an online stream, e.g. for billing and monitoring
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StreamStats {
+    /// Samples fed, at whatever rate they came in
+    pub samples: u64,
+    /// Duration of the audio fed
+    pub audio_seconds: f64,
+    pub decode_calls: u64,
+    /// Wall time spent in decode calls. Batched calls are split evenly across their streams.
+    pub decode_time: std::time::Duration,
+    /// Endpoints detected, each counted once until the stream is reset
+    pub endpoints: u64,
+}
+
+/// Audio buffer longer than a native call takes in one go, for APIs that can't
+/// accept it in parts
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("{len} samples exceed the {max} a native call takes")]
+pub struct TooManySamples {
+    pub len: usize,
+    pub max: usize,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StreamingError {
+    #[error("Decoding failed with code: {0}")]
+    DecodingFailed(i32),
+
+    #[error("Stream not ready for processing")]
+    StreamNotReady,
+
+    /// E.g. audio fed after `input_finished`
+    #[error("Invalid stream state")]
+    InvalidState,
+
+    #[error("Model configuration error")]
+    ConfigError,
+}
+
+/// Common interface of the streaming recognizers, driving a single stream
+pub trait OnlineTranscriber {
+    /// Fails with `StreamingError::InvalidState` after `input_finished` until `reset`
+    fn accept_waveform(&mut self, sample_rate: u32, samples: &[f32]) -> Result<(), StreamingError>;
+    /// Decode all frames that are ready and return the current hypothesis
+    fn transcribe(&mut self) -> String;
+    fn is_endpoint(&self) -> bool;
+    fn reset(&mut self);
+    fn input_finished(&mut self);
+}
+
+/// Common interface of the preprocessing stages applied to audio before recognition,
+/// such as `preprocess::Agc` and `preprocess::HighPass`.
+/// Closures over the samples implement it too.
+pub trait AudioProcessor {
+    /// Process `samples` in place. Called with consecutive chunks of one stream,
+    /// so stages can keep state across calls.
+    fn process(&mut self, samples: &mut Vec<f32>);
+}
+
+/// Common interface of the voice activity detectors
+pub trait VoiceActivityDetector {
+    fn accept_waveform(&mut self, samples: Vec<f32>);
+    fn flush(&mut self);
+    /// Remove and return the oldest detected speech segment, if any
+    fn pop_segment(&mut self) -> Option<silero_vad::SpeechSegment>;
+    /// Whether speech is going on at the end of the audio fed so far.
+    /// Detectors that can't tell return false, which disables barge-in.
+    fn is_speech(&mut self) -> bool {
+        false
+    }
 }
 
 impl Default for OnnxConfig {
@@ -0,0 +1,163 @@
+use regex::{Captures, Regex};
+
+/// Numbers with fewer integer digits aren't grouped, so years stay as they are
+const MIN_GROUPED_DIGITS: usize = 5;
+
+/// How numbers, phone numbers and amounts are written in one locale.
+///
+/// Implement it for locales beyond the built-in `Locale`s and pass it to `LocaleFormatter`.
+pub trait LocaleRules: Send {
+    /// Write a number from its integer and fraction digits, e.g. "1234567" and "5"
+    fn number(&self, integer: &str, fraction: Option<&str>) -> String;
+
+    /// Write a run of digits as a phone number, or `None` when it doesn't read as one
+    fn phone(&self, digits: &str) -> Option<String> {
+        let _ = digits;
+        None
+    }
+
+    /// Write an amount with its currency symbol, e.g. "$", "€", "£", "¥" or "元".
+    /// `amount` is already formatted with `number`.
+    fn currency(&self, symbol: &str, amount: &str) -> String;
+}
+
+/// Built-in locale rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    /// 1,234,567.5, $5.50, (555) 123-4567
+    English,
+    /// 1.234.567,5, 5,50 €, 030 1234567
+    German,
+    /// 1234567.5, ¥5.50, 138 0013 8000
+    Chinese,
+}
+
+impl LocaleRules for Locale {
+    fn number(&self, integer: &str, fraction: Option<&str>) -> String {
+        let (group, decimal) = match self {
+            Locale::English => (Some(','), '.'),
+            Locale::German => (Some('.'), ','),
+            Locale::Chinese => (None, '.'),
+        };
+        let mut text = match group {
+            Some(group) if integer.len() >= MIN_GROUPED_DIGITS => group_digits(integer, group),
+            _ => integer.to_string(),
+        };
+        if let Some(fraction) = fraction {
+            text.push(decimal);
+            text.push_str(fraction);
+        }
+        text
+    }
+
+    fn phone(&self, digits: &str) -> Option<String> {
+        let d = |range: std::ops::Range<usize>| &digits[range];
+        match (self, digits.len()) {
+            (Locale::English, 7) => Some(format!("{}-{}", d(0..3), d(3..7))),
+            (Locale::English, 10) => Some(format!("({}) {}-{}", d(0..3), d(3..6), d(6..10))),
+            (Locale::English, 11) if digits.starts_with('1') => {
+                Some(format!("+1 ({}) {}-{}", d(1..4), d(4..7), d(7..11)))
+            }
+            // Mobile prefixes have four digits, most area codes three or more
+            (Locale::German, 6..=12) if digits.starts_with('0') => {
+                let split = if digits.starts_with("01") { 4 } else { 3 };
+                Some(format!("{} {}", d(0..split), d(split..digits.len())))
+            }
+            (Locale::Chinese, 11) if digits.starts_with('1') => {
+                Some(format!("{} {} {}", d(0..3), d(3..7), d(7..11)))
+            }
+            _ => None,
+        }
+    }
+
+    fn currency(&self, symbol: &str, amount: &str) -> String {
+        match (self, symbol) {
+            (_, "元") => format!("{}{}", amount, symbol),
+            (Locale::German, _) => format!("{} {}", amount, symbol),
+            _ => format!("{}{}", symbol, amount),
+        }
+    }
+}
+
+fn group_digits(integer: &str, separator: char) -> String {
+    let mut text = String::new();
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i).is_multiple_of(3) {
+            text.push(separator);
+        }
+        text.push(digit);
+    }
+    text
+}
+
+/// Formats numbers, phone numbers and currency amounts in transcripts for a locale.
+///
+/// Works on digits, so run it after inverse text normalization, e.g. turning
+/// "$1234.5" into "1.234,5 $" for `Locale::German`. Plain runs of digits are formatted
+/// as numbers, unless phone numbers are enabled with `phone_numbers`.
+pub struct LocaleFormatter {
+    rules: Box<dyn LocaleRules>,
+    amount: Regex,
+    phone_numbers: bool,
+}
+
+impl LocaleFormatter {
+    pub fn new<R: LocaleRules + 'static>(rules: R) -> Self {
+        Self {
+            rules: Box::new(rules),
+            amount: Regex::new(
+                r"(?P<symbol>[$€£¥])?(?P<integer>[0-9]+)(?:\.(?P<fraction>[0-9]+))?(?P<suffix>元)?",
+            )
+            .expect("amount pattern is valid"),
+            phone_numbers: false,
+        }
+    }
+
+    /// Format runs of digits the rules accept as phone numbers as such, e.g. "5551234"
+    /// as "555-1234" instead of "5,551,234". Off by default, as a transcript can't tell
+    /// a phone number from an amount of the same length; enable it for dictation of
+    /// contact details and the like.
+    pub fn phone_numbers(mut self, enabled: bool) -> Self {
+        self.phone_numbers = enabled;
+        self
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for caps in self.amount.captures_iter(text) {
+            let m = caps.get(0).unwrap();
+            // Part of a word or an already formatted number, e.g. "mp3" or "1,234"
+            let before = text[..m.start()].chars().next_back();
+            if before.is_some_and(|c| c.is_ascii_alphanumeric() || c == ',' || c == '.') {
+                continue;
+            }
+            out.push_str(&text[last..m.start()]);
+            out.push_str(&self.format(&caps));
+            last = m.end();
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+
+    /// Turn the formatter into a text hook for `PostProcessors` / `PostProcessed` / pipelines
+    pub fn into_post_processor(self) -> impl FnMut(String) -> String + Send + 'static {
+        move |text| self.apply(&text)
+    }
+
+    fn format(&self, caps: &Captures) -> String {
+        let integer = &caps["integer"];
+        let fraction = caps.name("fraction").map(|m| m.as_str());
+        let symbol = caps.name("symbol").or(caps.name("suffix"));
+        if let Some(symbol) = symbol {
+            let amount = self.rules.number(integer, fraction);
+            return self.rules.currency(symbol.as_str(), &amount);
+        }
+        if self.phone_numbers && fraction.is_none() {
+            if let Some(phone) = self.rules.phone(integer) {
+                return phone;
+            }
+        }
+        self.rules.number(integer, fraction)
+    }
+}
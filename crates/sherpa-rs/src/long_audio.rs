@@ -0,0 +1,141 @@
+use crate::{alignment::is_cjk, OfflineRecognizerResult, Transcriber};
+use std::ops::Range;
+
+#[derive(Debug, Clone)]
+pub struct LongAudioConfig {
+    /// Inputs up to this many seconds are decoded in one go; longer ones are split
+    /// into pieces of at most this length
+    pub max_duration: f32,
+    /// Seconds before each split point searched for the quietest spot to cut at
+    pub search_window: f32,
+    /// Length in seconds of the frames compared when searching for silence
+    pub frame_duration: f32,
+}
+
+impl Default for LongAudioConfig {
+    fn default() -> Self {
+        Self {
+            max_duration: 30.0,
+            search_window: 5.0,
+            frame_duration: 0.02,
+        }
+    }
+}
+
+/// Ranges of `samples` to decode separately, cut at the quietest frame within
+/// `search_window` before each `max_duration` limit. One range when the input is short enough.
+pub fn split_points(
+    samples: &[f32],
+    sample_rate: u32,
+    config: &LongAudioConfig,
+) -> Vec<Range<usize>> {
+    let seconds = |s: f32| (s.max(0.0) * sample_rate as f32) as usize;
+    let max_len = seconds(config.max_duration).max(1);
+    let window = seconds(config.search_window).min(max_len - 1);
+    let frame = seconds(config.frame_duration).max(1);
+
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    while samples.len() - start > max_len {
+        let limit = start + max_len;
+        let mut cut = limit;
+        let mut quietest = f32::INFINITY;
+        let mut frame_start = limit - window;
+        while frame_start + frame <= limit {
+            let energy: f32 = samples[frame_start..frame_start + frame]
+                .iter()
+                .map(|s| s * s)
+                .sum();
+            if energy < quietest {
+                quietest = energy;
+                cut = frame_start + frame / 2;
+            }
+            frame_start += frame;
+        }
+        ranges.push(start..cut);
+        start = cut;
+    }
+    ranges.push(start..samples.len());
+    ranges
+}
+
+/// Decode `samples` piece by piece when longer than `config.max_duration`, cut at
+/// silence, and join the results into one with timestamps relative to the whole input
+pub fn transcribe_long<R: Transcriber + ?Sized>(
+    recognizer: &mut R,
+    sample_rate: u32,
+    samples: &[f32],
+    config: &LongAudioConfig,
+) -> OfflineRecognizerResult {
+    let ranges = split_points(samples, sample_rate, config);
+    if ranges.len() == 1 {
+        return recognizer.transcribe(sample_rate, samples);
+    }
+
+    let mut joined = OfflineRecognizerResult::default();
+    for range in ranges {
+        let offset = range.start as f32 / sample_rate as f32;
+        let mut result = recognizer.transcribe(sample_rate, &samples[range]);
+        let text = result.text.trim();
+        if !text.is_empty() {
+            if needs_space(&joined.text, text) {
+                joined.text.push(' ');
+            }
+            joined.text.push_str(text);
+        }
+        joined.tokens.append(&mut result.tokens);
+        joined
+            .timestamps
+            .extend(result.timestamps.iter().map(|t| t + offset));
+        // Per-input tags come from the first piece that has them
+        for (to, from) in [
+            (&mut joined.lang, result.lang),
+            (&mut joined.emotion, result.emotion),
+            (&mut joined.event, result.event),
+        ] {
+            if to.is_empty() {
+                *to = from;
+            }
+        }
+        joined.is_translation |= result.is_translation;
+    }
+    joined
+}
+
+/// Scripts written without spaces, e.g. Chinese and Japanese, are joined as is
+fn needs_space(before: &str, after: &str) -> bool {
+    // CJK text, or CJK and fullwidth punctuation
+    let unspaced =
+        |c: char| is_cjk(c) || matches!(c, '\u{3000}'..='\u{303f}' | '\u{ff00}'..='\u{ffef}');
+    match (before.chars().last(), after.chars().next()) {
+        (Some(a), Some(b)) => !unspaced(a) && !unspaced(b),
+        _ => false,
+    }
+}
+
+/// Offline recognizer that splits inputs longer than `max_duration` at silence,
+/// so hours of audio never reach the native recognizer in one buffer
+pub struct LongAudio<R> {
+    inner: R,
+    config: LongAudioConfig,
+}
+
+impl<R> LongAudio<R> {
+    pub fn new(inner: R, config: LongAudioConfig) -> Self {
+        Self { inner, config }
+    }
+
+    pub fn inner(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Transcriber> Transcriber for LongAudio<R> {
+    fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> OfflineRecognizerResult {
+        transcribe_long(&mut self.inner, sample_rate, samples, &self.config)
+    }
+}
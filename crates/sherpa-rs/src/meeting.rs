@@ -0,0 +1,151 @@
+use crate::{
+    denoise::Denoiser,
+    diarize::Diarize,
+    long_audio::{split_points, transcribe_long, LongAudioConfig},
+    punctuate::Punctuation,
+    transcript::{Transcript, Turn},
+    Transcriber,
+};
+use eyre::Result;
+use std::path::Path;
+
+const SAMPLE_RATE: u32 = 16000;
+
+/// Transcribes a meeting recording into a `Transcript`: optional denoising, speaker
+/// diarization, offline recognition of each speaker turn and punctuation.
+///
+/// Takes a whole recording with `transcribe` / `transcribe_file`, or a live feed with
+/// `push` and `finish`. Diarization clusters speakers over the whole recording, so a
+/// live feed is transcribed when it ends. Export the result with `Transcript::to_json`
+/// or `Transcript::to_srt`.
+pub struct MeetingTranscriber {
+    recognizer: Box<dyn Transcriber + Send>,
+    denoiser: Option<Denoiser>,
+    diarize: Option<Diarize>,
+    punctuation: Option<Punctuation>,
+    long_audio: LongAudioConfig,
+    /// Audio pushed since the last `finish`
+    recording: Vec<f32>,
+}
+
+impl MeetingTranscriber {
+    /// Transcriber running `recognizer` alone. Without diarization the recording is
+    /// split at silence into turns of at most `LongAudioConfig::max_duration`.
+    pub fn new<T: Transcriber + Send + 'static>(recognizer: T) -> Self {
+        Self {
+            recognizer: Box::new(recognizer),
+            denoiser: None,
+            diarize: None,
+            punctuation: None,
+            long_audio: LongAudioConfig::default(),
+            recording: Vec::new(),
+        }
+    }
+
+    /// Denoise the recording first. The denoiser must take 16 kHz audio.
+    pub fn denoiser(mut self, denoiser: Denoiser) -> Self {
+        self.denoiser = Some(denoiser);
+        self
+    }
+
+    /// Split the recording into speaker turns and label them
+    pub fn diarize(mut self, diarize: Diarize) -> Self {
+        self.diarize = Some(diarize);
+        self
+    }
+
+    pub fn punctuation(mut self, punctuation: Punctuation) -> Self {
+        self.punctuation = Some(punctuation);
+        self
+    }
+
+    /// How turns longer than the recognizer handles well are split
+    pub fn long_audio(mut self, config: LongAudioConfig) -> Self {
+        self.long_audio = config;
+        self
+    }
+
+    /// Transcribe a WAV file. The audio is downmixed to mono and resampled to 16 kHz.
+    pub fn transcribe_file<P: AsRef<Path>>(&mut self, path: P) -> Result<Transcript> {
+        let samples = crate::subtitle::read_mono_16k(path.as_ref())?;
+        self.transcribe(&samples)
+    }
+
+    /// Transcribe 16 kHz mono samples
+    pub fn transcribe(&mut self, samples: &[f32]) -> Result<Transcript> {
+        let samples = match &mut self.denoiser {
+            Some(denoiser) => denoiser.denoise(samples, SAMPLE_RATE)?.samples,
+            None => samples.to_vec(),
+        };
+
+        // (start sample, end sample, speaker)
+        let spans: Vec<(usize, usize, Option<i32>)> = match &mut self.diarize {
+            Some(diarize) => diarize
+                .compute(samples.clone(), None)?
+                .into_iter()
+                .map(|s| {
+                    let start = (s.start * SAMPLE_RATE as f32) as usize;
+                    let end = (s.end * SAMPLE_RATE as f32) as usize;
+                    (start, end.min(samples.len()), Some(s.speaker))
+                })
+                .collect(),
+            None => split_points(&samples, SAMPLE_RATE, &self.long_audio)
+                .into_iter()
+                .map(|range| (range.start, range.end, None))
+                .collect(),
+        };
+
+        let mut transcript = Transcript::new();
+        for (start, end, speaker) in spans {
+            if start >= end {
+                continue;
+            }
+            let result = transcribe_long(
+                self.recognizer.as_mut(),
+                SAMPLE_RATE,
+                &samples[start..end],
+                &self.long_audio,
+            );
+            let mut turn = Turn {
+                speaker,
+                end: end as f32 / SAMPLE_RATE as f32,
+                ..Turn::from_result(&result, start as f32 / SAMPLE_RATE as f32)
+            };
+            if let Some(punctuation) = &mut self.punctuation {
+                if !turn.text.is_empty() {
+                    turn.text = punctuation.add_punctuation(&turn.text);
+                    // Take punctuation and casing from the text when the words still line up
+                    let punctuated: Vec<&str> = turn.text.split_whitespace().collect();
+                    if punctuated.len() == turn.words.len() {
+                        for (word, text) in turn.words.iter_mut().zip(punctuated) {
+                            word.text = text.to_string();
+                        }
+                    }
+                }
+            }
+            transcript.push(turn);
+        }
+        Ok(transcript)
+    }
+
+    /// Add the next chunk of a live feed, 16 kHz mono
+    pub fn push(&mut self, chunk: &[f32]) {
+        self.recording.extend_from_slice(chunk);
+    }
+
+    /// Seconds of live audio pushed since the last `finish`
+    pub fn recorded(&self) -> f32 {
+        self.recording.len() as f32 / SAMPLE_RATE as f32
+    }
+
+    /// Transcribe the live feed and start a new recording. On error the recording is
+    /// kept, so `finish` can be called again.
+    pub fn finish(&mut self) -> Result<Transcript> {
+        let recording = std::mem::take(&mut self.recording);
+        let transcript = self.transcribe(&recording);
+        if transcript.is_err() {
+            self.recording = recording;
+        }
+        transcript
+    }
+}
@@ -0,0 +1,54 @@
+use crate::config_dump::ConfigDump;
+use std::path::Path;
+
+/// Approximate native memory of a loaded engine, from the size of its model files.
+///
+/// ONNX Runtime doesn't report session arena sizes through the C API, so this can't
+/// measure them; use `estimated_bytes` as a starting point and measure your own models
+/// for precise planning.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryUsage {
+    /// Model files with their size in bytes
+    pub files: Vec<(String, u64)>,
+}
+
+impl MemoryUsage {
+    /// Sizes of the given files. Missing files are skipped and repeated ones counted once.
+    pub fn from_paths<I, P>(paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut files: Vec<(String, u64)> = Vec::new();
+        for path in paths {
+            let path = path.as_ref();
+            let Ok(metadata) = std::fs::metadata(path) else {
+                continue;
+            };
+            let name = path.display().to_string();
+            if metadata.is_file() && !files.iter().any(|(f, _)| *f == name) {
+                files.push((name, metadata.len()));
+            }
+        }
+        Self { files }
+    }
+
+    /// Total size of the model files. Weights are loaded into memory once per engine.
+    pub fn model_bytes(&self) -> u64 {
+        self.files.iter().map(|(_, size)| size).sum()
+    }
+
+    /// Rough resident size: the weights plus a quarter for ONNX Runtime arenas and
+    /// intermediate buffers. Grows with `num_threads` and the length of decoded audio.
+    pub fn estimated_bytes(&self) -> u64 {
+        self.model_bytes() + self.model_bytes() / 4
+    }
+}
+
+impl ConfigDump {
+    /// Memory of the files referenced by the config's path fields, e.g.
+    /// `recognizer.config_dump().memory_usage()`
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage::from_paths(self.paths())
+    }
+}
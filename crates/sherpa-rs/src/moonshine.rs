@@ -1,10 +1,16 @@
-use crate::{get_default_provider, utils::cstring_from_str};
+use crate::config_dump::{self, ConfigDump, NativeConfig};
+use crate::{
+    get_default_provider,
+    offline_stream::{self, OfflineStream},
+    utils::cstring_from_str,
+};
 use eyre::{bail, Result};
 use std::{mem, ptr::null};
 
 #[derive(Debug)]
 pub struct MoonshineRecognizer {
     recognizer: *const sherpa_rs_sys::SherpaOnnxOfflineRecognizer,
+    config_dump: ConfigDump,
 }
 
 pub type MoonshineRecognizerResult = super::OfflineRecognizerResult;
@@ -42,7 +48,7 @@ impl Default for MoonshineConfig {
 
 impl MoonshineRecognizer {
     pub fn new(config: MoonshineConfig) -> Result<Self> {
-        let debug = config.debug.into();
+        let debug = crate::native_debug(config.debug);
         let provider = config.provider.unwrap_or(get_default_provider());
 
         // Onnx
@@ -110,33 +116,37 @@ impl MoonshineRecognizer {
             }
         };
 
+        let config_dump = config_dump::offline_recognizer(&config);
         let recognizer = unsafe { sherpa_rs_sys::SherpaOnnxCreateOfflineRecognizer(&config) };
 
         if recognizer.is_null() {
             bail!("Failed to create recognizer");
         }
 
-        Ok(Self { recognizer })
+        Ok(Self {
+            recognizer,
+            config_dump,
+        })
+    }
+
+    pub fn create_stream(&self) -> Result<OfflineStream<'_>> {
+        unsafe { OfflineStream::new(self.recognizer) }
     }
 
     pub fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> MoonshineRecognizerResult {
-        unsafe {
-            let stream = sherpa_rs_sys::SherpaOnnxCreateOfflineStream(self.recognizer);
-            sherpa_rs_sys::SherpaOnnxAcceptWaveformOffline(
-                stream,
-                sample_rate as i32,
-                samples.as_ptr(),
-                samples.len().try_into().unwrap(),
-            );
-            sherpa_rs_sys::SherpaOnnxDecodeOfflineStream(self.recognizer, stream);
-            let result_ptr = sherpa_rs_sys::SherpaOnnxGetOfflineStreamResult(stream);
-            let raw_result = result_ptr.read();
-            let result = MoonshineRecognizerResult::new(&raw_result);
-            // Free
-            sherpa_rs_sys::SherpaOnnxDestroyOfflineRecognizerResult(result_ptr);
-            sherpa_rs_sys::SherpaOnnxDestroyOfflineStream(stream);
-            result
-        }
+        offline_stream::transcribe_once(self.create_stream(), sample_rate, samples)
+    }
+}
+
+impl NativeConfig for MoonshineRecognizer {
+    fn config_dump(&self) -> &ConfigDump {
+        &self.config_dump
+    }
+}
+
+impl crate::Transcriber for MoonshineRecognizer {
+    fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> crate::OfflineRecognizerResult {
+        MoonshineRecognizer::transcribe(self, sample_rate, samples)
     }
 }
 
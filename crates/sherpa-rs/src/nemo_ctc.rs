@@ -0,0 +1,151 @@
+use crate::config_dump::{self, ConfigDump, NativeConfig};
+use crate::{
+    get_default_provider,
+    offline_stream::{self, OfflineStream},
+    utils::cstring_from_str,
+};
+use eyre::{bail, Result};
+use std::mem;
+
+#[derive(Debug)]
+pub struct NemoCtcRecognizer {
+    recognizer: *const sherpa_rs_sys::SherpaOnnxOfflineRecognizer,
+    config_dump: ConfigDump,
+}
+
+pub type NemoCtcRecognizerResult = super::OfflineRecognizerResult;
+
+/// Offline NeMo EncDecCTC model, e.g. the Conformer/FastConformer CTC checkpoints
+/// exported for sherpa-onnx
+#[derive(Debug, Clone)]
+pub struct NemoCtcConfig {
+    pub model: String,
+    pub tokens: String,
+    pub decoding_method: String,
+    /// Number of mel bins the model was trained with: 80 for most models, 64 for some
+    /// older QuartzNet/Citrinet exports. Not read from the model, so set it by hand from
+    /// `features` in the model's NeMo config; a wrong value fails or garbles decoding.
+    pub feature_dim: i32,
+
+    pub provider: Option<String>,
+    pub num_threads: Option<i32>,
+    pub debug: bool,
+}
+
+impl Default for NemoCtcConfig {
+    fn default() -> Self {
+        Self {
+            model: String::new(),
+            tokens: String::new(),
+            decoding_method: String::from("greedy_search"),
+            feature_dim: 80,
+            debug: false,
+            provider: None,
+            num_threads: Some(1),
+        }
+    }
+}
+
+impl NemoCtcRecognizer {
+    pub fn new(config: NemoCtcConfig) -> Result<Self> {
+        let debug = crate::native_debug(config.debug);
+        let provider = config.provider.unwrap_or(get_default_provider());
+
+        let provider_ptr = cstring_from_str(&provider);
+        let num_threads = config.num_threads.unwrap_or(2);
+        let model_ptr = cstring_from_str(&config.model);
+        let tokens_ptr = cstring_from_str(&config.tokens);
+        let decoding_method_ptr = cstring_from_str(&config.decoding_method);
+
+        let model_config = unsafe {
+            sherpa_rs_sys::SherpaOnnxOfflineModelConfig {
+                debug,
+                num_threads,
+                provider: provider_ptr.as_ptr(),
+                nemo_ctc: sherpa_rs_sys::SherpaOnnxOfflineNemoEncDecCtcModelConfig {
+                    model: model_ptr.as_ptr(),
+                },
+                tokens: tokens_ptr.as_ptr(),
+
+                // Zeros
+                dolphin: mem::zeroed::<_>(),
+                paraformer: mem::zeroed::<_>(),
+                tdnn: mem::zeroed::<_>(),
+                telespeech_ctc: mem::zeroed::<_>(),
+                fire_red_asr: mem::zeroed::<_>(),
+                transducer: mem::zeroed::<_>(),
+                whisper: mem::zeroed::<_>(),
+                sense_voice: mem::zeroed::<_>(),
+                moonshine: mem::zeroed::<_>(),
+                bpe_vocab: mem::zeroed::<_>(),
+                model_type: mem::zeroed::<_>(),
+                modeling_unit: mem::zeroed::<_>(),
+                zipformer_ctc: mem::zeroed::<_>(),
+                canary: mem::zeroed::<_>(),
+                ..mem::zeroed::<_>()
+            }
+        };
+
+        let config = unsafe {
+            sherpa_rs_sys::SherpaOnnxOfflineRecognizerConfig {
+                decoding_method: decoding_method_ptr.as_ptr(),
+                model_config,
+                feat_config: sherpa_rs_sys::SherpaOnnxFeatureConfig {
+                    sample_rate: 16000,
+                    feature_dim: config.feature_dim,
+                },
+                hotwords_file: mem::zeroed::<_>(),
+                hotwords_score: mem::zeroed::<_>(),
+                lm_config: mem::zeroed::<_>(),
+                max_active_paths: mem::zeroed::<_>(),
+                rule_fars: mem::zeroed::<_>(),
+                rule_fsts: mem::zeroed::<_>(),
+                blank_penalty: mem::zeroed::<_>(),
+                hr: mem::zeroed::<_>(),
+            }
+        };
+
+        let config_dump = config_dump::offline_recognizer(&config);
+        let recognizer = unsafe { sherpa_rs_sys::SherpaOnnxCreateOfflineRecognizer(&config) };
+
+        if recognizer.is_null() {
+            bail!("Failed to create recognizer");
+        }
+
+        Ok(Self {
+            recognizer,
+            config_dump,
+        })
+    }
+
+    pub fn create_stream(&self) -> Result<OfflineStream<'_>> {
+        unsafe { OfflineStream::new(self.recognizer) }
+    }
+
+    pub fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> NemoCtcRecognizerResult {
+        offline_stream::transcribe_once(self.create_stream(), sample_rate, samples)
+    }
+}
+
+impl NativeConfig for NemoCtcRecognizer {
+    fn config_dump(&self) -> &ConfigDump {
+        &self.config_dump
+    }
+}
+
+impl crate::Transcriber for NemoCtcRecognizer {
+    fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> crate::OfflineRecognizerResult {
+        NemoCtcRecognizer::transcribe(self, sample_rate, samples)
+    }
+}
+
+unsafe impl Send for NemoCtcRecognizer {}
+unsafe impl Sync for NemoCtcRecognizer {}
+
+impl Drop for NemoCtcRecognizer {
+    fn drop(&mut self) {
+        unsafe {
+            sherpa_rs_sys::SherpaOnnxDestroyOfflineRecognizer(self.recognizer);
+        }
+    }
+}
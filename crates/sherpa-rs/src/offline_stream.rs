@@ -0,0 +1,228 @@
+use crate::{
+    hypothesis::Hypothesis,
+    utils::{cstr_to_string, cstring_from_str, native_len},
+    OfflineRecognizerResult, TooManySamples,
+};
+use eyre::{bail, Result};
+use std::ffi::CString;
+use std::marker::PhantomData;
+
+/// Stream of an offline recognizer.
+/// Audio can be fed in several chunks as it arrives and is decoded in one go.
+///
+/// A native offline stream takes its whole input in one call and is decoded once, so
+/// chunks are collected here and handed over by `decode`. Decoding again after more
+/// audio recreates the native stream with everything accepted so far, which is kept
+/// until the stream is dropped.
+#[derive(Debug)]
+pub struct OfflineStream<'a> {
+    recognizer: *const sherpa_rs_sys::SherpaOnnxOfflineRecognizer,
+    stream: *const sherpa_rs_sys::SherpaOnnxOfflineStream,
+    /// Given again when the native stream is recreated
+    hotwords: Option<CString>,
+    /// All audio accepted so far
+    samples: Vec<f32>,
+    /// Rate of the first chunk, which later chunks must match
+    sample_rate: Option<u32>,
+    /// The native stream was decoded and can't take more audio
+    decoded: bool,
+    _recognizer: PhantomData<&'a ()>,
+}
+
+/// Chunk an `OfflineStream` can't take
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum AcceptError {
+    #[error(transparent)]
+    TooManySamples(#[from] TooManySamples),
+
+    #[error("chunk at {got} Hz after audio at {expected} Hz")]
+    SampleRateChanged { expected: u32, got: u32 },
+}
+
+impl OfflineStream<'_> {
+    /// The recognizer must outlive the stream
+    pub(crate) unsafe fn new(
+        recognizer: *const sherpa_rs_sys::SherpaOnnxOfflineRecognizer,
+    ) -> Result<Self> {
+        Self::create(recognizer, None)
+    }
+
+    /// Stream with its own hotwords, one per line. The recognizer must outlive the stream.
+    pub(crate) unsafe fn with_hotwords(
+        recognizer: *const sherpa_rs_sys::SherpaOnnxOfflineRecognizer,
+        hotwords: &str,
+    ) -> Result<Self> {
+        Self::create(recognizer, Some(cstring_from_str(hotwords)))
+    }
+
+    unsafe fn create(
+        recognizer: *const sherpa_rs_sys::SherpaOnnxOfflineRecognizer,
+        hotwords: Option<CString>,
+    ) -> Result<Self> {
+        let stream = create_native(recognizer, hotwords.as_ref());
+        if stream.is_null() {
+            bail!("Failed to create offline stream");
+        }
+        Ok(Self {
+            recognizer,
+            stream,
+            hotwords,
+            samples: Vec::new(),
+            sample_rate: None,
+            decoded: false,
+            _recognizer: PhantomData,
+        })
+    }
+
+    /// Append samples to the stream. Can be called any number of times, before and
+    /// after `decode`, all at the same sample rate. Fails once the audio adds up to more
+    /// than `MAX_NATIVE_SAMPLES`, as the native stream takes it in one call.
+    pub fn accept_waveform(
+        &mut self,
+        sample_rate: u32,
+        samples: &[f32],
+    ) -> Result<(), AcceptError> {
+        let expected = *self.sample_rate.get_or_insert(sample_rate);
+        if sample_rate != expected {
+            return Err(AcceptError::SampleRateChanged {
+                expected,
+                got: sample_rate,
+            });
+        }
+        native_len(self.samples.len() + samples.len())?;
+        self.samples.extend_from_slice(samples);
+        Ok(())
+    }
+
+    /// Decode all audio accepted so far.
+    /// More audio can be accepted afterwards and the stream decoded again.
+    pub fn decode(&mut self) -> OfflineRecognizerResult {
+        if let Err(error) = self.prepare() {
+            tracing::warn!("offline decoding failed: {}", error);
+            return OfflineRecognizerResult::default();
+        }
+        unsafe {
+            sherpa_rs_sys::SherpaOnnxDecodeOfflineStream(self.recognizer, self.stream);
+        }
+        self.result()
+    }
+
+    /// Hand the collected audio to a native stream that wasn't decoded yet, in one call
+    fn prepare(&mut self) -> Result<()> {
+        if self.decoded {
+            let stream = unsafe { create_native(self.recognizer, self.hotwords.as_ref()) };
+            if stream.is_null() {
+                bail!("Failed to recreate offline stream");
+            }
+            unsafe { sherpa_rs_sys::SherpaOnnxDestroyOfflineStream(self.stream) };
+            self.stream = stream;
+        }
+        self.decoded = true;
+        let Some(sample_rate) = self.sample_rate else {
+            return Ok(());
+        };
+        // Checked by `accept_waveform`
+        let len = native_len(self.samples.len())?;
+        unsafe {
+            sherpa_rs_sys::SherpaOnnxAcceptWaveformOffline(
+                self.stream,
+                sample_rate as i32,
+                self.samples.as_ptr(),
+                len,
+            );
+        }
+        Ok(())
+    }
+
+    /// Result of the last decode, without decoding again
+    pub fn result(&self) -> OfflineRecognizerResult {
+        unsafe {
+            let result_ptr = sherpa_rs_sys::SherpaOnnxGetOfflineStreamResult(self.stream);
+            if result_ptr.is_null() {
+                return OfflineRecognizerResult::default();
+            }
+            let raw_result = result_ptr.read();
+            let result = OfflineRecognizerResult::new(&raw_result);
+            // Free
+            sherpa_rs_sys::SherpaOnnxDestroyOfflineRecognizerResult(result_ptr);
+            result
+        }
+    }
+
+    /// Result of the last decode with per-token probabilities, when the model reports them
+    pub fn hypothesis(&self) -> Result<Hypothesis> {
+        let json = unsafe {
+            let json_ptr = sherpa_rs_sys::SherpaOnnxGetOfflineStreamResultAsJson(self.stream);
+            let json = cstr_to_string(json_ptr as _);
+            sherpa_rs_sys::SherpaOnnxDestroyOfflineStreamResultJson(json_ptr);
+            json
+        };
+        Hypothesis::from_json(&json)
+    }
+
+    /// Decode several streams of the same recognizer in one batch.
+    /// Results are in the same order as `streams`.
+    pub fn decode_batch(streams: &mut [OfflineStream<'_>]) -> Result<Vec<OfflineRecognizerResult>> {
+        let Some(first) = streams.first() else {
+            return Ok(Vec::new());
+        };
+        let recognizer = first.recognizer;
+        if streams.iter().any(|s| s.recognizer != recognizer) {
+            bail!("All streams must belong to the same recognizer");
+        }
+        for stream in streams.iter_mut() {
+            stream.prepare()?;
+        }
+        let mut ptrs: Vec<_> = streams.iter().map(|s| s.stream).collect();
+        unsafe {
+            sherpa_rs_sys::SherpaOnnxDecodeMultipleOfflineStreams(
+                recognizer,
+                ptrs.as_mut_ptr(),
+                ptrs.len().try_into().unwrap(),
+            );
+        }
+        Ok(streams.iter().map(|s| s.result()).collect())
+    }
+}
+
+unsafe fn create_native(
+    recognizer: *const sherpa_rs_sys::SherpaOnnxOfflineRecognizer,
+    hotwords: Option<&CString>,
+) -> *const sherpa_rs_sys::SherpaOnnxOfflineStream {
+    match hotwords {
+        Some(hotwords) => {
+            sherpa_rs_sys::SherpaOnnxCreateOfflineStreamWithHotwords(recognizer, hotwords.as_ptr())
+        }
+        None => sherpa_rs_sys::SherpaOnnxCreateOfflineStream(recognizer),
+    }
+}
+
+unsafe impl Send for OfflineStream<'_> {}
+
+impl Drop for OfflineStream<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            sherpa_rs_sys::SherpaOnnxDestroyOfflineStream(self.stream);
+        }
+    }
+}
+
+/// Decode `samples` in a stream of their own. Failures are logged and give an empty
+/// result, for the recognizers' infallible `transcribe`.
+pub(crate) fn transcribe_once(
+    stream: Result<OfflineStream<'_>>,
+    sample_rate: u32,
+    samples: &[f32],
+) -> OfflineRecognizerResult {
+    let decoded = stream.and_then(|mut stream| {
+        stream.accept_waveform(sample_rate, samples)?;
+        Ok(stream.decode())
+    });
+    match decoded {
+        Ok(result) => result,
+        Err(error) => {
+            tracing::warn!("offline decoding failed: {}", error);
+            OfflineRecognizerResult::default()
+        }
+    }
+}
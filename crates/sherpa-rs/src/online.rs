@@ -0,0 +1,813 @@
+use crate::config_dump::{ConfigDump, NativeConfig};
+use crate::online_config::OnlineRecognizerConfigBuilder;
+use crate::utils::{cstr_to_string, native_chunks};
+use crate::{
+    DecodePoll, OnlineRecognizerResult, OnlineStreamSnapshot, StreamStats, StreamingError,
+};
+use eyre::{bail, Result};
+use std::ops::Deref;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+
+type FinalResultSink = Arc<dyn Fn(OnlineRecognizerResult) + Send + Sync>;
+
+/// Streaming model family and the paths of its files
+#[derive(Debug, Clone, PartialEq)]
+pub enum OnlineModel {
+    /// Zipformer, conformer or LSTM transducer
+    Transducer {
+        encoder: String,
+        decoder: String,
+        joiner: String,
+    },
+    Paraformer {
+        encoder: String,
+        decoder: String,
+    },
+    Zipformer2Ctc {
+        model: String,
+    },
+    NemoCtc {
+        model: String,
+    },
+}
+
+impl Default for OnlineModel {
+    fn default() -> Self {
+        OnlineModel::Transducer {
+            encoder: String::new(),
+            decoder: String::new(),
+            joiner: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OnlineRecognizerConfig {
+    pub model: OnlineModel,
+    pub tokens: String,
+    pub num_threads: i32,
+    pub sample_rate: i32,
+    pub feature_dim: i32,
+    pub decoding_method: String,
+    pub hotwords_file: String,
+    pub hotwords_score: f32,
+    pub modeling_unit: String,
+    pub bpe_vocab: String,
+    pub blank_penalty: f32,
+    /// Transducer architecture, e.g. "zipformer2". Empty reads it from the model metadata.
+    pub model_type: String,
+    pub debug: bool,
+    pub provider: Option<String>,
+    pub enable_endpoint: bool,
+    pub rule1_min_trailing_silence: f32,
+    pub rule2_min_trailing_silence: f32,
+    pub rule3_min_utterance_length: f32,
+    pub max_active_paths: i32,
+}
+
+impl Default for OnlineRecognizerConfig {
+    fn default() -> Self {
+        Self {
+            model: OnlineModel::default(),
+            tokens: String::new(),
+            num_threads: 1,
+            sample_rate: 16000,
+            feature_dim: 80,
+            decoding_method: String::from("greedy_search"),
+            hotwords_file: String::new(),
+            hotwords_score: 1.0,
+            modeling_unit: String::new(),
+            bpe_vocab: String::new(),
+            blank_penalty: 0.0,
+            model_type: String::new(),
+            debug: false,
+            provider: None,
+            enable_endpoint: true,
+            rule1_min_trailing_silence: 2.4,
+            rule2_min_trailing_silence: 1.2,
+            rule3_min_utterance_length: 20.0,
+            max_active_paths: 4,
+        }
+    }
+}
+
+impl OnlineRecognizerConfig {
+    /// Defaults for 8 kHz telephony models, see `telephony::TELEPHONY_SAMPLE_RATE`
+    pub fn telephony() -> Self {
+        Self {
+            sample_rate: crate::telephony::TELEPHONY_SAMPLE_RATE as i32,
+            ..Default::default()
+        }
+    }
+
+    /// Config for a streaming Paraformer, e.g. the bilingual Chinese-English models
+    pub fn paraformer<S: Into<String>>(encoder: S, decoder: S, tokens: S) -> Self {
+        Self {
+            model: OnlineModel::Paraformer {
+                encoder: encoder.into(),
+                decoder: decoder.into(),
+            },
+            tokens: tokens.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Config for a streaming Zipformer2 CTC model, distributed as a single `model.onnx`
+    pub fn zipformer2_ctc<S: Into<String>>(model: S, tokens: S) -> Self {
+        Self {
+            model: OnlineModel::Zipformer2Ctc {
+                model: model.into(),
+            },
+            tokens: tokens.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Native recognizer, destroyed once the `OnlineRecognizer` and all its streams are gone
+pub(crate) struct NativeRecognizer {
+    ptr: *const sherpa_rs_sys::SherpaOnnxOnlineRecognizer,
+    /// Receives the final result of streams finalized on drop
+    final_sink: Mutex<Option<FinalResultSink>>,
+}
+
+impl NativeRecognizer {
+    fn final_sink(&self) -> Option<FinalResultSink> {
+        self.final_sink
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn set_final_sink(&self, sink: Option<FinalResultSink>) {
+        *self
+            .final_sink
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = sink;
+    }
+}
+
+impl std::fmt::Debug for NativeRecognizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NativeRecognizer")
+            .field("ptr", &self.ptr)
+            .finish_non_exhaustive()
+    }
+}
+
+unsafe impl Send for NativeRecognizer {}
+unsafe impl Sync for NativeRecognizer {}
+
+impl Drop for NativeRecognizer {
+    fn drop(&mut self) {
+        unsafe { sherpa_rs_sys::SherpaOnnxDestroyOnlineRecognizer(self.ptr) };
+    }
+}
+
+/// Audio stream of an `OnlineRecognizer`, for decoding several sessions with one model.
+/// Created by `OnlineRecognizer::create_stream` and freed when dropped, finalizing it
+/// first when `finalize_on_drop` is enabled. It keeps the native recognizer alive and
+/// can only be decoded by the recognizer that created it.
+/// Prefer `OnlineStream`, which can't outlive its recognizer.
+#[derive(Debug)]
+pub struct SafeOnlineStream {
+    /// Null when the native stream couldn't be created; such a stream is finished and empty
+    pub(crate) ptr: *const sherpa_rs_sys::SherpaOnnxOnlineStream,
+    pub(crate) owner: Arc<NativeRecognizer>,
+    /// Set by `input_finished`; the stream takes no more audio afterwards
+    pub(crate) finished: AtomicBool,
+    pub(crate) tag: Option<String>,
+    pub(crate) stats: Mutex<StreamStats>,
+    /// Whether the endpoint was already counted, until the next reset
+    pub(crate) at_endpoint: AtomicBool,
+}
+
+impl SafeOnlineStream {
+    pub(crate) fn new(
+        ptr: *const sherpa_rs_sys::SherpaOnnxOnlineStream,
+        owner: Arc<NativeRecognizer>,
+    ) -> Self {
+        Self {
+            ptr,
+            owner,
+            finished: AtomicBool::new(ptr.is_null()),
+            tag: None,
+            stats: Mutex::default(),
+            at_endpoint: AtomicBool::new(false),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::SeqCst)
+    }
+
+    /// Usage of the stream since it was created or `reset_stats` was called
+    pub fn stats(&self) -> StreamStats {
+        *self.lock_stats()
+    }
+
+    pub fn reset_stats(&self) {
+        *self.lock_stats() = StreamStats::default();
+    }
+
+    fn lock_stats(&self) -> std::sync::MutexGuard<'_, StreamStats> {
+        self.stats.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn update_stats(&self, update: impl FnOnce(&mut StreamStats)) {
+        update(&mut self.lock_stats());
+    }
+
+    /// Caller data, e.g. a connection id, copied into every `get_result_full` result
+    pub fn tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+
+    pub fn set_tag<S: Into<String>>(&mut self, tag: S) {
+        self.tag = Some(tag.into());
+    }
+
+    /// Feed audio to the stream.
+    /// Fails with `StreamingError::InvalidState` once `input_finished` was called.
+    pub fn accept_waveform(&self, sample_rate: u32, samples: &[f32]) -> Result<(), StreamingError> {
+        if self.is_finished() {
+            return Err(StreamingError::InvalidState);
+        }
+        for chunk in native_chunks(samples) {
+            unsafe {
+                sherpa_rs_sys::SherpaOnnxOnlineStreamAcceptWaveform(
+                    self.ptr,
+                    sample_rate as i32,
+                    chunk.as_ptr(),
+                    chunk.len() as i32,
+                );
+            }
+        }
+        self.update_stats(|stats| {
+            stats.samples += samples.len() as u64;
+            if sample_rate > 0 {
+                stats.audio_seconds += samples.len() as f64 / f64::from(sample_rate);
+            }
+        });
+        Ok(())
+    }
+
+    /// Mark the end of the stream's audio. The stream can still be decoded
+    /// but takes no more audio; create a new stream for the next session.
+    pub fn input_finished(&self) {
+        if self.finished.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        unsafe {
+            sherpa_rs_sys::SherpaOnnxOnlineStreamInputFinished(self.ptr);
+        }
+    }
+
+    /// Replace the native stream of a finished stream with a fresh one, so it takes audio
+    /// again. Tag and stats carry over, as they cover the whole session.
+    pub(crate) fn reopen(&mut self) -> Result<()> {
+        let ptr = unsafe { sherpa_rs_sys::SherpaOnnxCreateOnlineStream(self.owner.ptr) };
+        if ptr.is_null() {
+            bail!("SherpaOnnxCreateOnlineStream failed");
+        }
+        if !self.ptr.is_null() {
+            unsafe { sherpa_rs_sys::SherpaOnnxDestroyOnlineStream(self.ptr) };
+        }
+        self.ptr = ptr;
+        self.finished.store(false, Ordering::SeqCst);
+        self.at_endpoint.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Finish the stream and deliver its result if finalize-on-drop is enabled.
+    /// A panicking sink is logged, as this runs while the stream is dropped.
+    fn finalize(&self) {
+        let Some(sink) = self.owner.final_sink() else {
+            return;
+        };
+        if self.is_finished() {
+            return;
+        }
+        self.input_finished();
+        let recognizer = self.owner.ptr;
+        let mut result = unsafe {
+            while sherpa_rs_sys::SherpaOnnxIsOnlineStreamReady(recognizer, self.ptr) != 0 {
+                sherpa_rs_sys::SherpaOnnxDecodeOnlineStream(recognizer, self.ptr);
+            }
+            OnlineRecognizerResult::fetch(recognizer, self.ptr)
+        };
+        if result.text.trim().is_empty() {
+            return;
+        }
+        result.tag = self.tag.clone();
+        if let Err(panic) = panic::catch_unwind(AssertUnwindSafe(|| sink(result))) {
+            tracing::error!(
+                "finalize-on-drop sink panicked: {}",
+                crate::shutdown::panic_message(&*panic)
+            );
+        }
+    }
+}
+
+unsafe impl Send for SafeOnlineStream {}
+
+impl Drop for SafeOnlineStream {
+    fn drop(&mut self) {
+        if self.ptr.is_null() {
+            return;
+        }
+        self.finalize();
+        unsafe { sherpa_rs_sys::SherpaOnnxDestroyOnlineStream(self.ptr) };
+    }
+}
+
+/// Stream of an `OnlineRecognizer`, freed when dropped.
+///
+/// Created by `OnlineRecognizer::open_stream` and borrows the recognizer, so it can't
+/// outlive it. Decoding only needs the shared borrow, so several streams can be decoded
+/// side by side, also from scoped threads, and batched with `OnlineRecognizer::decode_batch`.
+/// Derefs to `SafeOnlineStream` for feeding audio, tags and stats. There is no mutable
+/// deref, so the native stream can't be swapped out from under its recognizer.
+pub struct OnlineStream<'a> {
+    recognizer: &'a OnlineRecognizer,
+    /// Taken on drop
+    stream: Option<SafeOnlineStream>,
+}
+
+impl OnlineStream<'_> {
+    fn stream(&self) -> &SafeOnlineStream {
+        self.stream.as_ref().expect("stream is only taken on drop")
+    }
+
+    /// Run one decode step
+    pub fn decode(&self) {
+        self.recognizer.decode_native(self.stream());
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.recognizer.is_stream_ready(self.stream())
+    }
+
+    /// Decode all ready frames and return the current text
+    pub fn transcribe(&self) -> String {
+        while self.is_ready() {
+            self.decode();
+        }
+        self.result()
+    }
+
+    pub fn result(&self) -> String {
+        self.recognizer.stream_result(self.stream())
+    }
+
+    /// Current result with tokens, timestamps and the stream's tag
+    pub fn result_full(&self) -> OnlineRecognizerResult {
+        self.recognizer.stream_result_full(self.stream())
+    }
+
+    pub fn is_endpoint(&self) -> bool {
+        self.recognizer.is_stream_endpoint(self.stream())
+    }
+
+    /// Start a new utterance. Doesn't undo `input_finished`.
+    pub fn reset(&self) {
+        self.recognizer.reset_stream(self.stream())
+    }
+
+    /// See `SafeOnlineStream::set_tag`
+    pub fn set_tag<S: Into<String>>(&mut self, tag: S) {
+        self.stream
+            .as_mut()
+            .expect("stream is only taken on drop")
+            .set_tag(tag);
+    }
+}
+
+impl Deref for OnlineStream<'_> {
+    type Target = SafeOnlineStream;
+
+    fn deref(&self) -> &SafeOnlineStream {
+        self.stream()
+    }
+}
+
+impl Drop for OnlineStream<'_> {
+    fn drop(&mut self) {
+        if let Some(stream) = self.stream.take() {
+            self.recognizer.destroy_stream(stream);
+        }
+    }
+}
+
+/// Streaming recognizer for every online model family.
+///
+/// Used directly, it decodes one built-in stream: feed audio with `accept_waveform`,
+/// then `decode` while `is_ready`. For several concurrent sessions sharing the model,
+/// create extra streams with `create_stream` and use the `*_stream` methods.
+///
+/// # Panics
+///
+/// The `*_stream` methods panic when given a stream created by another recognizer.
+pub struct OnlineRecognizer {
+    native: Arc<NativeRecognizer>,
+    config_dump: ConfigDump,
+    stream: SafeOnlineStream,
+    /// Audio of the current utterance, when recording is enabled
+    recording: Option<OnlineStreamSnapshot>,
+}
+
+impl OnlineRecognizer {
+    pub fn new(config: OnlineRecognizerConfig) -> Result<Self> {
+        Self::from_builder(config.into())
+    }
+
+    pub(crate) fn from_builder(builder: OnlineRecognizerConfigBuilder) -> Result<Self> {
+        let Some((recognizer, config_dump)) = builder.create() else {
+            bail!("SherpaOnnxCreateOnlineRecognizer failed");
+        };
+        let native = Arc::new(NativeRecognizer {
+            ptr: recognizer,
+            final_sink: Mutex::new(None),
+        });
+        let stream = unsafe { sherpa_rs_sys::SherpaOnnxCreateOnlineStream(recognizer) };
+        if stream.is_null() {
+            bail!("SherpaOnnxCreateOnlineStream failed");
+        }
+
+        Ok(Self {
+            stream: SafeOnlineStream::new(stream, native.clone()),
+            native,
+            config_dump,
+            recording: None,
+        })
+    }
+
+    /// Feed a chunk of audio samples to the recognizer (call in a loop for streaming).
+    /// Fails with `StreamingError::InvalidState` after `input_finished` until `reset`.
+    pub fn accept_waveform(
+        &mut self,
+        sample_rate: u32,
+        samples: &[f32],
+    ) -> Result<(), StreamingError> {
+        self.stream.accept_waveform(sample_rate, samples)?;
+        if let Some(recording) = &mut self.recording {
+            recording.sample_rate = sample_rate;
+            recording.samples.extend_from_slice(samples);
+        }
+        Ok(())
+    }
+
+    /// Decode the current stream state (call after accept_waveform)
+    pub fn decode(&mut self) {
+        self.decode_native(&self.stream);
+    }
+
+    /// Run at most one decode step without blocking on further frames.
+    /// Returns true while more frames are ready to decode.
+    pub fn try_decode(&mut self) -> bool {
+        if !self.is_ready() {
+            return false;
+        }
+        self.decode();
+        self.is_ready()
+    }
+
+    /// Run at most one decode step and report whether more work remains,
+    /// for event loops that interleave decoding with other work
+    pub fn poll_result(&mut self) -> DecodePoll {
+        if self.try_decode() {
+            DecodePoll::Pending
+        } else {
+            DecodePoll::Ready(self.get_result())
+        }
+    }
+
+    /// Check if a partial result is ready
+    pub fn is_ready(&self) -> bool {
+        self.is_stream_ready(&self.stream)
+    }
+
+    /// Get the current transcription result (partial or final; call while is_ready())
+    pub fn get_result(&self) -> String {
+        self.stream_result(&self.stream)
+    }
+
+    /// Current result with tokens, per-token timestamps and the sherpa-onnx JSON
+    pub fn get_result_full(&self) -> OnlineRecognizerResult {
+        self.stream_result_full(&self.stream)
+    }
+
+    /// Check if an endpoint (end of utterance) is detected
+    pub fn is_endpoint(&self) -> bool {
+        self.is_stream_endpoint(&self.stream)
+    }
+
+    /// Usage of the built-in stream since the recognizer was created or
+    /// `reset_stats` was called. Resets for a new utterance don't clear it.
+    pub fn stats(&self) -> StreamStats {
+        self.stream.stats()
+    }
+
+    pub fn reset_stats(&self) {
+        self.stream.reset_stats();
+    }
+
+    /// Reset the stream for a new utterance (call after endpoint).
+    /// After `input_finished` this replaces the stream so audio can be fed again.
+    pub fn reset(&mut self) {
+        if self.stream.is_finished() {
+            if let Err(error) = self.stream.reopen() {
+                tracing::warn!("{}, stream stays finished", error);
+            }
+        } else {
+            self.reset_stream(&self.stream);
+        }
+        if let Some(recording) = &mut self.recording {
+            recording.samples.clear();
+        }
+    }
+
+    /// Keep a copy of the audio fed since the last reset, so the stream can be snapshotted.
+    /// Disabling drops the recorded audio.
+    pub fn record_utterance(&mut self, enabled: bool) {
+        match (enabled, self.recording.is_some()) {
+            (true, false) => self.recording = Some(OnlineStreamSnapshot::default()),
+            (false, true) => self.recording = None,
+            _ => {}
+        }
+    }
+
+    /// Snapshot of the current utterance: its audio (when recording is enabled) and partial text.
+    /// The native stream state itself can't be serialized, so restoring replays the audio.
+    pub fn snapshot(&self) -> OnlineStreamSnapshot {
+        let mut snapshot = self.recording.clone().unwrap_or_default();
+        snapshot.partial = self.get_result();
+        snapshot
+    }
+
+    /// Replace the current utterance with a snapshot by replaying its audio on a fresh stream state.
+    /// Returns the hypothesis after replay.
+    pub fn restore(&mut self, snapshot: &OnlineStreamSnapshot) -> Result<String, StreamingError> {
+        self.reset();
+        if !snapshot.samples.is_empty() {
+            self.accept_waveform(snapshot.sample_rate, &snapshot.samples)?;
+            while self.is_ready() {
+                self.decode();
+            }
+        }
+        Ok(self.get_result())
+    }
+
+    /// Signal end of input and finalize any pending decoding (call at session end)
+    pub fn input_finished(&mut self) {
+        if self.stream.is_finished() {
+            return;
+        }
+        self.stream.input_finished();
+        while self.is_ready() {
+            self.decode();
+        }
+    }
+
+    /// Finalize unfinished streams when they go away: every stream created by this
+    /// recognizer when it's dropped or passed to `destroy_stream`, and the built-in stream
+    /// when the recognizer is dropped. Their input is marked finished, the remaining frames
+    /// are decoded and a non-empty result is passed to `sink`, so the last utterance of a
+    /// session isn't lost when the caller forgets `input_finished`.
+    ///
+    /// Streams already finished with `input_finished` are left alone, their result was
+    /// available to the caller. Results carry the stream's tag to tell sessions apart.
+    /// A panic in `sink` is logged and the stream is freed regardless.
+    pub fn finalize_on_drop<F>(&mut self, sink: F)
+    where
+        F: Fn(OnlineRecognizerResult) + Send + Sync + 'static,
+    {
+        self.native.set_final_sink(Some(Arc::new(sink)));
+    }
+
+    /// Drop streams without decoding their remaining audio, the default
+    pub fn disable_finalize_on_drop(&mut self) {
+        self.native.set_final_sink(None);
+    }
+
+    /// Create another stream decoded by this recognizer
+    pub fn create_stream(&self) -> Result<SafeOnlineStream> {
+        let ptr = unsafe { sherpa_rs_sys::SherpaOnnxCreateOnlineStream(self.native.ptr) };
+        if ptr.is_null() {
+            bail!("SherpaOnnxCreateOnlineStream failed");
+        }
+        Ok(SafeOnlineStream::new(ptr, self.native.clone()))
+    }
+
+    /// Finished stream without a native stream, for callers that can't report errors
+    pub(crate) fn null_stream(&self) -> SafeOnlineStream {
+        SafeOnlineStream::new(std::ptr::null(), self.native.clone())
+    }
+
+    fn check_owner(&self, stream: &SafeOnlineStream) {
+        assert!(
+            Arc::ptr_eq(&stream.owner, &self.native),
+            "stream created by another recognizer"
+        );
+    }
+
+    /// Create another stream decoded by this recognizer, freed when dropped
+    pub fn open_stream(&self) -> Result<OnlineStream<'_>> {
+        Ok(OnlineStream {
+            recognizer: self,
+            stream: Some(self.create_stream()?),
+        })
+    }
+
+    /// Start a new utterance on `stream`, reopening it when its input was finished
+    pub(crate) fn restart_stream(&self, stream: &mut SafeOnlineStream) {
+        if !stream.is_finished() {
+            return self.reset_stream(stream);
+        }
+        self.check_owner(stream);
+        if let Err(error) = stream.reopen() {
+            tracing::warn!("{}, stream stays finished", error);
+        }
+    }
+
+    /// Free `stream`, finalizing it first when `finalize_on_drop` is enabled
+    pub fn destroy_stream(&self, stream: SafeOnlineStream) {
+        self.finish_stream(&stream);
+    }
+
+    /// Finalize `stream` like `destroy_stream`, then mark its input finished
+    pub(crate) fn finish_stream(&self, stream: &SafeOnlineStream) {
+        self.check_owner(stream);
+        stream.finalize();
+        stream.input_finished();
+    }
+
+    /// Run one decode step on `stream`
+    pub fn decode_stream(&mut self, stream: &SafeOnlineStream) {
+        self.decode_native(stream);
+    }
+
+    fn decode_native(&self, stream: &SafeOnlineStream) {
+        self.check_owner(stream);
+        if stream.ptr.is_null() {
+            return;
+        }
+        let start = Instant::now();
+        unsafe {
+            sherpa_rs_sys::SherpaOnnxDecodeOnlineStream(self.native.ptr, stream.ptr);
+        }
+        let elapsed = start.elapsed();
+        stream.update_stats(|stats| {
+            stats.decode_calls += 1;
+            stats.decode_time += elapsed;
+        });
+    }
+
+    /// Run one decode step on each stream in a single batch, which is faster than
+    /// decoding them one by one when many sessions are active
+    pub fn decode_streams(&mut self, streams: &[&SafeOnlineStream]) {
+        self.decode_native_batch(streams);
+    }
+
+    /// Run one decode step on each stream in a single batch, like `decode_streams`.
+    ///
+    /// # Panics
+    ///
+    /// If a stream was opened by another recognizer
+    pub fn decode_batch(&self, streams: &[&OnlineStream<'_>]) {
+        assert!(
+            streams
+                .iter()
+                .all(|stream| std::ptr::eq(stream.recognizer, self)),
+            "stream opened by another recognizer"
+        );
+        let streams: Vec<&SafeOnlineStream> =
+            streams.iter().map(|stream| stream.stream()).collect();
+        self.decode_native_batch(&streams);
+    }
+
+    fn decode_native_batch(&self, streams: &[&SafeOnlineStream]) {
+        for stream in streams {
+            self.check_owner(stream);
+        }
+        let streams: Vec<&SafeOnlineStream> = streams
+            .iter()
+            .copied()
+            .filter(|stream| !stream.ptr.is_null())
+            .collect();
+        let mut ptrs: Vec<_> = streams.iter().map(|stream| stream.ptr).collect();
+        let start = Instant::now();
+        unsafe {
+            sherpa_rs_sys::SherpaOnnxDecodeMultipleOnlineStreams(
+                self.native.ptr,
+                ptrs.as_mut_ptr(),
+                ptrs.len() as i32,
+            );
+        }
+        // Every stream in the batch runs one decode step, so they share the time evenly
+        let elapsed = start.elapsed() / streams.len().max(1) as u32;
+        for stream in &streams {
+            stream.update_stats(|stats| {
+                stats.decode_calls += 1;
+                stats.decode_time += elapsed;
+            });
+        }
+    }
+
+    pub fn is_stream_ready(&self, stream: &SafeOnlineStream) -> bool {
+        self.check_owner(stream);
+        !stream.ptr.is_null()
+            && unsafe {
+                sherpa_rs_sys::SherpaOnnxIsOnlineStreamReady(self.native.ptr, stream.ptr) != 0
+            }
+    }
+
+    pub fn stream_result(&self, stream: &SafeOnlineStream) -> String {
+        self.check_owner(stream);
+        if stream.ptr.is_null() {
+            return String::new();
+        }
+        unsafe {
+            let result_ptr =
+                sherpa_rs_sys::SherpaOnnxGetOnlineStreamResult(self.native.ptr, stream.ptr);
+            if result_ptr.is_null() {
+                return String::new();
+            }
+            let text = cstr_to_string((*result_ptr).text as _);
+            sherpa_rs_sys::SherpaOnnxDestroyOnlineRecognizerResult(result_ptr);
+            text
+        }
+    }
+
+    /// Like `get_result_full`, tagged with the stream's tag
+    pub fn stream_result_full(&self, stream: &SafeOnlineStream) -> OnlineRecognizerResult {
+        self.check_owner(stream);
+        let mut result = if stream.ptr.is_null() {
+            OnlineRecognizerResult::default()
+        } else {
+            unsafe { OnlineRecognizerResult::fetch(self.native.ptr, stream.ptr) }
+        };
+        result.tag = stream.tag.clone();
+        result
+    }
+
+    pub fn is_stream_endpoint(&self, stream: &SafeOnlineStream) -> bool {
+        self.check_owner(stream);
+        let endpoint = !stream.ptr.is_null()
+            && unsafe {
+                sherpa_rs_sys::SherpaOnnxOnlineStreamIsEndpoint(self.native.ptr, stream.ptr) != 0
+            };
+        if endpoint && !stream.at_endpoint.swap(true, Ordering::SeqCst) {
+            stream.update_stats(|stats| stats.endpoints += 1);
+        }
+        endpoint
+    }
+
+    /// Start a new utterance on `stream`. Doesn't undo `input_finished`.
+    pub fn reset_stream(&self, stream: &SafeOnlineStream) {
+        self.check_owner(stream);
+        if !stream.ptr.is_null() {
+            unsafe {
+                sherpa_rs_sys::SherpaOnnxOnlineStreamReset(self.native.ptr, stream.ptr);
+            }
+        }
+        stream.at_endpoint.store(false, Ordering::SeqCst);
+    }
+}
+
+impl NativeConfig for OnlineRecognizer {
+    fn config_dump(&self) -> &ConfigDump {
+        &self.config_dump
+    }
+}
+
+impl crate::OnlineTranscriber for OnlineRecognizer {
+    fn accept_waveform(&mut self, sample_rate: u32, samples: &[f32]) -> Result<(), StreamingError> {
+        OnlineRecognizer::accept_waveform(self, sample_rate, samples)
+    }
+
+    fn transcribe(&mut self) -> String {
+        while self.is_ready() {
+            self.decode();
+        }
+        self.get_result()
+    }
+
+    fn is_endpoint(&self) -> bool {
+        OnlineRecognizer::is_endpoint(self)
+    }
+
+    fn reset(&mut self) {
+        OnlineRecognizer::reset(self)
+    }
+
+    fn input_finished(&mut self) {
+        OnlineRecognizer::input_finished(self)
+    }
+}
+
+unsafe impl Send for OnlineRecognizer {}
+unsafe impl Sync for OnlineRecognizer {}
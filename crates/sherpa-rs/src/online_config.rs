@@ -0,0 +1,159 @@
+use crate::config_dump::{self, ConfigDump};
+use crate::online::{OnlineModel, OnlineRecognizerConfig};
+use crate::utils::cstring_from_str;
+use std::mem;
+
+/// Settings shared by the online recognizer wrappers, assembled into the native
+/// config in one place so new fields only need to be wired here
+#[derive(Debug, Clone)]
+pub(crate) struct OnlineRecognizerConfigBuilder {
+    pub model: OnlineModel,
+    pub tokens: String,
+    pub num_threads: i32,
+    pub debug: bool,
+    pub provider: String,
+    pub model_type: String,
+    pub modeling_unit: String,
+    pub bpe_vocab: String,
+    pub sample_rate: i32,
+    pub feature_dim: i32,
+    pub decoding_method: String,
+    pub max_active_paths: i32,
+    pub enable_endpoint: bool,
+    pub rule1_min_trailing_silence: f32,
+    pub rule2_min_trailing_silence: f32,
+    pub rule3_min_utterance_length: f32,
+    pub hotwords_file: String,
+    pub hotwords_score: f32,
+    pub blank_penalty: f32,
+}
+
+/// Same defaults as `OnlineRecognizerConfig`, so every wrapper starts from one set
+impl Default for OnlineRecognizerConfigBuilder {
+    fn default() -> Self {
+        OnlineRecognizerConfig::default().into()
+    }
+}
+
+impl From<OnlineRecognizerConfig> for OnlineRecognizerConfigBuilder {
+    fn from(config: OnlineRecognizerConfig) -> Self {
+        Self {
+            model: config.model,
+            tokens: config.tokens,
+            num_threads: config.num_threads,
+            debug: config.debug,
+            provider: config.provider.unwrap_or_else(crate::get_default_provider),
+            model_type: config.model_type,
+            modeling_unit: config.modeling_unit,
+            bpe_vocab: config.bpe_vocab,
+            sample_rate: config.sample_rate,
+            feature_dim: config.feature_dim,
+            decoding_method: config.decoding_method,
+            max_active_paths: config.max_active_paths,
+            enable_endpoint: config.enable_endpoint,
+            rule1_min_trailing_silence: config.rule1_min_trailing_silence,
+            rule2_min_trailing_silence: config.rule2_min_trailing_silence,
+            rule3_min_utterance_length: config.rule3_min_utterance_length,
+            hotwords_file: config.hotwords_file,
+            hotwords_score: config.hotwords_score,
+            blank_penalty: config.blank_penalty,
+        }
+    }
+}
+
+impl OnlineRecognizerConfigBuilder {
+    /// Create the native recognizer and dump of the config it was created with.
+    /// `None` when sherpa-onnx rejects the config.
+    pub fn create(&self) -> Option<(*const sherpa_rs_sys::SherpaOnnxOnlineRecognizer, ConfigDump)> {
+        // Paths of the selected model family; the others stay null
+        let (encoder, decoder, joiner, model) = match &self.model {
+            OnlineModel::Transducer {
+                encoder,
+                decoder,
+                joiner,
+            } => (Some(encoder), Some(decoder), Some(joiner), None),
+            OnlineModel::Paraformer { encoder, decoder } => {
+                (Some(encoder), Some(decoder), None, None)
+            }
+            OnlineModel::Zipformer2Ctc { model } | OnlineModel::NemoCtc { model } => {
+                (None, None, None, Some(model))
+            }
+        };
+        let encoder = encoder.map(|path| cstring_from_str(path));
+        let decoder = decoder.map(|path| cstring_from_str(path));
+        let joiner = joiner.map(|path| cstring_from_str(path));
+        let model = model.map(|path| cstring_from_str(path));
+        let ptr =
+            |s: &Option<std::ffi::CString>| s.as_ref().map_or(std::ptr::null(), |s| s.as_ptr());
+        let tokens = cstring_from_str(&self.tokens);
+        let provider = cstring_from_str(&self.provider);
+        let model_type = cstring_from_str(&self.model_type);
+        let modeling_unit = cstring_from_str(&self.modeling_unit);
+        let bpe_vocab = cstring_from_str(&self.bpe_vocab);
+        let decoding_method = cstring_from_str(&self.decoding_method);
+        let hotwords_file = cstring_from_str(&self.hotwords_file);
+
+        unsafe {
+            let mut config = sherpa_rs_sys::SherpaOnnxOnlineRecognizerConfig {
+                feat_config: sherpa_rs_sys::SherpaOnnxFeatureConfig {
+                    sample_rate: self.sample_rate,
+                    feature_dim: self.feature_dim,
+                },
+                model_config: sherpa_rs_sys::SherpaOnnxOnlineModelConfig {
+                    tokens: tokens.as_ptr(),
+                    num_threads: self.num_threads,
+                    debug: crate::native_debug(self.debug),
+                    provider: provider.as_ptr(),
+                    model_type: model_type.as_ptr(),
+                    modeling_unit: modeling_unit.as_ptr(),
+                    bpe_vocab: bpe_vocab.as_ptr(),
+                    tokens_buf: std::ptr::null(),
+                    tokens_buf_size: 0,
+                    // Paths of the selected model family are set below
+                    ..mem::zeroed()
+                },
+                decoding_method: decoding_method.as_ptr(),
+                max_active_paths: self.max_active_paths,
+                enable_endpoint: self.enable_endpoint.into(),
+                rule1_min_trailing_silence: self.rule1_min_trailing_silence,
+                rule2_min_trailing_silence: self.rule2_min_trailing_silence,
+                rule3_min_utterance_length: self.rule3_min_utterance_length,
+                hotwords_file: hotwords_file.as_ptr(),
+                hotwords_score: self.hotwords_score,
+                ctc_fst_decoder_config: mem::zeroed(),
+                rule_fsts: std::ptr::null(),
+                rule_fars: std::ptr::null(),
+                blank_penalty: self.blank_penalty,
+                hotwords_buf: std::ptr::null(),
+                hotwords_buf_size: 0,
+                hr: mem::zeroed(),
+            };
+
+            match &self.model {
+                OnlineModel::Transducer { .. } => {
+                    config.model_config.transducer =
+                        sherpa_rs_sys::SherpaOnnxOnlineTransducerModelConfig {
+                            encoder: ptr(&encoder),
+                            decoder: ptr(&decoder),
+                            joiner: ptr(&joiner),
+                        }
+                }
+                OnlineModel::Paraformer { .. } => {
+                    config.model_config.paraformer =
+                        sherpa_rs_sys::SherpaOnnxOnlineParaformerModelConfig {
+                            encoder: ptr(&encoder),
+                            decoder: ptr(&decoder),
+                        }
+                }
+                OnlineModel::Zipformer2Ctc { .. } => {
+                    config.model_config.zipformer2_ctc.model = ptr(&model)
+                }
+                OnlineModel::NemoCtc { .. } => config.model_config.nemo_ctc.model = ptr(&model),
+            }
+
+            let dump = config_dump::online_recognizer(&config);
+            let recognizer = sherpa_rs_sys::SherpaOnnxCreateOnlineRecognizer(&config);
+            (!recognizer.is_null()).then_some((recognizer, dump))
+        }
+    }
+}
@@ -0,0 +1,384 @@
+use audiopus::{coder::Decoder, packet::Packet, Channels, MutSignals, SampleRate};
+use eyre::{bail, Result};
+use std::collections::VecDeque;
+use std::path::Path;
+
+/// Longest Opus packet: 120 ms at 48 kHz, per channel
+const MAX_FRAME: usize = 5760;
+
+/// Decodes raw Opus packets to mono f32 samples, e.g. from RTP or a websocket
+pub struct OpusPacketDecoder {
+    decoder: Decoder,
+    channels: usize,
+    sample_rate: u32,
+    /// Samples still to drop from the start of the stream (pre-skip)
+    skip: usize,
+    buffer: Vec<f32>,
+}
+
+impl OpusPacketDecoder {
+    /// `sample_rate` is the output rate: 8000, 12000, 16000, 24000 or 48000.
+    /// Opus decodes natively at any of them, so pick the recognizer's rate.
+    pub fn new(sample_rate: u32, channels: usize) -> Result<Self> {
+        let Ok(rate) = SampleRate::try_from(sample_rate as i32) else {
+            bail!("Opus can't decode at {} Hz", sample_rate);
+        };
+        let layout = match channels {
+            1 => Channels::Mono,
+            2 => Channels::Stereo,
+            _ => bail!("Only mono and stereo Opus streams are supported"),
+        };
+        Ok(Self {
+            decoder: Decoder::new(rate, layout)?,
+            channels,
+            sample_rate,
+            skip: 0,
+            buffer: vec![0.0; MAX_FRAME * channels],
+        })
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Decode one packet, downmixing stereo
+    pub fn decode(&mut self, packet: &[u8]) -> Result<Vec<f32>> {
+        let packet = Packet::try_from(packet)?;
+        let output = MutSignals::try_from(&mut self.buffer[..])?;
+        let frames = self.decoder.decode_float(Some(packet), output, false)?;
+        let mut samples: Vec<f32> = self.buffer[..frames * self.channels]
+            .chunks(self.channels)
+            .map(|frame| frame.iter().sum::<f32>() / self.channels as f32)
+            .collect();
+        let skipped = self.skip.min(samples.len());
+        samples.drain(..skipped);
+        self.skip -= skipped;
+        Ok(samples)
+    }
+
+    /// Samples a lost packet would have held, interpolated by the decoder
+    pub fn conceal(&mut self, frames: usize) -> Result<Vec<f32>> {
+        let len = frames.min(MAX_FRAME) * self.channels;
+        let output = MutSignals::try_from(&mut self.buffer[..len])?;
+        let frames = self.decoder.decode_float(None, output, false)?;
+        Ok(self.buffer[..frames * self.channels]
+            .chunks(self.channels)
+            .map(|frame| frame.iter().sum::<f32>() / self.channels as f32)
+            .collect())
+    }
+}
+
+/// Fields of an `OpusHead` header used for decoding
+fn parse_opus_head(head: &[u8]) -> Result<(usize, usize)> {
+    if head.len() < 19 || &head[..8] != b"OpusHead" {
+        bail!("Invalid OpusHead header");
+    }
+    let channels = head[9] as usize;
+    let pre_skip = u16::from_le_bytes([head[10], head[11]]) as usize;
+    Ok((channels, pre_skip))
+}
+
+/// Decoder created from an `OpusHead`, with pre-skip converted to the output rate
+fn decoder_from_head(head: &[u8], sample_rate: u32) -> Result<OpusPacketDecoder> {
+    let (channels, pre_skip) = parse_opus_head(head)?;
+    let mut decoder = OpusPacketDecoder::new(sample_rate, channels)?;
+    decoder.skip = pre_skip * sample_rate as usize / 48000;
+    Ok(decoder)
+}
+
+/// Incremental Ogg Opus decoder, e.g. for `audio/ogg` chunks arriving over a websocket.
+/// Only the first logical stream is decoded.
+///
+/// A packet that fails to decode is dropped with an error, and the next `push` carries
+/// on after it. Bytes that aren't an Ogg page leave the reader failed for good.
+pub struct OggOpusReader {
+    sample_rate: u32,
+    pending: Vec<u8>,
+    /// Packet continued on the next page
+    partial: Vec<u8>,
+    /// Complete packets not decoded yet, after an error in an earlier one
+    queued: VecDeque<Vec<u8>>,
+    serial: Option<u32>,
+    decoder: Option<OpusPacketDecoder>,
+    /// Packets seen so far; the first two are headers
+    packets: usize,
+    corrupt: bool,
+}
+
+impl OggOpusReader {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            pending: Vec::new(),
+            partial: Vec::new(),
+            queued: VecDeque::new(),
+            serial: None,
+            decoder: None,
+            packets: 0,
+            corrupt: false,
+        }
+    }
+
+    /// Feed the next bytes of the file and return the audio of the completed pages
+    pub fn push(&mut self, bytes: &[u8]) -> Result<Vec<f32>> {
+        if self.corrupt {
+            bail!("Ogg stream is corrupt");
+        }
+        self.pending.extend_from_slice(bytes);
+        let mut offset = 0;
+        loop {
+            let page = &self.pending[offset..];
+            if page.len() < 27 {
+                break;
+            }
+            if &page[..4] != b"OggS" {
+                self.corrupt = true;
+                self.pending.clear();
+                bail!("Invalid Ogg page");
+            }
+            let segments = page[26] as usize;
+            if page.len() < 27 + segments {
+                break;
+            }
+            let lacing = &page[27..27 + segments];
+            let body_len: usize = lacing.iter().map(|&l| l as usize).sum();
+            let page_len = 27 + segments + body_len;
+            if page.len() < page_len {
+                break;
+            }
+            let serial = u32::from_le_bytes([page[14], page[15], page[16], page[17]]);
+            if *self.serial.get_or_insert(serial) == serial {
+                let mut body = &page[27 + segments..page_len];
+                for &len in lacing {
+                    self.partial.extend_from_slice(&body[..len as usize]);
+                    body = &body[len as usize..];
+                    // A lacing value below 255 ends the packet
+                    if len < 255 {
+                        self.queued.push_back(std::mem::take(&mut self.partial));
+                    }
+                }
+            }
+            offset += page_len;
+        }
+        self.pending.drain(..offset);
+
+        let mut samples = Vec::new();
+        while let Some(packet) = self.queued.pop_front() {
+            samples.extend(self.handle_packet(&packet)?);
+        }
+        Ok(samples)
+    }
+
+    fn handle_packet(&mut self, packet: &[u8]) -> Result<Vec<f32>> {
+        self.packets += 1;
+        match self.packets {
+            1 => {
+                self.decoder = Some(decoder_from_head(packet, self.sample_rate)?);
+                Ok(Vec::new())
+            }
+            // OpusTags
+            2 => Ok(Vec::new()),
+            _ => match &mut self.decoder {
+                Some(decoder) => decoder.decode(packet),
+                None => bail!("Ogg stream doesn't start with OpusHead"),
+            },
+        }
+    }
+}
+
+const EBML_HEADER: u32 = 0x1A45DFA3;
+const SEGMENT: u32 = 0x18538067;
+const CLUSTER: u32 = 0x1F43B675;
+const TRACKS: u32 = 0x1654AE6B;
+const TRACK_ENTRY: u32 = 0xAE;
+const TRACK_NUMBER: u32 = 0xD7;
+const CODEC_ID: u32 = 0x86;
+const CODEC_PRIVATE: u32 = 0x63A2;
+const BLOCK_GROUP: u32 = 0xA0;
+const BLOCK: u32 = 0xA1;
+const SIMPLE_BLOCK: u32 = 0xA3;
+
+/// Incremental WebM Opus decoder for browser `MediaRecorder` output
+/// (`audio/webm;codecs=opus`). Decodes the first Opus track; laced blocks are not supported.
+///
+/// An element that fails to decode is dropped with an error, and the next `push` carries
+/// on after it. Malformed element headers leave the reader failed for good.
+pub struct WebmOpusReader {
+    sample_rate: u32,
+    pending: Vec<u8>,
+    /// Track entry being parsed: (number, codec id, codec private)
+    entry: (u64, String, Vec<u8>),
+    track: Option<u64>,
+    decoder: Option<OpusPacketDecoder>,
+    corrupt: bool,
+}
+
+impl WebmOpusReader {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            pending: Vec::new(),
+            entry: (0, String::new(), Vec::new()),
+            track: None,
+            decoder: None,
+            corrupt: false,
+        }
+    }
+
+    /// Feed the next bytes of the file and return the audio of the completed blocks
+    pub fn push(&mut self, bytes: &[u8]) -> Result<Vec<f32>> {
+        if self.corrupt {
+            bail!("WebM stream is corrupt");
+        }
+        self.pending.extend_from_slice(bytes);
+        let mut samples = Vec::new();
+        let mut offset = 0;
+        loop {
+            let data = &self.pending[offset..];
+            let header = read_vint(data, true).and_then(|id| match id {
+                Some((id, id_len)) => Ok(read_vint(&data[id_len..], false)?
+                    .map(|(size, size_len)| (id, size, id_len + size_len))),
+                None => Ok(None),
+            });
+            let (id, size, header_len) = match header {
+                Ok(Some(header)) => header,
+                Ok(None) => break,
+                Err(error) => return Err(self.fail(error)),
+            };
+            // Ids keep their marker bit, so they are never "unknown"
+            let id = id.unwrap_or_default() as u32;
+            match id {
+                // Containers are entered rather than skipped, which also handles the
+                // unknown sizes live recordings use
+                SEGMENT | CLUSTER | TRACKS | BLOCK_GROUP => {
+                    offset += header_len;
+                    continue;
+                }
+                TRACK_ENTRY => {
+                    self.entry = (0, String::new(), Vec::new());
+                    offset += header_len;
+                    continue;
+                }
+                _ => {}
+            }
+            let Some(size) = size else {
+                return Err(self.fail(eyre::eyre!("Unknown size for WebM element {:#x}", id)));
+            };
+            let end = header_len + size as usize;
+            if data.len() < end {
+                break;
+            }
+            let payload = data[header_len..end].to_vec();
+            offset += end;
+            let handled = match id {
+                TRACK_NUMBER => {
+                    self.entry.0 = payload.iter().fold(0, |n, &b| (n << 8) | b as u64);
+                    self.select_track()
+                }
+                CODEC_ID => {
+                    self.entry.1 = String::from_utf8_lossy(&payload).into_owned();
+                    self.select_track()
+                }
+                CODEC_PRIVATE => {
+                    self.entry.2 = payload;
+                    self.select_track()
+                }
+                SIMPLE_BLOCK | BLOCK => self
+                    .handle_block(&payload)
+                    .map(|block| samples.extend(block)),
+                _ => Ok(()),
+            };
+            if let Err(error) = handled {
+                // Skip the element, so the next push doesn't fail on it again
+                self.pending.drain(..offset);
+                return Err(error);
+            }
+        }
+        self.pending.drain(..offset);
+        Ok(samples)
+    }
+
+    /// Give up on the stream: its element boundaries can't be found anymore
+    fn fail(&mut self, error: eyre::Report) -> eyre::Report {
+        self.corrupt = true;
+        self.pending.clear();
+        error
+    }
+
+    /// Use the current track entry once it's known to be Opus
+    fn select_track(&mut self) -> Result<()> {
+        let (number, codec, head) = &self.entry;
+        if self.track.is_none() && *number != 0 && codec == "A_OPUS" && !head.is_empty() {
+            self.decoder = Some(decoder_from_head(head, self.sample_rate)?);
+            self.track = Some(*number);
+        }
+        Ok(())
+    }
+
+    fn handle_block(&mut self, block: &[u8]) -> Result<Vec<f32>> {
+        let Ok(Some((Some(track), len))) = read_vint(block, false) else {
+            bail!("Invalid WebM block");
+        };
+        if Some(track) != self.track {
+            return Ok(Vec::new());
+        }
+        // Timecode (2 bytes) and flags
+        let Some(&flags) = block.get(len + 2) else {
+            bail!("Invalid WebM block");
+        };
+        if flags & 0x06 != 0 {
+            bail!("Laced WebM blocks are not supported");
+        }
+        match &mut self.decoder {
+            Some(decoder) => decoder.decode(&block[len + 3..]),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// EBML variable-length integer: `(value, length)`, or `None` when more bytes are needed.
+/// Element ids keep their marker bit; sizes drop it, and all ones means unknown (`Some(None)`).
+fn read_vint(data: &[u8], keep_marker: bool) -> Result<Option<(Option<u64>, usize)>> {
+    let Some(&first) = data.first() else {
+        return Ok(None);
+    };
+    // A zero first byte would mean a length above 8 bytes
+    if first == 0 {
+        bail!("Invalid EBML variable-length integer");
+    }
+    let len = first.leading_zeros() as usize + 1;
+    if data.len() < len {
+        return Ok(None);
+    }
+    // Bits after the length marker; the shift is done in u16 as it reaches 8
+    let bits = (0xffu16 >> len) as u8;
+    let mut value = if keep_marker { first } else { first & bits } as u64;
+    let mut all_ones = first & bits == bits;
+    for &b in &data[1..len] {
+        value = (value << 8) | b as u64;
+        all_ones &= b == 0xff;
+    }
+    if all_ones && !keep_marker {
+        return Ok(Some((None, len)));
+    }
+    Ok(Some((Some(value), len)))
+}
+
+/// Decode an Ogg Opus (`.ogg`, `.opus`) or WebM Opus (`.webm`) file to mono samples
+/// at `sample_rate`
+pub fn decode_opus_file<P: AsRef<Path>>(path: P, sample_rate: u32) -> Result<Vec<f32>> {
+    let bytes = std::fs::read(path)?;
+    decode_opus_bytes(&bytes, sample_rate)
+}
+
+/// Same as `decode_opus_file` for a file already in memory. The container is detected
+/// from its first bytes.
+pub fn decode_opus_bytes(bytes: &[u8], sample_rate: u32) -> Result<Vec<f32>> {
+    if bytes.starts_with(b"OggS") {
+        OggOpusReader::new(sample_rate).push(bytes)
+    } else if bytes.starts_with(&EBML_HEADER.to_be_bytes()) {
+        WebmOpusReader::new(sample_rate).push(bytes)
+    } else {
+        bail!("Not an Ogg or WebM file")
+    }
+}
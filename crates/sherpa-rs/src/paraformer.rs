@@ -1,12 +1,23 @@
-use crate::{get_default_provider, utils::cstring_from_str};
+use crate::config_dump::{self, ConfigDump, NativeConfig};
+use crate::{
+    get_default_provider,
+    offline_stream::{self, OfflineStream},
+    utils::cstring_from_str,
+};
 use eyre::{bail, Result};
 use std::{mem, ptr::null};
 
 #[derive(Debug)]
 pub struct ParaformerRecognizer {
     recognizer: *const sherpa_rs_sys::SherpaOnnxOfflineRecognizer,
+    config_dump: ConfigDump,
 }
 
+/// Result of a Paraformer recognizer.
+///
+/// Models exported with timestamp outputs (e.g. `paraformer-zh` with `us_cif_peak`)
+/// fill `timestamps` with the start of each token: use `token_timings` for per-character
+/// times, or `words(TokenStyle::ContinuationMarker)` to join `@@` subwords into words.
 pub type ParaformerRecognizerResult = super::OfflineRecognizerResult;
 
 #[derive(Debug, Clone)]
@@ -32,7 +43,7 @@ impl Default for ParaformerConfig {
 
 impl ParaformerRecognizer {
     pub fn new(config: ParaformerConfig) -> Result<Self> {
-        let debug = config.debug.into();
+        let debug = crate::native_debug(config.debug);
         let provider = config.provider.unwrap_or(get_default_provider());
 
         // Prepare C strings
@@ -96,34 +107,38 @@ impl ParaformerRecognizer {
             }
         };
 
+        let config_dump = config_dump::offline_recognizer(&recognizer_config);
         let recognizer =
             unsafe { sherpa_rs_sys::SherpaOnnxCreateOfflineRecognizer(&recognizer_config) };
         if recognizer.is_null() {
             bail!("Failed to create Paraformer recognizer");
         }
 
-        Ok(Self { recognizer })
+        Ok(Self {
+            recognizer,
+            config_dump,
+        })
     }
 
+    pub fn create_stream(&self) -> Result<OfflineStream<'_>> {
+        unsafe { OfflineStream::new(self.recognizer) }
+    }
+
+    /// Transcribe a whole utterance. See `ParaformerRecognizerResult` for its timestamps.
     pub fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> ParaformerRecognizerResult {
-        unsafe {
-            let stream = sherpa_rs_sys::SherpaOnnxCreateOfflineStream(self.recognizer);
-            sherpa_rs_sys::SherpaOnnxAcceptWaveformOffline(
-                stream,
-                sample_rate as i32,
-                samples.as_ptr(),
-                samples.len() as i32,
-            );
-            sherpa_rs_sys::SherpaOnnxDecodeOfflineStream(self.recognizer, stream);
-            let result_ptr = sherpa_rs_sys::SherpaOnnxGetOfflineStreamResult(stream);
-            let raw_result = result_ptr.read();
-            let result = ParaformerRecognizerResult::new(&raw_result);
-
-            sherpa_rs_sys::SherpaOnnxDestroyOfflineRecognizerResult(result_ptr);
-            sherpa_rs_sys::SherpaOnnxDestroyOfflineStream(stream);
-
-            result
-        }
+        offline_stream::transcribe_once(self.create_stream(), sample_rate, samples)
+    }
+}
+
+impl NativeConfig for ParaformerRecognizer {
+    fn config_dump(&self) -> &ConfigDump {
+        &self.config_dump
+    }
+}
+
+impl crate::Transcriber for ParaformerRecognizer {
+    fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> crate::OfflineRecognizerResult {
+        ParaformerRecognizer::transcribe(self, sample_rate, samples)
     }
 }
 
@@ -0,0 +1,299 @@
+use crate::{
+    denoise::Denoiser,
+    levels::LevelCheck,
+    post_process::PostProcessors,
+    preprocess::{Agc, AudioProcessors, HighPass},
+    punctuate::Punctuation,
+    resample::Resampler,
+    AudioProcessor, OfflineRecognizerResult, Transcriber, VoiceActivityDetector,
+};
+use eyre::{bail, Result};
+
+pub use crate::post_process::PostProcessor;
+
+#[derive(Debug, Clone)]
+pub struct PipelineSegment {
+    /// Start time in seconds, relative to the first sample pushed into the pipeline
+    pub start: f32,
+    /// Duration in seconds
+    pub duration: f32,
+    /// Text after punctuation and post-processing
+    pub text: String,
+    /// Recognizer output, with `text` and anything the result post-processors changed
+    /// updated to match
+    pub result: OfflineRecognizerResult,
+}
+
+pub struct PipelineBuilder {
+    recognizer: Box<dyn Transcriber + Send>,
+    input_sample_rate: Option<u32>,
+    sample_rate: u32,
+    level_check: LevelCheck,
+    high_pass: Option<HighPass>,
+    denoiser: Option<Denoiser>,
+    processors: AudioProcessors,
+    vad: Option<Box<dyn VoiceActivityDetector + Send>>,
+    punctuation: Option<Punctuation>,
+    post_processors: PostProcessors,
+}
+
+impl PipelineBuilder {
+    /// Sample rate of the audio passed to `push` / `process`.
+    /// When it differs from `sample_rate` a resampling stage is added.
+    pub fn input_sample_rate(mut self, sample_rate: u32) -> Self {
+        self.input_sample_rate = Some(sample_rate);
+        self
+    }
+
+    /// Sample rate the models run at. Defaults to 16000.
+    /// The VAD must be configured with the same rate.
+    pub fn sample_rate(mut self, sample_rate: u32) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    /// Check input levels for out of range, silent or clipped audio.
+    /// `push` checks each chunk and warns once per stream at most; silence is only reported
+    /// by `process`.
+    pub fn level_check(mut self, level_check: LevelCheck) -> Self {
+        self.level_check = level_check;
+        self
+    }
+
+    /// Filter out DC offset and rumble before any other stage.
+    /// Must run at `sample_rate`.
+    pub fn high_pass(mut self, high_pass: HighPass) -> Self {
+        self.high_pass = Some(high_pass);
+        self
+    }
+
+    /// Denoise audio before VAD. Chunks are denoised independently,
+    /// so prefer pushing chunks of at least a few hundred milliseconds.
+    pub fn denoiser(mut self, denoiser: Denoiser) -> Self {
+        self.denoiser = Some(denoiser);
+        self
+    }
+
+    /// Append a preprocessing stage, e.g. `Agc`, `HighPass` or a closure over the samples.
+    /// Stages run after denoising and before VAD, in the order they were added.
+    /// Their output is cut or zero-padded to the input length, so timestamps stay in sync
+    /// with the audio pushed.
+    pub fn processor<P: AudioProcessor + Send + 'static>(mut self, processor: P) -> Self {
+        self.processors.push(processor);
+        self
+    }
+
+    /// Bring quiet audio up to a steady level
+    pub fn agc(self, agc: Agc) -> Self {
+        self.processor(agc)
+    }
+
+    /// Split the audio into speech segments before recognition.
+    /// Without a VAD, audio is buffered and recognized as a single segment on `flush`.
+    pub fn vad<V: VoiceActivityDetector + Send + 'static>(mut self, vad: V) -> Self {
+        self.vad = Some(Box::new(vad));
+        self
+    }
+
+    pub fn punctuation(mut self, punctuation: Punctuation) -> Self {
+        self.punctuation = Some(punctuation);
+        self
+    }
+
+    /// Append a text post-processor. Post-processors run in the order they were added.
+    pub fn post_processor<F: FnMut(String) -> String + Send + 'static>(mut self, f: F) -> Self {
+        self.post_processors.push(f);
+        self
+    }
+
+    /// Append a hook over the full segment result, run in order with the text post-processors.
+    /// `result.text` already has punctuation applied.
+    pub fn result_post_processor<F: FnMut(&mut OfflineRecognizerResult) + Send + 'static>(
+        mut self,
+        f: F,
+    ) -> Self {
+        self.post_processors.push_result(f);
+        self
+    }
+
+    pub fn build(self) -> Result<Pipeline> {
+        if let Some(denoiser) = &self.denoiser {
+            if denoiser.sample_rate() != self.sample_rate {
+                bail!(
+                    "Denoiser expects {} Hz but the pipeline runs at {} Hz",
+                    denoiser.sample_rate(),
+                    self.sample_rate
+                );
+            }
+        }
+        let resampler = match self.input_sample_rate {
+            Some(rate) if rate != self.sample_rate => Some(Resampler::new(rate, self.sample_rate)?),
+            _ => None,
+        };
+
+        Ok(Pipeline {
+            recognizer: self.recognizer,
+            resampler,
+            sample_rate: self.sample_rate,
+            level_check: self.level_check,
+            level_warned: false,
+            high_pass: self.high_pass,
+            denoiser: self.denoiser,
+            processors: self.processors,
+            vad: self.vad,
+            punctuation: self.punctuation,
+            post_processors: self.post_processors,
+            buffer: Vec::new(),
+            position: 0,
+        })
+    }
+}
+
+/// Chains the optional stages resample -> high-pass -> denoise -> preprocessors -> VAD -> ASR ->
+/// punctuation -> post-processors behind a single `process` / `push` API.
+pub struct Pipeline {
+    recognizer: Box<dyn Transcriber + Send>,
+    resampler: Option<Resampler>,
+    sample_rate: u32,
+    level_check: LevelCheck,
+    /// Whether `push` already warned about the levels of the current stream
+    level_warned: bool,
+    high_pass: Option<HighPass>,
+    denoiser: Option<Denoiser>,
+    processors: AudioProcessors,
+    vad: Option<Box<dyn VoiceActivityDetector + Send>>,
+    punctuation: Option<Punctuation>,
+    post_processors: PostProcessors,
+    /// Pending audio when there's no VAD
+    buffer: Vec<f32>,
+    /// Samples received so far, at the model sample rate
+    position: usize,
+}
+
+impl Pipeline {
+    pub fn builder<T: Transcriber + Send + 'static>(recognizer: T) -> PipelineBuilder {
+        PipelineBuilder {
+            recognizer: Box::new(recognizer),
+            input_sample_rate: None,
+            sample_rate: 16000,
+            level_check: LevelCheck::Off,
+            high_pass: None,
+            denoiser: None,
+            processors: AudioProcessors::new(),
+            vad: None,
+            punctuation: None,
+            post_processors: PostProcessors::new(),
+        }
+    }
+
+    /// Process a complete recording and return all of its segments
+    pub fn process(&mut self, samples: &[f32]) -> Result<Vec<PipelineSegment>> {
+        self.level_check.check(samples)?;
+        let mut segments = self.resample_and_feed(samples)?;
+        segments.extend(self.flush()?);
+        Ok(segments)
+    }
+
+    /// Feed the next chunk of a stream and return the segments completed so far
+    pub fn push(&mut self, chunk: &[f32]) -> Result<Vec<PipelineSegment>> {
+        // Once is enough for a stream
+        if !self.level_warned {
+            self.level_warned = self.level_check.check_chunk(chunk)?;
+        }
+        self.resample_and_feed(chunk)
+    }
+
+    fn resample_and_feed(&mut self, chunk: &[f32]) -> Result<Vec<PipelineSegment>> {
+        let samples = match &mut self.resampler {
+            Some(resampler) => resampler.resample(chunk, false),
+            None => chunk.to_vec(),
+        };
+        self.feed(samples)
+    }
+
+    /// Signal end of input and return the remaining segments.
+    /// More audio can be pushed afterwards; timestamps keep counting from the first stream.
+    pub fn flush(&mut self) -> Result<Vec<PipelineSegment>> {
+        self.level_warned = false;
+        let mut segments = Vec::new();
+        if let Some(resampler) = &mut self.resampler {
+            let tail = resampler.resample(&[], true);
+            resampler.reset();
+            segments.extend(self.feed(tail)?);
+        }
+
+        match &mut self.vad {
+            Some(vad) => {
+                vad.flush();
+                segments.extend(self.drain_vad());
+            }
+            None => {
+                let samples = std::mem::take(&mut self.buffer);
+                let start = self.position - samples.len();
+                segments.extend(self.recognize(start, &samples));
+            }
+        }
+        Ok(segments)
+    }
+
+    fn feed(&mut self, mut samples: Vec<f32>) -> Result<Vec<PipelineSegment>> {
+        if samples.is_empty() {
+            return Ok(Vec::new());
+        }
+        let len = samples.len();
+        if let Some(high_pass) = &mut self.high_pass {
+            high_pass.process(&mut samples);
+        }
+        if let Some(denoiser) = &mut self.denoiser {
+            samples = denoiser.denoise(&samples, self.sample_rate)?.samples;
+        }
+        self.processors.process(&mut samples);
+        if samples.len() != len {
+            tracing::debug!("preprocessing changed {} samples to {}", len, samples.len());
+            samples.resize(len, 0.0);
+        }
+        self.position += len;
+
+        match &mut self.vad {
+            Some(vad) => {
+                vad.accept_waveform(samples);
+                Ok(self.drain_vad())
+            }
+            None => {
+                self.buffer.extend(samples);
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    fn drain_vad(&mut self) -> Vec<PipelineSegment> {
+        let mut segments = Vec::new();
+        while let Some(segment) = self.vad.as_mut().and_then(|vad| vad.pop_segment()) {
+            let start = segment.start.max(0) as usize;
+            segments.extend(self.recognize(start, &segment.samples));
+        }
+        segments
+    }
+
+    fn recognize(&mut self, start: usize, samples: &[f32]) -> Option<PipelineSegment> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut result = self.recognizer.transcribe(self.sample_rate, samples);
+        result.text = result.text.trim().to_string();
+        if result.text.is_empty() {
+            return None;
+        }
+        if let Some(punctuation) = &mut self.punctuation {
+            result.text = punctuation.add_punctuation(&result.text);
+        }
+        self.post_processors.apply(&mut result);
+
+        Some(PipelineSegment {
+            start: start as f32 / self.sample_rate as f32,
+            duration: samples.len() as f32 / self.sample_rate as f32,
+            text: result.text.clone(),
+            result,
+        })
+    }
+}
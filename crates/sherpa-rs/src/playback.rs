@@ -0,0 +1,226 @@
+use crate::{resample::Resampler, tts::TextToSpeech};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use eyre::{bail, Result};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+
+struct Queue {
+    samples: Mutex<VecDeque<f32>>,
+    /// Signaled whenever the device consumes samples or playback is stopped
+    changed: Condvar,
+    stopped: AtomicBool,
+}
+
+impl Queue {
+    fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        self.samples.lock().unwrap().clear();
+        self.changed.notify_all();
+    }
+}
+
+/// Cloneable, thread-safe control over an `AudioPlayer`
+#[derive(Clone)]
+pub struct PlaybackHandle {
+    queue: Arc<Queue>,
+}
+
+impl PlaybackHandle {
+    /// Same as `AudioPlayer::stop`
+    pub fn stop(&self) {
+        self.queue.stop();
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.queue.stopped.load(Ordering::SeqCst)
+    }
+
+    pub fn is_playing(&self) -> bool {
+        !self.queue.samples.lock().unwrap().is_empty()
+    }
+}
+
+/// Plays mono f32 audio on the default output device.
+///
+/// At most `queue_seconds` of audio are buffered: `play` blocks until the device
+/// catches up, so a producer can't run far ahead of what is heard.
+pub struct AudioPlayer {
+    _stream: cpal::Stream,
+    queue: Arc<Queue>,
+    sample_rate: u32,
+    max_queued: usize,
+    resampler: Mutex<Option<Resampler>>,
+}
+
+impl AudioPlayer {
+    pub fn open(queue_seconds: f32) -> Result<Self> {
+        let host = cpal::default_host();
+        let Some(device) = host.default_output_device() else {
+            bail!("No output device available");
+        };
+        let config = device.default_output_config()?;
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels() as usize;
+        let queue = Arc::new(Queue {
+            samples: Mutex::new(VecDeque::new()),
+            changed: Condvar::new(),
+            stopped: AtomicBool::new(false),
+        });
+
+        let q = queue.clone();
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::F32 => build_stream::<f32>(&device, &config.into(), channels, q)?,
+            cpal::SampleFormat::I16 => build_stream::<i16>(&device, &config.into(), channels, q)?,
+            cpal::SampleFormat::U16 => build_stream::<u16>(&device, &config.into(), channels, q)?,
+            cpal::SampleFormat::I32 => build_stream::<i32>(&device, &config.into(), channels, q)?,
+            format => bail!("Unsupported sample format {format}"),
+        };
+        stream.play()?;
+
+        Ok(Self {
+            _stream: stream,
+            queue,
+            sample_rate,
+            max_queued: ((queue_seconds * sample_rate as f32) as usize).max(1),
+            resampler: Mutex::new(None),
+        })
+    }
+
+    /// Sample rate of the output device
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Queue audio for playback, blocking while the queue is full.
+    /// Returns immediately, dropping the audio, once `stop` was called.
+    pub fn play(&self, samples: &[f32], sample_rate: u32) -> Result<()> {
+        self.enqueue(samples, sample_rate, false)
+    }
+
+    /// Drop all queued audio and make `play` and `speak` return early,
+    /// e.g. when the user starts talking over the output
+    pub fn stop(&self) {
+        self.queue.stop();
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.queue.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Handle that can stop playback from another thread while `speak` is running
+    pub fn handle(&self) -> PlaybackHandle {
+        PlaybackHandle {
+            queue: self.queue.clone(),
+        }
+    }
+
+    /// Accept audio again after `stop`
+    pub fn resume(&self) {
+        // Drop the tail of the interrupted audio, so it isn't played ahead of what's next
+        *self.resampler.lock().unwrap() = None;
+        self.queue.stopped.store(false, Ordering::SeqCst);
+    }
+
+    /// Whether queued audio is still being played
+    pub fn is_playing(&self) -> bool {
+        !self.queue.samples.lock().unwrap().is_empty()
+    }
+
+    /// Block until the queue is empty or playback is stopped
+    pub fn wait(&self) {
+        let mut samples = self.queue.samples.lock().unwrap();
+        while !samples.is_empty() && !self.is_stopped() {
+            samples = self.queue.changed.wait(samples).unwrap();
+        }
+    }
+
+    /// Synthesize `text` and play it as it's generated.
+    ///
+    /// Playback starts with the first generated batch of sentences, so set
+    /// `max_num_sentences` to 1 in the TTS config for the lowest latency.
+    /// Synthesis pauses while the queue is full. Returns `false` when interrupted by `stop`,
+    /// or right away if playback is stopped already; call `resume` first to play again.
+    pub fn speak<T: TextToSpeech + ?Sized>(
+        &self,
+        tts: &mut T,
+        text: &str,
+        sid: i32,
+        speed: f32,
+    ) -> Result<bool> {
+        if self.is_stopped() {
+            return Ok(false);
+        }
+        let sample_rate = tts.sample_rate();
+        let mut error = None;
+        tts.create_with_progress(text, sid, speed, &mut |progress| {
+            if let Err(err) = self.enqueue(progress.samples, sample_rate, false) {
+                error = Some(err);
+            }
+            error.is_none() && !self.is_stopped()
+        })?;
+        if let Some(err) = error {
+            return Err(err);
+        }
+        self.enqueue(&[], sample_rate, true)?;
+        self.wait();
+        Ok(!self.is_stopped())
+    }
+
+    fn enqueue(&self, samples: &[f32], sample_rate: u32, flush: bool) -> Result<()> {
+        let samples = if sample_rate == self.sample_rate {
+            samples.to_vec()
+        } else {
+            let mut resampler = self.resampler.lock().unwrap();
+            if resampler
+                .as_ref()
+                .is_none_or(|r| r.input_sample_rate() != sample_rate)
+            {
+                *resampler = Some(Resampler::new(sample_rate, self.sample_rate)?);
+            }
+            resampler.as_mut().unwrap().resample(samples, flush)
+        };
+
+        let mut rest = samples.as_slice();
+        while !rest.is_empty() {
+            let mut queued = self.queue.samples.lock().unwrap();
+            while queued.len() >= self.max_queued && !self.is_stopped() {
+                queued = self.queue.changed.wait(queued).unwrap();
+            }
+            if self.is_stopped() {
+                return Ok(());
+            }
+            let n = rest.len().min(self.max_queued - queued.len());
+            queued.extend(&rest[..n]);
+            rest = &rest[n..];
+        }
+        Ok(())
+    }
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    channels: usize,
+    queue: Arc<Queue>,
+) -> Result<cpal::Stream>
+where
+    T: cpal::SizedSample + cpal::FromSample<f32>,
+{
+    let stream = device.build_output_stream(
+        config,
+        move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+            let mut samples = queue.samples.lock().unwrap();
+            for frame in data.chunks_mut(channels) {
+                // Silence on underrun
+                let sample = samples.pop_front().unwrap_or(0.0);
+                frame.fill(T::from_sample(sample));
+            }
+            drop(samples);
+            queue.changed.notify_all();
+        },
+        |err| tracing::error!("audio playback error: {}", err),
+        None,
+    )?;
+    Ok(stream)
+}
@@ -0,0 +1,294 @@
+use crate::{
+    alignment::is_cjk, OfflineRecognizerResult, OnlineTranscriber, StreamingError, Transcriber,
+};
+
+pub type PostProcessor = Box<dyn FnMut(String) -> String + Send>;
+pub type ResultPostProcessor = Box<dyn FnMut(&mut OfflineRecognizerResult) + Send>;
+
+enum Hook {
+    Text(PostProcessor),
+    Result(ResultPostProcessor),
+}
+
+/// Ordered list of hooks applied to recognizer output before it's returned.
+/// Text hooks rewrite the text; result hooks can also touch tokens, timestamps and language.
+#[derive(Default)]
+pub struct PostProcessors {
+    hooks: Vec<Hook>,
+}
+
+impl PostProcessors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Append a text hook. Hooks run in the order they were added.
+    pub fn push<F: FnMut(String) -> String + Send + 'static>(&mut self, f: F) {
+        self.hooks.push(Hook::Text(Box::new(f)));
+    }
+
+    /// Append a hook over the full result. Only runs on offline results.
+    pub fn push_result<F: FnMut(&mut OfflineRecognizerResult) + Send + 'static>(&mut self, f: F) {
+        self.hooks.push(Hook::Result(Box::new(f)));
+    }
+
+    /// Run the text hooks on `text`
+    pub fn apply_text(&mut self, mut text: String) -> String {
+        for hook in &mut self.hooks {
+            if let Hook::Text(f) = hook {
+                text = f(text);
+            }
+        }
+        text
+    }
+
+    /// Run every hook on `result`, text hooks on `result.text`
+    pub fn apply(&mut self, result: &mut OfflineRecognizerResult) {
+        for hook in &mut self.hooks {
+            match hook {
+                Hook::Text(f) => result.text = f(std::mem::take(&mut result.text)),
+                Hook::Result(f) => f(result),
+            }
+        }
+    }
+}
+
+/// Recognizer wrapper that runs post-processors on everything it returns.
+/// Works with offline (`Transcriber`) and online (`OnlineTranscriber`) recognizers;
+/// for online ones the text hooks run on partial results as well.
+pub struct PostProcessed<R> {
+    inner: R,
+    post_processors: PostProcessors,
+}
+
+impl<R> PostProcessed<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            post_processors: PostProcessors::new(),
+        }
+    }
+
+    pub fn post_processor<F: FnMut(String) -> String + Send + 'static>(mut self, f: F) -> Self {
+        self.post_processors.push(f);
+        self
+    }
+
+    pub fn result_post_processor<F: FnMut(&mut OfflineRecognizerResult) + Send + 'static>(
+        mut self,
+        f: F,
+    ) -> Self {
+        self.post_processors.push_result(f);
+        self
+    }
+
+    pub fn post_processors(&mut self) -> &mut PostProcessors {
+        &mut self.post_processors
+    }
+
+    pub fn inner(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Transcriber> Transcriber for PostProcessed<R> {
+    fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> OfflineRecognizerResult {
+        let mut result = self.inner.transcribe(sample_rate, samples);
+        self.post_processors.apply(&mut result);
+        result
+    }
+}
+
+impl<R: OnlineTranscriber> OnlineTranscriber for PostProcessed<R> {
+    fn accept_waveform(&mut self, sample_rate: u32, samples: &[f32]) -> Result<(), StreamingError> {
+        self.inner.accept_waveform(sample_rate, samples)
+    }
+
+    fn transcribe(&mut self) -> String {
+        let text = self.inner.transcribe();
+        self.post_processors.apply_text(text)
+    }
+
+    fn is_endpoint(&self) -> bool {
+        self.inner.is_endpoint()
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn input_finished(&mut self) {
+        self.inner.input_finished();
+    }
+}
+
+/// Built-in post-processor for word replacements and masking of flagged words,
+/// e.g. profanity filtering on final results.
+///
+/// Plain words match whole words, case-insensitively. In scripts written without spaces,
+/// e.g. Chinese or Japanese, there are no word boundaries to check, so they match anywhere.
+/// CJK characters count as word characters though, so a Latin word written right next
+/// to one, as in `好ok`, doesn't match.
+/// Rules run in the order they were added, masking after all replacements.
+#[derive(Debug, Clone)]
+pub struct WordFilter {
+    replacements: Vec<(regex::Regex, Replacement)>,
+    masked: Vec<regex::Regex>,
+    mask_char: char,
+}
+
+impl Default for WordFilter {
+    fn default() -> Self {
+        Self {
+            replacements: Vec::new(),
+            masked: Vec::new(),
+            mask_char: '*',
+        }
+    }
+}
+
+impl WordFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the whole word or phrase `from` with `to`, taken literally
+    pub fn replace(mut self, from: &str, to: &str) -> Self {
+        self.replacements
+            .push((word_regex(from), Replacement::Literal(to.to_string())));
+        self
+    }
+
+    /// Replace every match of `pattern`. `to` may reference capture groups, e.g. `$1`.
+    pub fn replace_regex(mut self, pattern: &str, to: &str) -> eyre::Result<Self> {
+        self.replacements.push((
+            regex::Regex::new(pattern)?,
+            Replacement::Template(to.to_string()),
+        ));
+        Ok(self)
+    }
+
+    /// Mask the whole word or phrase `word`
+    pub fn mask(mut self, word: &str) -> Self {
+        self.masked.push(word_regex(word));
+        self
+    }
+
+    /// Mask every match of `pattern`
+    pub fn mask_regex(mut self, pattern: &str) -> eyre::Result<Self> {
+        self.masked.push(regex::Regex::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Character masked words are replaced with, one per character. Defaults to `*`.
+    pub fn mask_char(mut self, mask_char: char) -> Self {
+        self.mask_char = mask_char;
+        self
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for (pattern, to) in &self.replacements {
+            text = match to {
+                Replacement::Literal(to) => pattern.replace_all(&text, regex::NoExpand(to)),
+                Replacement::Template(to) => pattern.replace_all(&text, to.as_str()),
+            }
+            .into_owned();
+        }
+        for pattern in &self.masked {
+            text = pattern
+                .replace_all(&text, |caps: &regex::Captures| {
+                    caps[0]
+                        .chars()
+                        .map(|c| if c.is_whitespace() { c } else { self.mask_char })
+                        .collect::<String>()
+                })
+                .into_owned();
+        }
+        text
+    }
+
+    /// Turn the filter into a text hook for `PostProcessors` / `PostProcessed` / pipelines
+    pub fn into_post_processor(self) -> impl FnMut(String) -> String + Send + 'static {
+        move |text| self.apply(&text)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Replacement {
+    Literal(String),
+    /// May reference capture groups
+    Template(String),
+}
+
+fn word_regex(word: &str) -> regex::Regex {
+    let word = word.trim();
+    // `\b` only makes sense next to a word character of a script that separates words
+    // with spaces. Around CJK characters, which are word characters too, it never matches.
+    let boundary = |c: Option<char>| match c {
+        Some(c) if c.is_alphanumeric() && !is_cjk(c) => r"\b",
+        _ => "",
+    };
+    let pattern = format!(
+        "(?i){}{}{}",
+        boundary(word.chars().next()),
+        regex::escape(word),
+        boundary(word.chars().last())
+    );
+    regex::Regex::new(&pattern).expect("escaped pattern is valid")
+}
+
+/// Longest token sequence `collapse_repetitions` looks for
+const MAX_REPEATED_TOKENS: usize = 16;
+
+/// Cut runs of the same token sequence repeated more than `max_repeats` times in a row
+/// down to `max_repeats` copies, rebuilding `text` from the remaining tokens.
+///
+/// Whisper-style decoders sometimes loop on a phrase until they hit their token limit,
+/// typically on silence or noise. Results without tokens are left alone.
+pub fn collapse_repetitions(result: &mut OfflineRecognizerResult, max_repeats: usize) {
+    let max_repeats = max_repeats.max(1);
+    let tokens = &result.tokens;
+    let mut keep = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        // Shortest sequence starting here that repeats too often
+        let run = (1..=MAX_REPEATED_TOKENS.min((tokens.len() - i) / 2))
+            .map(|len| {
+                let unit = &tokens[i..i + len];
+                let repeats = tokens[i..]
+                    .chunks_exact(len)
+                    .take_while(|chunk| *chunk == unit)
+                    .count();
+                (len, repeats)
+            })
+            .find(|&(_, repeats)| repeats > max_repeats);
+        match run {
+            Some((len, repeats)) => {
+                keep.extend(i..i + len * max_repeats);
+                i += len * repeats;
+            }
+            None => {
+                keep.push(i);
+                i += 1;
+            }
+        }
+    }
+    if keep.len() == tokens.len() {
+        return;
+    }
+
+    if result.timestamps.len() == result.tokens.len() {
+        result.timestamps = keep.iter().map(|&i| result.timestamps[i]).collect();
+    }
+    result.tokens = keep.iter().map(|&i| result.tokens[i].clone()).collect();
+    result.text = result.tokens.concat().trim().to_string();
+}
@@ -0,0 +1,289 @@
+use crate::{
+    AudioProcessor, OfflineRecognizerResult, OnlineTranscriber, StreamingError, Transcriber,
+};
+use eyre::{bail, Result};
+
+/// Samples per gain update
+const AGC_FRAME_LEN: usize = 160;
+
+#[derive(Debug, Clone)]
+pub struct AgcConfig {
+    /// Level speech is brought to
+    pub target_rms: f32,
+    /// Largest amplification, so background noise isn't blown up in quiet passages
+    pub max_gain: f32,
+    /// Frames quieter than this count as silence and leave the gain unchanged
+    pub min_rms: f32,
+    /// Share of the way to the desired gain moved per 160-sample frame, from 0 to 1.
+    /// Higher reacts faster but pumps more.
+    pub adaptation: f32,
+}
+
+impl Default for AgcConfig {
+    fn default() -> Self {
+        Self {
+            target_rms: 0.1,
+            max_gain: 10.0,
+            min_rms: 0.001,
+            adaptation: 0.05,
+        }
+    }
+}
+
+/// Automatic gain control for quiet far-field audio.
+///
+/// The gain follows the level of the incoming audio, is capped at `max_gain` and
+/// backed off whenever it would push a frame past full scale.
+#[derive(Debug, Clone)]
+pub struct Agc {
+    config: AgcConfig,
+    gain: f32,
+}
+
+impl Agc {
+    pub fn new(config: AgcConfig) -> Result<Self> {
+        if config.target_rms <= 0.0 || config.max_gain <= 0.0 {
+            bail!("target_rms and max_gain must be positive");
+        }
+        if !(config.adaptation > 0.0 && config.adaptation <= 1.0) {
+            bail!("adaptation must be in (0, 1]");
+        }
+        Ok(Self { config, gain: 1.0 })
+    }
+
+    /// Current gain
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    pub fn reset(&mut self) {
+        self.gain = 1.0;
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for frame in samples.chunks_mut(AGC_FRAME_LEN) {
+            let energy: f32 = frame.iter().map(|s| s * s).sum();
+            let rms = (energy / frame.len() as f32).sqrt();
+            let peak = frame.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+
+            let previous = self.gain;
+            if rms >= self.config.min_rms {
+                let desired = (self.config.target_rms / rms).min(self.config.max_gain);
+                self.gain += (desired - self.gain) * self.config.adaptation;
+            }
+            if peak * self.gain > 1.0 {
+                self.gain = 1.0 / peak;
+            }
+
+            // Ramp across the frame to avoid steps in the gain, unless the old gain
+            // would already clip it
+            let start = if peak * previous > 1.0 {
+                self.gain
+            } else {
+                previous
+            };
+            let step = (self.gain - start) / frame.len() as f32;
+            for (i, sample) in frame.iter_mut().enumerate() {
+                let gain = start + step * (i + 1) as f32;
+                *sample = (*sample * gain).clamp(-1.0, 1.0);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HighPassConfig {
+    pub sample_rate: u32,
+    /// Frequencies below this (Hz) are attenuated
+    pub cutoff: f32,
+}
+
+impl Default for HighPassConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 16000,
+            cutoff: 80.0,
+        }
+    }
+}
+
+/// Second-order Butterworth high-pass filter.
+///
+/// Removes DC offset and low rumble, e.g. from cheap USB microphones, which otherwise
+/// skew the features the models see. Speech above about 100 Hz passes unchanged.
+#[derive(Debug, Clone)]
+pub struct HighPass {
+    b: [f32; 3],
+    a: [f32; 2],
+    /// Last two inputs and outputs
+    x: [f32; 2],
+    y: [f32; 2],
+}
+
+impl HighPass {
+    pub fn new(config: HighPassConfig) -> Result<Self> {
+        let nyquist = config.sample_rate as f32 / 2.0;
+        if !(config.cutoff > 0.0 && config.cutoff < nyquist) {
+            bail!("cutoff must be between 0 and {} Hz", nyquist);
+        }
+        let w0 = std::f32::consts::TAU * config.cutoff / config.sample_rate as f32;
+        let alpha = w0.sin() / std::f32::consts::SQRT_2;
+        let cos = w0.cos();
+        let a0 = 1.0 + alpha;
+        Ok(Self {
+            b: [
+                (1.0 + cos) / 2.0 / a0,
+                -(1.0 + cos) / a0,
+                (1.0 + cos) / 2.0 / a0,
+            ],
+            a: [-2.0 * cos / a0, (1.0 - alpha) / a0],
+            x: [0.0; 2],
+            y: [0.0; 2],
+        })
+    }
+
+    pub fn reset(&mut self) {
+        self.x = [0.0; 2];
+        self.y = [0.0; 2];
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples {
+            let x = *sample;
+            let y = self.b[0] * x + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+                - self.a[0] * self.y[0]
+                - self.a[1] * self.y[1];
+            self.x = [x, self.x[0]];
+            self.y = [y, self.y[0]];
+            *sample = y;
+        }
+    }
+}
+
+impl AudioProcessor for Agc {
+    fn process(&mut self, samples: &mut Vec<f32>) {
+        Agc::process(self, samples)
+    }
+}
+
+impl AudioProcessor for HighPass {
+    fn process(&mut self, samples: &mut Vec<f32>) {
+        HighPass::process(self, samples)
+    }
+}
+
+impl<F: FnMut(&mut Vec<f32>)> AudioProcessor for F {
+    fn process(&mut self, samples: &mut Vec<f32>) {
+        self(samples)
+    }
+}
+
+/// Ordered chain of preprocessing stages, itself usable as one stage
+#[derive(Default)]
+pub struct AudioProcessors {
+    stages: Vec<Box<dyn AudioProcessor + Send>>,
+}
+
+impl AudioProcessors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Append a stage. Stages run in the order they were added.
+    pub fn push<P: AudioProcessor + Send + 'static>(&mut self, processor: P) {
+        self.stages.push(Box::new(processor));
+    }
+}
+
+impl AudioProcessor for AudioProcessors {
+    fn process(&mut self, samples: &mut Vec<f32>) {
+        for stage in &mut self.stages {
+            stage.process(samples);
+        }
+    }
+}
+
+/// Recognizer with preprocessing applied to its input audio.
+///
+/// Wraps offline and online recognizers alike, so each recognizer can have its own chain.
+pub struct Preprocessed<R> {
+    inner: R,
+    processors: AudioProcessors,
+}
+
+impl<R> Preprocessed<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            processors: AudioProcessors::new(),
+        }
+    }
+
+    /// Append a stage, e.g. `Agc`, `HighPass` or a closure over the samples.
+    /// Stages run in the order they were added.
+    pub fn processor<P: AudioProcessor + Send + 'static>(mut self, processor: P) -> Self {
+        self.processors.push(processor);
+        self
+    }
+
+    /// Filter out DC offset and rumble. Add it before the AGC.
+    pub fn high_pass(self, high_pass: HighPass) -> Self {
+        self.processor(high_pass)
+    }
+
+    pub fn agc(self, agc: Agc) -> Self {
+        self.processor(agc)
+    }
+
+    pub fn processors(&mut self) -> &mut AudioProcessors {
+        &mut self.processors
+    }
+
+    pub fn inner(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn apply(&mut self, samples: &[f32]) -> Vec<f32> {
+        let mut samples = samples.to_vec();
+        self.processors.process(&mut samples);
+        samples
+    }
+}
+
+impl<R: Transcriber> Transcriber for Preprocessed<R> {
+    fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> OfflineRecognizerResult {
+        let samples = self.apply(samples);
+        self.inner.transcribe(sample_rate, &samples)
+    }
+}
+
+impl<R: OnlineTranscriber> OnlineTranscriber for Preprocessed<R> {
+    fn accept_waveform(&mut self, sample_rate: u32, samples: &[f32]) -> Result<(), StreamingError> {
+        let samples = self.apply(samples);
+        self.inner.accept_waveform(sample_rate, &samples)
+    }
+
+    fn transcribe(&mut self) -> String {
+        self.inner.transcribe()
+    }
+
+    fn is_endpoint(&self) -> bool {
+        self.inner.is_endpoint()
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn input_finished(&mut self) {
+        self.inner.input_finished();
+    }
+}
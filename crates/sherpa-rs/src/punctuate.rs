@@ -20,18 +20,19 @@ pub struct Punctuation {
 impl Punctuation {
     pub fn new(config: PunctuationConfig) -> Result<Self> {
         let model = cstring_from_str(&config.model);
-        let provider = cstring_from_str(&config.provider.unwrap_or(if cfg!(target_os = "macos") {
+        let provider = config.provider.unwrap_or(if cfg!(target_os = "macos") {
             // TODO: sherpa-onnx/issues/1448
             "cpu".into()
         } else {
             get_default_provider()
-        }));
+        });
+        let provider = cstring_from_str(&provider);
 
         let sherpa_config = sherpa_rs_sys::SherpaOnnxOfflinePunctuationConfig {
             model: sherpa_rs_sys::SherpaOnnxOfflinePunctuationModelConfig {
                 ct_transformer: model.as_ptr(),
                 num_threads: config.num_threads.unwrap_or(1),
-                debug: config.debug.into(),
+                debug: crate::native_debug(config.debug),
                 provider: provider.as_ptr(),
             },
         };
@@ -0,0 +1,455 @@
+use crate::{
+    recorder::UtteranceRecorder,
+    shutdown::{self, Stopper},
+    OnlineTranscriber,
+};
+use std::panic;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SessionEvent {
+    /// Hypothesis for the utterance in progress. Sent whenever it changes.
+    Partial(String),
+    /// Utterance finished at an endpoint or at the end of the audio
+    Final(String),
+    /// The recognizer rejected audio with `error` and the stream was recreated with the
+    /// utterance in progress replayed, see `RecoveryPolicy::Replay`
+    Recovered { error: String },
+}
+
+/// What a session does when the recognizer rejects audio.
+///
+/// A panic while decoding is never recovered from, as the recognizer may be left in a
+/// broken state: it ends the session like with `Stop` and is reported by `stop`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RecoveryPolicy {
+    /// End the session
+    #[default]
+    Stop,
+    /// Recreate the stream with `input_finished` and `reset`, replay the audio of the
+    /// utterance in progress and carry on, at most `max_attempts` times per session.
+    /// Keeps a copy of the utterance's audio until its endpoint.
+    Replay { max_attempts: u32 },
+}
+
+/// Audio sent to a session but not decoded yet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backlog {
+    pub chunks: usize,
+    pub samples: usize,
+    /// Seconds of audio the decoder is behind real time
+    pub lag: f32,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FeedError {
+    /// The queue is full. Holds the rejected chunk, which can be dropped or sent again later.
+    #[error("Decoder is {:.2}s behind real time", backlog.lag)]
+    Full { chunk: Vec<f32>, backlog: Backlog },
+
+    #[error("Session has finished")]
+    Disconnected(Vec<f32>),
+}
+
+#[derive(Default)]
+struct Queued {
+    chunks: AtomicUsize,
+    samples: AtomicUsize,
+}
+
+/// Bounded sending side of a session created with `RealtimeSession::bounded`
+#[derive(Clone)]
+pub struct AudioFeeder {
+    tx: SyncSender<Vec<f32>>,
+    queued: Arc<Queued>,
+    sample_rate: u32,
+}
+
+impl AudioFeeder {
+    /// Queue a chunk without blocking. Fails with `FeedError::Full` when the decoder
+    /// is too far behind, so the caller can drop audio or switch to a smaller model
+    /// instead of letting memory grow.
+    pub fn try_send_audio(&self, chunk: Vec<f32>) -> Result<Backlog, FeedError> {
+        let len = chunk.len();
+        // Counted before sending, so the worker never sees more than was added
+        self.queued.chunks.fetch_add(1, Ordering::SeqCst);
+        self.queued.samples.fetch_add(len, Ordering::SeqCst);
+        match self.tx.try_send(chunk) {
+            Ok(()) => Ok(self.backlog()),
+            Err(error) => {
+                self.queued.chunks.fetch_sub(1, Ordering::SeqCst);
+                self.queued.samples.fetch_sub(len, Ordering::SeqCst);
+                Err(match error {
+                    TrySendError::Full(chunk) => FeedError::Full {
+                        chunk,
+                        backlog: self.backlog(),
+                    },
+                    TrySendError::Disconnected(chunk) => FeedError::Disconnected(chunk),
+                })
+            }
+        }
+    }
+
+    /// Queue a chunk, blocking while the queue is full
+    pub fn send_audio(&self, chunk: Vec<f32>) -> Result<(), FeedError> {
+        let len = chunk.len();
+        self.queued.chunks.fetch_add(1, Ordering::SeqCst);
+        self.queued.samples.fetch_add(len, Ordering::SeqCst);
+        self.tx.send(chunk).map_err(|error| {
+            self.queued.chunks.fetch_sub(1, Ordering::SeqCst);
+            self.queued.samples.fetch_sub(len, Ordering::SeqCst);
+            FeedError::Disconnected(error.0)
+        })
+    }
+
+    /// Audio waiting for the decoder, including the chunk being decoded
+    pub fn backlog(&self) -> Backlog {
+        let samples = self.queued.samples.load(Ordering::SeqCst);
+        Backlog {
+            chunks: self.queued.chunks.load(Ordering::SeqCst),
+            samples,
+            lag: samples as f32 / self.sample_rate as f32,
+        }
+    }
+}
+
+/// Delivers an event of the session with the given id; returns false once nobody is listening
+type EventSink = Box<dyn FnMut(u64, SessionEvent) -> bool + Send>;
+
+#[derive(Default)]
+struct SessionOptions {
+    recorder: Option<UtteranceRecorder>,
+    queued: Option<Arc<Queued>>,
+    sink: Option<EventSink>,
+    recovery: RecoveryPolicy,
+}
+
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Runs an online recognizer on a background thread, feeding it from an audio source
+/// and reporting partial and final results on a channel.
+///
+/// Endpoint detection follows the recognizer config, so enable endpointing there
+/// to get a `Final` event per utterance.
+pub struct RealtimeSession<R: OnlineTranscriber + Send + 'static> {
+    id: u64,
+    events: Receiver<SessionEvent>,
+    stop: Arc<AtomicBool>,
+    /// Registered with `shutdown_all`
+    _stopper: Arc<Stopper>,
+    /// Yields `None` when `shutdown_all` destroyed the recognizer
+    worker: Option<JoinHandle<Option<R>>>,
+    #[cfg(feature = "capture")]
+    microphone: Option<crate::capture::Microphone>,
+}
+
+impl<R: OnlineTranscriber + Send + 'static> RealtimeSession<R> {
+    /// Start a session fed from `audio`, mono chunks at `sample_rate`.
+    /// The session finishes when the sender side is dropped.
+    pub fn start(recognizer: R, sample_rate: u32, audio: Receiver<Vec<f32>>) -> Self {
+        Self::spawn(recognizer, sample_rate, audio, SessionOptions::default())
+    }
+
+    /// Same as `start`, recovering from recognizer failures as `recovery` says
+    /// instead of ending the session
+    pub fn start_with_recovery(
+        recognizer: R,
+        sample_rate: u32,
+        audio: Receiver<Vec<f32>>,
+        recovery: RecoveryPolicy,
+    ) -> Self {
+        let options = SessionOptions {
+            recovery,
+            ..Default::default()
+        };
+        Self::spawn(recognizer, sample_rate, audio, options)
+    }
+
+    /// Start a session reporting to a channel shared by many sessions, each event paired
+    /// with `tag` (e.g. a connection id). `events()` of this session stays empty.
+    pub fn start_tagged<T: Clone + Send + 'static>(
+        recognizer: R,
+        sample_rate: u32,
+        audio: Receiver<Vec<f32>>,
+        tag: T,
+        events: Sender<(T, SessionEvent)>,
+    ) -> Self {
+        let options = SessionOptions {
+            sink: Some(Box::new(move |_, event| {
+                events.send((tag.clone(), event)).is_ok()
+            })),
+            ..Default::default()
+        };
+        Self::spawn(recognizer, sample_rate, audio, options)
+    }
+
+    /// Same as `start_tagged` with the session's `id` as the tag
+    pub fn start_shared(
+        recognizer: R,
+        sample_rate: u32,
+        audio: Receiver<Vec<f32>>,
+        events: Sender<(u64, SessionEvent)>,
+    ) -> Self {
+        let options = SessionOptions {
+            sink: Some(Box::new(move |id, event| events.send((id, event)).is_ok())),
+            ..Default::default()
+        };
+        Self::spawn(recognizer, sample_rate, audio, options)
+    }
+
+    /// Start a session fed through a queue of at most `capacity` chunks.
+    /// The session finishes when all feeders are dropped.
+    pub fn bounded(recognizer: R, sample_rate: u32, capacity: usize) -> (Self, AudioFeeder) {
+        let (tx, audio) = mpsc::sync_channel(capacity);
+        let queued = Arc::new(Queued::default());
+        let feeder = AudioFeeder {
+            tx,
+            queued: queued.clone(),
+            sample_rate,
+        };
+        let options = SessionOptions {
+            queued: Some(queued),
+            ..Default::default()
+        };
+        (Self::spawn(recognizer, sample_rate, audio, options), feeder)
+    }
+
+    /// Same as `start`, also persisting the audio of the utterance in progress with `recorder`.
+    /// The recording is cleared at every endpoint and when the session finishes cleanly,
+    /// so a leftover file holds speech that was never finalized.
+    pub fn start_with_recorder(
+        recognizer: R,
+        sample_rate: u32,
+        audio: Receiver<Vec<f32>>,
+        recorder: UtteranceRecorder,
+    ) -> Self {
+        let options = SessionOptions {
+            recorder: Some(recorder),
+            ..Default::default()
+        };
+        Self::spawn(recognizer, sample_rate, audio, options)
+    }
+
+    fn spawn(
+        recognizer: R,
+        sample_rate: u32,
+        audio: Receiver<Vec<f32>>,
+        mut options: SessionOptions,
+    ) -> Self {
+        let (tx, events) = mpsc::channel();
+        let mut sink = options
+            .sink
+            .take()
+            .unwrap_or_else(|| Box::new(move |_, event| tx.send(event).is_ok()));
+        let id = NEXT_SESSION_ID.fetch_add(1, Ordering::Relaxed);
+        let stop = Arc::new(AtomicBool::new(false));
+        let release = Arc::new(AtomicBool::new(false));
+        let worker = {
+            let stop = stop.clone();
+            let release = release.clone();
+            shutdown::spawn_worker(move || {
+                let emit = move |event| sink(id, event);
+                let recognizer = run(recognizer, sample_rate, audio, options, emit, stop);
+                (!release.load(Ordering::SeqCst)).then_some(recognizer)
+            })
+        };
+        let stopper: Arc<Stopper> = {
+            let stop = stop.clone();
+            Arc::new(move || {
+                release.store(true, Ordering::SeqCst);
+                stop.store(true, Ordering::Relaxed);
+            })
+        };
+        shutdown::on_shutdown(&stopper);
+
+        Self {
+            id,
+            events,
+            stop,
+            _stopper: stopper,
+            worker: Some(worker),
+            #[cfg(feature = "capture")]
+            microphone: None,
+        }
+    }
+
+    /// Start a session fed from the default input device
+    #[cfg(feature = "capture")]
+    pub fn from_microphone(recognizer: R) -> eyre::Result<Self> {
+        let (microphone, audio) = crate::capture::Microphone::open()?;
+        let mut session = Self::start(recognizer, microphone.sample_rate(), audio);
+        session.microphone = Some(microphone);
+        Ok(session)
+    }
+
+    /// Id unique among the sessions of this process, reported by `start_shared` sessions
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn events(&self) -> &Receiver<SessionEvent> {
+        &self.events
+    }
+
+    /// Stop the session and return the recognizer, or `None` if `shutdown_all` already
+    /// destroyed it. Pending events stay available on the channel until the session is
+    /// dropped.
+    ///
+    /// A panic of the session's worker is resumed here. Dropping the session logs it instead.
+    pub fn stop(mut self) -> Option<R> {
+        let worker = self.shutdown().expect("session worker already joined");
+        match worker.join() {
+            Ok(recognizer) => recognizer,
+            Err(panic) => panic::resume_unwind(panic),
+        }
+    }
+
+    /// Stop the session and destroy the recognizer
+    pub fn close(self) {
+        drop(self.stop());
+    }
+
+    fn shutdown(&mut self) -> Option<JoinHandle<Option<R>>> {
+        #[cfg(feature = "capture")]
+        drop(self.microphone.take());
+        self.stop.store(true, Ordering::Relaxed);
+        self.worker.take()
+    }
+}
+
+impl<R: OnlineTranscriber + Send + 'static> Drop for RealtimeSession<R> {
+    fn drop(&mut self) {
+        if let Some(worker) = self.shutdown() {
+            shutdown::join_logged(worker, "realtime session worker");
+        }
+    }
+}
+
+fn run<R: OnlineTranscriber, E: FnMut(SessionEvent) -> bool>(
+    mut recognizer: R,
+    sample_rate: u32,
+    audio: Receiver<Vec<f32>>,
+    options: SessionOptions,
+    mut emit: E,
+    stop: Arc<AtomicBool>,
+) -> R {
+    let SessionOptions {
+        mut recorder,
+        queued,
+        recovery,
+        ..
+    } = options;
+    let mut last = String::new();
+    // Audio of the utterance in progress, kept for replay
+    let mut utterance = Vec::new();
+    let mut recoveries = 0;
+    while !stop.load(Ordering::Relaxed) {
+        let chunk = match audio.recv_timeout(Duration::from_millis(100)) {
+            Ok(chunk) => chunk,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        if let Some(recorder) = &mut recorder {
+            if let Err(error) = recorder.push(&chunk) {
+                tracing::warn!("failed to record audio: {}", error);
+            }
+        }
+        if let RecoveryPolicy::Replay { .. } = recovery {
+            utterance.extend_from_slice(&chunk);
+        }
+        let fed = feed(&mut recognizer, sample_rate, &chunk);
+        if let Some(queued) = &queued {
+            queued.chunks.fetch_sub(1, Ordering::SeqCst);
+            queued.samples.fetch_sub(chunk.len(), Ordering::SeqCst);
+        }
+        let text = match fed {
+            Ok(text) => text,
+            Err(error) => match recovery {
+                RecoveryPolicy::Replay { max_attempts } if recoveries < max_attempts => {
+                    recoveries += 1;
+                    tracing::warn!("recognizer failed, replaying the utterance: {}", error);
+                    let replayed = recover(&mut recognizer, sample_rate, &utterance);
+                    let Ok(text) = replayed else {
+                        tracing::warn!("recovery failed: {}", replayed.unwrap_err());
+                        break;
+                    };
+                    if !emit(SessionEvent::Recovered { error }) {
+                        break;
+                    }
+                    text
+                }
+                _ => {
+                    tracing::warn!("recognizer failed: {}", error);
+                    break;
+                }
+            },
+        };
+
+        let event = if recognizer.is_endpoint() {
+            recognizer.reset();
+            clear_recording(&mut recorder);
+            utterance.clear();
+            last.clear();
+            (!text.is_empty()).then_some(SessionEvent::Final(text))
+        } else if text != last {
+            last = text.clone();
+            Some(SessionEvent::Partial(text))
+        } else {
+            None
+        };
+        if let Some(event) = event {
+            if !emit(event) {
+                // Nobody is listening anymore
+                break;
+            }
+        }
+    }
+
+    recognizer.input_finished();
+    let text = recognizer.transcribe();
+    if !text.is_empty() {
+        emit(SessionEvent::Final(text));
+    }
+    recognizer.reset();
+    clear_recording(&mut recorder);
+    recognizer
+}
+
+/// Feed a chunk and decode it
+fn feed<R: OnlineTranscriber>(
+    recognizer: &mut R,
+    sample_rate: u32,
+    chunk: &[f32],
+) -> Result<String, String> {
+    recognizer
+        .accept_waveform(sample_rate, chunk)
+        .map_err(|error| error.to_string())?;
+    Ok(recognizer.transcribe())
+}
+
+/// Recreate the stream and replay the utterance in progress
+fn recover<R: OnlineTranscriber>(
+    recognizer: &mut R,
+    sample_rate: u32,
+    utterance: &[f32],
+) -> Result<String, String> {
+    recognizer.input_finished();
+    recognizer.reset();
+    recognizer
+        .accept_waveform(sample_rate, utterance)
+        .map_err(|error| error.to_string())?;
+    Ok(recognizer.transcribe())
+}
+
+fn clear_recording(recorder: &mut Option<UtteranceRecorder>) {
+    if let Some(recorder) = recorder {
+        if let Err(error) = recorder.clear() {
+            tracing::warn!("failed to clear recorded audio: {}", error);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use eyre::{bail, Result};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+/// Keeps the raw audio of the current utterance in a WAV file on disk,
+/// so it can be decoded offline after a crash or a reconnect.
+///
+/// At most `max_seconds` of audio are kept; older samples are dropped.
+/// Chunks are appended and the file is flushed after every one of them. Once it holds
+/// twice the limit it's rewritten with the newest `max_seconds`, so the file may hold up
+/// to twice as much audio as `samples` returns.
+pub struct UtteranceRecorder {
+    path: PathBuf,
+    sample_rate: u32,
+    max_samples: usize,
+    samples: VecDeque<f32>,
+    writer: Option<hound::WavWriter<BufWriter<File>>>,
+    /// Samples in the file
+    written: usize,
+}
+
+impl UtteranceRecorder {
+    pub fn new<P: AsRef<Path>>(path: P, sample_rate: u32, max_seconds: f32) -> Result<Self> {
+        if max_seconds <= 0.0 {
+            bail!("max_seconds must be positive");
+        }
+        let mut recorder = Self {
+            path: path.as_ref().to_path_buf(),
+            sample_rate,
+            max_samples: (max_seconds * sample_rate as f32) as usize,
+            samples: VecDeque::new(),
+            writer: None,
+            written: 0,
+        };
+        recorder.rewrite()?;
+        Ok(recorder)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Audio of the current utterance that's been persisted
+    pub fn samples(&self) -> Vec<f32> {
+        self.samples.iter().copied().collect()
+    }
+
+    /// Append a chunk to the current utterance
+    pub fn push(&mut self, samples: &[f32]) -> Result<()> {
+        self.samples.extend(samples);
+        if self.samples.len() > self.max_samples {
+            let excess = self.samples.len() - self.max_samples;
+            self.samples.drain(..excess);
+        }
+
+        let Some(writer) = &mut self.writer else {
+            return self.rewrite();
+        };
+        if self.written + samples.len() > 2 * self.max_samples {
+            return self.rewrite();
+        }
+        for &sample in samples {
+            writer.write_sample(sample)?;
+        }
+        self.written += samples.len();
+        // Keep the header valid in case the process dies
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Start a new utterance, emptying the file
+    pub fn clear(&mut self) -> Result<()> {
+        self.samples.clear();
+        self.rewrite()
+    }
+
+    /// Read back a file written by a recorder. Returns the samples and their sample rate.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<(Vec<f32>, u32)> {
+        let mut reader = hound::WavReader::open(path)?;
+        let sample_rate = reader.spec().sample_rate;
+        let samples = reader.samples::<f32>().collect::<Result<_, _>>()?;
+        Ok((samples, sample_rate))
+    }
+
+    fn rewrite(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.take() {
+            writer.finalize()?;
+        }
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut writer = hound::WavWriter::create(&self.path, spec)?;
+        for &sample in &self.samples {
+            writer.write_sample(sample)?;
+        }
+        writer.flush()?;
+        self.writer = Some(writer);
+        self.written = self.samples.len();
+        Ok(())
+    }
+}
+
+impl Drop for UtteranceRecorder {
+    fn drop(&mut self) {
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.finalize();
+        }
+    }
+}
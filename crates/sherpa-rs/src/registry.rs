@@ -0,0 +1,82 @@
+use eyre::{bail, Result};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+type Loader<T> = Box<dyn Fn(&str) -> Result<T> + Send + Sync>;
+
+/// Loads recognizers on first use and keeps at most `max_loaded` of them, evicting
+/// the least recently used, for servers with more models than fit in memory.
+///
+/// Keys are whatever the loader understands, e.g. a language code or a model name.
+/// Recognizers are shared as `Arc<Mutex<T>>`: an evicted recognizer is freed once the
+/// last caller holding it lets go. A full registry evicts before loading, so the old
+/// and new models aren't both held by it at once, even if the load then fails.
+/// Loading happens under the registry lock, so one slow load delays other lookups.
+/// A loader that panics leaves the registry usable.
+pub struct RecognizerRegistry<T> {
+    loader: Loader<T>,
+    max_loaded: usize,
+    /// Loaded recognizers, most recently used last
+    loaded: Mutex<Vec<(String, Arc<Mutex<T>>)>>,
+}
+
+impl<T> RecognizerRegistry<T> {
+    pub fn new<F>(max_loaded: usize, loader: F) -> Result<Self>
+    where
+        F: Fn(&str) -> Result<T> + Send + Sync + 'static,
+    {
+        if max_loaded == 0 {
+            bail!("max_loaded must be at least 1");
+        }
+        Ok(Self {
+            loader: Box::new(loader),
+            max_loaded,
+            loaded: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Recognizer for `key`, loading it and evicting the least recently used one if needed
+    pub fn get(&self, key: &str) -> Result<Arc<Mutex<T>>> {
+        let mut loaded = self.lock();
+        if let Some(index) = loaded.iter().position(|(k, _)| k == key) {
+            let entry = loaded.remove(index);
+            let recognizer = entry.1.clone();
+            loaded.push(entry);
+            return Ok(recognizer);
+        }
+
+        if loaded.len() >= self.max_loaded {
+            let (evicted, _) = loaded.remove(0);
+            tracing::debug!("evicting recognizer {}", evicted);
+        }
+        let recognizer = Arc::new(Mutex::new((self.loader)(key)?));
+        loaded.push((key.to_string(), recognizer.clone()));
+        Ok(recognizer)
+    }
+
+    pub fn is_loaded(&self, key: &str) -> bool {
+        self.lock().iter().any(|(k, _)| k == key)
+    }
+
+    /// Keys of the loaded recognizers, least recently used first
+    pub fn loaded(&self) -> Vec<String> {
+        self.lock().iter().map(|(k, _)| k.clone()).collect()
+    }
+
+    /// Unload `key`. Returns whether it was loaded.
+    pub fn evict(&self, key: &str) -> bool {
+        let mut loaded = self.lock();
+        let before = loaded.len();
+        loaded.retain(|(k, _)| k != key);
+        loaded.len() != before
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// The list is only changed after a load returns, so a panicking loader leaves it
+    /// consistent
+    fn lock(&self) -> MutexGuard<'_, Vec<(String, Arc<Mutex<T>>)>> {
+        self.loaded.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
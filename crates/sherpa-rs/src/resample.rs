@@ -0,0 +1,95 @@
+use eyre::{bail, Result};
+
+/// Streaming linear resampler from sherpa-onnx
+#[derive(Debug)]
+pub struct Resampler {
+    resampler: *const sherpa_rs_sys::SherpaOnnxLinearResampler,
+    input_sample_rate: u32,
+    output_sample_rate: u32,
+}
+
+impl Resampler {
+    pub fn new(input_sample_rate: u32, output_sample_rate: u32) -> Result<Self> {
+        // Same low-pass settings as the sherpa-onnx examples
+        let min_freq = input_sample_rate.min(output_sample_rate) as f32;
+        let filter_cutoff = 0.99 * 0.5 * min_freq;
+        let num_zeros = 6;
+
+        let resampler = unsafe {
+            sherpa_rs_sys::SherpaOnnxCreateLinearResampler(
+                input_sample_rate as i32,
+                output_sample_rate as i32,
+                filter_cutoff,
+                num_zeros,
+            )
+        };
+        if resampler.is_null() {
+            bail!("Failed to create resampler");
+        }
+
+        Ok(Self {
+            resampler,
+            input_sample_rate,
+            output_sample_rate,
+        })
+    }
+
+    pub fn input_sample_rate(&self) -> u32 {
+        self.input_sample_rate
+    }
+
+    pub fn output_sample_rate(&self) -> u32 {
+        self.output_sample_rate
+    }
+
+    /// Resample the next chunk of a stream.
+    /// Pass `flush = true` with the last chunk to drain the filter.
+    pub fn resample(&mut self, samples: &[f32], flush: bool) -> Vec<f32> {
+        if samples.len() > crate::utils::MAX_NATIVE_SAMPLES {
+            let mut output = Vec::new();
+            let mut chunks = crate::utils::native_chunks(samples).peekable();
+            while let Some(chunk) = chunks.next() {
+                let last = chunks.peek().is_none();
+                output.extend(self.resample(chunk, flush && last));
+            }
+            return output;
+        }
+        unsafe {
+            let out_ptr = sherpa_rs_sys::SherpaOnnxLinearResamplerResample(
+                self.resampler,
+                samples.as_ptr(),
+                samples.len() as i32,
+                flush.into(),
+            );
+            if out_ptr.is_null() {
+                return Vec::new();
+            }
+            let out = out_ptr.read();
+            let samples = if out.samples.is_null() || out.n <= 0 {
+                Vec::new()
+            } else {
+                std::slice::from_raw_parts(out.samples, out.n as usize).to_vec()
+            };
+            // Free
+            sherpa_rs_sys::SherpaOnnxLinearResamplerResampleFree(out_ptr);
+            samples
+        }
+    }
+
+    pub fn reset(&mut self) {
+        unsafe {
+            sherpa_rs_sys::SherpaOnnxLinearResamplerReset(self.resampler);
+        }
+    }
+}
+
+unsafe impl Send for Resampler {}
+unsafe impl Sync for Resampler {}
+
+impl Drop for Resampler {
+    fn drop(&mut self) {
+        unsafe {
+            sherpa_rs_sys::SherpaOnnxDestroyLinearResampler(self.resampler);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use eyre::{Result, WrapErr};
+use std::io::{self, ErrorKind, Read};
+use std::path::Path;
+use std::time::Duration;
+
+/// Retries with exponential backoff for loading models, e.g. from a network filesystem
+/// or while another process is still downloading them.
+///
+/// Only transient failures are retried, see `is_transient`. The native libraries report
+/// a missing or truncated model file as a plain creation failure, so check the files
+/// with `ensure_readable` inside the retried closure:
+/// `policy.run(|| { ensure_readable([&config.model])?; WhisperRecognizer::new(config.clone()) })`
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first
+    pub max_attempts: u32,
+    /// Wait before the second attempt
+    pub initial_backoff: Duration,
+    /// Longest wait between attempts
+    pub max_backoff: Duration,
+    /// Growth of the wait after each failed attempt
+    pub multiplier: f32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A single attempt
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Wait after failed attempt number `attempt`, counted from 1
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = f64::from(self.multiplier.max(1.0)).powi(attempt.saturating_sub(1) as i32);
+        let secs = self.initial_backoff.as_secs_f64() * factor;
+        Duration::from_secs_f64(secs.min(self.max_backoff.as_secs_f64()))
+    }
+
+    /// Run `load` until it succeeds, fails permanently or runs out of attempts.
+    /// Returns the last error.
+    pub fn run<T, F>(&self, mut load: F) -> Result<T>
+    where
+        F: FnMut() -> Result<T>,
+    {
+        let mut attempt = 1;
+        loop {
+            match load() {
+                Ok(value) => return Ok(value),
+                Err(error) if attempt < self.max_attempts && is_transient(&error) => {
+                    let backoff = self.backoff(attempt);
+                    tracing::warn!(
+                        "loading failed (attempt {}/{}), retrying in {:?}: {:#}",
+                        attempt,
+                        self.max_attempts,
+                        backoff,
+                        error
+                    );
+                    std::thread::sleep(backoff);
+                    attempt += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+/// Whether `error` may go away by trying again: I/O errors such as a file that doesn't
+/// exist yet, a timeout or a dropped connection. Anything else, e.g. an invalid config
+/// or a model the native library rejects, is permanent.
+pub fn is_transient(error: &eyre::Report) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<io::Error>())
+        .any(|error| {
+            matches!(
+                error.kind(),
+                ErrorKind::NotFound
+                    | ErrorKind::Interrupted
+                    | ErrorKind::TimedOut
+                    | ErrorKind::WouldBlock
+                    | ErrorKind::UnexpectedEof
+                    | ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::NotConnected
+                    | ErrorKind::BrokenPipe
+            )
+        })
+}
+
+/// Check that each file exists, is non-empty and can be read. Failures keep their
+/// `io::Error`, so `RetryPolicy` retries them. Empty paths are skipped, as configs
+/// leave unused models empty.
+pub fn ensure_readable<I, P>(paths: I) -> Result<()>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    for path in paths {
+        let path = path.as_ref();
+        if path.as_os_str().is_empty() {
+            continue;
+        }
+        let mut first = [0u8; 1];
+        std::fs::File::open(path)
+            .and_then(|mut file| file.read_exact(&mut first))
+            .wrap_err_with(|| format!("model file {} is not readable", path.display()))?;
+    }
+    Ok(())
+}
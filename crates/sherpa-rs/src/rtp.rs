@@ -0,0 +1,254 @@
+use crate::{
+    telephony::{decode_alaw, decode_mulaw, TELEPHONY_SAMPLE_RATE},
+    OnlineTranscriber,
+};
+use eyre::{bail, Result};
+use std::net::UdpSocket;
+
+/// Largest UDP payload
+const MAX_DATAGRAM: usize = 65536;
+
+/// Payload type of comfort noise (RFC 3389), sent during silence by some phones
+const COMFORT_NOISE: u8 = 13;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RtpCodec {
+    /// G.711 µ-law at 8 kHz, static payload type 0
+    Pcmu,
+    /// G.711 A-law at 8 kHz, static payload type 8
+    Pcma,
+    /// Mono 16-bit big-endian PCM
+    L16 { sample_rate: u32 },
+    /// Opus (RFC 7587), decoded at `sample_rate`. The RTP clock is always 48 kHz.
+    #[cfg(feature = "opus")]
+    Opus { sample_rate: u32 },
+}
+
+impl RtpCodec {
+    /// Rate of the RTP timestamps
+    pub fn clock_rate(&self) -> u32 {
+        match self {
+            RtpCodec::Pcmu | RtpCodec::Pcma => TELEPHONY_SAMPLE_RATE,
+            RtpCodec::L16 { sample_rate } => *sample_rate,
+            #[cfg(feature = "opus")]
+            RtpCodec::Opus { .. } => 48000,
+        }
+    }
+
+    /// Rate of the decoded samples
+    pub fn sample_rate(&self) -> u32 {
+        match self {
+            #[cfg(feature = "opus")]
+            RtpCodec::Opus { sample_rate } => *sample_rate,
+            _ => self.clock_rate(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RtpConfig {
+    pub codec: RtpCodec,
+    /// Packets with another payload type (e.g. DTMF events or RTCP) are ignored.
+    /// Dynamic codecs like Opus use the type negotiated in SDP, often 96 to 127.
+    pub payload_type: u8,
+    /// Longer timestamp gaps, in seconds, are treated as a new stream instead of
+    /// being filled with silence
+    pub max_gap: f32,
+}
+
+impl Default for RtpConfig {
+    fn default() -> Self {
+        Self {
+            codec: RtpCodec::Pcmu,
+            payload_type: 0,
+            max_gap: 5.0,
+        }
+    }
+}
+
+/// Fixed RTP header fields and the payload of one packet
+#[derive(Debug, Clone, PartialEq)]
+pub struct RtpPacket<'a> {
+    pub payload_type: u8,
+    pub marker: bool,
+    pub sequence: u16,
+    pub timestamp: u32,
+    pub ssrc: u32,
+    pub payload: &'a [u8],
+}
+
+impl<'a> RtpPacket<'a> {
+    /// Parse an RTP packet (RFC 3550), skipping CSRCs, header extensions and padding
+    pub fn parse(data: &'a [u8]) -> Result<Self> {
+        if data.len() < 12 || data[0] >> 6 != 2 {
+            bail!("Not an RTP version 2 packet");
+        }
+        let csrc_count = (data[0] & 0x0f) as usize;
+        let mut header_len = 12 + 4 * csrc_count;
+        if data[0] & 0x10 != 0 {
+            let Some(extension) = data.get(header_len..header_len + 4) else {
+                bail!("Truncated RTP header extension");
+            };
+            header_len += 4 + 4 * u16::from_be_bytes([extension[2], extension[3]]) as usize;
+        }
+        let padding = if data[0] & 0x20 != 0 {
+            *data.last().unwrap() as usize
+        } else {
+            0
+        };
+        if header_len + padding > data.len() {
+            bail!("Truncated RTP packet");
+        }
+        Ok(Self {
+            payload_type: data[1] & 0x7f,
+            marker: data[1] & 0x80 != 0,
+            sequence: u16::from_be_bytes([data[2], data[3]]),
+            timestamp: u32::from_be_bytes([data[4], data[5], data[6], data[7]]),
+            ssrc: u32::from_be_bytes([data[8], data[9], data[10], data[11]]),
+            payload: &data[header_len..data.len() - padding],
+        })
+    }
+}
+
+/// Turns RTP packets of one audio stream into contiguous mono samples.
+///
+/// Timestamps drive the output: gaps from lost packets or silence suppression are filled
+/// with silence (or Opus concealment), so the recognizer's sense of time and its
+/// endpointing stay right. Late and duplicate packets are dropped; put a jitter buffer in
+/// front when the network reorders packets. A new SSRC restarts the timeline.
+pub struct RtpDepacketizer {
+    config: RtpConfig,
+    ssrc: Option<u32>,
+    /// Timestamp the next packet should have
+    next_timestamp: Option<u32>,
+    #[cfg(feature = "opus")]
+    opus: Option<crate::opus_input::OpusPacketDecoder>,
+}
+
+impl RtpDepacketizer {
+    pub fn new(config: RtpConfig) -> Result<Self> {
+        if config.codec.clock_rate() == 0 {
+            bail!("RTP clock rate must be positive");
+        }
+        Ok(Self {
+            #[cfg(feature = "opus")]
+            opus: match config.codec {
+                RtpCodec::Opus { sample_rate } => {
+                    Some(crate::opus_input::OpusPacketDecoder::new(sample_rate, 1)?)
+                }
+                _ => None,
+            },
+            config,
+            ssrc: None,
+            next_timestamp: None,
+        })
+    }
+
+    /// Sample rate of the returned audio
+    pub fn sample_rate(&self) -> u32 {
+        self.config.codec.sample_rate()
+    }
+
+    /// Forget the stream position, e.g. after a call was transferred
+    pub fn reset(&mut self) {
+        self.ssrc = None;
+        self.next_timestamp = None;
+    }
+
+    /// Parse and decode one datagram. Returns the audio since the previous packet,
+    /// empty for ignored packets.
+    pub fn push(&mut self, datagram: &[u8]) -> Result<Vec<f32>> {
+        let packet = RtpPacket::parse(datagram)?;
+        self.push_packet(&packet)
+    }
+
+    /// Same as `push` for an already parsed packet
+    pub fn push_packet(&mut self, packet: &RtpPacket) -> Result<Vec<f32>> {
+        if packet.payload_type != self.config.payload_type
+            || packet.payload_type == COMFORT_NOISE
+            || packet.payload.is_empty()
+        {
+            return Ok(Vec::new());
+        }
+        if self.ssrc != Some(packet.ssrc) {
+            if self.ssrc.is_some() {
+                tracing::debug!("RTP SSRC changed to {:#x}", packet.ssrc);
+            }
+            self.ssrc = Some(packet.ssrc);
+            self.next_timestamp = None;
+        }
+
+        let mut samples = Vec::new();
+        if let Some(expected) = self.next_timestamp {
+            let gap = packet.timestamp.wrapping_sub(expected) as i32;
+            let max_gap = (self.config.max_gap * self.config.codec.clock_rate() as f32) as i32;
+            if gap < 0 {
+                tracing::trace!("dropping late RTP packet {}", packet.sequence);
+                return Ok(Vec::new());
+            } else if gap > max_gap {
+                tracing::debug!("RTP timestamp jumped by {}, resynchronizing", gap);
+            } else if gap > 0 {
+                samples = self.fill_gap(gap as u32)?;
+            }
+        }
+
+        let audio = self.decode(packet.payload)?;
+        let ticks =
+            audio.len() as u64 * self.config.codec.clock_rate() as u64 / self.sample_rate() as u64;
+        self.next_timestamp = Some(packet.timestamp.wrapping_add(ticks as u32));
+        samples.extend(audio);
+        Ok(samples)
+    }
+
+    /// Receive one datagram from `socket` and feed its audio to `recognizer`.
+    /// Malformed and ignored packets feed nothing. Returns the number of samples fed.
+    pub fn receive<R: OnlineTranscriber + ?Sized>(
+        &mut self,
+        socket: &UdpSocket,
+        recognizer: &mut R,
+    ) -> Result<usize> {
+        let mut buffer = vec![0; MAX_DATAGRAM];
+        let len = socket.recv(&mut buffer)?;
+        let samples = match self.push(&buffer[..len]) {
+            Ok(samples) => samples,
+            Err(error) => {
+                tracing::debug!("ignoring datagram: {}", error);
+                return Ok(0);
+            }
+        };
+        if !samples.is_empty() {
+            recognizer.accept_waveform(self.sample_rate(), &samples)?;
+        }
+        Ok(samples.len())
+    }
+
+    fn decode(&mut self, payload: &[u8]) -> Result<Vec<f32>> {
+        Ok(match self.config.codec {
+            RtpCodec::Pcmu => decode_mulaw(payload),
+            RtpCodec::Pcma => decode_alaw(payload),
+            RtpCodec::L16 { .. } => payload
+                .chunks_exact(2)
+                .map(|b| i16::from_be_bytes([b[0], b[1]]) as f32 / 32768.0)
+                .collect(),
+            #[cfg(feature = "opus")]
+            RtpCodec::Opus { .. } => self.opus.as_mut().unwrap().decode(payload)?,
+        })
+    }
+
+    /// Audio for `ticks` of RTP clock without packets
+    fn fill_gap(&mut self, ticks: u32) -> Result<Vec<f32>> {
+        let len = ticks as u64 * self.sample_rate() as u64 / self.config.codec.clock_rate() as u64;
+        #[cfg(feature = "opus")]
+        if let Some(opus) = &mut self.opus {
+            // Concealed in 20 ms frames, as libopus wants a multiple of 2.5 ms
+            let frame = opus.sample_rate() as usize / 50;
+            let mut samples = Vec::with_capacity(len as usize);
+            while samples.len() + frame <= len as usize {
+                samples.extend(opus.conceal(frame)?);
+            }
+            samples.resize(len as usize, 0.0);
+            return Ok(samples);
+        }
+        Ok(vec![0.0; len as usize])
+    }
+}
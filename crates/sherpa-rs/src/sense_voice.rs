@@ -1,14 +1,52 @@
-use crate::{get_default_provider, utils::cstring_from_str};
+use crate::config_dump::{self, ConfigDump, NativeConfig};
+use crate::{
+    get_default_provider,
+    offline_stream::{self, OfflineStream},
+    utils::cstring_from_str,
+};
 use eyre::{bail, Result};
 use std::mem;
 
 #[derive(Debug)]
 pub struct SenseVoiceRecognizer {
     recognizer: *const sherpa_rs_sys::SherpaOnnxOfflineRecognizer,
+    config_dump: ConfigDump,
 }
 
 pub type SenseVoiceRecognizerResult = super::OfflineRecognizerResult;
 
+/// Non-text output of SenseVoice, with the `<|...|>` markers stripped
+#[derive(Debug, Clone, PartialEq)]
+pub struct SenseVoiceTags {
+    /// e.g. `en`, `zh`
+    pub lang: String,
+    /// One of `HAPPY`, `SAD`, `ANGRY`, `NEUTRAL`, `FEARFUL`, `DISGUSTED`, `SURPRISED`, `EMO_UNKNOWN`
+    pub emotion: String,
+    /// e.g. `Speech`, `BGM`, `Applause`, `Laughter`, `Cry`, `Cough`
+    pub event: String,
+}
+
+impl SenseVoiceTags {
+    /// Tags from the `<|lang|><|emotion|><|event|>` markers SenseVoice puts ahead of its
+    /// text, e.g. `<|zh|><|NEUTRAL|><|Speech|>`. Missing markers give empty fields.
+    pub fn parse(markers: &str) -> Self {
+        let mut markers = markers.split_inclusive("|>").map(strip_marker);
+        let mut next = || markers.next().unwrap_or_default();
+        Self {
+            lang: next(),
+            emotion: next(),
+            event: next(),
+        }
+    }
+}
+
+fn strip_marker(tag: &str) -> String {
+    tag.trim()
+        .trim_start_matches("<|")
+        .trim_end_matches("|>")
+        .to_string()
+}
+
 #[derive(Debug, Clone)]
 pub struct SenseVoiceConfig {
     pub model: String,
@@ -36,7 +74,7 @@ impl Default for SenseVoiceConfig {
 
 impl SenseVoiceRecognizer {
     pub fn new(config: SenseVoiceConfig) -> Result<Self> {
-        let debug = config.debug.into();
+        let debug = crate::native_debug(config.debug);
         let provider = config.provider.unwrap_or(get_default_provider());
         let provider_ptr = cstring_from_str(&provider);
         let num_threads = config.num_threads.unwrap_or(1);
@@ -103,35 +141,52 @@ impl SenseVoiceRecognizer {
             }
         };
 
+        let config_dump = config_dump::offline_recognizer(&config);
         let recognizer = unsafe { sherpa_rs_sys::SherpaOnnxCreateOfflineRecognizer(&config) };
         if recognizer.is_null() {
             bail!("Failed to create recognizer");
         }
 
-        Ok(Self { recognizer })
+        Ok(Self {
+            recognizer,
+            config_dump,
+        })
+    }
+
+    pub fn create_stream(&self) -> Result<OfflineStream<'_>> {
+        unsafe { OfflineStream::new(self.recognizer) }
     }
 
     pub fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> SenseVoiceRecognizerResult {
-        unsafe {
-            let stream = sherpa_rs_sys::SherpaOnnxCreateOfflineStream(self.recognizer);
-            sherpa_rs_sys::SherpaOnnxAcceptWaveformOffline(
-                stream,
-                sample_rate as i32,
-                samples.as_ptr(),
-                samples.len().try_into().unwrap(),
-            );
-            sherpa_rs_sys::SherpaOnnxDecodeOfflineStream(self.recognizer, stream);
-            let result_ptr = sherpa_rs_sys::SherpaOnnxGetOfflineStreamResult(stream);
-            let raw_result = result_ptr.read();
-            let result = SenseVoiceRecognizerResult::new(&raw_result);
-            // Free resources
-            sherpa_rs_sys::SherpaOnnxDestroyOfflineRecognizerResult(result_ptr);
-            sherpa_rs_sys::SherpaOnnxDestroyOfflineStream(stream);
-            result
+        offline_stream::transcribe_once(self.create_stream(), sample_rate, samples)
+    }
+
+    /// Language, emotion and audio event of a clip, without the transcription.
+    ///
+    /// SenseVoice predicts the tags ahead of the text, so the model still runs in full;
+    /// keep clips short (a few seconds) for analytics over many clips.
+    pub fn tags(&mut self, sample_rate: u32, samples: &[f32]) -> SenseVoiceTags {
+        let result = self.transcribe(sample_rate, samples);
+        SenseVoiceTags {
+            lang: strip_marker(&result.lang),
+            emotion: strip_marker(&result.emotion),
+            event: strip_marker(&result.event),
         }
     }
 }
 
+impl NativeConfig for SenseVoiceRecognizer {
+    fn config_dump(&self) -> &ConfigDump {
+        &self.config_dump
+    }
+}
+
+impl crate::Transcriber for SenseVoiceRecognizer {
+    fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> crate::OfflineRecognizerResult {
+        SenseVoiceRecognizer::transcribe(self, sample_rate, samples)
+    }
+}
+
 unsafe impl Send for SenseVoiceRecognizer {}
 unsafe impl Sync for SenseVoiceRecognizer {}
 
@@ -0,0 +1,91 @@
+use std::any::Any;
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+pub(crate) type Stopper = dyn Fn() + Send + Sync;
+
+/// Stop callbacks of the live sessions owning background workers
+static STOPPERS: Mutex<Vec<Weak<Stopper>>> = Mutex::new(Vec::new());
+static RUNNING: Mutex<usize> = Mutex::new(0);
+static EXITED: Condvar = Condvar::new();
+
+/// Decrements the running count once the worker's closure, and everything it owned,
+/// was dropped
+struct WorkerGuard;
+
+impl Drop for WorkerGuard {
+    fn drop(&mut self) {
+        *RUNNING.lock().unwrap() -= 1;
+        EXITED.notify_all();
+    }
+}
+
+/// Have `shutdown_all` call `stopper` while the session holding it is alive
+pub(crate) fn on_shutdown(stopper: &Arc<Stopper>) {
+    let mut stoppers = STOPPERS.lock().unwrap();
+    stoppers.retain(|stopper| stopper.strong_count() > 0);
+    stoppers.push(Arc::downgrade(stopper));
+}
+
+/// Spawn a background worker counted by `running_workers`
+pub(crate) fn spawn_worker<F, T>(f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    *RUNNING.lock().unwrap() += 1;
+    std::thread::spawn(move || {
+        let _guard = WorkerGuard;
+        f()
+    })
+}
+
+/// Join a worker from `Drop`, logging its panic instead of resuming it there
+pub(crate) fn join_logged<T>(worker: JoinHandle<T>, name: &str) -> Option<T> {
+    match worker.join() {
+        Ok(value) => Some(value),
+        Err(panic) => {
+            tracing::error!("{} panicked: {}", name, panic_message(&*panic));
+            None
+        }
+    }
+}
+
+pub(crate) fn panic_message(panic: &(dyn Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panic".to_string())
+}
+
+/// Background worker threads still running
+pub fn running_workers() -> usize {
+    *RUNNING.lock().unwrap()
+}
+
+/// Stop every `RealtimeSession`, `KeywordStream` and `VoiceAgent` worker and wait up to `timeout` for
+/// their threads to exit. Returns whether all of them did.
+///
+/// Each worker destroys the recognizers, spotters, VADs and TTS engines it owns before
+/// exiting, so `stop` on those sessions returns `None` afterwards. Other native objects
+/// are destroyed when dropped: after this and dropping the crate's objects no sherpa-onnx
+/// memory is left, except what ONNX Runtime keeps in process-wide statics.
+pub fn shutdown_all(timeout: Duration) -> bool {
+    let stoppers: Vec<_> = STOPPERS.lock().unwrap().drain(..).collect();
+    for stopper in stoppers.iter().filter_map(Weak::upgrade) {
+        stopper();
+    }
+    let deadline = Instant::now() + timeout;
+    let mut running = RUNNING.lock().unwrap();
+    while *running > 0 {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            tracing::warn!("{} workers still running after shutdown", *running);
+            return false;
+        }
+        running = EXITED.wait_timeout(running, left).unwrap().0;
+    }
+    true
+}
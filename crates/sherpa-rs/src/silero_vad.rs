@@ -1,7 +1,7 @@
 use std::mem;
 
 use crate::{get_default_provider, utils::cstring_from_str};
-use eyre::Result;
+use eyre::{bail, Result};
 
 #[derive(Debug)]
 pub struct SileroVad {
@@ -41,10 +41,23 @@ impl Default for SileroVadConfig {
 
 #[derive(Debug)]
 pub struct SpeechSegment {
+    /// Offset of the first sample from the start of the audio fed to the detector
     pub start: i32,
     pub samples: Vec<f32>,
 }
 
+impl SpeechSegment {
+    /// Start time in seconds
+    pub fn start_time(&self, sample_rate: u32) -> f32 {
+        self.start.max(0) as f32 / sample_rate as f32
+    }
+
+    /// Length in seconds
+    pub fn duration(&self, sample_rate: u32) -> f32 {
+        self.samples.len() as f32 / sample_rate as f32
+    }
+}
+
 impl SileroVad {
     pub fn new(config: SileroVadConfig, buffer_size_in_seconds: f32) -> Result<Self> {
         let provider = config.provider.unwrap_or(get_default_provider());
@@ -61,7 +74,7 @@ impl SileroVad {
             window_size: config.window_size,
             max_speech_duration: config.max_speech_duration,
         };
-        let debug = config.debug.into();
+        let debug = crate::native_debug(config.debug);
         let vad_config = unsafe {
             sherpa_rs_sys::SherpaOnnxVadModelConfig {
                 debug,
@@ -78,6 +91,9 @@ impl SileroVad {
                 &vad_config,
                 buffer_size_in_seconds,
             );
+            if vad.is_null() {
+                bail!("SherpaOnnxCreateVoiceActivityDetector failed");
+            }
 
             Ok(Self { vad })
         }
@@ -112,16 +128,16 @@ impl SileroVad {
         }
     }
 
-    pub fn accept_waveform(&mut self, mut samples: Vec<f32>) {
-        let samples_ptr = samples.as_mut_ptr();
-        let samples_length = samples.len();
-        unsafe {
-            sherpa_rs_sys::SherpaOnnxVoiceActivityDetectorAcceptWaveform(
-                self.vad,
-                samples_ptr,
-                samples_length.try_into().unwrap(),
-            );
-        };
+    pub fn accept_waveform(&mut self, samples: Vec<f32>) {
+        for chunk in crate::utils::native_chunks(&samples) {
+            unsafe {
+                sherpa_rs_sys::SherpaOnnxVoiceActivityDetectorAcceptWaveform(
+                    self.vad,
+                    chunk.as_ptr(),
+                    chunk.len() as i32,
+                );
+            }
+        }
     }
 
     pub fn pop(&mut self) {
@@ -141,6 +157,29 @@ impl SileroVad {
     }
 }
 
+impl crate::VoiceActivityDetector for SileroVad {
+    fn accept_waveform(&mut self, samples: Vec<f32>) {
+        SileroVad::accept_waveform(self, samples)
+    }
+
+    fn flush(&mut self) {
+        SileroVad::flush(self)
+    }
+
+    fn pop_segment(&mut self) -> Option<SpeechSegment> {
+        if self.is_empty() {
+            return None;
+        }
+        let segment = self.front();
+        self.pop();
+        Some(segment)
+    }
+
+    fn is_speech(&mut self) -> bool {
+        SileroVad::is_speech(self)
+    }
+}
+
 unsafe impl Send for SileroVad {}
 unsafe impl Sync for SileroVad {}
 
@@ -4,8 +4,28 @@ use std::path::PathBuf;
 use crate::{get_default_provider, utils::cstring_from_str};
 
 /// If similarity is greater or equal to thresold than it's a match!
+/// Cosine similarity, reasonable for the NeMo / 3D-Speaker / WeSpeaker models;
+/// tune it on your own recordings when false accepts matter.
 pub const DEFAULT_SIMILARITY_THRESHOLD: f32 = 0.5;
 
+/// Outcome of a 1:1 voice match
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VerificationResult {
+    /// Cosine similarity of the two embeddings, in [-1, 1]
+    pub score: f32,
+    pub same_speaker: bool,
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
 #[derive(Debug, Default)]
 pub struct ExtractorConfig {
     pub model: String,
@@ -25,7 +45,7 @@ impl EmbeddingExtractor {
         let provider = config.provider.unwrap_or(get_default_provider());
 
         let num_threads = config.num_threads.unwrap_or(1);
-        let debug = config.debug.into();
+        let debug = crate::native_debug(config.debug);
 
         let model_path = PathBuf::from(&config.model);
         if !model_path.exists() {
@@ -42,6 +62,9 @@ impl EmbeddingExtractor {
         };
         let extractor =
             unsafe { sherpa_rs_sys::SherpaOnnxCreateSpeakerEmbeddingExtractor(&extractor_config) };
+        if extractor.is_null() {
+            bail!("SherpaOnnxCreateSpeakerEmbeddingExtractor failed");
+        }
         // Assume embedding size is known or can be retrieved
         let embedding_size =
             unsafe { sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingExtractorDim(extractor) }
@@ -53,55 +76,195 @@ impl EmbeddingExtractor {
         })
     }
 
+    /// Dimension of the embeddings produced by the loaded model
+    pub fn dim(&self) -> usize {
+        self.embedding_size
+    }
+
     pub fn compute_speaker_embedding(
         &mut self,
         samples: Vec<f32>,
         sample_rate: u32,
     ) -> Result<Vec<f32>> {
-        unsafe {
-            let stream =
-                sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingExtractorCreateStream(self.extractor);
-            if stream.is_null() {
-                bail!("Failed to create SherpaOnnxOnlineStream");
+        self.compute(&samples, sample_rate)
+    }
+
+    /// Compute embeddings for many segments in one call.
+    /// Segments are spread across the available CPU cores and the
+    /// embeddings are returned in the same order as the input.
+    pub fn compute_speaker_embeddings<S>(
+        &mut self,
+        segments: &[S],
+        sample_rate: u32,
+    ) -> Result<Vec<Vec<f32>>>
+    where
+        S: AsRef<[f32]> + Sync,
+    {
+        if segments.is_empty() {
+            return Ok(Vec::new());
+        }
+        let num_workers = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .min(segments.len());
+        let chunk_size = segments.len().div_ceil(num_workers);
+
+        let extractor = &*self;
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = segments
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|segment| extractor.compute(segment.as_ref(), sample_rate))
+                            .collect::<Result<Vec<_>>>()
+                    })
+                })
+                .collect();
+
+            let mut embeddings = Vec::with_capacity(segments.len());
+            for worker in workers {
+                match worker.join() {
+                    Ok(chunk) => embeddings.extend(chunk?),
+                    Err(_) => bail!("Speaker embedding worker panicked"),
+                }
             }
+            Ok(embeddings)
+        })
+    }
 
-            sherpa_rs_sys::SherpaOnnxOnlineStreamAcceptWaveform(
-                stream,
-                sample_rate as i32,
-                samples.as_ptr(),
-                samples.len() as i32,
-            );
-            sherpa_rs_sys::SherpaOnnxOnlineStreamInputFinished(stream);
+    /// Stream for one utterance, e.g. to feed audio as it is recorded and compute the
+    /// embedding once the speaker stops
+    pub fn create_stream(&self) -> Result<EmbeddingStream<'_>> {
+        let stream = unsafe {
+            sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingExtractorCreateStream(self.extractor)
+        };
+        if stream.is_null() {
+            bail!("Failed to create SherpaOnnxOnlineStream");
+        }
+        Ok(EmbeddingStream {
+            extractor: self,
+            stream,
+        })
+    }
+
+    fn compute(&self, samples: &[f32], sample_rate: u32) -> Result<Vec<f32>> {
+        let mut stream = self.create_stream()?;
+        stream.accept_waveform(sample_rate, samples);
+        stream.input_finished();
+        stream.compute()
+    }
+
+    /// Whether two recordings are of the same speaker, using `DEFAULT_SIMILARITY_THRESHOLD`
+    pub fn verify(
+        &mut self,
+        samples_a: &[f32],
+        samples_b: &[f32],
+        sample_rate: u32,
+    ) -> Result<VerificationResult> {
+        self.verify_with_threshold(
+            samples_a,
+            samples_b,
+            sample_rate,
+            DEFAULT_SIMILARITY_THRESHOLD,
+        )
+    }
 
-            if !self.is_ready(stream) {
-                bail!("Embedding extractor is not ready");
+    pub fn verify_with_threshold(
+        &mut self,
+        samples_a: &[f32],
+        samples_b: &[f32],
+        sample_rate: u32,
+        threshold: f32,
+    ) -> Result<VerificationResult> {
+        let a = self.compute(samples_a, sample_rate)?;
+        let b = self.compute(samples_b, sample_rate)?;
+        let score = cosine_similarity(&a, &b);
+        Ok(VerificationResult {
+            score,
+            same_speaker: score >= threshold,
+        })
+    }
+
+    #[allow(clippy::missing_safety_doc)]
+    pub unsafe fn is_ready(
+        &mut self,
+        stream: *const sherpa_rs_sys::SherpaOnnxOnlineStream,
+    ) -> bool {
+        unsafe {
+            let result =
+                sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingExtractorIsReady(self.extractor, stream);
+            result != 0
+        }
+    }
+}
+
+/// Audio of one utterance for `EmbeddingExtractor`, freed when dropped
+#[derive(Debug)]
+pub struct EmbeddingStream<'a> {
+    extractor: &'a EmbeddingExtractor,
+    stream: *const sherpa_rs_sys::SherpaOnnxOnlineStream,
+}
+
+impl EmbeddingStream<'_> {
+    pub fn accept_waveform(&mut self, sample_rate: u32, samples: &[f32]) {
+        for chunk in crate::utils::native_chunks(samples) {
+            unsafe {
+                sherpa_rs_sys::SherpaOnnxOnlineStreamAcceptWaveform(
+                    self.stream,
+                    sample_rate as i32,
+                    chunk.as_ptr(),
+                    chunk.len() as i32,
+                );
             }
+        }
+    }
+
+    /// Mark the end of the utterance. Later audio is ignored.
+    pub fn input_finished(&mut self) {
+        unsafe { sherpa_rs_sys::SherpaOnnxOnlineStreamInputFinished(self.stream) }
+    }
 
+    /// Whether there is enough audio for an embedding
+    pub fn is_ready(&self) -> bool {
+        unsafe {
+            sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingExtractorIsReady(
+                self.extractor.extractor,
+                self.stream,
+            ) != 0
+        }
+    }
+
+    /// Embedding of the audio fed so far. Call `input_finished` first.
+    pub fn compute(&self) -> Result<Vec<f32>> {
+        if !self.is_ready() {
+            bail!("Embedding extractor is not ready");
+        }
+        unsafe {
             let embedding_ptr = sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingExtractorComputeEmbedding(
-                self.extractor,
-                stream,
+                self.extractor.extractor,
+                self.stream,
             );
             if embedding_ptr.is_null() {
                 bail!("Failed to compute speaker embedding");
             }
-            tracing::debug!("using dimensions {}", self.embedding_size);
-            let embedding = std::slice::from_raw_parts(embedding_ptr, self.embedding_size).to_vec();
+            let dim = self.extractor.embedding_size;
+            tracing::debug!("using dimensions {}", dim);
+            let embedding = std::slice::from_raw_parts(embedding_ptr, dim).to_vec();
             // Free
-            sherpa_rs_sys::SherpaOnnxDestroyOnlineStream(stream);
             sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingExtractorDestroyEmbedding(embedding_ptr);
             Ok(embedding)
         }
     }
+}
 
-    #[allow(clippy::missing_safety_doc)]
-    pub unsafe fn is_ready(
-        &mut self,
-        stream: *const sherpa_rs_sys::SherpaOnnxOnlineStream,
-    ) -> bool {
+unsafe impl Send for EmbeddingStream<'_> {}
+
+impl Drop for EmbeddingStream<'_> {
+    fn drop(&mut self) {
         unsafe {
-            let result =
-                sherpa_rs_sys::SherpaOnnxSpeakerEmbeddingExtractorIsReady(self.extractor, stream);
-            result != 0
+            sherpa_rs_sys::SherpaOnnxDestroyOnlineStream(self.stream);
         }
     }
 }
@@ -0,0 +1,281 @@
+use crate::{
+    speaker_id::{cosine_similarity, EmbeddingExtractor, DEFAULT_SIMILARITY_THRESHOLD},
+    OnlineTranscriber,
+};
+use eyre::{bail, Result};
+
+#[derive(Debug, Clone)]
+pub struct StreamingDiarizerConfig {
+    pub sample_rate: u32,
+    /// Audio used for each speaker embedding, in seconds
+    pub window: f32,
+    /// Step between embeddings, in seconds. Each step is labeled with one speaker.
+    pub hop: f32,
+    /// Minimum cosine similarity to an existing speaker to join it
+    pub threshold: f32,
+    /// Once reached, windows go to the closest speaker instead of starting a new one
+    pub max_speakers: Option<usize>,
+    /// Windows quieter than this (dBFS) are left unlabeled
+    pub silence_threshold_db: f32,
+}
+
+impl Default for StreamingDiarizerConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 16000,
+            window: 1.5,
+            hop: 0.75,
+            threshold: DEFAULT_SIMILARITY_THRESHOLD,
+            max_speakers: None,
+            silence_threshold_db: -50.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeakerTurn {
+    /// Start time in seconds from the first sample
+    pub start: f32,
+    pub end: f32,
+    pub speaker: i32,
+}
+
+struct Cluster {
+    centroid: Vec<f32>,
+    count: usize,
+}
+
+/// Online speaker diarization for live audio.
+///
+/// Embeds a sliding window of audio and assigns it to the closest known speaker,
+/// or to a new speaker when none is similar enough. Speaker ids are stable for the
+/// lifetime of the diarizer, but early decisions are never revised.
+pub struct StreamingDiarizer {
+    extractor: EmbeddingExtractor,
+    config: StreamingDiarizerConfig,
+    window_len: usize,
+    hop_len: usize,
+    buffer: Vec<f32>,
+    /// Sample position of `buffer[0]`
+    buffer_start: usize,
+    clusters: Vec<Cluster>,
+    turns: Vec<SpeakerTurn>,
+}
+
+impl StreamingDiarizer {
+    pub fn new(extractor: EmbeddingExtractor, config: StreamingDiarizerConfig) -> Result<Self> {
+        let window_len = (config.window * config.sample_rate as f32) as usize;
+        let hop_len = (config.hop * config.sample_rate as f32) as usize;
+        if hop_len == 0 || hop_len > window_len {
+            bail!("hop must be positive and no longer than window");
+        }
+        Ok(Self {
+            extractor,
+            config,
+            window_len,
+            hop_len,
+            buffer: Vec::new(),
+            buffer_start: 0,
+            clusters: Vec::new(),
+            turns: Vec::new(),
+        })
+    }
+
+    pub fn num_speakers(&self) -> usize {
+        self.clusters.len()
+    }
+
+    /// All turns so far. Consecutive steps of the same speaker are merged.
+    pub fn turns(&self) -> &[SpeakerTurn] {
+        &self.turns
+    }
+
+    /// Feed the next chunk and return the turns that were added or extended by it
+    pub fn accept_waveform(&mut self, samples: &[f32]) -> Result<Vec<SpeakerTurn>> {
+        self.buffer.extend_from_slice(samples);
+        let mut changed = Vec::new();
+        while self.buffer.len() >= self.window_len {
+            let window = self.buffer[..self.window_len].to_vec();
+            if let Some(speaker) = self.label(window)? {
+                let start = self.buffer_start as f32 / self.config.sample_rate as f32;
+                let end =
+                    (self.buffer_start + self.hop_len) as f32 / self.config.sample_rate as f32;
+                let turn = self.add_turn(start, end, speaker);
+                if changed
+                    .last()
+                    .is_some_and(|t: &SpeakerTurn| t.start == turn.start)
+                {
+                    changed.pop();
+                }
+                changed.push(turn);
+            }
+            self.buffer.drain(..self.hop_len);
+            self.buffer_start += self.hop_len;
+        }
+        Ok(changed)
+    }
+
+    /// Speaker covering most of `[start, end)` (seconds), if any turn overlaps it
+    pub fn speaker_for(&self, start: f32, end: f32) -> Option<i32> {
+        let mut overlap: Vec<(i32, f32)> = Vec::new();
+        for turn in &self.turns {
+            let covered = turn.end.min(end) - turn.start.max(start);
+            if covered <= 0.0 {
+                continue;
+            }
+            match overlap.iter_mut().find(|(s, _)| *s == turn.speaker) {
+                Some((_, total)) => *total += covered,
+                None => overlap.push((turn.speaker, covered)),
+            }
+        }
+        overlap
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(speaker, _)| speaker)
+    }
+
+    pub fn into_extractor(self) -> EmbeddingExtractor {
+        self.extractor
+    }
+
+    fn label(&mut self, window: Vec<f32>) -> Result<Option<i32>> {
+        let energy = window.iter().map(|s| s * s).sum::<f32>() / window.len() as f32;
+        if 10.0 * energy.max(1e-10).log10() < self.config.silence_threshold_db {
+            return Ok(None);
+        }
+        let embedding = self
+            .extractor
+            .compute_speaker_embedding(window, self.config.sample_rate)?;
+
+        let best = self
+            .clusters
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (i, cosine_similarity(&c.centroid, &embedding)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        let full = self
+            .config
+            .max_speakers
+            .is_some_and(|max| self.clusters.len() >= max);
+        let index = match best {
+            Some((i, score)) if score >= self.config.threshold || full => {
+                // Running mean of the speaker's embeddings
+                let cluster = &mut self.clusters[i];
+                cluster.count += 1;
+                let weight = 1.0 / cluster.count as f32;
+                for (c, e) in cluster.centroid.iter_mut().zip(&embedding) {
+                    *c += (e - *c) * weight;
+                }
+                i
+            }
+            _ => {
+                self.clusters.push(Cluster {
+                    centroid: embedding,
+                    count: 1,
+                });
+                self.clusters.len() - 1
+            }
+        };
+        Ok(Some(index as i32))
+    }
+
+    fn add_turn(&mut self, start: f32, end: f32, speaker: i32) -> SpeakerTurn {
+        match self.turns.last_mut() {
+            Some(last) if last.speaker == speaker && last.end >= start => {
+                last.end = end;
+                last.clone()
+            }
+            _ => {
+                let turn = SpeakerTurn {
+                    start,
+                    end,
+                    speaker,
+                };
+                self.turns.push(turn.clone());
+                turn
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaggedUtterance {
+    /// Start time in seconds from the first sample
+    pub start: f32,
+    pub end: f32,
+    /// `None` when no speaker was detected during the utterance
+    pub speaker: Option<i32>,
+    pub text: String,
+}
+
+/// Runs an online recognizer and a streaming diarizer on the same audio,
+/// tagging each finished utterance with its main speaker.
+///
+/// Enable endpointing in the recognizer config to get one utterance per endpoint.
+/// Labels lag the audio by one diarizer window, so the last moments of an utterance
+/// may not count towards its speaker.
+pub struct DiarizedTranscriber<R: OnlineTranscriber> {
+    recognizer: R,
+    diarizer: StreamingDiarizer,
+    /// Samples fed so far
+    position: usize,
+    utterance_start: usize,
+}
+
+impl<R: OnlineTranscriber> DiarizedTranscriber<R> {
+    pub fn new(recognizer: R, diarizer: StreamingDiarizer) -> Self {
+        Self {
+            recognizer,
+            diarizer,
+            position: 0,
+            utterance_start: 0,
+        }
+    }
+
+    /// Feed the next chunk at the diarizer's sample rate and return finished utterances
+    pub fn accept_waveform(&mut self, samples: &[f32]) -> Result<Vec<TaggedUtterance>> {
+        let sample_rate = self.diarizer.config.sample_rate;
+        self.recognizer.accept_waveform(sample_rate, samples)?;
+        self.diarizer.accept_waveform(samples)?;
+        self.position += samples.len();
+
+        let text = self.recognizer.transcribe();
+        if !self.recognizer.is_endpoint() {
+            return Ok(Vec::new());
+        }
+        self.recognizer.reset();
+        Ok(self.finish_utterance(text).into_iter().collect())
+    }
+
+    /// Finish the input and return the last utterance, if anything was said
+    pub fn flush(&mut self) -> Option<TaggedUtterance> {
+        self.recognizer.input_finished();
+        let text = self.recognizer.transcribe();
+        self.recognizer.reset();
+        self.finish_utterance(text)
+    }
+
+    pub fn diarizer(&self) -> &StreamingDiarizer {
+        &self.diarizer
+    }
+
+    pub fn into_inner(self) -> (R, StreamingDiarizer) {
+        (self.recognizer, self.diarizer)
+    }
+
+    fn finish_utterance(&mut self, text: String) -> Option<TaggedUtterance> {
+        let sample_rate = self.diarizer.config.sample_rate as f32;
+        let start = self.utterance_start as f32 / sample_rate;
+        let end = self.position as f32 / sample_rate;
+        self.utterance_start = self.position;
+        if text.trim().is_empty() {
+            return None;
+        }
+        Some(TaggedUtterance {
+            start,
+            end,
+            speaker: self.diarizer.speaker_for(start, end),
+            text,
+        })
+    }
+}
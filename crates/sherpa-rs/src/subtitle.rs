@@ -0,0 +1,241 @@
+use crate::{
+    alignment::{TokenStyle, WordTiming},
+    caption::{format_captions, CaptionConfig},
+    diarize::Diarize,
+    punctuate::Punctuation,
+    resample::Resampler,
+    Transcriber, VoiceActivityDetector,
+};
+use eyre::{bail, Result};
+use std::fmt::Write;
+use std::path::Path;
+
+const SAMPLE_RATE: u32 = 16000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    /// Start time in seconds
+    pub start: f32,
+    /// End time in seconds
+    pub end: f32,
+    /// Speaker index from diarization, if enabled
+    pub speaker: Option<i32>,
+    pub text: String,
+}
+
+pub struct SubtitleOptions<'a> {
+    pub recognizer: &'a mut dyn Transcriber,
+    /// Split the audio on speech. Ignored when `diarize` is set.
+    pub vad: Option<&'a mut dyn VoiceActivityDetector>,
+    /// Split the audio by speaker turns and label cues with the speaker
+    pub diarize: Option<&'a mut Diarize>,
+    pub punctuation: Option<&'a mut Punctuation>,
+    /// Window length in seconds used when neither VAD nor diarization is set
+    pub max_cue_duration: f32,
+    /// Lay out each segment into captions with line limits and word timing.
+    /// Segments without word timestamps stay one cue each.
+    pub captions: Option<CaptionConfig>,
+}
+
+impl<'a> SubtitleOptions<'a> {
+    pub fn new(recognizer: &'a mut dyn Transcriber) -> Self {
+        Self {
+            recognizer,
+            vad: None,
+            diarize: None,
+            punctuation: None,
+            max_cue_duration: 10.0,
+            captions: None,
+        }
+    }
+}
+
+/// Transcribe a WAV file into subtitle cues.
+/// The audio is downmixed to mono and resampled to 16 kHz.
+pub fn subtitle_file<P: AsRef<Path>>(path: P, options: SubtitleOptions) -> Result<Vec<Cue>> {
+    let samples = read_mono_16k(path.as_ref())?;
+    subtitle_samples(samples, options)
+}
+
+/// Same as `subtitle_file` for 16 kHz mono samples already in memory
+pub fn subtitle_samples(samples: Vec<f32>, options: SubtitleOptions) -> Result<Vec<Cue>> {
+    let SubtitleOptions {
+        recognizer,
+        vad,
+        diarize,
+        mut punctuation,
+        max_cue_duration,
+        captions,
+    } = options;
+
+    // (start sample, end sample, speaker)
+    let spans: Vec<(usize, usize, Option<i32>)> = if let Some(diarize) = diarize {
+        diarize
+            .compute(samples.clone(), None)?
+            .into_iter()
+            .map(|s| {
+                let start = (s.start * SAMPLE_RATE as f32) as usize;
+                let end = (s.end * SAMPLE_RATE as f32) as usize;
+                (start, end, Some(s.speaker))
+            })
+            .collect()
+    } else if let Some(vad) = vad {
+        vad.accept_waveform(samples.clone());
+        vad.flush();
+        let mut spans = Vec::new();
+        while let Some(segment) = vad.pop_segment() {
+            let start = segment.start.max(0) as usize;
+            spans.push((start, start + segment.samples.len(), None));
+        }
+        spans
+    } else {
+        if max_cue_duration <= 0.0 {
+            bail!("max_cue_duration must be positive");
+        }
+        let window = (max_cue_duration * SAMPLE_RATE as f32) as usize;
+        (0..samples.len())
+            .step_by(window)
+            .map(|start| (start, (start + window).min(samples.len()), None))
+            .collect()
+    };
+
+    let mut cues = Vec::new();
+    for (start, end, speaker) in spans {
+        let end = end.min(samples.len());
+        if start >= end {
+            continue;
+        }
+        let result = recognizer.transcribe(SAMPLE_RATE, &samples[start..end]);
+        let mut text = result.text.trim().to_string();
+        if text.is_empty() {
+            continue;
+        }
+        if let Some(punctuation) = punctuation.as_mut() {
+            text = punctuation.add_punctuation(&text);
+        }
+
+        let words = match &captions {
+            Some(_) => result.words(TokenStyle::detect(&result.tokens)),
+            None => Vec::new(),
+        };
+        if let (Some(config), false) = (&captions, words.is_empty()) {
+            let offset = start as f32 / SAMPLE_RATE as f32;
+            // Take punctuation and casing from the text when the words still line up
+            let punctuated: Vec<&str> = text.split_whitespace().collect();
+            let same_words = punctuated.len() == words.len();
+            let words: Vec<_> = words
+                .into_iter()
+                .enumerate()
+                .map(|(i, word)| WordTiming {
+                    word: if same_words {
+                        punctuated[i].to_string()
+                    } else {
+                        word.word
+                    },
+                    start: word.start + offset,
+                    end: word.end + offset,
+                })
+                .collect();
+            cues.extend(
+                format_captions(&words, config)
+                    .into_iter()
+                    .map(|caption| Cue {
+                        start: caption.start,
+                        end: caption.end,
+                        speaker,
+                        text: caption.text(),
+                    }),
+            );
+            continue;
+        }
+        cues.push(Cue {
+            start: start as f32 / SAMPLE_RATE as f32,
+            end: end as f32 / SAMPLE_RATE as f32,
+            speaker,
+            text,
+        });
+    }
+    Ok(cues)
+}
+
+pub fn to_srt(cues: &[Cue]) -> String {
+    let mut out = String::new();
+    for (i, cue) in cues.iter().enumerate() {
+        let _ = writeln!(out, "{}", i + 1);
+        let _ = writeln!(
+            out,
+            "{} --> {}",
+            format_timestamp(cue.start, ','),
+            format_timestamp(cue.end, ',')
+        );
+        match cue.speaker {
+            Some(speaker) => {
+                let _ = writeln!(out, "[Speaker {}] {}\n", speaker, cue.text);
+            }
+            None => {
+                let _ = writeln!(out, "{}\n", cue.text);
+            }
+        }
+    }
+    out
+}
+
+pub fn to_vtt(cues: &[Cue]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for cue in cues {
+        let _ = writeln!(
+            out,
+            "{} --> {}",
+            format_timestamp(cue.start, '.'),
+            format_timestamp(cue.end, '.')
+        );
+        match cue.speaker {
+            Some(speaker) => {
+                let _ = writeln!(out, "<v Speaker {}>{}\n", speaker, cue.text);
+            }
+            None => {
+                let _ = writeln!(out, "{}\n", cue.text);
+            }
+        }
+    }
+    out
+}
+
+pub(crate) fn format_timestamp(seconds: f32, separator: char) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        separator,
+        millis % 1000
+    )
+}
+
+pub(crate) fn read_mono_16k(path: &Path) -> Result<Vec<f32>> {
+    let mut reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = (1_i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    let channels = spec.channels as usize;
+    let mono: Vec<f32> = samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect();
+
+    if spec.sample_rate == SAMPLE_RATE {
+        return Ok(mono);
+    }
+    let mut resampler = Resampler::new(spec.sample_rate, SAMPLE_RATE)?;
+    Ok(resampler.resample(&mono, true))
+}
@@ -0,0 +1,131 @@
+use std::f32::consts::PI;
+
+/// Sample rate of G.711 and most SIP/call-center audio.
+///
+/// The `telephony()` config presets set it for models trained on 8 kHz audio.
+/// 16 kHz models should keep their default and be fed audio upsampled with
+/// `upsample_8k_to_16k`.
+pub const TELEPHONY_SAMPLE_RATE: u32 = 8000;
+
+/// Decode one G.711 µ-law byte (PCMU) to 16-bit PCM
+pub fn mulaw_to_i16(byte: u8) -> i16 {
+    let u = !byte;
+    let exponent = (u >> 4) & 0x07;
+    let mantissa = (u & 0x0f) as i32;
+    let magnitude = (((mantissa << 3) + 0x84) << exponent) - 0x84;
+    if u & 0x80 != 0 {
+        -magnitude as i16
+    } else {
+        magnitude as i16
+    }
+}
+
+/// Decode one G.711 A-law byte (PCMA) to 16-bit PCM
+pub fn alaw_to_i16(byte: u8) -> i16 {
+    let a = byte ^ 0x55;
+    let exponent = (a >> 4) & 0x07;
+    let mantissa = (a & 0x0f) as i32;
+    let magnitude = match exponent {
+        0 => (mantissa << 4) + 8,
+        _ => ((mantissa << 4) + 0x108) << (exponent - 1),
+    };
+    if a & 0x80 != 0 {
+        magnitude as i16
+    } else {
+        -magnitude as i16
+    }
+}
+
+/// Decode a G.711 µ-law payload to f32 samples in [-1, 1]
+pub fn decode_mulaw(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .iter()
+        .map(|&b| mulaw_to_i16(b) as f32 / 32768.0)
+        .collect()
+}
+
+/// Decode a G.711 A-law payload to f32 samples in [-1, 1]
+pub fn decode_alaw(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .iter()
+        .map(|&b| alaw_to_i16(b) as f32 / 32768.0)
+        .collect()
+}
+
+/// Half the number of filter taps
+const HALF_TAPS: usize = 8;
+
+/// Streaming 2x upsampler for 8 kHz telephony audio to the 16 kHz most models expect.
+///
+/// Pure Rust windowed-sinc interpolation: input samples pass through unchanged and the
+/// samples between them are interpolated. Output lags the input by a millisecond.
+pub struct Upsampler {
+    taps: [f32; 2 * HALF_TAPS],
+    /// Input not yet fully consumed, with the filter's left context
+    history: Vec<f32>,
+}
+
+impl Default for Upsampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Upsampler {
+    pub fn new() -> Self {
+        let mut taps = [0.0; 2 * HALF_TAPS];
+        let len = taps.len() as f32;
+        for (j, tap) in taps.iter_mut().enumerate() {
+            // Distance from the interpolated point, halfway between two inputs
+            let t = j as f32 - (HALF_TAPS as f32 - 0.5);
+            let sinc = (PI * t).sin() / (PI * t);
+            let window = 0.42 - 0.5 * (2.0 * PI * (j as f32 + 0.5) / len).cos()
+                + 0.08 * (4.0 * PI * (j as f32 + 0.5) / len).cos();
+            *tap = sinc * window;
+        }
+        let sum: f32 = taps.iter().sum();
+        taps.iter_mut().for_each(|t| *t /= sum);
+        Self {
+            taps,
+            history: vec![0.0; HALF_TAPS - 1],
+        }
+    }
+
+    /// Upsample the next chunk. Returns two samples per input sample once the
+    /// filter is primed; call `flush` at the end to get the rest.
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        self.history.extend_from_slice(samples);
+        let window = self.taps.len();
+        if self.history.len() < window {
+            return Vec::new();
+        }
+        let steps = self.history.len() - window + 1;
+        let mut out = Vec::with_capacity(2 * steps);
+        for i in 0..steps {
+            let context = &self.history[i..i + window];
+            out.push(context[HALF_TAPS - 1]);
+            out.push(context.iter().zip(&self.taps).map(|(x, h)| x * h).sum());
+        }
+        self.history.drain(..steps);
+        out
+    }
+
+    /// Upsample the samples still held back by the filter
+    pub fn flush(&mut self) -> Vec<f32> {
+        let out = self.process(&[0.0; HALF_TAPS]);
+        self.reset();
+        out
+    }
+
+    pub fn reset(&mut self) {
+        self.history = vec![0.0; HALF_TAPS - 1];
+    }
+}
+
+/// Upsample a whole 8 kHz clip to 16 kHz
+pub fn upsample_8k_to_16k(samples: &[f32]) -> Vec<f32> {
+    let mut upsampler = Upsampler::new();
+    let mut out = upsampler.process(samples);
+    out.extend(upsampler.flush());
+    out
+}
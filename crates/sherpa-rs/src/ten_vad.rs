@@ -1,7 +1,7 @@
 use std::mem;
 
 use crate::{get_default_provider, utils::cstring_from_str};
-use eyre::Result;
+use eyre::{bail, Result};
 
 #[derive(Debug)]
 pub struct TenVad {
@@ -39,11 +39,7 @@ impl Default for TenVadConfig {
     }
 }
 
-#[derive(Debug)]
-pub struct SpeechSegment {
-    pub start: i32,
-    pub samples: Vec<f32>,
-}
+pub use crate::silero_vad::SpeechSegment;
 
 impl TenVad {
     pub fn new(config: TenVadConfig, buffer_size_in_seconds: f32) -> Result<Self> {
@@ -60,7 +56,7 @@ impl TenVad {
             window_size: config.window_size,
             max_speech_duration: config.max_speech_duration,
         };
-        let debug = config.debug.into();
+        let debug = crate::native_debug(config.debug);
         let vad_config = unsafe {
             sherpa_rs_sys::SherpaOnnxVadModelConfig {
                 debug,
@@ -77,6 +73,9 @@ impl TenVad {
                 &vad_config,
                 buffer_size_in_seconds,
             );
+            if vad.is_null() {
+                bail!("SherpaOnnxCreateVoiceActivityDetector failed");
+            }
 
             Ok(Self { vad })
         }
@@ -111,16 +110,16 @@ impl TenVad {
         }
     }
 
-    pub fn accept_waveform(&mut self, mut samples: Vec<f32>) {
-        let samples_ptr = samples.as_mut_ptr();
-        let samples_length = samples.len();
-        unsafe {
-            sherpa_rs_sys::SherpaOnnxVoiceActivityDetectorAcceptWaveform(
-                self.vad,
-                samples_ptr,
-                samples_length.try_into().unwrap(),
-            );
-        };
+    pub fn accept_waveform(&mut self, samples: Vec<f32>) {
+        for chunk in crate::utils::native_chunks(&samples) {
+            unsafe {
+                sherpa_rs_sys::SherpaOnnxVoiceActivityDetectorAcceptWaveform(
+                    self.vad,
+                    chunk.as_ptr(),
+                    chunk.len() as i32,
+                );
+            }
+        }
     }
 
     pub fn pop(&mut self) {
@@ -140,6 +139,29 @@ impl TenVad {
     }
 }
 
+impl crate::VoiceActivityDetector for TenVad {
+    fn accept_waveform(&mut self, samples: Vec<f32>) {
+        TenVad::accept_waveform(self, samples)
+    }
+
+    fn flush(&mut self) {
+        TenVad::flush(self)
+    }
+
+    fn pop_segment(&mut self) -> Option<SpeechSegment> {
+        if self.is_empty() {
+            return None;
+        }
+        let segment = self.front();
+        self.pop();
+        Some(segment)
+    }
+
+    fn is_speech(&mut self) -> bool {
+        TenVad::is_speech(self)
+    }
+}
+
 unsafe impl Send for TenVad {}
 unsafe impl Sync for TenVad {}
 
@@ -0,0 +1,180 @@
+use crate::{diarize::Segment, silero_vad::SpeechSegment, streaming_diarize::SpeakerTurn};
+use eyre::{bail, Result};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Time span produced by VAD or diarization, ready to export
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineSpan {
+    /// Start time in seconds
+    pub start: f32,
+    /// End time in seconds
+    pub end: f32,
+    /// Speaker index from diarization, `None` for plain speech
+    pub speaker: Option<i32>,
+}
+
+impl TimelineSpan {
+    /// Span of a VAD segment, whose start is in samples
+    pub fn from_speech_segment(segment: &SpeechSegment, sample_rate: u32) -> Self {
+        let start = segment.start_time(sample_rate);
+        Self {
+            start,
+            end: start + segment.duration(sample_rate),
+            speaker: None,
+        }
+    }
+
+    /// Text used for the span in label tracks: `speaker N` or `speech`
+    pub fn label(&self) -> String {
+        match self.speaker {
+            Some(speaker) => format!("speaker {}", speaker),
+            None => "speech".to_string(),
+        }
+    }
+}
+
+impl From<&Segment> for TimelineSpan {
+    fn from(segment: &Segment) -> Self {
+        Self {
+            start: segment.start,
+            end: segment.end,
+            speaker: Some(segment.speaker),
+        }
+    }
+}
+
+impl From<&SpeakerTurn> for TimelineSpan {
+    fn from(turn: &SpeakerTurn) -> Self {
+        Self {
+            start: turn.start,
+            end: turn.end,
+            speaker: Some(turn.speaker),
+        }
+    }
+}
+
+/// Audacity label track: `start<TAB>end<TAB>label` per line, importable with
+/// File > Import > Labels
+pub fn to_audacity_labels(spans: &[TimelineSpan]) -> String {
+    let mut out = String::new();
+    for span in spans {
+        let _ = writeln!(out, "{:.6}\t{:.6}\t{}", span.start, span.end, span.label());
+    }
+    out
+}
+
+/// JSON array of `{"start", "end", "speaker"}` objects; `speaker` is null for plain speech
+pub fn to_json(spans: &[TimelineSpan]) -> String {
+    let spans: Vec<_> = spans
+        .iter()
+        .map(|span| {
+            json!({
+                "start": round_millis(span.start),
+                "end": round_millis(span.end),
+                "speaker": span.speaker,
+            })
+        })
+        .collect();
+    serde_json::Value::Array(spans).to_string()
+}
+
+/// CSV with a `start,end,speaker` header; `speaker` is empty for plain speech
+pub fn to_csv(spans: &[TimelineSpan]) -> String {
+    let mut out = String::from("start,end,speaker\n");
+    for span in spans {
+        let speaker = span.speaker.map(|s| s.to_string()).unwrap_or_default();
+        let _ = writeln!(out, "{:.3},{:.3},{}", span.start, span.end, speaker);
+    }
+    out
+}
+
+/// RTTM as read by dscore and pyannote: one `SPEAKER` line per span, named
+/// `speaker_N`, or `speech` for plain speech. `file_id` names the recording and
+/// must not contain whitespace.
+pub fn to_rttm(spans: &[TimelineSpan], file_id: &str) -> String {
+    let mut out = String::new();
+    for span in spans {
+        let speaker = match span.speaker {
+            Some(speaker) => format!("speaker_{}", speaker),
+            None => "speech".to_string(),
+        };
+        let _ = writeln!(
+            out,
+            "SPEAKER {} 1 {:.3} {:.3} <NA> <NA> {} <NA> <NA>",
+            file_id,
+            span.start,
+            span.end - span.start,
+            speaker
+        );
+    }
+    out
+}
+
+/// Speaker turn read from an RTTM file
+#[derive(Debug, Clone, PartialEq)]
+pub struct RttmTurn {
+    pub file_id: String,
+    pub channel: u32,
+    /// Start time in seconds
+    pub start: f32,
+    /// End time in seconds
+    pub end: f32,
+    pub speaker: String,
+}
+
+/// Spans of RTTM turns. Speakers are numbered from 0 in the order they first appear,
+/// so any naming scheme works; turns named `speech`, as written by `to_rttm` for plain
+/// speech, get no speaker.
+pub fn rttm_spans(turns: &[RttmTurn]) -> Vec<TimelineSpan> {
+    let mut speakers: HashMap<&str, i32> = HashMap::new();
+    turns
+        .iter()
+        .map(|turn| {
+            let speaker = (turn.speaker != "speech").then(|| {
+                let next = speakers.len() as i32;
+                *speakers.entry(&turn.speaker).or_insert(next)
+            });
+            TimelineSpan {
+                start: turn.start,
+                end: turn.end,
+                speaker,
+            }
+        })
+        .collect()
+}
+
+/// Read the `SPEAKER` lines of an RTTM file, e.g. a reference to compare diarization
+/// output with. Other record types, blank lines and `;` comments are skipped.
+pub fn parse_rttm(text: &str) -> Result<Vec<RttmTurn>> {
+    let mut turns = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.first() != Some(&"SPEAKER") {
+            continue;
+        }
+        if fields.len() < 8 {
+            bail!("line {}: expected at least 8 fields", index + 1);
+        }
+        let (Ok(channel), Ok(start), Ok(duration)) = (
+            fields[2].parse::<u32>(),
+            fields[3].parse::<f32>(),
+            fields[4].parse::<f32>(),
+        ) else {
+            bail!("line {}: invalid channel, onset or duration", index + 1);
+        };
+        turns.push(RttmTurn {
+            file_id: fields[1].to_string(),
+            channel,
+            start,
+            end: start + duration,
+            speaker: fields[7].to_string(),
+        });
+    }
+    Ok(turns)
+}
+
+fn round_millis(seconds: f32) -> f64 {
+    (seconds as f64 * 1000.0).round() / 1000.0
+}
@@ -0,0 +1,84 @@
+use crate::alignment::TokenStyle;
+use eyre::{bail, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Vocabulary of a model, parsed from its `tokens.txt` (one `symbol id` pair per line)
+#[derive(Debug, Clone, Default)]
+pub struct Tokens {
+    symbols: HashMap<i32, String>,
+    ids: HashMap<String, i32>,
+}
+
+impl Tokens {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut tokens = Self::default();
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() {
+                continue;
+            }
+            // The symbol itself may be a space
+            let Some((symbol, id)) = line.rsplit_once(' ') else {
+                bail!("Invalid token on line {}: {:?}", i + 1, line);
+            };
+            let Ok(id) = id.parse::<i32>() else {
+                bail!("Invalid token id on line {}: {:?}", i + 1, id);
+            };
+            tokens.ids.insert(symbol.to_string(), id);
+            tokens.symbols.insert(id, symbol.to_string());
+        }
+        Ok(tokens)
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    pub fn symbol(&self, id: i32) -> Option<&str> {
+        self.symbols.get(&id).map(|s| s.as_str())
+    }
+
+    pub fn id(&self, symbol: &str) -> Option<i32> {
+        self.ids.get(symbol).copied()
+    }
+
+    pub fn contains(&self, symbol: &str) -> bool {
+        self.ids.contains_key(symbol)
+    }
+
+    /// `(id, symbol)` pairs in id order
+    pub fn iter(&self) -> impl Iterator<Item = (i32, &str)> {
+        let mut ids: Vec<_> = self.symbols.keys().copied().collect();
+        ids.sort_unstable();
+        ids.into_iter().map(|id| (id, self.symbols[&id].as_str()))
+    }
+
+    /// How the vocabulary marks word boundaries
+    pub fn style(&self) -> TokenStyle {
+        let symbols: Vec<&str> = self.ids.keys().map(|s| s.as_str()).collect();
+        TokenStyle::detect(&symbols)
+    }
+
+    /// Characters of `text` that no token contains, ignoring whitespace.
+    /// For char-based models (e.g. `cjkchar`) these can't be recognized,
+    /// so hotwords containing them will never match.
+    pub fn unknown_chars(&self, text: &str) -> Vec<char> {
+        let mut unknown = Vec::new();
+        for c in text.chars().filter(|c| !c.is_whitespace()) {
+            if !unknown.contains(&c) && !self.ids.keys().any(|s| s.contains(c)) {
+                unknown.push(c);
+            }
+        }
+        unknown
+    }
+}
@@ -0,0 +1,288 @@
+use crate::alignment::{align_words, TokenStyle, WordTiming};
+use crate::caption::{format_captions, CaptionConfig};
+use crate::hypothesis::Hypothesis;
+use crate::streaming_diarize::TaggedUtterance;
+use crate::subtitle::{format_timestamp, Cue};
+use crate::timeline::TimelineSpan;
+use crate::OfflineRecognizerResult;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Transcript of a conversation: who said what and when.
+///
+/// Shared output of the recognition and diarization pipelines, serialized to JSON with
+/// `to_json`, so applications can exchange transcripts in one schema. Times are in
+/// seconds from the start of the recording.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Transcript {
+    /// Main language of the recording, when known, e.g. "en"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub speakers: Vec<Speaker>,
+    #[serde(default)]
+    pub turns: Vec<Turn>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Speaker {
+    /// Speaker index from diarization
+    pub id: i32,
+    /// Display name, `speaker N` until renamed
+    pub label: String,
+}
+
+/// Stretch of speech by one speaker
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Turn {
+    /// Id of a `Speaker`, `None` when unknown
+    pub speaker: Option<i32>,
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+    /// Language of the turn when it differs between turns or is reported per turn
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Empty when the model gives no timestamps
+    #[serde(default)]
+    pub words: Vec<Word>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Word {
+    pub text: String,
+    pub start: f32,
+    pub end: f32,
+    /// Probability from 0 to 1, when the decoder reports it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+}
+
+impl Turn {
+    /// Turn of an offline result for audio starting at `offset` seconds.
+    /// Set `speaker`, and `end` when the audio ran on past the last word.
+    pub fn from_result(result: &OfflineRecognizerResult, offset: f32) -> Self {
+        let words: Vec<Word> = result
+            .words(TokenStyle::detect(&result.tokens))
+            .into_iter()
+            .map(|word| Word {
+                text: word.word,
+                start: offset + word.start,
+                end: offset + word.end,
+                confidence: None,
+            })
+            .collect();
+        let language = result
+            .lang
+            .trim_start_matches("<|")
+            .trim_end_matches("|>")
+            .to_string();
+        Self {
+            speaker: None,
+            start: offset,
+            end: words.last().map_or(offset, |word| word.end),
+            text: result.text.trim().to_string(),
+            language: (!language.is_empty()).then_some(language),
+            words,
+        }
+    }
+
+    /// Turn of a hypothesis for audio starting at `offset` seconds. Words get the mean
+    /// probability of their tokens as confidence, when the decoder reports them.
+    pub fn from_hypothesis(hypothesis: &Hypothesis, offset: f32, style: TokenStyle) -> Self {
+        let tokens: Vec<&str> = hypothesis.tokens.iter().map(|t| t.text.as_str()).collect();
+        let starts: Vec<f32> = hypothesis.tokens.iter().map(|t| t.start).collect();
+        let words: Vec<Word> = align_words(&tokens, &starts, style)
+            .into_iter()
+            .map(|word| {
+                let probs: Vec<f32> = hypothesis
+                    .tokens
+                    .iter()
+                    .filter(|t| t.start >= word.start && t.start < word.end)
+                    .filter_map(|t| t.prob)
+                    .collect();
+                Word {
+                    confidence: (!probs.is_empty())
+                        .then(|| probs.iter().sum::<f32>() / probs.len() as f32),
+                    text: word.word,
+                    start: offset + word.start,
+                    end: offset + word.end,
+                }
+            })
+            .collect();
+        Self {
+            speaker: None,
+            start: offset,
+            end: words.last().map_or(offset, |word| word.end),
+            text: hypothesis.text.trim().to_string(),
+            language: None,
+            words,
+        }
+    }
+}
+
+impl From<&TaggedUtterance> for Turn {
+    fn from(utterance: &TaggedUtterance) -> Self {
+        Self {
+            speaker: utterance.speaker,
+            start: utterance.start,
+            end: utterance.end,
+            text: utterance.text.trim().to_string(),
+            language: None,
+            words: Vec::new(),
+        }
+    }
+}
+
+impl Transcript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Transcript of the utterances of a `DiarizedTranscriber`
+    pub fn from_utterances(utterances: &[TaggedUtterance]) -> Self {
+        let mut transcript = Self::new();
+        for utterance in utterances {
+            transcript.push(utterance.into());
+        }
+        transcript
+    }
+
+    /// Append a turn, adding its speaker when new and taking the language of the
+    /// first turn that has one. Empty turns are skipped.
+    pub fn push(&mut self, turn: Turn) {
+        if turn.text.is_empty() {
+            return;
+        }
+        if let Some(id) = turn.speaker {
+            if !self.speakers.iter().any(|speaker| speaker.id == id) {
+                self.speakers.push(Speaker {
+                    id,
+                    label: format!("speaker {}", id),
+                });
+            }
+        }
+        if self.language.is_none() {
+            self.language = turn.language.clone();
+        }
+        self.turns.push(turn);
+    }
+
+    /// Label of a speaker, `None` for unknown ids
+    pub fn speaker_label(&self, id: i32) -> Option<&str> {
+        self.speakers
+            .iter()
+            .find(|speaker| speaker.id == id)
+            .map(|speaker| speaker.label.as_str())
+    }
+
+    /// Rename a speaker, e.g. once the voice was identified. Returns whether it exists.
+    pub fn rename_speaker<S: Into<String>>(&mut self, id: i32, label: S) -> bool {
+        match self.speakers.iter_mut().find(|speaker| speaker.id == id) {
+            Some(speaker) => {
+                speaker.label = label.into();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Plain text, one `label: text` line per turn
+    pub fn text(&self) -> String {
+        let mut out = String::new();
+        for turn in &self.turns {
+            if let Some(label) = turn.speaker.and_then(|id| self.speaker_label(id)) {
+                out.push_str(label);
+                out.push_str(": ");
+            }
+            out.push_str(&turn.text);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Time spans of the turns for `timeline` exports such as RTTM
+    pub fn spans(&self) -> Vec<TimelineSpan> {
+        self.turns
+            .iter()
+            .map(|turn| TimelineSpan {
+                start: turn.start,
+                end: turn.end,
+                speaker: turn.speaker,
+            })
+            .collect()
+    }
+
+    /// Subtitle cues: one per turn, or laid out into captions with `captions` for turns
+    /// with word timing
+    pub fn cues(&self, captions: Option<&CaptionConfig>) -> Vec<Cue> {
+        let mut cues = Vec::new();
+        for turn in &self.turns {
+            if let (Some(config), false) = (captions, turn.words.is_empty()) {
+                let words: Vec<WordTiming> = turn
+                    .words
+                    .iter()
+                    .map(|word| WordTiming {
+                        word: word.text.clone(),
+                        start: word.start,
+                        end: word.end,
+                    })
+                    .collect();
+                cues.extend(
+                    format_captions(&words, config)
+                        .into_iter()
+                        .map(|caption| Cue {
+                            start: caption.start,
+                            end: caption.end,
+                            speaker: turn.speaker,
+                            text: caption.text(),
+                        }),
+                );
+            } else {
+                cues.push(Cue {
+                    start: turn.start,
+                    end: turn.end,
+                    speaker: turn.speaker,
+                    text: turn.text.clone(),
+                });
+            }
+        }
+        cues
+    }
+
+    /// SRT with each cue prefixed by its speaker's label, e.g. `[Alice] Good morning`
+    pub fn to_srt(&self, captions: Option<&CaptionConfig>) -> String {
+        let mut out = String::new();
+        for (i, cue) in self.cues(captions).iter().enumerate() {
+            let _ = writeln!(out, "{}", i + 1);
+            let _ = writeln!(
+                out,
+                "{} --> {}",
+                format_timestamp(cue.start, ','),
+                format_timestamp(cue.end, ',')
+            );
+            match cue.speaker.and_then(|id| self.speaker_label(id)) {
+                Some(label) => {
+                    let _ = writeln!(out, "[{}] {}\n", label, cue.text);
+                }
+                None => {
+                    let _ = writeln!(out, "{}\n", cue.text);
+                }
+            }
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("transcript serializes")
+    }
+
+    pub fn to_json_pretty(&self) -> String {
+        serde_json::to_string_pretty(self).expect("transcript serializes")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
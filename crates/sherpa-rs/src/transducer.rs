@@ -1,10 +1,16 @@
-use crate::utils::cstr_to_string;
-use crate::{get_default_provider, utils::cstring_from_str};
+use crate::config_dump::{self, ConfigDump, NativeConfig};
+use crate::{
+    get_default_provider,
+    offline_stream::{self, OfflineStream},
+    utils::cstring_from_str,
+    DecodeOptions,
+};
 use eyre::{bail, Result};
 use std::mem;
 
 pub struct TransducerRecognizer {
     recognizer: *const sherpa_rs_sys::SherpaOnnxOfflineRecognizer,
+    config_dump: ConfigDump,
 }
 
 #[derive(Debug, Clone)]
@@ -50,10 +56,20 @@ impl Default for TransducerConfig {
     }
 }
 
+impl TransducerConfig {
+    /// Defaults for 8 kHz telephony models, see `telephony::TELEPHONY_SAMPLE_RATE`
+    pub fn telephony() -> Self {
+        Self {
+            sample_rate: crate::telephony::TELEPHONY_SAMPLE_RATE as i32,
+            ..Default::default()
+        }
+    }
+}
+
 impl TransducerRecognizer {
     pub fn new(config: TransducerConfig) -> Result<Self> {
-        let recognizer = unsafe {
-            let debug = config.debug.into();
+        let (recognizer, config_dump) = unsafe {
+            let debug = crate::native_debug(config.debug);
             let provider = config.provider.unwrap_or(get_default_provider());
             let provider_ptr = cstring_from_str(&provider);
 
@@ -115,35 +131,57 @@ impl TransducerRecognizer {
                 hr: mem::zeroed::<_>(),
             };
 
+            let config_dump = config_dump::offline_recognizer(&recognizer_config);
             let recognizer = sherpa_rs_sys::SherpaOnnxCreateOfflineRecognizer(&recognizer_config);
             if recognizer.is_null() {
                 bail!("SherpaOnnxCreateOfflineRecognizer failed");
             }
-            recognizer
+            (recognizer, config_dump)
         };
 
-        Ok(Self { recognizer })
+        Ok(Self {
+            recognizer,
+            config_dump,
+        })
+    }
+
+    pub fn create_stream(&self) -> Result<OfflineStream<'_>> {
+        unsafe { OfflineStream::new(self.recognizer) }
+    }
+
+    /// Stream with its own hotwords, one per line.
+    /// Needs decoding method "modified_beam_search".
+    pub fn create_stream_with_hotwords(&self, hotwords: &str) -> Result<OfflineStream<'_>> {
+        unsafe { OfflineStream::with_hotwords(self.recognizer, hotwords) }
     }
 
     pub fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> String {
-        unsafe {
-            let stream = sherpa_rs_sys::SherpaOnnxCreateOfflineStream(self.recognizer);
-            sherpa_rs_sys::SherpaOnnxAcceptWaveformOffline(
-                stream,
-                sample_rate as i32,
-                samples.as_ptr(),
-                samples.len().try_into().unwrap(),
-            );
-            sherpa_rs_sys::SherpaOnnxDecodeOfflineStream(self.recognizer, stream);
-            let result_ptr = sherpa_rs_sys::SherpaOnnxGetOfflineStreamResult(stream);
-            let raw_result = result_ptr.read();
-            let text = cstr_to_string(raw_result.text as _);
-
-            // Free
-            sherpa_rs_sys::SherpaOnnxDestroyOfflineRecognizerResult(result_ptr);
-            sherpa_rs_sys::SherpaOnnxDestroyOfflineStream(stream);
-            text
-        }
+        self.transcribe_with_options(sample_rate, samples, &DecodeOptions::default())
+    }
+
+    pub fn transcribe_with_options(
+        &mut self,
+        sample_rate: u32,
+        samples: &[f32],
+        options: &DecodeOptions,
+    ) -> String {
+        let stream = match &options.hotwords {
+            Some(hotwords) => self.create_stream_with_hotwords(hotwords),
+            None => self.create_stream(),
+        };
+        offline_stream::transcribe_once(stream, sample_rate, samples).text
+    }
+}
+
+impl NativeConfig for TransducerRecognizer {
+    fn config_dump(&self) -> &ConfigDump {
+        &self.config_dump
+    }
+}
+
+impl crate::Transcriber for TransducerRecognizer {
+    fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> crate::OfflineRecognizerResult {
+        offline_stream::transcribe_once(self.create_stream(), sample_rate, samples)
     }
 }
 
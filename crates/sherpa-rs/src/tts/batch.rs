@@ -0,0 +1,70 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use eyre::{eyre, Result};
+
+use super::{TextToSpeech, TtsAudio};
+use crate::shutdown::panic_message;
+
+/// Synthesize many texts in parallel, one thread per engine.
+///
+/// Texts are handed out to whichever engine is free next, and results come back in the
+/// order of `texts`. A failed text doesn't stop the others. An engine that panics fails
+/// its text and takes no more, and the other engines carry on. Each engine loads its own
+/// copy of the model, so a few engines with `num_threads` of 1 or 2 usually beat one
+/// engine with many threads for large prompt sets.
+pub fn synthesize_batch<T, S>(
+    engines: &mut [T],
+    texts: &[S],
+    sid: i32,
+    speed: f32,
+) -> Vec<Result<TtsAudio>>
+where
+    T: TextToSpeech + Send,
+    S: AsRef<str> + Sync,
+{
+    if engines.is_empty() {
+        return texts
+            .iter()
+            .map(|_| Err(eyre!("No TTS engines to synthesize with")))
+            .collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<TtsAudio>>>> =
+        Mutex::new((0..texts.len()).map(|_| None).collect());
+    std::thread::scope(|scope| {
+        for engine in engines.iter_mut() {
+            let next = &next;
+            let results = &results;
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(text) = texts.get(i) else {
+                    break;
+                };
+                let audio = panic::catch_unwind(AssertUnwindSafe(|| {
+                    engine.create(text.as_ref(), sid, speed)
+                }));
+                let panicked = audio.is_err();
+                results.lock().unwrap()[i] = Some(audio.unwrap_or_else(|panic| {
+                    Err(eyre!("TTS engine panicked: {}", panic_message(&*panic)))
+                }));
+                if panicked {
+                    break;
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.unwrap_or_else(|| Err(eyre!("Every TTS engine panicked before this text"))))
+        .collect()
+}
@@ -0,0 +1,129 @@
+use std::io::Cursor;
+
+use eyre::Result;
+
+use super::TtsAudio;
+
+impl TtsAudio {
+    /// Samples as 16-bit PCM, clamped to the i16 range
+    pub fn to_i16(&self) -> Vec<i16> {
+        self.samples
+            .iter()
+            .map(|&s| (s * i16::MAX as f32).clamp(i16::MIN as f32, i16::MAX as f32) as i16)
+            .collect()
+    }
+
+    /// Raw 16-bit little-endian PCM, e.g. for `audio/L16` responses
+    pub fn to_pcm_bytes(&self) -> Vec<u8> {
+        self.to_i16().iter().flat_map(|s| s.to_le_bytes()).collect()
+    }
+
+    /// 16-bit mono WAV file in memory
+    pub fn to_wav_bytes(&self) -> Result<Vec<u8>> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut buffer = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut buffer, spec)?;
+        for sample in self.to_i16() {
+            writer.write_sample(sample)?;
+        }
+        writer.finalize()?;
+        Ok(buffer.into_inner())
+    }
+
+    /// Ogg Opus file in memory, playable by browsers (`audio/ogg; codecs=opus`).
+    ///
+    /// Audio at a rate Opus doesn't support (e.g. 22050 Hz) is resampled to 48 kHz first.
+    #[cfg(feature = "opus")]
+    pub fn to_ogg_opus(&self, bitrate: i32) -> Result<Vec<u8>> {
+        opus::encode_ogg(&self.samples, self.sample_rate, bitrate)
+    }
+}
+
+#[cfg(feature = "opus")]
+mod opus {
+    use audiopus::{coder::Encoder, Application, Bitrate, Channels, SampleRate};
+    use eyre::{bail, Result};
+    use ogg::writing::{PacketWriteEndInfo, PacketWriter};
+
+    use crate::resample::Resampler;
+
+    const SERIAL: u32 = 0x5348_5250;
+
+    pub(super) fn encode_ogg(samples: &[f32], sample_rate: u32, bitrate: i32) -> Result<Vec<u8>> {
+        if sample_rate == 0 {
+            bail!("sample rate must be positive");
+        }
+        let (samples, rate) = match SampleRate::try_from(sample_rate as i32) {
+            Ok(rate) => (samples.to_vec(), rate),
+            Err(_) => {
+                let mut resampler = Resampler::new(sample_rate, 48000)?;
+                (resampler.resample(samples, true), SampleRate::Hz48000)
+            }
+        };
+        let rate_hz = rate as i32 as u64;
+        let mut encoder = Encoder::new(rate, Channels::Mono, Application::Audio)?;
+        encoder.set_bitrate(Bitrate::BitsPerSecond(bitrate))?;
+        // Granule positions are always counted at 48 kHz
+        let pre_skip = encoder.lookahead()? as u64 * 48000 / rate_hz;
+
+        let mut writer = PacketWriter::new(Vec::new());
+        let mut head = b"OpusHead".to_vec();
+        head.push(1);
+        head.push(1);
+        head.extend_from_slice(&(pre_skip as u16).to_le_bytes());
+        head.extend_from_slice(&sample_rate.to_le_bytes());
+        head.extend_from_slice(&0i16.to_le_bytes());
+        head.push(0);
+        writer.write_packet(
+            head.into_boxed_slice(),
+            SERIAL,
+            PacketWriteEndInfo::EndPage,
+            0,
+        )?;
+
+        let vendor = concat!("sherpa-rs ", env!("CARGO_PKG_VERSION"));
+        let mut tags = b"OpusTags".to_vec();
+        tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+        tags.extend_from_slice(vendor.as_bytes());
+        tags.extend_from_slice(&0u32.to_le_bytes());
+        writer.write_packet(
+            tags.into_boxed_slice(),
+            SERIAL,
+            PacketWriteEndInfo::EndPage,
+            0,
+        )?;
+
+        // 20 ms frames, the last one padded with silence
+        let frame_len = rate_hz as usize / 50;
+        let frames = samples.len().div_ceil(frame_len).max(1);
+        let total = pre_skip + samples.len() as u64 * 48000 / rate_hz;
+        let mut frame = vec![0.0; frame_len];
+        let mut packet = vec![0u8; 4000];
+        for i in 0..frames {
+            let chunk = samples.get(i * frame_len..).unwrap_or_default();
+            let chunk = &chunk[..chunk.len().min(frame_len)];
+            frame[..chunk.len()].copy_from_slice(chunk);
+            frame[chunk.len()..].fill(0.0);
+
+            let len = encoder.encode_float(&frame, &mut packet)?;
+            let last = i + 1 == frames;
+            let granule = if last {
+                total
+            } else {
+                pre_skip + ((i + 1) * frame_len) as u64 * 48000 / rate_hz
+            };
+            let end = if last {
+                PacketWriteEndInfo::EndStream
+            } else {
+                PacketWriteEndInfo::NormalPacket
+            };
+            writer.write_packet(packet[..len].into(), SERIAL, end, granule)?;
+        }
+        Ok(writer.into_inner())
+    }
+}
@@ -1,29 +1,55 @@
-use std::{mem, ptr::null};
+use std::mem;
 
 use crate::{utils::cstring_from_str, OnnxConfig};
 use eyre::Result;
 use sherpa_rs_sys;
 
-use super::{CommonTtsConfig, TtsAudio};
+use super::{CommonTtsConfig, TtsAudio, TtsProgress};
 
 pub struct KokoroTts {
-    tts: *const sherpa_rs_sys::SherpaOnnxOfflineTts,
+    pub(super) tts: *const sherpa_rs_sys::SherpaOnnxOfflineTts,
 }
 
-#[derive(Default)]
+/// Multilingual Kokoro models (e.g. kokoro-multi-lang-v1_0) need `dict_dir`,
+/// one lexicon per language and optionally `lang` to synthesize mixed zh/en text.
+#[derive(Debug, Default)]
 pub struct KokoroTtsConfig {
     pub model: String,
+    /// Path to voices.bin, which holds the style vectors of all speakers.
+    /// The speaker is selected with the `sid` argument of `create`.
     pub voices: String,
     pub tokens: String,
     pub data_dir: String,
+    /// Jieba dictionary directory, needed for Chinese text
     pub dict_dir: String,
+    /// Lexicon path. Multiple paths can be separated with commas, or use `lexicons`.
     pub lexicon: String,
+    /// Additional lexicon paths, e.g. lexicon-us-en.txt and lexicon-zh.txt.
+    /// Merged with `lexicon`.
+    pub lexicons: Vec<String>,
     pub length_scale: f32,
     pub onnx_config: OnnxConfig,
     pub common_config: CommonTtsConfig,
+    /// Language of the input text (e.g. "en-us", "zh"). Empty lets the model decide.
     pub lang: String,
 }
 
+impl KokoroTtsConfig {
+    fn joined_lexicons(&self) -> String {
+        let mut lexicons: Vec<&str> = Vec::new();
+        if !self.lexicon.is_empty() {
+            lexicons.push(&self.lexicon);
+        }
+        lexicons.extend(
+            self.lexicons
+                .iter()
+                .map(|l| l.as_str())
+                .filter(|l| !l.is_empty()),
+        );
+        lexicons.join(",")
+    }
+}
+
 impl KokoroTts {
     pub fn new(config: KokoroTtsConfig) -> Self {
         let tts = unsafe {
@@ -32,7 +58,7 @@ impl KokoroTts {
             let tokens = cstring_from_str(&config.tokens);
             let data_dir = cstring_from_str(&config.data_dir);
             let dict_dir = cstring_from_str(&config.dict_dir);
-            let lexicon = cstring_from_str(&config.lexicon);
+            let lexicon = cstring_from_str(&config.joined_lexicons());
             let lang = cstring_from_str(&config.lang);
 
             let provider = cstring_from_str(&config.onnx_config.provider);
@@ -42,7 +68,7 @@ impl KokoroTts {
             let model_config = sherpa_rs_sys::SherpaOnnxOfflineTtsModelConfig {
                 vits: mem::zeroed::<_>(),
                 num_threads: config.onnx_config.num_threads,
-                debug: config.onnx_config.debug.into(),
+                debug: crate::native_debug(config.onnx_config.debug),
                 provider: provider.as_ptr(),
                 matcha: mem::zeroed::<_>(),
                 kokoro: sherpa_rs_sys::SherpaOnnxOfflineTtsKokoroModelConfig {
@@ -61,8 +87,8 @@ impl KokoroTts {
             let config = sherpa_rs_sys::SherpaOnnxOfflineTtsConfig {
                 max_num_sentences: config.common_config.max_num_sentences,
                 model: model_config,
-                rule_fars: tts_config.rule_fars.map(|v| v.as_ptr()).unwrap_or(null()),
-                rule_fsts: tts_config.rule_fsts.map(|v| v.as_ptr()).unwrap_or(null()),
+                rule_fars: tts_config.rule_fars_ptr(),
+                rule_fsts: tts_config.rule_fsts_ptr(),
                 silence_scale: config.common_config.silence_scale,
             };
             sherpa_rs_sys::SherpaOnnxCreateOfflineTts(&config)
@@ -71,9 +97,49 @@ impl KokoroTts {
         Self { tts }
     }
 
+    /// Sample rate of the generated audio
+    pub fn sample_rate(&self) -> u32 {
+        unsafe { sherpa_rs_sys::SherpaOnnxOfflineTtsSampleRate(self.tts) as u32 }
+    }
+
     pub fn create(&mut self, text: &str, sid: i32, speed: f32) -> Result<TtsAudio> {
         unsafe { super::create(self.tts, text, sid, speed) }
     }
+
+    /// Same as `create`, but reports progress after each generated batch of sentences.
+    /// Return `false` from the callback to stop early.
+    pub fn create_with_progress<F>(
+        &mut self,
+        text: &str,
+        sid: i32,
+        speed: f32,
+        mut callback: F,
+    ) -> Result<TtsAudio>
+    where
+        F: FnMut(TtsProgress<'_>) -> bool,
+    {
+        unsafe { super::create_with_progress(self.tts, text, sid, speed, &mut callback) }
+    }
+}
+
+impl super::TextToSpeech for KokoroTts {
+    fn create(&mut self, text: &str, sid: i32, speed: f32) -> Result<TtsAudio> {
+        KokoroTts::create(self, text, sid, speed)
+    }
+
+    fn create_with_progress(
+        &mut self,
+        text: &str,
+        sid: i32,
+        speed: f32,
+        callback: &mut dyn FnMut(TtsProgress<'_>) -> bool,
+    ) -> Result<TtsAudio> {
+        unsafe { super::create_with_progress(self.tts, text, sid, speed, callback) }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        KokoroTts::sample_rate(self)
+    }
 }
 
 unsafe impl Send for KokoroTts {}
@@ -1,27 +1,38 @@
-use std::{mem, ptr::null};
+use std::mem;
 
 use crate::{utils::cstring_from_str, OnnxConfig};
 use eyre::Result;
 use sherpa_rs_sys;
 
-use super::{CommonTtsConfig, TtsAudio};
+use super::{CommonTtsConfig, TtsAudio, TtsProgress};
 
 pub struct MatchaTts {
-    tts: *const sherpa_rs_sys::SherpaOnnxOfflineTts,
+    pub(super) tts: *const sherpa_rs_sys::SherpaOnnxOfflineTts,
 }
 
-#[derive(Default)]
+/// Matcha is a two stage model: the acoustic model turns text into a mel spectrogram
+/// and the vocoder (e.g. hifigan or vocos) turns the spectrogram into audio.
+///
+/// Scale parameters left at `0.0` fall back to the model defaults.
+#[derive(Debug, Default)]
 pub struct MatchaTtsConfig {
+    /// Unused by Matcha, see `acoustic_model`
     pub model: String,
     pub lexicon: String,
     pub dict_dir: String,
     pub tokens: String,
     pub data_dir: String,
+    /// Path to the Matcha acoustic model
     pub acoustic_model: String,
+    /// Path to the vocoder model. Required, Matcha cannot produce audio without it.
     pub vocoder: String,
+    /// Speaking rate. Larger is slower, smaller is faster. Defaults to 1.0.
     pub length_scale: f32,
+    /// Amount of variation in the generated voice. Defaults to 1.0.
     pub noise_scale: f32,
+    /// Unused by Matcha
     pub noise_scale_w: f32,
+    /// Scale of the silence inserted between sentences. Defaults to 0.2.
     pub silence_scale: f32,
 
     pub common_config: CommonTtsConfig,
@@ -46,7 +57,7 @@ impl MatchaTts {
             let model_config = sherpa_rs_sys::SherpaOnnxOfflineTtsModelConfig {
                 num_threads: config.onnx_config.num_threads,
                 vits: mem::zeroed::<_>(),
-                debug: config.onnx_config.debug.into(),
+                debug: crate::native_debug(config.onnx_config.debug),
                 provider: provider.as_ptr(),
                 matcha: sherpa_rs_sys::SherpaOnnxOfflineTtsMatchaModelConfig {
                     acoustic_model: acoustic_model.as_ptr(),
@@ -65,8 +76,8 @@ impl MatchaTts {
             let config = sherpa_rs_sys::SherpaOnnxOfflineTtsConfig {
                 max_num_sentences: config.common_config.max_num_sentences,
                 model: model_config,
-                rule_fars: tts_config.rule_fars.map(|v| v.as_ptr()).unwrap_or(null()),
-                rule_fsts: tts_config.rule_fsts.map(|v| v.as_ptr()).unwrap_or(null()),
+                rule_fars: tts_config.rule_fars_ptr(),
+                rule_fsts: tts_config.rule_fsts_ptr(),
                 silence_scale: config.silence_scale,
             };
             sherpa_rs_sys::SherpaOnnxCreateOfflineTts(&config)
@@ -75,9 +86,49 @@ impl MatchaTts {
         Self { tts }
     }
 
+    /// Sample rate of the generated audio
+    pub fn sample_rate(&self) -> u32 {
+        unsafe { sherpa_rs_sys::SherpaOnnxOfflineTtsSampleRate(self.tts) as u32 }
+    }
+
     pub fn create(&mut self, text: &str, sid: i32, speed: f32) -> Result<TtsAudio> {
         unsafe { super::create(self.tts, text, sid, speed) }
     }
+
+    /// Same as `create`, but reports progress after each generated batch of sentences.
+    /// Return `false` from the callback to stop early.
+    pub fn create_with_progress<F>(
+        &mut self,
+        text: &str,
+        sid: i32,
+        speed: f32,
+        mut callback: F,
+    ) -> Result<TtsAudio>
+    where
+        F: FnMut(TtsProgress<'_>) -> bool,
+    {
+        unsafe { super::create_with_progress(self.tts, text, sid, speed, &mut callback) }
+    }
+}
+
+impl super::TextToSpeech for MatchaTts {
+    fn create(&mut self, text: &str, sid: i32, speed: f32) -> Result<TtsAudio> {
+        MatchaTts::create(self, text, sid, speed)
+    }
+
+    fn create_with_progress(
+        &mut self,
+        text: &str,
+        sid: i32,
+        speed: f32,
+        callback: &mut dyn FnMut(TtsProgress<'_>) -> bool,
+    ) -> Result<TtsAudio> {
+        unsafe { super::create_with_progress(self.tts, text, sid, speed, callback) }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        MatchaTts::sample_rate(self)
+    }
 }
 
 unsafe impl Send for MatchaTts {}
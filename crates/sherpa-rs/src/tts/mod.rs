@@ -1,15 +1,22 @@
+mod batch;
+mod encode;
 mod kitten;
 mod kokoro;
 mod matcha;
+mod ssml;
 mod vits;
 
-use std::ffi::CString;
+use std::ffi::{c_char, c_void, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
 
 use eyre::{bail, Result};
 
+pub use batch::synthesize_batch;
 pub use kitten::{KittenTts, KittenTtsConfig};
 pub use kokoro::{KokoroTts, KokoroTtsConfig};
 pub use matcha::{MatchaTts, MatchaTtsConfig};
+pub use ssml::{parse_ssml, synthesize_ssml, SsmlSegment, MAX_BREAK_SECONDS};
 pub use vits::{VitsTts, VitsTtsConfig};
 
 use crate::utils::cstring_from_str;
@@ -21,7 +28,134 @@ pub struct TtsAudio {
     pub duration: i32,
 }
 
-#[derive(Default)]
+/// Common interface of the TTS engines
+pub trait TextToSpeech {
+    fn create(&mut self, text: &str, sid: i32, speed: f32) -> Result<TtsAudio>;
+
+    /// Same as `create`, but hands out audio as each batch of sentences is generated.
+    /// Return `false` from the callback to stop early.
+    ///
+    /// The default implementation reports the whole audio once, when it's done.
+    fn create_with_progress(
+        &mut self,
+        text: &str,
+        sid: i32,
+        speed: f32,
+        callback: &mut dyn FnMut(TtsProgress<'_>) -> bool,
+    ) -> Result<TtsAudio> {
+        let audio = self.create(text, sid, speed)?;
+        callback(TtsProgress {
+            samples: &audio.samples,
+            progress: 1.0,
+            sentence_index: 0,
+        });
+        Ok(audio)
+    }
+
+    /// Sample rate of the generated audio
+    fn sample_rate(&self) -> u32;
+}
+
+impl<T: TextToSpeech + ?Sized> TextToSpeech for Box<T> {
+    fn create(&mut self, text: &str, sid: i32, speed: f32) -> Result<TtsAudio> {
+        (**self).create(text, sid, speed)
+    }
+
+    fn create_with_progress(
+        &mut self,
+        text: &str,
+        sid: i32,
+        speed: f32,
+        callback: &mut dyn FnMut(TtsProgress<'_>) -> bool,
+    ) -> Result<TtsAudio> {
+        (**self).create_with_progress(text, sid, speed, callback)
+    }
+
+    fn sample_rate(&self) -> u32 {
+        (**self).sample_rate()
+    }
+}
+
+/// Config of any TTS engine, to pick the engine at runtime, e.g. from user settings
+#[derive(Debug)]
+pub enum TtsModelConfig {
+    Vits(VitsTtsConfig),
+    Kokoro(KokoroTtsConfig),
+    Matcha(MatchaTtsConfig),
+    Kitten(KittenTtsConfig),
+}
+
+impl TtsModelConfig {
+    /// Create the engine. Fails if sherpa-onnx can't, e.g. for a missing model file.
+    pub fn load(self) -> Result<Box<dyn TextToSpeech + Send + Sync>> {
+        match self {
+            TtsModelConfig::Vits(config) => {
+                let tts = VitsTts::new(config);
+                loaded(tts.tts, tts)
+            }
+            TtsModelConfig::Kokoro(config) => {
+                let tts = KokoroTts::new(config);
+                loaded(tts.tts, tts)
+            }
+            TtsModelConfig::Matcha(config) => {
+                let tts = MatchaTts::new(config);
+                loaded(tts.tts, tts)
+            }
+            TtsModelConfig::Kitten(config) => {
+                let tts = KittenTts::new(config);
+                loaded(tts.tts, tts)
+            }
+        }
+    }
+}
+
+/// Box `engine`, unless its native engine `tts` wasn't created
+fn loaded<T: TextToSpeech + Send + Sync + 'static>(
+    tts: *const sherpa_rs_sys::SherpaOnnxOfflineTts,
+    engine: T,
+) -> Result<Box<dyn TextToSpeech + Send + Sync>> {
+    if tts.is_null() {
+        bail!("SherpaOnnxCreateOfflineTts failed");
+    }
+    Ok(Box::new(engine))
+}
+
+impl From<VitsTtsConfig> for TtsModelConfig {
+    fn from(config: VitsTtsConfig) -> Self {
+        TtsModelConfig::Vits(config)
+    }
+}
+
+impl From<KokoroTtsConfig> for TtsModelConfig {
+    fn from(config: KokoroTtsConfig) -> Self {
+        TtsModelConfig::Kokoro(config)
+    }
+}
+
+impl From<MatchaTtsConfig> for TtsModelConfig {
+    fn from(config: MatchaTtsConfig) -> Self {
+        TtsModelConfig::Matcha(config)
+    }
+}
+
+impl From<KittenTtsConfig> for TtsModelConfig {
+    fn from(config: KittenTtsConfig) -> Self {
+        TtsModelConfig::Kitten(config)
+    }
+}
+
+/// Progress of a running synthesis, reported each time a batch of sentences is generated
+#[derive(Debug)]
+pub struct TtsProgress<'a> {
+    /// Audio generated for the current batch of sentences
+    pub samples: &'a [f32],
+    /// Overall progress in the range 0.0 to 1.0
+    pub progress: f32,
+    /// Index of the current batch, starting from 0
+    pub sentence_index: usize,
+}
+
+#[derive(Debug, Default)]
 pub struct CommonTtsConfig {
     pub rule_fars: String,
     pub rule_fsts: String,
@@ -35,6 +169,18 @@ pub struct CommonTtsRaw {
     pub max_num_sentences: i32,
 }
 
+impl CommonTtsRaw {
+    /// Pointer for the native config, null when unset. Valid while `self` is alive.
+    pub fn rule_fars_ptr(&self) -> *const c_char {
+        self.rule_fars.as_ref().map_or(ptr::null(), |v| v.as_ptr())
+    }
+
+    /// Pointer for the native config, null when unset. Valid while `self` is alive.
+    pub fn rule_fsts_ptr(&self) -> *const c_char {
+        self.rule_fsts.as_ref().map_or(ptr::null(), |v| v.as_ptr())
+    }
+}
+
 impl CommonTtsConfig {
     pub fn to_raw(&self) -> CommonTtsRaw {
         let rule_fars = if self.rule_fars.is_empty() {
@@ -68,7 +214,86 @@ pub unsafe fn create(
 ) -> Result<TtsAudio> {
     let text = cstring_from_str(text);
     let audio_ptr = sherpa_rs_sys::SherpaOnnxOfflineTtsGenerate(tts, text.as_ptr(), sid, speed);
+    read_generated_audio(audio_ptr)
+}
 
+/// # Safety
+///
+/// This function dereference sherpa_rs_sys::SherpaOnnxOfflineTts
+///
+/// The callback is invoked for every generated batch of sentences.
+/// Return `false` from it to stop the synthesis early. A panic in the callback
+/// stops the synthesis and is resumed once the native call has returned.
+pub unsafe fn create_with_progress(
+    tts: *const sherpa_rs_sys::SherpaOnnxOfflineTts,
+    text: &str,
+    sid: i32,
+    speed: f32,
+    callback: &mut dyn FnMut(TtsProgress<'_>) -> bool,
+) -> Result<TtsAudio> {
+    let text = cstring_from_str(text);
+    let mut state = ProgressState {
+        callback,
+        sentence_index: 0,
+        panic: None,
+    };
+    let audio_ptr = sherpa_rs_sys::SherpaOnnxOfflineTtsGenerateWithProgressCallbackWithArg(
+        tts,
+        text.as_ptr(),
+        sid,
+        speed,
+        Some(progress_callback_wrapper),
+        &mut state as *mut ProgressState as *mut c_void,
+    );
+    let audio = read_generated_audio(audio_ptr);
+    if let Some(panic) = state.panic {
+        panic::resume_unwind(panic);
+    }
+    audio
+}
+
+struct ProgressState<'a> {
+    callback: &'a mut dyn FnMut(TtsProgress<'_>) -> bool,
+    sentence_index: usize,
+    // Unwinding through the native frames is undefined behavior
+    panic: Option<Box<dyn std::any::Any + Send>>,
+}
+
+unsafe extern "C" fn progress_callback_wrapper(
+    samples: *const f32,
+    n: i32,
+    progress: f32,
+    arg: *mut c_void,
+) -> i32 {
+    let state = &mut *(arg as *mut ProgressState);
+    if state.panic.is_some() {
+        return 0;
+    }
+    let samples = if samples.is_null() || n <= 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(samples, n as usize)
+    };
+    let progress = TtsProgress {
+        samples,
+        progress,
+        sentence_index: state.sentence_index,
+    };
+    match panic::catch_unwind(AssertUnwindSafe(|| (state.callback)(progress))) {
+        Ok(keep_going) => {
+            state.sentence_index += 1;
+            keep_going.into()
+        }
+        Err(panic) => {
+            state.panic = Some(panic);
+            0
+        }
+    }
+}
+
+unsafe fn read_generated_audio(
+    audio_ptr: *const sherpa_rs_sys::SherpaOnnxGeneratedAudio,
+) -> Result<TtsAudio> {
     if audio_ptr.is_null() {
         bail!("audio is null");
     }
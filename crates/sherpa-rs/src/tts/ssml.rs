@@ -0,0 +1,214 @@
+use std::sync::OnceLock;
+
+use eyre::{bail, Result};
+use regex::Regex;
+
+use super::{TextToSpeech, TtsAudio};
+
+/// One step of a compiled SSML document
+#[derive(Debug, Clone, PartialEq)]
+pub enum SsmlSegment {
+    Text {
+        text: String,
+        sid: i32,
+        speed: f32,
+    },
+    /// Silence in seconds
+    Break(f32),
+}
+
+/// Longest silence a single `<break>` inserts, in seconds
+pub const MAX_BREAK_SECONDS: f32 = 10.0;
+
+fn tag_regex() -> &'static Regex {
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    TAG.get_or_init(|| Regex::new(r"<(/?)\s*([A-Za-z:_-]+)([^>]*?)(/?)>").unwrap())
+}
+
+fn attribute_regex() -> &'static Regex {
+    static ATTRIBUTE: OnceLock<Regex> = OnceLock::new();
+    ATTRIBUTE.get_or_init(|| Regex::new(r#"([A-Za-z:_-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap())
+}
+
+/// Compile the supported SSML subset into text and silence segments.
+///
+/// Supported elements:
+/// - `<break time="500ms"/>` or `time="1.5s"`, capped at `MAX_BREAK_SECONDS`;
+///   `strength` from `none` to `x-strong`
+/// - `<prosody rate="...">`: a percentage (`150%`), a factor (`1.2`) or `x-slow` to `x-fast`
+/// - `<voice name="3">` or `speaker="3"`: speaker id of the enclosed text
+///
+/// `<speak>` and unknown elements are ignored but their text is kept.
+/// Text outside any element uses `sid` and `speed`.
+pub fn parse_ssml(ssml: &str, sid: i32, speed: f32) -> Result<Vec<SsmlSegment>> {
+    // (element name, sid, speed) of the open elements
+    let mut stack: Vec<(String, i32, f32)> = Vec::new();
+    let mut segments = Vec::new();
+    let mut last = 0;
+
+    for caps in tag_regex().captures_iter(ssml) {
+        let whole = caps.get(0).unwrap();
+        let (sid, speed) = stack.last().map(|e| (e.1, e.2)).unwrap_or((sid, speed));
+        push_text(&mut segments, &ssml[last..whole.start()], sid, speed);
+        last = whole.end();
+
+        let name = caps[2].to_lowercase();
+        let closing = !caps[1].is_empty();
+        let self_closing = !caps[4].is_empty();
+        let attrs = &caps[3];
+
+        if closing {
+            match stack.iter().rposition(|e| e.0 == name) {
+                Some(i) => stack.truncate(i),
+                None => bail!("Unexpected closing tag </{}>", name),
+            }
+            continue;
+        }
+        match name.as_str() {
+            "break" => segments.push(SsmlSegment::Break(break_duration(attrs)?)),
+            "prosody" | "voice" if !self_closing => {
+                let mut entry = (name.clone(), sid, speed);
+                if name == "prosody" {
+                    if let Some(rate) = attribute(attrs, "rate") {
+                        entry.2 = speed * parse_rate(&rate)?;
+                    }
+                } else if let Some(id) =
+                    attribute(attrs, "speaker").or_else(|| attribute(attrs, "name"))
+                {
+                    let Ok(id) = id.trim().parse() else {
+                        bail!("Voice must be a numeric speaker id, got {:?}", id);
+                    };
+                    entry.1 = id;
+                }
+                stack.push(entry);
+            }
+            _ if !self_closing => stack.push((name, sid, speed)),
+            _ => {}
+        }
+    }
+    let (sid, speed) = stack.last().map(|e| (e.1, e.2)).unwrap_or((sid, speed));
+    push_text(&mut segments, &ssml[last..], sid, speed);
+    Ok(segments)
+}
+
+/// Synthesize an SSML document, joining the generated pieces and inserting silence for breaks
+pub fn synthesize_ssml<T: TextToSpeech + ?Sized>(
+    tts: &mut T,
+    ssml: &str,
+    sid: i32,
+    speed: f32,
+) -> Result<TtsAudio> {
+    let segments = parse_ssml(ssml, sid, speed)?;
+    let mut pieces = Vec::with_capacity(segments.len());
+    let mut sample_rate = None;
+    for segment in segments {
+        match segment {
+            SsmlSegment::Text { text, sid, speed } => {
+                let audio = tts.create(&text, sid, speed)?;
+                sample_rate.get_or_insert(audio.sample_rate);
+                pieces.push(Ok(audio.samples));
+            }
+            SsmlSegment::Break(seconds) => pieces.push(Err(seconds)),
+        }
+    }
+    let Some(sample_rate) = sample_rate else {
+        bail!("SSML contains no text to synthesize");
+    };
+
+    let mut samples = Vec::new();
+    for piece in pieces {
+        match piece {
+            Ok(audio) => samples.extend(audio),
+            Err(seconds) => {
+                samples.resize(samples.len() + (seconds * sample_rate as f32) as usize, 0.0)
+            }
+        }
+    }
+    Ok(TtsAudio {
+        duration: (samples.len() / sample_rate as usize) as i32,
+        samples,
+        sample_rate,
+    })
+}
+
+fn push_text(segments: &mut Vec<SsmlSegment>, raw: &str, sid: i32, speed: f32) {
+    let text = unescape(
+        raw.split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .as_str(),
+    );
+    if text.is_empty() {
+        return;
+    }
+    match segments.last_mut() {
+        Some(SsmlSegment::Text {
+            text: last,
+            sid: last_sid,
+            speed: last_speed,
+        }) if *last_sid == sid && *last_speed == speed => {
+            last.push(' ');
+            last.push_str(&text);
+        }
+        _ => segments.push(SsmlSegment::Text { text, sid, speed }),
+    }
+}
+
+fn attribute(attrs: &str, name: &str) -> Option<String> {
+    let caps = attribute_regex()
+        .captures_iter(attrs)
+        .find(|caps| caps[1].eq_ignore_ascii_case(name))?;
+    caps.get(2).or(caps.get(3)).map(|m| unescape(m.as_str()))
+}
+
+fn break_duration(attrs: &str) -> Result<f32> {
+    if let Some(time) = attribute(attrs, "time") {
+        let time = time.trim();
+        let (value, scale) = match time.strip_suffix("ms") {
+            Some(ms) => (ms, 0.001),
+            None => (time.strip_suffix('s').unwrap_or(time), 1.0),
+        };
+        return match value.trim().parse::<f32>() {
+            Ok(v) if v.is_finite() && v >= 0.0 => Ok((v * scale).min(MAX_BREAK_SECONDS)),
+            _ => bail!("Invalid break time {:?}", time),
+        };
+    }
+    Ok(
+        match attribute(attrs, "strength").as_deref().unwrap_or("medium") {
+            "none" => 0.0,
+            "x-weak" => 0.1,
+            "weak" => 0.25,
+            "medium" => 0.5,
+            "strong" => 0.75,
+            "x-strong" => 1.0,
+            other => bail!("Invalid break strength {:?}", other),
+        },
+    )
+}
+
+fn parse_rate(rate: &str) -> Result<f32> {
+    let rate = rate.trim();
+    let factor = match rate {
+        "x-slow" => Some(0.5),
+        "slow" => Some(0.75),
+        "medium" | "default" => Some(1.0),
+        "fast" => Some(1.25),
+        "x-fast" => Some(1.75),
+        _ => match rate.strip_suffix('%') {
+            Some(percent) => percent.trim().parse::<f32>().ok().map(|p| p / 100.0),
+            None => rate.parse().ok(),
+        },
+    };
+    match factor {
+        Some(f) if f.is_finite() && f > 0.0 => Ok(f),
+        _ => bail!("Invalid prosody rate {:?}", rate),
+    }
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
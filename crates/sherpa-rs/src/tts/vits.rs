@@ -1,16 +1,16 @@
-use std::{mem, ptr::null};
+use std::mem;
 
 use crate::{utils::cstring_from_str, OnnxConfig};
 use eyre::Result;
 use sherpa_rs_sys;
 
-use super::{CommonTtsConfig, TtsAudio};
+use super::{CommonTtsConfig, TtsAudio, TtsProgress};
 
 pub struct VitsTts {
-    tts: *const sherpa_rs_sys::SherpaOnnxOfflineTts,
+    pub(super) tts: *const sherpa_rs_sys::SherpaOnnxOfflineTts,
 }
 
-#[derive(Default)]
+#[derive(Debug, Default)]
 pub struct VitsTtsConfig {
     pub model: String,
     pub lexicon: String,
@@ -51,7 +51,7 @@ impl VitsTts {
                     length_scale: config.length_scale,
                     dict_dir: dict_dir.as_ptr(),
                 },
-                debug: config.onnx_config.debug.into(),
+                debug: crate::native_debug(config.onnx_config.debug),
                 provider: provider.as_ptr(),
                 matcha: mem::zeroed::<_>(),
                 kokoro: mem::zeroed::<_>(),
@@ -61,8 +61,8 @@ impl VitsTts {
             let config = sherpa_rs_sys::SherpaOnnxOfflineTtsConfig {
                 max_num_sentences: config.tts_config.max_num_sentences,
                 model: model_config,
-                rule_fars: tts_config.rule_fars.map(|v| v.as_ptr()).unwrap_or(null()),
-                rule_fsts: tts_config.rule_fsts.map(|v| v.as_ptr()).unwrap_or(null()),
+                rule_fars: tts_config.rule_fars_ptr(),
+                rule_fsts: tts_config.rule_fsts_ptr(),
                 silence_scale: config.silence_scale,
             };
             sherpa_rs_sys::SherpaOnnxCreateOfflineTts(&config)
@@ -71,9 +71,49 @@ impl VitsTts {
         Self { tts }
     }
 
+    /// Sample rate of the generated audio
+    pub fn sample_rate(&self) -> u32 {
+        unsafe { sherpa_rs_sys::SherpaOnnxOfflineTtsSampleRate(self.tts) as u32 }
+    }
+
     pub fn create(&mut self, text: &str, sid: i32, speed: f32) -> Result<TtsAudio> {
         unsafe { super::create(self.tts, text, sid, speed) }
     }
+
+    /// Same as `create`, but reports progress after each generated batch of sentences.
+    /// Return `false` from the callback to stop early.
+    pub fn create_with_progress<F>(
+        &mut self,
+        text: &str,
+        sid: i32,
+        speed: f32,
+        mut callback: F,
+    ) -> Result<TtsAudio>
+    where
+        F: FnMut(TtsProgress<'_>) -> bool,
+    {
+        unsafe { super::create_with_progress(self.tts, text, sid, speed, &mut callback) }
+    }
+}
+
+impl super::TextToSpeech for VitsTts {
+    fn create(&mut self, text: &str, sid: i32, speed: f32) -> Result<TtsAudio> {
+        VitsTts::create(self, text, sid, speed)
+    }
+
+    fn create_with_progress(
+        &mut self,
+        text: &str,
+        sid: i32,
+        speed: f32,
+        callback: &mut dyn FnMut(TtsProgress<'_>) -> bool,
+    ) -> Result<TtsAudio> {
+        unsafe { super::create_with_progress(self.tts, text, sid, speed, callback) }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        VitsTts::sample_rate(self)
+    }
 }
 
 unsafe impl Send for VitsTts {}
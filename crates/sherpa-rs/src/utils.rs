@@ -1,5 +1,23 @@
+use crate::TooManySamples;
 use std::ffi::{c_char, CString};
 
+/// Most samples one native call takes, as sherpa-onnx counts them in an `i32`
+pub const MAX_NATIVE_SAMPLES: usize = i32::MAX as usize;
+
+/// Sample count for a native call, or an error for buffers too long to pass in one call
+pub fn native_len(len: usize) -> Result<i32, TooManySamples> {
+    i32::try_from(len).map_err(|_| TooManySamples {
+        len,
+        max: MAX_NATIVE_SAMPLES,
+    })
+}
+
+/// `samples` split into parts a native call takes, for APIs that accept audio
+/// in several calls
+pub fn native_chunks(samples: &[f32]) -> std::slice::Chunks<'_, f32> {
+    samples.chunks(MAX_NATIVE_SAMPLES)
+}
+
 pub fn cstring_from_str(s: &str) -> CString {
     CString::new(s).expect("CString::new failed")
 }
@@ -0,0 +1,381 @@
+use crate::{
+    playback::{AudioPlayer, PlaybackHandle},
+    resample::Resampler,
+    shutdown::{self, Stopper},
+    tts::TextToSpeech,
+    OnlineTranscriber, VoiceActivityDetector,
+};
+use eyre::Result;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, JoinHandle, Thread};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentEvent {
+    /// Hypothesis for the user's utterance in progress
+    Partial(String),
+    /// The user finished an utterance; it was passed to the handler
+    Heard(String),
+    /// The handler answered; the reply is being spoken
+    Reply(String),
+    /// The user spoke over the reply, so playback was cut off
+    BargeIn,
+}
+
+#[derive(Debug, Clone)]
+pub struct VoiceAgentConfig {
+    /// Sample rate of the incoming audio
+    pub sample_rate: u32,
+    /// Sample rate expected by the VAD
+    pub vad_sample_rate: u32,
+    /// Speaker id and speed of the TTS voice
+    pub sid: i32,
+    pub speed: f32,
+    /// Seconds of synthesized audio buffered ahead of playback
+    pub queue_seconds: f32,
+}
+
+impl Default for VoiceAgentConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 16000,
+            vad_sample_rate: 16000,
+            sid: 0,
+            speed: 1.0,
+            queue_seconds: 2.0,
+        }
+    }
+}
+
+/// Speech-to-speech loop for voice assistants: online ASR, then an async text handler
+/// (e.g. an LLM call), then streaming TTS on the default output device.
+///
+/// Each stage runs on its own thread. The handler's futures are polled on the agent's
+/// thread, so futures tied to a runtime (e.g. tokio I/O) should be spawned on it and
+/// their `JoinHandle` awaited. A reply still being computed is dropped when the agent
+/// stops or the user barges in. While a reply is playing, speech detected by the VAD
+/// stops playback and drops replies to earlier utterances (barge-in). Without echo
+/// cancellation the agent can hear itself, so use headphones or a device with AEC.
+pub struct VoiceAgent {
+    events: Receiver<AgentEvent>,
+    stop: Arc<AtomicBool>,
+    playback: PlaybackHandle,
+    /// Registered with `shutdown_all`
+    _stopper: Arc<Stopper>,
+    workers: Vec<JoinHandle<()>>,
+    #[cfg(feature = "capture")]
+    microphone: Option<crate::capture::Microphone>,
+}
+
+impl VoiceAgent {
+    /// Start an agent fed from `audio`, mono chunks at `config.sample_rate`.
+    /// The agent finishes when the sender side is dropped.
+    pub fn start<R, V, T, H, F>(
+        recognizer: R,
+        vad: V,
+        tts: T,
+        handler: H,
+        audio: Receiver<Vec<f32>>,
+        config: VoiceAgentConfig,
+    ) -> Result<Self>
+    where
+        R: OnlineTranscriber + Send + 'static,
+        V: VoiceActivityDetector + Send + 'static,
+        T: TextToSpeech + Send + 'static,
+        H: FnMut(String) -> F + Send + 'static,
+        F: Future<Output = Result<String>>,
+    {
+        let (events_tx, events) = mpsc::channel();
+        let (heard_tx, heard_rx) = mpsc::channel();
+        let (reply_tx, reply_rx) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        // Bumped on barge-in, so replies to earlier utterances are dropped
+        let generation = Arc::new(AtomicU64::new(0));
+
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let speaker = {
+            let generation = generation.clone();
+            let stop = stop.clone();
+            let config = config.clone();
+            shutdown::spawn_worker(move || {
+                let player = match AudioPlayer::open(config.queue_seconds) {
+                    Ok(player) => player,
+                    Err(error) => {
+                        let _ = ready_tx.send(Err(error));
+                        return;
+                    }
+                };
+                let _ = ready_tx.send(Ok(player.handle()));
+                speak(tts, player, reply_rx, generation, stop, config)
+            })
+        };
+        let playback = match ready_rx.recv() {
+            Ok(Ok(playback)) => playback,
+            Ok(Err(error)) => return Err(error),
+            Err(_) => eyre::bail!("TTS worker exited before opening the output device"),
+        };
+
+        let thinker = {
+            let generation = generation.clone();
+            let stop = stop.clone();
+            let events = events_tx.clone();
+            shutdown::spawn_worker(move || {
+                think(handler, heard_rx, reply_tx, generation, stop, events)
+            })
+        };
+        let listener = {
+            let stop = stop.clone();
+            let playback = playback.clone();
+            let resampler = if config.sample_rate == config.vad_sample_rate {
+                None
+            } else {
+                Some(Resampler::new(config.sample_rate, config.vad_sample_rate)?)
+            };
+            shutdown::spawn_worker(move || {
+                listen(Listener {
+                    recognizer,
+                    vad,
+                    resampler,
+                    sample_rate: config.sample_rate,
+                    audio,
+                    heard: heard_tx,
+                    events: events_tx,
+                    playback,
+                    generation,
+                    stop,
+                })
+            })
+        };
+
+        let stopper: Arc<Stopper> = {
+            let stop = stop.clone();
+            let playback = playback.clone();
+            Arc::new(move || {
+                stop.store(true, Ordering::Relaxed);
+                playback.stop();
+            })
+        };
+        shutdown::on_shutdown(&stopper);
+
+        Ok(Self {
+            events,
+            stop,
+            playback,
+            _stopper: stopper,
+            workers: vec![listener, thinker, speaker],
+            #[cfg(feature = "capture")]
+            microphone: None,
+        })
+    }
+
+    /// Start an agent fed from the default input device
+    #[cfg(feature = "capture")]
+    pub fn from_microphone<R, V, T, H, F>(
+        recognizer: R,
+        vad: V,
+        tts: T,
+        handler: H,
+        config: VoiceAgentConfig,
+    ) -> Result<Self>
+    where
+        R: OnlineTranscriber + Send + 'static,
+        V: VoiceActivityDetector + Send + 'static,
+        T: TextToSpeech + Send + 'static,
+        H: FnMut(String) -> F + Send + 'static,
+        F: Future<Output = Result<String>>,
+    {
+        let (microphone, audio) = crate::capture::Microphone::open()?;
+        let config = VoiceAgentConfig {
+            sample_rate: microphone.sample_rate(),
+            ..config
+        };
+        let mut agent = Self::start(recognizer, vad, tts, handler, audio, config)?;
+        agent.microphone = Some(microphone);
+        Ok(agent)
+    }
+
+    pub fn events(&self) -> &Receiver<AgentEvent> {
+        &self.events
+    }
+
+    /// Stop listening and speaking, and wait for the workers to exit.
+    ///
+    /// A panic in the recognizer, VAD, TTS or handler is resumed here. Dropping the
+    /// agent instead logs it.
+    pub fn stop(mut self) {
+        for worker in self.shutdown() {
+            if let Err(panic) = worker.join() {
+                std::panic::resume_unwind(panic);
+            }
+        }
+    }
+
+    fn shutdown(&mut self) -> Vec<JoinHandle<()>> {
+        #[cfg(feature = "capture")]
+        drop(self.microphone.take());
+        self.stop.store(true, Ordering::Relaxed);
+        self.playback.stop();
+        self.workers.drain(..).collect()
+    }
+}
+
+impl Drop for VoiceAgent {
+    fn drop(&mut self) {
+        for worker in self.shutdown() {
+            shutdown::join_logged(worker, "voice agent worker");
+        }
+    }
+}
+
+struct Listener<R, V> {
+    recognizer: R,
+    vad: V,
+    resampler: Option<Resampler>,
+    sample_rate: u32,
+    audio: Receiver<Vec<f32>>,
+    heard: Sender<(u64, String)>,
+    events: Sender<AgentEvent>,
+    playback: PlaybackHandle,
+    generation: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+}
+
+fn listen<R: OnlineTranscriber, V: VoiceActivityDetector>(mut l: Listener<R, V>) {
+    let mut last = String::new();
+    while !l.stop.load(Ordering::Relaxed) {
+        let chunk = match l.audio.recv_timeout(Duration::from_millis(100)) {
+            Ok(chunk) => chunk,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+
+        let vad_chunk = match &mut l.resampler {
+            Some(resampler) => resampler.resample(&chunk, false),
+            None => chunk.clone(),
+        };
+        l.vad.accept_waveform(vad_chunk);
+        while l.vad.pop_segment().is_some() {}
+        if l.vad.is_speech() && l.playback.is_playing() {
+            l.generation.fetch_add(1, Ordering::SeqCst);
+            l.playback.stop();
+            let _ = l.events.send(AgentEvent::BargeIn);
+        }
+
+        if let Err(error) = l.recognizer.accept_waveform(l.sample_rate, &chunk) {
+            tracing::warn!("recognizer rejected audio: {}", error);
+            break;
+        }
+        let text = l.recognizer.transcribe();
+        let event = if l.recognizer.is_endpoint() {
+            l.recognizer.reset();
+            last.clear();
+            if text.trim().is_empty() {
+                None
+            } else {
+                let generation = l.generation.load(Ordering::SeqCst);
+                if l.heard.send((generation, text.clone())).is_err() {
+                    break;
+                }
+                Some(AgentEvent::Heard(text))
+            }
+        } else if text != last {
+            last = text.clone();
+            Some(AgentEvent::Partial(text))
+        } else {
+            None
+        };
+        if let Some(event) = event {
+            if l.events.send(event).is_err() {
+                // Nobody is listening anymore
+                break;
+            }
+        }
+    }
+}
+
+fn think<H, F>(
+    mut handler: H,
+    heard: Receiver<(u64, String)>,
+    replies: Sender<(u64, String)>,
+    generation: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+    events: Sender<AgentEvent>,
+) where
+    H: FnMut(String) -> F,
+    F: Future<Output = Result<String>>,
+{
+    for (heard_generation, text) in heard {
+        let cancelled = || {
+            stop.load(Ordering::Relaxed) || heard_generation != generation.load(Ordering::SeqCst)
+        };
+        let reply = match poll_until(handler(text), cancelled) {
+            Some(Ok(reply)) => reply,
+            Some(Err(error)) => {
+                tracing::warn!("voice agent handler failed: {}", error);
+                continue;
+            }
+            None => continue,
+        };
+        if reply.trim().is_empty() || heard_generation != generation.load(Ordering::SeqCst) {
+            continue;
+        }
+        let _ = events.send(AgentEvent::Reply(reply.clone()));
+        if replies.send((heard_generation, reply)).is_err() {
+            break;
+        }
+    }
+}
+
+fn speak<T: TextToSpeech>(
+    mut tts: T,
+    player: AudioPlayer,
+    replies: Receiver<(u64, String)>,
+    generation: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+    config: VoiceAgentConfig,
+) {
+    for (reply_generation, text) in replies {
+        // A barge-in stopped the previous reply; this one may answer the newer utterance.
+        // Checked after resuming, so a barge-in or shutdown in between still stops playback.
+        player.resume();
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        if reply_generation != generation.load(Ordering::SeqCst) {
+            continue;
+        }
+        if let Err(error) = player.speak(&mut tts, &text, config.sid, config.speed) {
+            tracing::warn!("failed to speak reply: {}", error);
+        }
+    }
+}
+
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Poll `future` on this thread until it finishes, or return `None` once `cancelled`
+fn poll_until<F: Future>(future: F, cancelled: impl Fn() -> bool) -> Option<F::Output> {
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        if cancelled() {
+            return None;
+        }
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return Some(output);
+        }
+        // Woken early by the future; the timeout only bounds how late a cancel is seen
+        thread::park_timeout(Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{keyword_spot::KeywordSpot, OnlineTranscriber, StreamingError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WakeWordEvent {
+    /// The wake word was heard, transcription of the following utterance starts
+    Detected(String),
+    /// Hypothesis for the utterance in progress. Sent whenever it changes.
+    Partial(String),
+    /// The utterance reached an endpoint; the session is listening for the wake word again.
+    /// `text` is empty when nothing was said after the wake word.
+    Utterance { keyword: String, text: String },
+}
+
+enum State {
+    Listening,
+    Transcribing { keyword: String, last: String },
+}
+
+/// Scans audio for a wake word, transcribes the utterance that follows it until endpoint,
+/// then goes back to listening.
+///
+/// Enable endpointing in the recognizer config, otherwise the session never returns to listening.
+pub struct WakeWordSession<R: OnlineTranscriber> {
+    spotter: KeywordSpot,
+    recognizer: R,
+    state: State,
+    /// Samples fed to the spotter so far
+    spotted: usize,
+}
+
+impl<R: OnlineTranscriber> WakeWordSession<R> {
+    pub fn new(spotter: KeywordSpot, recognizer: R) -> Self {
+        Self {
+            spotter,
+            recognizer,
+            state: State::Listening,
+            spotted: 0,
+        }
+    }
+
+    pub fn is_listening(&self) -> bool {
+        matches!(self.state, State::Listening)
+    }
+
+    /// Feed the next chunk of audio and return the events it produced.
+    /// Audio after the wake word in the same chunk goes to the recognizer.
+    pub fn accept_waveform(
+        &mut self,
+        sample_rate: u32,
+        samples: &[f32],
+    ) -> Result<Vec<WakeWordEvent>, StreamingError> {
+        let mut events = Vec::new();
+        let mut samples = samples;
+        if self.is_listening() {
+            let offset = self.spotted;
+            self.spotted += samples.len();
+            // Later keywords in the chunk are part of the command
+            let Some(detection) = self.spotter.detect(sample_rate, samples).into_iter().next()
+            else {
+                return Ok(events);
+            };
+            // The keyword ends at its last token, in seconds since the spotter started
+            let end = (detection.end * sample_rate as f32) as usize;
+            samples = &samples[end.saturating_sub(offset).min(samples.len())..];
+            events.push(WakeWordEvent::Detected(detection.keyword.clone()));
+            self.state = State::Transcribing {
+                keyword: detection.keyword,
+                last: String::new(),
+            };
+            if samples.is_empty() {
+                return Ok(events);
+            }
+        }
+
+        let State::Transcribing { keyword, last } = &mut self.state else {
+            unreachable!("listening is handled above");
+        };
+        self.recognizer.accept_waveform(sample_rate, samples)?;
+        let text = self.recognizer.transcribe();
+        if self.recognizer.is_endpoint() {
+            self.recognizer.reset();
+            events.push(WakeWordEvent::Utterance {
+                keyword: std::mem::take(keyword),
+                text,
+            });
+            self.state = State::Listening;
+        } else if text != *last {
+            *last = text.clone();
+            events.push(WakeWordEvent::Partial(text));
+        }
+        Ok(events)
+    }
+
+    pub fn into_inner(self) -> (KeywordSpot, R) {
+        (self.spotter, self.recognizer)
+    }
+}
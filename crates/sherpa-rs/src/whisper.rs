@@ -1,22 +1,43 @@
-use crate::{get_default_provider, utils::cstring_from_str};
+use crate::config_dump::{self, ConfigDump, NativeConfig};
+use crate::{
+    get_default_provider,
+    offline_stream::{self, OfflineStream},
+    utils::cstring_from_str,
+};
 use eyre::{bail, Result};
 use std::mem;
 
 #[derive(Debug)]
 pub struct WhisperRecognizer {
     recognizer: *const sherpa_rs_sys::SherpaOnnxOfflineRecognizer,
+    config_dump: ConfigDump,
+    language: String,
+    is_translation: bool,
+    max_repeats: Option<usize>,
 }
 
 pub type WhisperRecognizerResult = super::OfflineRecognizerResult;
 
+/// Whisper model settings.
+///
+/// sherpa-onnx decodes Whisper greedily and its C config has no beam or token limit
+/// settings, so truncation and hallucination are tuned with `tail_paddings` and
+/// `max_repeats`.
 #[derive(Debug, Clone)]
 pub struct WhisperConfig {
     pub decoder: String,
     pub encoder: String,
     pub tokens: String,
     pub language: String,
+    /// "transcribe" (default) or "translate" to English
+    pub task: Option<String>,
     pub bpe_vocab: Option<String>,
+    /// Silent feature frames appended after the audio. More padding helps when the last
+    /// words of an utterance are dropped. Unset uses the sherpa-onnx default.
     pub tail_paddings: Option<i32>,
+    /// Cut phrases repeated more than this many times in a row, see
+    /// `post_process::collapse_repetitions`. Unset keeps the output as decoded.
+    pub max_repeats: Option<usize>,
 
     pub provider: Option<String>,
     pub num_threads: Option<i32>,
@@ -30,8 +51,10 @@ impl Default for WhisperConfig {
             encoder: String::new(),
             tokens: String::new(),
             language: String::from("en"),
+            task: None,
             bpe_vocab: None,
             tail_paddings: None,
+            max_repeats: None,
             debug: false,
             provider: None,
             num_threads: Some(1),
@@ -41,7 +64,7 @@ impl Default for WhisperConfig {
 
 impl WhisperRecognizer {
     pub fn new(config: WhisperConfig) -> Result<Self> {
-        let debug = config.debug.into();
+        let debug = crate::native_debug(config.debug);
         let provider = config.provider.unwrap_or(get_default_provider());
 
         // Onnx
@@ -51,10 +74,13 @@ impl WhisperRecognizer {
         // Whisper
         let bpe_vocab_ptr = cstring_from_str(&config.bpe_vocab.unwrap_or("".into()));
         let tail_paddings = config.tail_paddings.unwrap_or(0);
+        let max_repeats = config.max_repeats;
         let decoder_ptr = cstring_from_str(&config.decoder);
         let encoder_ptr = cstring_from_str(&config.encoder);
-        let language_ptr = cstring_from_str(&config.language);
-        let task_ptr = cstring_from_str("transcribe");
+        let language = config.language;
+        let language_ptr = cstring_from_str(&language);
+        let task = config.task.unwrap_or("transcribe".into());
+        let task_ptr = cstring_from_str(&task);
         let tokens_ptr = cstring_from_str(&config.tokens);
         let decoding_method_ptr = cstring_from_str("greedy_search");
 
@@ -112,33 +138,49 @@ impl WhisperRecognizer {
                 hr: mem::zeroed::<_>(),
             }
         };
+        let config_dump = config_dump::offline_recognizer(&config);
         let recognizer = unsafe { sherpa_rs_sys::SherpaOnnxCreateOfflineRecognizer(&config) };
 
         if recognizer.is_null() {
             bail!("Failed to create recognizer");
         }
 
-        Ok(Self { recognizer })
+        Ok(Self {
+            recognizer,
+            config_dump,
+            language,
+            is_translation: task == "translate",
+            max_repeats,
+        })
+    }
+
+    pub fn create_stream(&self) -> Result<OfflineStream<'_>> {
+        unsafe { OfflineStream::new(self.recognizer) }
     }
 
     pub fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> WhisperRecognizerResult {
-        unsafe {
-            let stream = sherpa_rs_sys::SherpaOnnxCreateOfflineStream(self.recognizer);
-            sherpa_rs_sys::SherpaOnnxAcceptWaveformOffline(
-                stream,
-                sample_rate as i32,
-                samples.as_ptr(),
-                samples.len().try_into().unwrap(),
-            );
-            sherpa_rs_sys::SherpaOnnxDecodeOfflineStream(self.recognizer, stream);
-            let result_ptr = sherpa_rs_sys::SherpaOnnxGetOfflineStreamResult(stream);
-            let raw_result = result_ptr.read();
-            let result = WhisperRecognizerResult::new(&raw_result);
-            // Free
-            sherpa_rs_sys::SherpaOnnxDestroyOfflineRecognizerResult(result_ptr);
-            sherpa_rs_sys::SherpaOnnxDestroyOfflineStream(stream);
-            result
+        let mut result =
+            offline_stream::transcribe_once(self.create_stream(), sample_rate, samples);
+        result.is_translation = self.is_translation;
+        if result.lang.is_empty() {
+            result.lang = self.language.clone();
         }
+        if let Some(max_repeats) = self.max_repeats {
+            crate::post_process::collapse_repetitions(&mut result, max_repeats);
+        }
+        result
+    }
+}
+
+impl NativeConfig for WhisperRecognizer {
+    fn config_dump(&self) -> &ConfigDump {
+        &self.config_dump
+    }
+}
+
+impl crate::Transcriber for WhisperRecognizer {
+    fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> crate::OfflineRecognizerResult {
+        WhisperRecognizer::transcribe(self, sample_rate, samples)
     }
 }
 
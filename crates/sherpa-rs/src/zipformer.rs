@@ -1,6 +1,8 @@
+use crate::config_dump::{self, ConfigDump, NativeConfig};
 use crate::{
     get_default_provider,
-    utils::{cstr_to_string, cstring_from_str},
+    offline_stream::{self, OfflineStream},
+    utils::cstring_from_str,
 };
 use eyre::{bail, Result};
 use std::mem;
@@ -19,6 +21,7 @@ pub struct ZipFormerConfig {
 
 pub struct ZipFormer {
     recognizer: *const sherpa_rs_sys::SherpaOnnxOfflineRecognizer,
+    config_dump: ConfigDump,
 }
 
 impl ZipFormer {
@@ -40,7 +43,7 @@ impl ZipFormer {
         let model_config = unsafe {
             sherpa_rs_sys::SherpaOnnxOfflineModelConfig {
                 num_threads: config.num_threads.unwrap_or(1),
-                debug: config.debug.into(),
+                debug: crate::native_debug(config.debug),
                 provider: provider_ptr.as_ptr(),
                 transducer: transcuder_config,
                 tokens: tokens_ptr.as_ptr(),
@@ -80,34 +83,37 @@ impl ZipFormer {
             }
         };
 
+        let config_dump = config_dump::offline_recognizer(&recognizer_config);
         let recognizer =
             unsafe { sherpa_rs_sys::SherpaOnnxCreateOfflineRecognizer(&recognizer_config) };
 
         if recognizer.is_null() {
             bail!("Failed to create recognizer");
         }
-        Ok(Self { recognizer })
+        Ok(Self {
+            recognizer,
+            config_dump,
+        })
+    }
+
+    pub fn create_stream(&self) -> Result<OfflineStream<'_>> {
+        unsafe { OfflineStream::new(self.recognizer) }
     }
 
     pub fn decode(&mut self, sample_rate: u32, samples: Vec<f32>) -> String {
-        unsafe {
-            let stream = sherpa_rs_sys::SherpaOnnxCreateOfflineStream(self.recognizer);
-            sherpa_rs_sys::SherpaOnnxAcceptWaveformOffline(
-                stream,
-                sample_rate as i32,
-                samples.as_ptr(),
-                samples.len().try_into().unwrap(),
-            );
-            sherpa_rs_sys::SherpaOnnxDecodeOfflineStream(self.recognizer, stream);
-            let result_ptr = sherpa_rs_sys::SherpaOnnxGetOfflineStreamResult(stream);
-            let raw_result = result_ptr.read();
-            let text = cstr_to_string(raw_result.text as _);
+        offline_stream::transcribe_once(self.create_stream(), sample_rate, &samples).text
+    }
+}
 
-            // Free
-            sherpa_rs_sys::SherpaOnnxDestroyOfflineRecognizerResult(result_ptr);
-            sherpa_rs_sys::SherpaOnnxDestroyOfflineStream(stream);
-            text
-        }
+impl NativeConfig for ZipFormer {
+    fn config_dump(&self) -> &ConfigDump {
+        &self.config_dump
+    }
+}
+
+impl crate::Transcriber for ZipFormer {
+    fn transcribe(&mut self, sample_rate: u32, samples: &[f32]) -> crate::OfflineRecognizerResult {
+        offline_stream::transcribe_once(self.create_stream(), sample_rate, samples)
     }
 }
 
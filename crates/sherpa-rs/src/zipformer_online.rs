@@ -1,11 +1,14 @@
-use crate::{
-    get_default_provider,
-    utils::{cstr_to_string, cstring_from_str},
-};
+#![allow(deprecated)]
+
+use crate::config_dump::{ConfigDump, NativeConfig};
+use crate::online::{OnlineModel, OnlineRecognizer};
+use crate::online_config::OnlineRecognizerConfigBuilder;
+use crate::{get_default_provider, DecodePoll, OnlineRecognizerResult};
 use eyre::Result;
-use std::ffi::CStr;
-use std::mem;
 
+pub use crate::online::{OnlineStream, SafeOnlineStream};
+
+#[deprecated(note = "use `online::OnlineRecognizerConfig`")]
 #[derive(Debug, Default)]
 pub struct ZipFormerOnlineConfig {
     pub decoder: String,
@@ -31,245 +34,136 @@ pub struct ZipFormerOnlineConfig {
     pub max_active_paths: Option<i32>,
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum StreamingError {
-    #[error("Decoding failed with code: {0}")]
-    DecodingFailed(i32),
-
-    #[error("Stream not ready for processing")]
-    StreamNotReady,
-
-    #[error("Invalid stream state")]
-    InvalidState,
-
-    #[error("Model configuration error")]
-    ConfigError,
-}
-
-// Newtype wrapper for the stream
-#[derive(Debug)] // Optional, for debugging
-pub struct SafeOnlineStream(*const sherpa_rs_sys::SherpaOnnxOnlineStream); // Adjust type path if needed
-
-unsafe impl Send for SafeOnlineStream {} // Now safe to impl here
+pub use crate::StreamingError;
 
+/// Multi-stream transducer recognizer, now a thin wrapper over `OnlineRecognizer`
+#[deprecated(note = "use `online::OnlineRecognizer` and its `*_stream` methods")]
 pub struct ZipFormerOnline {
-    recognizer_ptr: *mut sherpa_rs_sys::SherpaOnnxOnlineRecognizer,
-    // stream_ptr: *mut sherpa_rs_sys::SherpaOnnxOnlineStream,
+    inner: OnlineRecognizer,
 }
 
 impl ZipFormerOnline {
     pub fn new(config: ZipFormerOnlineConfig) -> Result<Self, StreamingError> {
-        // Online Zipformer transducer config
-        let decoder_ptr = cstring_from_str(&config.decoder);
-        let encoder_ptr = cstring_from_str(&config.encoder);
-        let joiner_ptr = cstring_from_str(&config.joiner);
-        let provider_ptr =
-            cstring_from_str(&config.provider.clone().unwrap_or_else(get_default_provider));
-        let tokens_ptr = cstring_from_str(&config.tokens);
-        let decoding_method_ptr = cstring_from_str(
-            &config
+        let builder = OnlineRecognizerConfigBuilder {
+            model: OnlineModel::Transducer {
+                encoder: config.encoder,
+                decoder: config.decoder,
+                joiner: config.joiner,
+            },
+            tokens: config.tokens,
+            num_threads: config.num_threads.unwrap_or(1),
+            debug: config.debug,
+            provider: config.provider.unwrap_or_else(get_default_provider),
+            sample_rate: config.sample_rate.unwrap_or(16000),
+            feature_dim: config.feature_dim.unwrap_or(80),
+            decoding_method: config
                 .decoding_method
-                .clone()
                 .unwrap_or_else(|| "greedy_search".to_string()),
-        );
-
-        let transducer_config = {
-            sherpa_rs_sys::SherpaOnnxOnlineTransducerModelConfig {
-                encoder: encoder_ptr.as_ptr(),
-                decoder: decoder_ptr.as_ptr(),
-                joiner: joiner_ptr.as_ptr(),
-            }
-        };
-
-        // Online model config
-        let model_config = unsafe {
-            sherpa_rs_sys::SherpaOnnxOnlineModelConfig {
-                transducer: transducer_config,
-                tokens: tokens_ptr.as_ptr(),
-                num_threads: config.num_threads.unwrap_or(1),
-                debug: config.debug.into(),
-                provider: provider_ptr.as_ptr(),
-                // Zero other fields (paraformer, etc.)
-                paraformer: mem::zeroed(),
-                zipformer2_ctc: mem::zeroed(),
-                model_type: mem::zeroed(),
-                modeling_unit: mem::zeroed(),
-                bpe_vocab: mem::zeroed(),
-                tokens_buf: mem::zeroed(),
-                tokens_buf_size: mem::zeroed(),
-                nemo_ctc: mem::zeroed(),
-                ..mem::zeroed()
-            }
+            // Unset values are left zeroed for sherpa-onnx to fill in
+            max_active_paths: config.max_active_paths.unwrap_or(0),
+            enable_endpoint: config.enable_endpoint.unwrap_or(0) != 0,
+            rule1_min_trailing_silence: config.rule1_min_trailing_silence.unwrap_or(0.0),
+            rule2_min_trailing_silence: config.rule2_min_trailing_silence.unwrap_or(0.0),
+            rule3_min_utterance_length: config.rule3_min_utterance_length.unwrap_or(0.0),
+            hotwords_score: 0.0,
+            ..Default::default()
         };
-
-        // Feature config (set defaults if not provided)
-        let feat_config = {
-            sherpa_rs_sys::SherpaOnnxFeatureConfig {
-                sample_rate: config.sample_rate.unwrap_or(16000),
-                feature_dim: config.feature_dim.unwrap_or(80),
-            }
-        };
-
-        // Online recognizer config
-        let recognizer_config = unsafe {
-            sherpa_rs_sys::SherpaOnnxOnlineRecognizerConfig {
-                feat_config,
-                model_config,
-                decoding_method: decoding_method_ptr.as_ptr(),
-                // Zero other fields (endpoint, hotwords, etc.)
-                enable_endpoint: config.enable_endpoint.unwrap_or(mem::zeroed()),
-                hotwords_file: mem::zeroed(),
-                hotwords_score: mem::zeroed(),
-                ctc_fst_decoder_config: mem::zeroed(),
-                rule_fsts: mem::zeroed(),
-                rule_fars: mem::zeroed(),
-                blank_penalty: mem::zeroed(),
-                hotwords_buf: mem::zeroed(),
-                hotwords_buf_size: mem::zeroed(),
-                hr: mem::zeroed(),
-                max_active_paths: config.max_active_paths.unwrap_or(mem::zeroed()),
-                rule1_min_trailing_silence: config
-                    .rule1_min_trailing_silence
-                    .unwrap_or(mem::zeroed()),
-                rule2_min_trailing_silence: config
-                    .rule2_min_trailing_silence
-                    .unwrap_or(mem::zeroed()),
-                rule3_min_utterance_length: config
-                    .rule3_min_utterance_length
-                    .unwrap_or(mem::zeroed()),
-            }
-        };
-
-        let recognizer =
-            unsafe { sherpa_rs_sys::SherpaOnnxCreateOnlineRecognizer(&recognizer_config) };
-        if recognizer.is_null() {
-            return Err(StreamingError::ConfigError);
-        }
-
-        Ok(Self {
-            recognizer_ptr: recognizer as *mut _,
-        })
+        let inner =
+            OnlineRecognizer::from_builder(builder).map_err(|_| StreamingError::ConfigError)?;
+        Ok(Self { inner })
     }
 
+    /// Fails with `StreamingError::InvalidState` once `input_finished` was called on the stream
     pub fn accept_waveform(
         &mut self,
         stream: &SafeOnlineStream,
         sample_rate: u32,
         samples: &[f32],
-    ) {
-        unsafe {
-            sherpa_rs_sys::SherpaOnnxOnlineStreamAcceptWaveform(
-                stream.0,
-                sample_rate as i32,
-                samples.as_ptr(),
-                samples.len() as i32,
-            );
-        }
+    ) -> Result<(), StreamingError> {
+        stream.accept_waveform(sample_rate, samples)
     }
 
     pub fn decode(&mut self, stream: &SafeOnlineStream) -> String {
-        unsafe {
-            sherpa_rs_sys::SherpaOnnxDecodeOnlineStream(self.recognizer_ptr, stream.0);
-            let result_ptr =
-                sherpa_rs_sys::SherpaOnnxGetOnlineStreamResult(self.recognizer_ptr, stream.0);
-            if result_ptr.is_null() {
-                return String::new();
-            }
-            let raw_result = *result_ptr; // Assuming deref to get the struct
-            let text = cstr_to_string(raw_result.text as *const _);
-
-            // Note: Do not destroy the result here if it's a pointer to internal; check API. In C API, it's a copy, so destroy.
-            sherpa_rs_sys::SherpaOnnxDestroyOnlineRecognizerResult(result_ptr);
-            text
-        }
+        self.inner.decode_stream(stream);
+        self.inner.stream_result(stream)
     }
 
+    /// Mark the end of the stream's audio. The stream can still be decoded
+    /// but takes no more audio until `reset`.
     pub fn input_finished(&mut self, stream: &SafeOnlineStream) {
-        unsafe {
-            sherpa_rs_sys::SherpaOnnxOnlineStreamInputFinished(stream.0);
-        }
+        stream.input_finished()
     }
 
-    pub fn reset(&mut self, stream: &SafeOnlineStream) {
-        unsafe {
-            sherpa_rs_sys::SherpaOnnxOnlineStreamReset(self.recognizer_ptr, stream.0);
-        }
+    /// Start a new utterance on the stream. After `input_finished` the stream is reopened
+    /// and takes audio again, like `OnlineTransducerRecognizer::reset`.
+    pub fn reset(&mut self, stream: &mut SafeOnlineStream) {
+        self.inner.restart_stream(stream)
     }
 
-    // Streaming-specific methods
-
-    /// Check if the streaming recognizer is ready to process audio
-    /// This replaces manual chunk size checking with model-driven processing
     pub fn is_ready(&self, stream: &SafeOnlineStream) -> bool {
-        unsafe { sherpa_rs_sys::SherpaOnnxIsOnlineStreamReady(self.recognizer_ptr, stream.0) != 0 }
+        self.inner.is_stream_ready(stream)
     }
 
-    /// Get current recognition result
-    /// Returns empty string if no result available
     pub fn get_result(&self, stream: &SafeOnlineStream) -> String {
-        unsafe {
-            let result_ptr =
-                sherpa_rs_sys::SherpaOnnxGetOnlineStreamResult(self.recognizer_ptr, stream.0);
-            if result_ptr.is_null() {
-                return String::new();
-            }
-
-            // Convert C string to Rust string
-            let raw_result = *result_ptr;
-            let text = if !raw_result.text.is_null() {
-                CStr::from_ptr(raw_result.text)
-                    .to_string_lossy()
-                    .into_owned()
-            } else {
-                String::new()
-            };
-
-            // Free the result (if required by sherpa-onnx API)
-            sherpa_rs_sys::SherpaOnnxDestroyOnlineRecognizerResult(result_ptr);
+        self.inner.stream_result(stream)
+    }
 
-            text
-        }
+    /// Current result with tokens, per-token timestamps and the sherpa-onnx JSON
+    pub fn get_result_full(&self, stream: &SafeOnlineStream) -> OnlineRecognizerResult {
+        self.inner.stream_result_full(stream)
     }
 
-    /// Check if endpoint (end of utterance) has been detected
     pub fn is_endpoint(&self, stream: &SafeOnlineStream) -> bool {
-        unsafe {
-            sherpa_rs_sys::SherpaOnnxOnlineStreamIsEndpoint(self.recognizer_ptr, stream.0) != 0
-        }
+        self.inner.is_stream_endpoint(stream)
     }
 
-    /// Decode the current streaming audio
-    /// This is called when is_ready() returns true
     pub fn decode_stream(&mut self, stream: &SafeOnlineStream) -> Result<(), StreamingError> {
-        unsafe {
-            sherpa_rs_sys::SherpaOnnxDecodeOnlineStream(self.recognizer_ptr, stream.0);
+        self.inner.decode_stream(stream);
+        Ok(())
+    }
 
-            Ok(())
+    /// Run at most one decode step without blocking on further frames.
+    /// Returns true while more frames are ready to decode.
+    pub fn try_decode(&mut self, stream: &SafeOnlineStream) -> bool {
+        if !self.is_ready(stream) {
+            return false;
         }
+        self.inner.decode_stream(stream);
+        self.is_ready(stream)
     }
 
-    pub fn create_stream(&mut self) -> SafeOnlineStream {
-        unsafe {
-            SafeOnlineStream(sherpa_rs_sys::SherpaOnnxCreateOnlineStream(
-                self.recognizer_ptr,
-            ))
+    /// Run at most one decode step and report whether more work remains
+    pub fn poll_result(&mut self, stream: &SafeOnlineStream) -> DecodePoll {
+        if self.try_decode(stream) {
+            DecodePoll::Pending
+        } else {
+            DecodePoll::Ready(self.get_result(stream))
         }
     }
 
+    /// Create another stream. If the native stream can't be created, the failure is
+    /// logged and the returned stream is finished and never yields text.
+    pub fn create_stream(&mut self) -> SafeOnlineStream {
+        self.inner.create_stream().unwrap_or_else(|error| {
+            tracing::error!("{error}");
+            self.inner.null_stream()
+        })
+    }
+
+    /// Stream freed when dropped, see `OnlineStream`
+    pub fn open_stream(&self) -> Result<OnlineStream<'_>> {
+        self.inner.open_stream()
+    }
+
+    /// Finish `stream`, finalizing it when `finalize_on_drop` is enabled.
+    /// Streams free themselves when dropped, so this only ends their input.
     pub fn destroy_stream(&mut self, stream: &SafeOnlineStream) {
-        unsafe {
-            sherpa_rs_sys::SherpaOnnxDestroyOnlineStream(stream.0);
-        }
+        self.inner.finish_stream(stream);
     }
 }
 
-unsafe impl Send for ZipFormerOnline {}
-unsafe impl Sync for ZipFormerOnline {}
-
-impl Drop for ZipFormerOnline {
-    fn drop(&mut self) {
-        unsafe {
-            sherpa_rs_sys::SherpaOnnxDestroyOnlineRecognizer(self.recognizer_ptr);
-        }
+impl NativeConfig for ZipFormerOnline {
+    fn config_dump(&self) -> &ConfigDump {
+        self.inner.config_dump()
     }
 }
@@ -0,0 +1,81 @@
+use sherpa_rs::alignment::{align_tokens, align_words, TokenStyle};
+
+fn words(tokens: &[&str], style: TokenStyle) -> Vec<(String, f32, f32)> {
+    let timestamps: Vec<f32> = (0..tokens.len()).map(|i| i as f32 * 0.1).collect();
+    align_words(tokens, &timestamps, style)
+        .into_iter()
+        .map(|w| (w.word, w.start, w.end))
+        .collect()
+}
+
+#[test]
+fn merges_subwords() {
+    assert_eq!(
+        words(
+            &["▁he", "llo", "▁world", "你", "好"],
+            TokenStyle::SentencePiece
+        ),
+        [
+            ("hello".to_string(), 0.0, 0.2),
+            ("world".to_string(), 0.2, 0.3),
+            ("你".to_string(), 0.3, 0.4),
+            ("好".to_string(), 0.4, 0.5),
+        ]
+    );
+    let merged: Vec<String> = words(
+        &["he@@", "llo", "你", "wor@@", "ld"],
+        TokenStyle::ContinuationMarker,
+    )
+    .into_iter()
+    .map(|w| w.0)
+    .collect();
+    assert_eq!(merged, ["hello", "你", "world"]);
+    let merged: Vec<String> = words(&[" Hel", "lo", " world", "."], TokenStyle::Whitespace)
+        .into_iter()
+        .map(|w| w.0)
+        .collect();
+    assert_eq!(merged, ["Hello", "world."]);
+}
+
+#[test]
+fn times_each_token() {
+    let timings: Vec<(String, f32, f32)> =
+        align_tokens(&["北", "京", "欢", "迎"], &[0.0, 0.2, 0.4, 0.8])
+            .into_iter()
+            .map(|t| (t.token, t.start, t.end))
+            .collect();
+    assert_eq!(
+        timings,
+        [
+            ("北".to_string(), 0.0, 0.2),
+            ("京".to_string(), 0.2, 0.4),
+            ("欢".to_string(), 0.4, 0.8),
+            // Median gap past the last start
+            ("迎".to_string(), 0.8, 1.0),
+        ]
+    );
+    assert!(align_tokens(&["a"], &[]).is_empty());
+}
+
+#[test]
+fn parses_tokens() {
+    let tokens = sherpa_rs::tokens::Tokens::parse("<blk> 0\n▁the 1\n  2\n你 3\n").unwrap();
+    assert_eq!(tokens.len(), 4);
+    assert_eq!(tokens.id("▁the"), Some(1));
+    assert_eq!(tokens.symbol(2), Some(" "));
+    assert_eq!(tokens.style(), TokenStyle::SentencePiece);
+    assert_eq!(tokens.unknown_chars("你好"), ['好']);
+}
+
+#[test]
+fn parses_hypothesis() {
+    let json = r#"{"text": "hi there", "tokens": ["▁hi", "▁there"], "timestamps": [0.0, 0.4], "ys_log_probs": [-0.1, -2.3]}"#;
+    let hypothesis = sherpa_rs::hypothesis::Hypothesis::from_json(json).unwrap();
+    assert_eq!(hypothesis.tokens[1].start, 0.4);
+    assert!((hypothesis.score.unwrap() + 2.4).abs() < 1e-5);
+    let uncertain: Vec<_> = hypothesis
+        .uncertain_tokens(0.5)
+        .map(|t| t.text.as_str())
+        .collect();
+    assert_eq!(uncertain, ["▁there"]);
+}
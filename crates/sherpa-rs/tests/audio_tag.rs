@@ -0,0 +1,64 @@
+use sherpa_rs::audio_tag::{AudioTag, AudioTagConfig, ContentClass};
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+/// 200 Hz sine at 16 kHz
+fn tone(amplitude: f32, len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| amplitude * (i as f32 * 200.0 * std::f32::consts::TAU / 16000.0).sin())
+        .collect()
+}
+
+fn tagger(top_k: i32) -> AudioTag {
+    AudioTag::new(AudioTagConfig {
+        model: required_env("SHERPA_RS_TEST_TAG_MODEL"),
+        labels: required_env("SHERPA_RS_TEST_TAG_LABELS"),
+        top_k,
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn returns_top_k_events_with_probs() {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_TAG_WAV")).unwrap();
+
+    let events = tagger(3).compute_with_probs(&samples, sample_rate);
+    assert_eq!(events.len(), 3);
+    assert!(events.windows(2).all(|w| w[0].1 >= w[1].1));
+    assert!(events.iter().all(|(_, prob)| (0.0..=1.0).contains(prob)));
+
+    // Unset top_k falls back to the sherpa-onnx default
+    assert_eq!(tagger(0).compute(samples, sample_rate).len(), 5);
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn quiet_segments_are_silence() {
+    let mut tagger = tagger(5);
+    assert_eq!(tagger.classify_segment(&[], 16000), ContentClass::Silence);
+    assert_eq!(
+        tagger.classify_segment(&vec![0.0; 16000], 16000),
+        ContentClass::Silence
+    );
+    // About -66 dBFS
+    assert_eq!(
+        tagger.classify_segment(&tone(0.0007, 16000), 16000),
+        ContentClass::Silence
+    );
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn speech_is_classified_as_speech() {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_WAV")).unwrap();
+    assert_eq!(
+        tagger(5).classify_segment(&samples, sample_rate),
+        ContentClass::Speech
+    );
+}
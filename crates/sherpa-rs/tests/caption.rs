@@ -0,0 +1,60 @@
+use sherpa_rs::alignment::WordTiming;
+use sherpa_rs::caption::{format_captions, CaptionConfig};
+
+fn words(text: &str) -> Vec<WordTiming> {
+    text.split(' ')
+        .enumerate()
+        .map(|(i, word)| WordTiming {
+            word: word.into(),
+            start: i as f32 * 0.5,
+            end: (i + 1) as f32 * 0.5,
+        })
+        .collect()
+}
+
+#[test]
+fn wraps_lines_and_splits_captions() {
+    let config = CaptionConfig {
+        max_chars_per_line: 16,
+        max_lines: 2,
+        prefer_sentence_breaks: false,
+        ..Default::default()
+    };
+    let captions = format_captions(
+        &words("the quick brown fox jumps over the lazy dog and runs far away"),
+        &config,
+    );
+    let lines: Vec<_> = captions.iter().map(|c| c.lines.clone()).collect();
+    assert_eq!(
+        lines,
+        [
+            vec!["the quick brown", "fox jumps over"],
+            vec!["the lazy dog and", "runs far away"],
+        ]
+    );
+    assert_eq!((captions[0].start, captions[0].end), (0.0, 3.0));
+    assert_eq!((captions[1].start, captions[1].end), (3.0, 6.5));
+    assert_eq!(captions[1].text(), "the lazy dog and\nruns far away");
+}
+
+#[test]
+fn prefers_sentence_ends_and_keeps_min_duration() {
+    let config = CaptionConfig {
+        max_chars_per_line: 20,
+        min_duration: 2.0,
+        ..Default::default()
+    };
+    let captions = format_captions(&words("Hello there, friend. Yes. It works."), &config);
+    let texts: Vec<_> = captions.iter().map(|c| c.text()).collect();
+    // "Yes." alone is too short to end a caption
+    assert_eq!(texts, ["Hello there, friend.", "Yes. It works."]);
+    // The first caption already lasts 1.5 s and stretches to the next one only
+    assert_eq!(captions[0].end, 1.5);
+    assert_eq!(captions[1].end, 3.0 + 0.5);
+}
+
+#[test]
+fn joins_cjk_without_spaces() {
+    let captions = format_captions(&words("你 好 世 界"), &CaptionConfig::default());
+    assert_eq!(captions[0].lines, ["你好世界"]);
+}
@@ -0,0 +1,70 @@
+use sherpa_rs::chunked::{ChunkedConfig, ChunkedEvent, ChunkedTranscriber};
+use sherpa_rs::energy_vad::{EnergyVad, EnergyVadConfig};
+use sherpa_rs::{OfflineRecognizerResult, Transcriber};
+
+/// 200 Hz sine at 16 kHz
+fn tone(amplitude: f32, len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| amplitude * (i as f32 * 200.0 * std::f32::consts::TAU / 16000.0).sin())
+        .collect()
+}
+
+/// Transcribes audio as its length in samples, as one token at its start
+#[derive(Default)]
+struct Counter {
+    calls: Vec<usize>,
+}
+
+impl Transcriber for Counter {
+    fn transcribe(&mut self, _sample_rate: u32, samples: &[f32]) -> OfflineRecognizerResult {
+        self.calls.push(samples.len());
+        OfflineRecognizerResult {
+            text: samples.len().to_string(),
+            tokens: vec![samples.len().to_string()],
+            timestamps: vec![0.0],
+            ..Default::default()
+        }
+    }
+}
+
+#[test]
+fn emits_partials_then_final() {
+    let vad = EnergyVad::new(EnergyVadConfig::default()).unwrap();
+    let mut transcriber =
+        ChunkedTranscriber::new(Counter::default(), vad, ChunkedConfig::default()).unwrap();
+
+    // 1 s silence, 1.5 s tone, 1 s silence at 16 kHz
+    let mut samples = vec![0.0; 16000];
+    samples.extend(tone(0.3, 24000));
+    samples.extend(vec![0.0; 16000]);
+
+    let mut events = Vec::new();
+    for chunk in samples.chunks(1600) {
+        events.extend(transcriber.accept_waveform(chunk));
+    }
+    events.extend(transcriber.finish());
+
+    let partials: Vec<(f32, usize)> = events
+        .iter()
+        .filter_map(|event| match event {
+            ChunkedEvent::Partial { start, text } => Some((*start, text.parse().unwrap())),
+            _ => None,
+        })
+        .collect();
+    assert!(partials.len() >= 2, "{partials:?}");
+    // Partials start just before the tone and grow with it
+    assert!(partials.iter().all(|(start, _)| (0.7..1.0).contains(start)));
+    assert!(partials.windows(2).all(|w| w[1].1 > w[0].1));
+
+    let ChunkedEvent::Final {
+        start,
+        duration,
+        result,
+    } = events.last().unwrap()
+    else {
+        panic!("last event should be the final result");
+    };
+    assert_eq!(*start, 0.9);
+    assert_eq!(*duration, 1.7);
+    assert_eq!(result.text, "27200");
+}
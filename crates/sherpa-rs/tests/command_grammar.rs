@@ -0,0 +1,42 @@
+use sherpa_rs::grammar::CommandGrammar;
+
+fn grammar() -> CommandGrammar {
+    let mut grammar = CommandGrammar::new();
+    grammar
+        .add("light_on", "turn on the light")
+        .add("light_off", "turn off the light")
+        .add("light_off", "lights out")
+        .add("volume_up", "volume up");
+    grammar
+}
+
+#[test]
+fn matches_exact_and_near_miss_phrases() {
+    let grammar = grammar();
+    let m = grammar.match_text("Turn on the light.").unwrap();
+    assert_eq!(m.id, "light_on");
+    assert_eq!(m.score, 1.0);
+
+    let m = grammar.match_text("turned off the light").unwrap();
+    assert_eq!(m.id, "light_off");
+    assert_eq!(grammar.match_text("LIGHT'S OUT").unwrap().id, "light_off");
+}
+
+#[test]
+fn rejects_unrelated_speech() {
+    let grammar = grammar();
+    assert_eq!(grammar.match_text("what is the weather today"), None);
+    assert_eq!(grammar.match_text(""), None);
+}
+
+#[test]
+fn builds_hotwords_without_duplicates() {
+    let mut grammar = grammar();
+    grammar.add("light_on", "turn on the light");
+    assert_eq!(
+        grammar.hotwords(),
+        "turn on the light\nturn off the light\nlights out\nvolume up"
+    );
+    grammar.boost = Some(2.5);
+    assert!(grammar.hotwords().starts_with("turn on the light :2.5\n"));
+}
@@ -0,0 +1,107 @@
+use sherpa_rs::config_dump::{ConfigDump, ConfigValue, NativeConfig};
+use sherpa_rs::online::{OnlineModel, OnlineRecognizer, OnlineRecognizerConfig};
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+fn recognizer() -> OnlineRecognizer {
+    OnlineRecognizer::new(OnlineRecognizerConfig {
+        model: OnlineModel::Transducer {
+            encoder: required_env("SHERPA_RS_TEST_ENCODER"),
+            decoder: required_env("SHERPA_RS_TEST_DECODER"),
+            joiner: required_env("SHERPA_RS_TEST_JOINER"),
+        },
+        tokens: required_env("SHERPA_RS_TEST_TOKENS"),
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn lists_every_field_including_zeroed_ones() {
+    let recognizer = recognizer();
+    let dump = recognizer.config_dump();
+    assert_eq!(
+        dump.get("model_config.transducer.encoder"),
+        Some(&ConfigValue::Str(Some(required_env(
+            "SHERPA_RS_TEST_ENCODER"
+        ))))
+    );
+    assert_eq!(
+        dump.get("decoding_method"),
+        Some(&ConfigValue::Str(Some("greedy_search".into())))
+    );
+    assert_eq!(
+        dump.get("feat_config.sample_rate"),
+        Some(&ConfigValue::Int(16000))
+    );
+    // Unused model kinds are listed too
+    assert!(dump.get("model_config.paraformer.encoder").is_some());
+    assert!(dump.get("model_config.nemo_ctc.model").is_some());
+    assert!(dump.get("no.such.field").is_none());
+
+    let text = dump.to_string();
+    assert_eq!(text.lines().count(), dump.fields().len());
+    assert!(text.contains("model_config.num_threads = "));
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn effective_config_json_nests_the_dump() {
+    let recognizer = recognizer();
+    let json: serde_json::Value =
+        serde_json::from_str(&recognizer.effective_config_json()).unwrap();
+    assert_eq!(
+        json["model_config"]["transducer"]["encoder"],
+        required_env("SHERPA_RS_TEST_ENCODER")
+    );
+    assert_eq!(json["decoding_method"], "greedy_search");
+    assert_eq!(json["feat_config"]["sample_rate"], 16000);
+    let Some(ConfigValue::Int(threads)) = recognizer.config_dump().get("model_config.num_threads")
+    else {
+        panic!("num_threads is an int");
+    };
+    assert_eq!(json["model_config"]["num_threads"], *threads);
+}
+
+/// Recognizer created with an empty native config
+struct Unconfigured(ConfigDump);
+
+impl NativeConfig for Unconfigured {
+    fn config_dump(&self) -> &ConfigDump {
+        &self.0
+    }
+}
+
+#[test]
+fn empty_dump_is_an_empty_object() {
+    let recognizer = Unconfigured(ConfigDump::default());
+    assert_eq!(recognizer.effective_config_json(), "{}");
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn memory_usage_only_counts_path_fields() {
+    let recognizer = recognizer();
+    let dump = recognizer.config_dump();
+    let mut expected = [
+        required_env("SHERPA_RS_TEST_ENCODER"),
+        required_env("SHERPA_RS_TEST_DECODER"),
+        required_env("SHERPA_RS_TEST_JOINER"),
+        required_env("SHERPA_RS_TEST_TOKENS"),
+    ];
+    let mut paths = dump.paths();
+    paths.sort();
+    expected.sort();
+    assert_eq!(paths, expected);
+
+    // A file named like a non-path value isn't counted
+    let cwd = std::env::current_dir().unwrap();
+    let decoy = cwd.join("greedy_search");
+    std::fs::write(&decoy, "x").unwrap();
+    let files = dump.memory_usage().files;
+    std::fs::remove_file(&decoy).unwrap();
+    assert_eq!(files.len(), 4);
+}
@@ -0,0 +1,47 @@
+use sherpa_rs::delta::{TranscriptDiff, WordDelta};
+
+fn words(text: &str) -> Vec<String> {
+    text.split(' ').map(String::from).collect()
+}
+
+#[test]
+fn emits_appends_and_revisions() {
+    let mut diff = TranscriptDiff::new();
+    assert_eq!(
+        diff.update("HELLO"),
+        Some(WordDelta::Append(words("HELLO")))
+    );
+    assert_eq!(
+        diff.update("HELLO WORLD"),
+        Some(WordDelta::Append(words("WORLD")))
+    );
+    assert_eq!(diff.update("HELLO WORLD "), None);
+    assert_eq!(
+        diff.update("HELLO WORD IS"),
+        Some(WordDelta::Revise {
+            from_index: 1,
+            words: words("WORD IS"),
+        })
+    );
+    assert_eq!(
+        diff.update("HELLO"),
+        Some(WordDelta::Revise {
+            from_index: 1,
+            words: Vec::new(),
+        })
+    );
+    assert_eq!(diff.words(), words("HELLO"));
+
+    diff.reset();
+    assert_eq!(diff.update("NEXT"), Some(WordDelta::Append(words("NEXT"))));
+}
+
+#[test]
+fn splits_unspaced_scripts_per_character() {
+    let mut diff = TranscriptDiff::new();
+    diff.update("你好");
+    assert_eq!(
+        diff.update("你好世界 ok"),
+        Some(WordDelta::Append(words("世 界 ok")))
+    );
+}
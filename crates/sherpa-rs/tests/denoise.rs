@@ -0,0 +1,45 @@
+use sherpa_rs::denoise::{DenoiseError, Denoiser, DenoiserConfig};
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+/// 200 Hz sine at 16 kHz
+fn tone(amplitude: f32, len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| amplitude * (i as f32 * 200.0 * std::f32::consts::TAU / 16000.0).sin())
+        .collect()
+}
+
+fn denoiser() -> Denoiser {
+    Denoiser::new(DenoiserConfig {
+        model: required_env("SHERPA_RS_TEST_DENOISE_MODEL"),
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn keeps_length_and_sample_rate() {
+    let mut denoiser = denoiser();
+    let sample_rate = denoiser.sample_rate();
+    let samples = tone(0.3, sample_rate as usize);
+    let denoised = denoiser.denoise(&samples, sample_rate).unwrap();
+    assert_eq!(denoised.sample_rate, sample_rate);
+    assert_eq!(denoised.samples.len(), samples.len());
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn rejects_other_sample_rates() {
+    let mut denoiser = denoiser();
+    let wrong = denoiser.sample_rate() * 2;
+    match denoiser.denoise(&[0.0; 1600], wrong) {
+        Err(DenoiseError::SampleRateMismatch { expected, actual }) => {
+            assert_eq!(expected, denoiser.sample_rate());
+            assert_eq!(actual, wrong);
+        }
+        other => panic!("expected a sample rate mismatch, got {other:?}"),
+    }
+}
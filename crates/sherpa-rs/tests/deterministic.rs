@@ -0,0 +1,61 @@
+use sherpa_rs::config_dump::{ConfigValue, NativeConfig};
+use sherpa_rs::deterministic::Deterministic;
+use sherpa_rs::online::{OnlineModel, OnlineRecognizer, OnlineRecognizerConfig};
+use sherpa_rs::OnnxConfig;
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+#[test]
+fn preset_pins_threads_and_provider() {
+    let config = OnlineRecognizerConfig {
+        num_threads: 4,
+        provider: Some("cuda".into()),
+        max_active_paths: 8,
+        ..Default::default()
+    }
+    .deterministic();
+    assert_eq!(config.num_threads, 1);
+    assert_eq!(config.provider.as_deref(), Some("cpu"));
+    assert_eq!(config.max_active_paths, 8);
+
+    let onnx = OnnxConfig {
+        num_threads: 8,
+        debug: true,
+        ..Default::default()
+    }
+    .deterministic();
+    assert_eq!((onnx.num_threads, onnx.provider.as_str()), (1, "cpu"));
+    assert!(onnx.debug);
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn preset_reaches_the_native_config() {
+    let recognizer = OnlineRecognizer::new(
+        OnlineRecognizerConfig {
+            model: OnlineModel::Transducer {
+                encoder: required_env("SHERPA_RS_TEST_ENCODER"),
+                decoder: required_env("SHERPA_RS_TEST_DECODER"),
+                joiner: required_env("SHERPA_RS_TEST_JOINER"),
+            },
+            tokens: required_env("SHERPA_RS_TEST_TOKENS"),
+            num_threads: 4,
+            provider: Some("cuda".into()),
+            ..Default::default()
+        }
+        .deterministic(),
+    )
+    .unwrap();
+
+    let dump = recognizer.config_dump();
+    assert_eq!(
+        dump.get("model_config.num_threads"),
+        Some(&ConfigValue::Int(1))
+    );
+    assert_eq!(
+        dump.get("model_config.provider"),
+        Some(&ConfigValue::Str(Some("cpu".into())))
+    );
+}
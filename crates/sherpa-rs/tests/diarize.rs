@@ -0,0 +1,18 @@
+use sherpa_rs::diarize::{Diarize, DiarizeConfig};
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn silence_has_no_segments() {
+    let mut diarize = Diarize::new(
+        required_env("SHERPA_RS_TEST_SEGMENTATION_MODEL"),
+        required_env("SHERPA_RS_TEST_SPEAKER_MODEL"),
+        DiarizeConfig::default(),
+    )
+    .unwrap();
+    let segments = diarize.compute(vec![0.0; 16000 * 5], None).unwrap();
+    assert!(segments.is_empty());
+}
@@ -0,0 +1,50 @@
+use sherpa_rs::embedding_manager::{EmbeddingManager, EmbeddingManagerError};
+
+fn voice(seed: usize) -> Vec<f32> {
+    (0..8).map(|i| if i == seed { 1.0 } else { 0.05 }).collect()
+}
+
+#[test]
+fn rejects_invalid_database() {
+    let path = std::env::temp_dir().join(format!("speakers-invalid-{}.json", std::process::id()));
+    std::fs::write(&path, "not json").unwrap();
+    let result = EmbeddingManager::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(
+        result,
+        Err(EmbeddingManagerError::InvalidDatabase(_))
+    ));
+    assert!(matches!(
+        EmbeddingManager::load(&path),
+        Err(EmbeddingManagerError::Io(_))
+    ));
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries"]
+fn enrolls_verifies_and_persists_speakers() {
+    let mut manager = EmbeddingManager::new(8);
+    manager.add("alice".into(), &mut voice(0)).unwrap();
+    manager
+        .add_list("bob".into(), &[voice(1), voice(1)])
+        .unwrap();
+    assert!(manager.add("alice".into(), &mut voice(2)).is_err());
+    assert_eq!(manager.speakers(), ["alice", "bob"]);
+    assert!(manager.verify("alice", &voice(0), 0.9).unwrap());
+    assert!(!manager.verify("alice", &voice(1), 0.9).unwrap());
+    assert!(!manager.verify("carol", &voice(0), 0.9).unwrap());
+
+    let path = std::env::temp_dir().join(format!("speakers-{}.json", std::process::id()));
+    manager.save(&path).unwrap();
+    assert!(manager.remove("alice"));
+    assert!(!manager.remove("alice"));
+    assert!(!manager.contains("alice"));
+
+    let mut loaded = EmbeddingManager::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.speakers(), ["alice", "bob"]);
+    assert_eq!(
+        loaded.search(&voice(1), 0.9).unwrap().as_deref(),
+        Some("bob")
+    );
+}
@@ -0,0 +1,30 @@
+use sherpa_rs::energy_vad::{trim_silence, EnergyVad, EnergyVadConfig};
+
+/// 1 s silence, 1 s of a 200 Hz tone, 1 s silence at 16 kHz
+fn burst() -> Vec<f32> {
+    let mut samples = vec![0.0; 16000];
+    samples.extend(
+        (0..16000).map(|i| 0.3 * (i as f32 * 200.0 * std::f32::consts::TAU / 16000.0).sin()),
+    );
+    samples.extend(vec![0.0; 16000]);
+    samples
+}
+
+#[test]
+fn detects_tone_burst() {
+    let mut vad = EnergyVad::new(EnergyVadConfig::default()).unwrap();
+    for chunk in burst().chunks(1000) {
+        vad.accept_waveform(chunk);
+    }
+    vad.flush();
+
+    let segment = vad.pop().unwrap();
+    assert!(vad.pop().is_none());
+    // Tone starts at 16000, with 0.1 s of padding on each side
+    assert_eq!(segment.start, 16000 - 1600);
+    assert_eq!(segment.samples.len(), 16000 + 2 * 1600);
+
+    let samples = burst();
+    let trimmed = trim_silence(&samples, EnergyVadConfig::default()).unwrap();
+    assert_eq!(trimmed.len(), 16000 + 2 * 1600);
+}
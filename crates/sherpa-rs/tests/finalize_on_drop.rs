@@ -0,0 +1,107 @@
+use sherpa_rs::online::{OnlineModel, OnlineRecognizer, OnlineRecognizerConfig};
+use std::sync::mpsc;
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+fn recognizer() -> OnlineRecognizer {
+    OnlineRecognizer::new(OnlineRecognizerConfig {
+        model: OnlineModel::Transducer {
+            encoder: required_env("SHERPA_RS_TEST_ENCODER"),
+            decoder: required_env("SHERPA_RS_TEST_DECODER"),
+            joiner: required_env("SHERPA_RS_TEST_JOINER"),
+        },
+        tokens: required_env("SHERPA_RS_TEST_TOKENS"),
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn delivers_last_utterance_when_dropped() {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_WAV")).unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    let mut recognizer = recognizer();
+    recognizer.finalize_on_drop(move |result| tx.send(result).unwrap());
+    let mut stream = recognizer.create_stream().unwrap();
+    stream.set_tag("extra");
+    stream.accept_waveform(sample_rate, &samples).unwrap();
+    recognizer.accept_waveform(sample_rate, &samples).unwrap();
+
+    recognizer.destroy_stream(stream);
+    let extra = rx.try_recv().unwrap();
+    assert_eq!(extra.tag.as_deref(), Some("extra"));
+    assert!(!extra.text.is_empty());
+
+    drop(recognizer);
+    let built_in = rx.try_recv().unwrap();
+    assert_eq!(built_in.text, extra.text);
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn leaves_finished_streams_alone() {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_WAV")).unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    let mut recognizer = recognizer();
+    recognizer.finalize_on_drop(move |result| tx.send(result).unwrap());
+    recognizer.accept_waveform(sample_rate, &samples).unwrap();
+    recognizer.input_finished();
+    assert!(!recognizer.get_result().is_empty());
+
+    drop(recognizer);
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn finalizes_bare_streams_when_dropped() {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_WAV")).unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    let mut recognizer = recognizer();
+    recognizer.finalize_on_drop(move |result| tx.send(result).unwrap());
+    let mut stream = recognizer.create_stream().unwrap();
+    stream.set_tag("bare");
+    stream.accept_waveform(sample_rate, &samples).unwrap();
+    // Outliving the recognizer doesn't matter either
+    drop(recognizer);
+    assert!(rx.try_recv().is_err());
+
+    drop(stream);
+    let result = rx.try_recv().unwrap();
+    assert_eq!(result.tag.as_deref(), Some("bare"));
+    assert!(!result.text.is_empty());
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn survives_a_panicking_sink() {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_WAV")).unwrap();
+
+    let mut recognizer = recognizer();
+    recognizer.finalize_on_drop(|_| panic!("sink bug"));
+    let stream = recognizer.create_stream().unwrap();
+    stream.accept_waveform(sample_rate, &samples).unwrap();
+    drop(stream);
+
+    // The recognizer keeps working after the sink panicked
+    let stream = recognizer.create_stream().unwrap();
+    stream.accept_waveform(sample_rate, &samples).unwrap();
+    stream.input_finished();
+    while recognizer.is_stream_ready(&stream) {
+        recognizer.decode_stream(&stream);
+    }
+    assert!(!recognizer.stream_result(&stream).is_empty());
+    recognizer.accept_waveform(sample_rate, &samples).unwrap();
+    drop(recognizer);
+}
@@ -0,0 +1,60 @@
+use sherpa_rs::global::{self, SharedConfig};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+static LOADS: AtomicUsize = AtomicUsize::new(0);
+static PANICKED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug)]
+struct FakeConfig {
+    model: &'static str,
+}
+
+impl SharedConfig for FakeConfig {
+    type Recognizer = String;
+
+    fn load(self) -> eyre::Result<String> {
+        if self.model == "missing" {
+            eyre::bail!("no model {}", self.model);
+        }
+        if self.model == "flaky" {
+            assert!(PANICKED.swap(true, Ordering::SeqCst), "loader bug");
+            return Ok("model-flaky".into());
+        }
+        LOADS.fetch_add(1, Ordering::SeqCst);
+        Ok(format!("model-{}", self.model))
+    }
+}
+
+#[test]
+fn shares_one_recognizer_per_config() {
+    let handles: Vec<_> = (0..8)
+        .map(|_| thread::spawn(|| global::recognizer(FakeConfig { model: "en" }).unwrap()))
+        .collect();
+    let recognizers: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    assert!(recognizers.windows(2).all(|w| Arc::ptr_eq(&w[0], &w[1])));
+    assert_eq!(*recognizers[0].lock().unwrap(), "model-en");
+
+    let zh = global::recognizer(FakeConfig { model: "zh" }).unwrap();
+    assert!(!Arc::ptr_eq(&zh, &recognizers[0]));
+    assert_eq!(LOADS.load(Ordering::SeqCst), 2);
+    assert!(global::is_loaded(&FakeConfig { model: "zh" }));
+}
+
+#[test]
+fn failed_loads_are_not_cached() {
+    assert!(global::recognizer(FakeConfig { model: "missing" }).is_err());
+    assert!(!global::is_loaded(&FakeConfig { model: "missing" }));
+    assert!(global::recognizer(FakeConfig { model: "missing" }).is_err());
+}
+
+#[test]
+fn survives_a_panicking_loader() {
+    let panicked = thread::spawn(|| global::recognizer(FakeConfig { model: "flaky" })).join();
+    assert!(panicked.is_err());
+    assert!(!global::is_loaded(&FakeConfig { model: "flaky" }));
+
+    let recognizer = global::recognizer(FakeConfig { model: "flaky" }).unwrap();
+    assert_eq!(*recognizer.lock().unwrap(), "model-flaky");
+}
@@ -0,0 +1,42 @@
+use sherpa_rs::itn::{InverseTextNormalizer, ItnLanguage};
+
+#[test]
+fn english() {
+    let itn = InverseTextNormalizer::new(ItnLanguage::English);
+    let cases = [
+        ("in twenty twenty five we grew", "in 2025 we grew"),
+        ("it costs five dollars and fifty cents.", "it costs $5.50."),
+        ("one hundred and twenty three people", "123 people"),
+        ("up three point five percent", "up 3.5%"),
+        (
+            "see you january first twenty twenty six",
+            "see you January 1, 2026",
+        ),
+        (
+            "the twenty-third time, I have one question",
+            "the 23rd time, I have one question",
+        ),
+        ("call five five five one two three four", "call 5551234"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(itn.apply(input), expected);
+    }
+}
+
+#[test]
+fn chinese() {
+    let itn = InverseTextNormalizer::new(ItnLanguage::Chinese);
+    let cases = [
+        ("二零二五年十月十六日", "2025年10月16日"),
+        ("增长了百分之三十五", "增长了35%"),
+        ("第三名得到十二万三千元", "第3名得到123000元"),
+        ("统一一下", "统一一下"),
+        ("三点五", "3.5"),
+        // Too large for a number, left as written
+        ("一亿亿亿亿亿亿", "一亿亿亿亿亿亿"),
+        ("两亿亿", "20000000000000000"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(itn.apply(input), expected);
+    }
+}
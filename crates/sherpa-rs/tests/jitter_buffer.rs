@@ -0,0 +1,107 @@
+use sherpa_rs::jitter_buffer::{JitterBuffer, JitterBufferConfig};
+use std::time::{Duration, Instant};
+
+fn config(adaptive: bool) -> JitterBufferConfig {
+    JitterBufferConfig {
+        sample_rate: 1000,
+        target_delay: 0.05,
+        adaptive,
+        max_delay: 0.5,
+    }
+}
+
+fn ms(start: Instant, ms: u64) -> Instant {
+    start + Duration::from_millis(ms)
+}
+
+#[test]
+fn reorders_chunks_and_fills_gaps() {
+    let start = Instant::now();
+    let mut buffer = JitterBuffer::new(config(false));
+    buffer.push(0, vec![1.0; 20], start);
+    buffer.push(40, vec![3.0; 20], ms(start, 20));
+    buffer.push(20, vec![2.0; 20], ms(start, 25));
+    // Held back for the delay
+    assert!(buffer.pop(ms(start, 40)).is_empty());
+
+    let out = buffer.pop(ms(start, 110));
+    assert_eq!(out.len(), 60);
+    assert!(out[..20].iter().all(|&s| s == 1.0));
+    assert!(out[20..40].iter().all(|&s| s == 2.0));
+    assert!(out[40..].iter().all(|&s| s == 3.0));
+
+    // 60..80 never arrives
+    buffer.push(80, vec![5.0; 20], ms(start, 80));
+    let out = buffer.pop(ms(start, 150));
+    assert_eq!(out.len(), 40);
+    assert!(out[..20].iter().all(|&s| s == 0.0));
+    assert!(out[20..].iter().all(|&s| s == 5.0));
+
+    // Too late now
+    buffer.push(60, vec![4.0; 20], ms(start, 150));
+    assert_eq!(buffer.late_chunks(), 1);
+    assert_eq!(buffer.buffered(), 0);
+}
+
+#[test]
+fn flush_releases_everything() {
+    let start = Instant::now();
+    let mut buffer = JitterBuffer::new(config(false));
+    buffer.push(100, vec![1.0; 10], start);
+    buffer.push(120, vec![1.0; 10], start);
+    let out = buffer.flush();
+    assert_eq!(out.len(), 30);
+    assert!(out[10..20].iter().all(|&s| s == 0.0));
+    assert!(buffer.flush().is_empty());
+}
+
+#[test]
+fn delay_adapts_to_jitter() {
+    let start = Instant::now();
+    let mut steady = JitterBuffer::new(config(true));
+    let mut bursty = JitterBuffer::new(config(true));
+    for i in 0..50 {
+        steady.push(i * 20, vec![0.0; 20], ms(start, i * 20));
+        // Pairs of chunks arrive together
+        bursty.push(
+            i * 20,
+            vec![0.0; 20],
+            ms(start, (i / 2) * 40 + 60 * (i % 2)),
+        );
+    }
+    assert_eq!(steady.delay(), 0.05);
+    assert!(bursty.delay() > 0.1, "{}", bursty.delay());
+    assert!(bursty.delay() <= 0.5);
+}
+
+#[test]
+fn overlapping_chunks_keep_the_first_copy() {
+    let start = Instant::now();
+    let mut buffer = JitterBuffer::new(config(false));
+    buffer.push(0, vec![1.0; 20], start);
+    buffer.push(10, vec![2.0; 20], start);
+    buffer.push(5, vec![3.0; 40], start);
+    buffer.push(10, vec![4.0; 5], start);
+    assert_eq!(buffer.buffered(), 45);
+
+    let out = buffer.flush();
+    assert_eq!(out.len(), 45);
+    assert!(out[..20].iter().all(|&s| s == 1.0));
+    assert!(out[20..30].iter().all(|&s| s == 2.0));
+    assert!(out[30..].iter().all(|&s| s == 3.0));
+}
+
+#[test]
+fn reset_starts_a_new_stream() {
+    let start = Instant::now();
+    let mut buffer = JitterBuffer::new(config(false));
+    buffer.push(0, vec![1.0; 20], start);
+    buffer.flush();
+    buffer.push(0, vec![1.0; 20], start);
+    assert_eq!(buffer.late_chunks(), 1);
+
+    buffer.reset();
+    assert_eq!(buffer.late_chunks(), 0);
+    buffer.push(0, vec![1.0; 20], start);
+    assert_eq!(buffer.buffered(), 20);
+}
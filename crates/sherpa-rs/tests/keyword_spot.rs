@@ -0,0 +1,40 @@
+use sherpa_rs::keyword_spot::{KeywordSpot, KeywordSpotConfig};
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+fn spotter() -> KeywordSpot {
+    KeywordSpot::new(KeywordSpotConfig {
+        zipformer_encoder: required_env("SHERPA_RS_TEST_KWS_ENCODER"),
+        zipformer_decoder: required_env("SHERPA_RS_TEST_KWS_DECODER"),
+        zipformer_joiner: required_env("SHERPA_RS_TEST_KWS_JOINER"),
+        tokens: required_env("SHERPA_RS_TEST_KWS_TOKENS"),
+        keywords: required_env("SHERPA_RS_TEST_KWS_KEYWORDS"),
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn detects_every_keyword_in_one_chunk() {
+    let (mut samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_KWS_WAV")).unwrap();
+    // Trailing silence lets the spotter finish the last keyword
+    samples.extend(vec![0.0; sample_rate as usize]);
+
+    let mut chunked = spotter();
+    let expected: Vec<_> = samples
+        .chunks(1600)
+        .flat_map(|chunk| chunked.detect(sample_rate, chunk))
+        .map(|detection| detection.keyword)
+        .collect();
+    let found: Vec<_> = spotter()
+        .detect(sample_rate, &samples)
+        .into_iter()
+        .map(|detection| detection.keyword)
+        .collect();
+    assert!(!found.is_empty());
+    assert_eq!(found, expected);
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "async")]
+
+use futures::executor::block_on;
+use futures::StreamExt;
+use sherpa_rs::keyword_spot::{KeywordSpot, KeywordSpotConfig};
+use sherpa_rs::keyword_stream::KeywordStream;
+use std::sync::mpsc;
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn streams_keyword_detections() {
+    let spotter = KeywordSpot::new(KeywordSpotConfig {
+        zipformer_encoder: required_env("SHERPA_RS_TEST_KWS_ENCODER"),
+        zipformer_decoder: required_env("SHERPA_RS_TEST_KWS_DECODER"),
+        zipformer_joiner: required_env("SHERPA_RS_TEST_KWS_JOINER"),
+        tokens: required_env("SHERPA_RS_TEST_KWS_TOKENS"),
+        keywords: required_env("SHERPA_RS_TEST_KWS_KEYWORDS"),
+        ..Default::default()
+    })
+    .unwrap();
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_KWS_WAV")).unwrap();
+
+    let (audio_tx, audio) = mpsc::channel();
+    let mut detections = KeywordStream::start(spotter, sample_rate, audio);
+    for chunk in samples.chunks(1600) {
+        audio_tx.send(chunk.to_vec()).unwrap();
+    }
+    // Trailing silence lets the spotter finish the last keyword
+    audio_tx.send(vec![0.0; sample_rate as usize]).unwrap();
+    drop(audio_tx);
+
+    let found: Vec<_> = block_on(detections.by_ref().collect());
+    assert!(!found.is_empty());
+    let duration = samples.len() as f32 / sample_rate as f32;
+    for detection in &found {
+        assert!(!detection.keyword.is_empty());
+        assert!(detection.start <= detection.end && detection.end <= duration + 1.0);
+    }
+    detections.stop();
+}
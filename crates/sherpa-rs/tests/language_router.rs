@@ -0,0 +1,62 @@
+use sherpa_rs::language_id::{LanguageRouter, SpokenLanguageId, SpokenLanguageIdConfig};
+use sherpa_rs::{OfflineRecognizerResult, Transcriber};
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+/// Transcribes everything as its name
+struct Named(&'static str);
+
+impl Transcriber for Named {
+    fn transcribe(&mut self, _sample_rate: u32, _samples: &[f32]) -> OfflineRecognizerResult {
+        OfflineRecognizerResult {
+            text: self.0.into(),
+            ..Default::default()
+        }
+    }
+}
+
+fn lid() -> SpokenLanguageId {
+    SpokenLanguageId::new(SpokenLanguageIdConfig {
+        encoder: required_env("SHERPA_RS_TEST_LID_ENCODER"),
+        decoder: required_env("SHERPA_RS_TEST_LID_DECODER"),
+        ..Default::default()
+    })
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn routes_to_the_detected_language() {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_WAV")).unwrap();
+    let mut router = LanguageRouter::new(lid()).detect_seconds(2.0);
+    let lang = router.detect(sample_rate, &samples).unwrap();
+    assert!(!lang.is_empty());
+
+    let mut router = LanguageRouter::new(lid())
+        .route(&lang, Named("routed"))
+        .route("xx", Named("other"))
+        .fallback(Named("fallback"));
+    let result = router.transcribe(sample_rate, &samples);
+    assert_eq!(
+        (result.text.as_str(), result.lang.as_str()),
+        ("routed", lang.as_str())
+    );
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn unrouted_languages_use_the_fallback() {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_WAV")).unwrap();
+    let mut router = LanguageRouter::new(lid()).route("xx", Named("other"));
+    assert!(router.transcribe_routed(sample_rate, &samples).is_err());
+    // Failures turn into empty results behind the Transcriber trait
+    assert_eq!(router.transcribe(sample_rate, &samples).text, "");
+
+    let mut router = router.fallback(Named("fallback"));
+    let result = router.transcribe_routed(sample_rate, &samples).unwrap();
+    assert_eq!(result.text, "fallback");
+    assert!(!result.lang.is_empty());
+}
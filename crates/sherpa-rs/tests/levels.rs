@@ -0,0 +1,47 @@
+use sherpa_rs::levels::{LevelCheck, LevelIssue, LevelReport};
+
+/// 200 Hz sine at 16 kHz
+fn tone(amplitude: f32, len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| amplitude * (i as f32 * 200.0 * std::f32::consts::TAU / 16000.0).sin())
+        .collect()
+}
+
+#[test]
+fn well_scaled_audio_has_no_issues() {
+    let report = LevelReport::measure(&tone(0.3, 16000));
+    assert!((report.peak - 0.3).abs() < 1e-3);
+    assert!((report.rms - 0.3 / 2f32.sqrt()).abs() < 1e-3);
+    assert!(report.issues().is_empty());
+    assert!(LevelCheck::Error.check(&tone(0.3, 16000)).is_ok());
+    assert!(LevelReport::measure(&[]).issues().is_empty());
+}
+
+#[test]
+fn detects_integer_scaled_silent_and_clipped_audio() {
+    let unscaled = tone(12000.0, 16000);
+    assert!(matches!(
+        LevelReport::measure(&unscaled).issues()[0],
+        LevelIssue::OutOfRange { .. }
+    ));
+    assert_eq!(
+        LevelCheck::Error.check(&vec![0.0; 1600]),
+        Err(LevelIssue::Silent)
+    );
+
+    // Overdriven tone hard-limited to full scale
+    let clipped: Vec<f32> = tone(3.0, 16000)
+        .iter()
+        .map(|s| s.clamp(-1.0, 1.0))
+        .collect();
+    let issues = LevelReport::measure(&clipped).issues();
+    assert!(
+        matches!(issues[..], [LevelIssue::Clipped { clipped }] if clipped > 0.5),
+        "{issues:?}"
+    );
+
+    // Only errors when asked to
+    assert!(LevelCheck::Warn.check(&unscaled).is_ok());
+    assert!(LevelCheck::Off.check(&unscaled).is_ok());
+    assert!(LevelCheck::Error.check(&unscaled).is_err());
+}
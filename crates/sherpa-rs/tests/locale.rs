@@ -0,0 +1,95 @@
+use sherpa_rs::itn::{InverseTextNormalizer, ItnLanguage};
+use sherpa_rs::locale::{Locale, LocaleFormatter, LocaleRules};
+use sherpa_rs::post_process::PostProcessors;
+use sherpa_rs::OfflineRecognizerResult;
+
+#[test]
+fn groups_digits_per_locale() {
+    let text = "paid $1234567.5 for 25000 units in 2025, up 3.5%";
+    let cases = [
+        (
+            Locale::English,
+            "paid $1,234,567.5 for 25,000 units in 2025, up 3.5%",
+        ),
+        (
+            Locale::German,
+            "paid 1.234.567,5 $ for 25.000 units in 2025, up 3,5%",
+        ),
+        (Locale::Chinese, text),
+    ];
+    for (locale, expected) in cases {
+        assert_eq!(LocaleFormatter::new(locale).apply(text), expected);
+    }
+    assert_eq!(
+        LocaleFormatter::new(Locale::Chinese).apply("得到123000元"),
+        "得到123000元"
+    );
+    // Words with digits and numbers already formatted are left alone
+    assert_eq!(
+        LocaleFormatter::new(Locale::English).apply("mp3 files, 1,234 of them"),
+        "mp3 files, 1,234 of them"
+    );
+}
+
+#[test]
+fn formats_phone_numbers_when_enabled() {
+    let en = LocaleFormatter::new(Locale::English).phone_numbers(true);
+    assert_eq!(en.apply("call 5551234"), "call 555-1234");
+    assert_eq!(en.apply("call 2125551234."), "call (212) 555-1234.");
+    let de = LocaleFormatter::new(Locale::German).phone_numbers(true);
+    assert_eq!(de.apply("Nummer 0301234567"), "Nummer 030 1234567");
+    let zh = LocaleFormatter::new(Locale::Chinese).phone_numbers(true);
+    assert_eq!(zh.apply("电话13800138000"), "电话138 0013 8000");
+}
+
+#[test]
+fn numbers_stay_numbers_by_default() {
+    let en = LocaleFormatter::new(Locale::English);
+    assert_eq!(en.apply("sold 1234567 units"), "sold 1,234,567 units");
+    assert_eq!(
+        en.apply("sold 2125551234 units"),
+        "sold 2,125,551,234 units"
+    );
+    let de = LocaleFormatter::new(Locale::German);
+    assert_eq!(de.apply("Summe 1234567"), "Summe 1.234.567");
+    let zh = LocaleFormatter::new(Locale::Chinese);
+    assert_eq!(zh.apply("共13800138000"), "共13800138000");
+}
+
+/// Swiss style: apostrophe grouping, no phone numbers
+struct Swiss;
+
+impl LocaleRules for Swiss {
+    fn number(&self, integer: &str, fraction: Option<&str>) -> String {
+        let mut text = String::new();
+        for (i, digit) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i).is_multiple_of(3) {
+                text.push('\'');
+            }
+            text.push(digit);
+        }
+        match fraction {
+            Some(fraction) => format!("{text}.{fraction}"),
+            None => text,
+        }
+    }
+
+    fn currency(&self, symbol: &str, amount: &str) -> String {
+        format!("{symbol} {amount}")
+    }
+}
+
+#[test]
+fn runs_custom_rules_after_itn() {
+    let itn = InverseTextNormalizer::new(ItnLanguage::English);
+    let mut post_processors = PostProcessors::new();
+    post_processors.push(itn.into_post_processor());
+    post_processors.push(LocaleFormatter::new(Swiss).into_post_processor());
+
+    let mut result = OfflineRecognizerResult {
+        text: "it costs five thousand dollars and fifty cents".into(),
+        ..Default::default()
+    };
+    post_processors.apply(&mut result);
+    assert_eq!(result.text, "it costs $ 5'000.50");
+}
@@ -0,0 +1,86 @@
+use sherpa_rs::long_audio::{split_points, LongAudio, LongAudioConfig};
+use sherpa_rs::{OfflineRecognizerResult, Transcriber};
+
+/// 200 Hz sine at 16 kHz
+fn tone(amplitude: f32, len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| amplitude * (i as f32 * 200.0 * std::f32::consts::TAU / 16000.0).sin())
+        .collect()
+}
+
+/// Transcribes audio as its length in samples, as one token at its start
+#[derive(Default)]
+struct Counter {
+    calls: Vec<usize>,
+}
+
+impl Transcriber for Counter {
+    fn transcribe(&mut self, _sample_rate: u32, samples: &[f32]) -> OfflineRecognizerResult {
+        self.calls.push(samples.len());
+        OfflineRecognizerResult {
+            text: samples.len().to_string(),
+            tokens: vec![samples.len().to_string()],
+            timestamps: vec![0.0],
+            ..Default::default()
+        }
+    }
+}
+
+#[test]
+fn short_inputs_pass_through() {
+    let mut recognizer = LongAudio::new(Counter::default(), LongAudioConfig::default());
+    let samples = tone(0.3, 16000);
+    let result = recognizer.transcribe(16000, &samples);
+    assert_eq!(result.text, "16000");
+    assert_eq!(result.timestamps, [0.0]);
+    assert_eq!(recognizer.into_inner().calls, [16000]);
+}
+
+#[test]
+fn splits_at_silence_and_shifts_timestamps() {
+    // 8 s tone, 0.5 s silence, 8 s tone, 0.5 s silence, 3 s tone at 16 kHz
+    let mut samples = tone(0.3, 128000);
+    samples.extend(vec![0.0; 8000]);
+    samples.extend(tone(0.3, 128000));
+    samples.extend(vec![0.0; 8000]);
+    samples.extend(tone(0.3, 48000));
+    let config = LongAudioConfig {
+        max_duration: 10.0,
+        search_window: 4.0,
+        ..Default::default()
+    };
+
+    let ranges = split_points(&samples, 16000, &config);
+    assert_eq!(ranges.len(), 3, "{ranges:?}");
+    // Cuts land in the silent gaps
+    assert!((128000..136000).contains(&ranges[0].end), "{ranges:?}");
+    assert!((264000..272000).contains(&ranges[1].end), "{ranges:?}");
+    assert_eq!(ranges[2].end, samples.len());
+
+    let mut recognizer = LongAudio::new(Counter::default(), config);
+    let result = recognizer.transcribe(16000, &samples);
+    let calls = recognizer.into_inner().calls;
+    assert_eq!(calls, ranges.iter().map(|r| r.len()).collect::<Vec<_>>());
+    assert_eq!(result.text.split(' ').count(), 3);
+    assert_eq!(result.tokens.len(), 3);
+    for (timestamp, range) in result.timestamps.iter().zip(&ranges) {
+        assert!((timestamp - range.start as f32 / 16000.0).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn cuts_at_the_limit_without_silence() {
+    let samples = tone(0.3, 16000 * 25);
+    let config = LongAudioConfig {
+        max_duration: 10.0,
+        ..Default::default()
+    };
+    let ranges = split_points(&samples, 16000, &config);
+    // Every cut is within the search window before the limit
+    let (last, cut) = ranges.split_last().unwrap();
+    assert!(cut
+        .iter()
+        .all(|r| (160000 - 80000..=160000).contains(&r.len())));
+    assert!(last.len() <= 160000);
+    assert_eq!(last.end, samples.len());
+}
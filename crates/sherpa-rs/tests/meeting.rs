@@ -0,0 +1,95 @@
+use sherpa_rs::long_audio::LongAudioConfig;
+use sherpa_rs::meeting::MeetingTranscriber;
+use sherpa_rs::transcript::{Transcript, Turn};
+use sherpa_rs::{OfflineRecognizerResult, Transcriber};
+
+/// 200 Hz sine at 16 kHz
+fn tone(amplitude: f32, len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| amplitude * (i as f32 * 200.0 * std::f32::consts::TAU / 16000.0).sin())
+        .collect()
+}
+
+/// Transcribes audio as its length in samples, as one token at its start
+#[derive(Default)]
+struct Counter {
+    calls: Vec<usize>,
+}
+
+impl Transcriber for Counter {
+    fn transcribe(&mut self, _sample_rate: u32, samples: &[f32]) -> OfflineRecognizerResult {
+        self.calls.push(samples.len());
+        OfflineRecognizerResult {
+            text: samples.len().to_string(),
+            tokens: vec![samples.len().to_string()],
+            timestamps: vec![0.0],
+            ..Default::default()
+        }
+    }
+}
+
+fn config() -> LongAudioConfig {
+    LongAudioConfig {
+        max_duration: 10.0,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn live_feed_is_split_into_turns_at_silence() {
+    // 8 s tone, 0.5 s silence, 4 s tone at 16 kHz
+    let mut samples = tone(0.3, 128000);
+    samples.extend(vec![0.0; 8000]);
+    samples.extend(tone(0.3, 64000));
+
+    let mut meeting = MeetingTranscriber::new(Counter::default()).long_audio(config());
+    for chunk in samples.chunks(1600) {
+        meeting.push(chunk);
+    }
+    assert_eq!(meeting.recorded(), 12.5);
+
+    let transcript = meeting.finish().unwrap();
+    assert_eq!(transcript.turns.len(), 2);
+    let first = &transcript.turns[0];
+    let cut = first.text.parse::<f32>().unwrap() / 16000.0;
+    assert!((8.0..8.5).contains(&cut), "cut at {}", cut);
+    assert_eq!((first.start, first.end), (0.0, cut));
+    assert_eq!(transcript.turns[1].start, cut);
+    assert_eq!(transcript.turns[1].words[0].start, cut);
+    assert_eq!(transcript.turns[1].end, 12.5);
+    assert!(transcript.speakers.is_empty());
+
+    assert_eq!(meeting.recorded(), 0.0);
+    assert!(meeting.finish().unwrap().turns.is_empty());
+}
+
+#[test]
+fn srt_and_json_carry_speaker_labels() {
+    let mut transcript = Transcript::new();
+    for (speaker, start, end, text) in [(0, 0.0, 1.5, "Good morning"), (1, 1.5, 3.25, "Hi")] {
+        transcript.push(Turn {
+            speaker: Some(speaker),
+            start,
+            end,
+            text: text.into(),
+            ..Default::default()
+        });
+    }
+    transcript.push(Turn {
+        start: 4.0,
+        end: 5.0,
+        text: "[laughter]".into(),
+        ..Default::default()
+    });
+    transcript.rename_speaker(0, "Alice");
+
+    assert_eq!(
+        transcript.to_srt(None),
+        "1\n00:00:00,000 --> 00:00:01,500\n[Alice] Good morning\n\n\
+         2\n00:00:01,500 --> 00:00:03,250\n[speaker 1] Hi\n\n\
+         3\n00:00:04,000 --> 00:00:05,000\n[laughter]\n\n"
+    );
+    let parsed = Transcript::from_json(&transcript.to_json()).unwrap();
+    assert_eq!(parsed.speaker_label(0), Some("Alice"));
+    assert_eq!(parsed, transcript);
+}
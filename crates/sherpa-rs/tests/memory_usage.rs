@@ -0,0 +1,18 @@
+use sherpa_rs::memory::MemoryUsage;
+
+#[test]
+fn sums_model_file_sizes() {
+    let dir = std::env::temp_dir().join(format!("sherpa-rs-memory-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let encoder = dir.join("encoder.onnx");
+    let tokens = dir.join("tokens.txt");
+    std::fs::write(&encoder, vec![0u8; 4000]).unwrap();
+    std::fs::write(&tokens, "a 0\nb 1\n").unwrap();
+
+    let usage = MemoryUsage::from_paths([&encoder, &tokens, &encoder, &dir.join("missing.onnx")]);
+    assert_eq!(usage.files.len(), 2);
+    assert_eq!(usage.model_bytes(), 4008);
+    assert_eq!(usage.estimated_bytes(), 5010);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,12 @@
+use sherpa_rs::{native_log_level, set_native_log_level, NativeLogLevel};
+
+#[test]
+fn native_log_level_round_trips() {
+    assert_eq!(native_log_level(), NativeLogLevel::PerConfig);
+    set_native_log_level(NativeLogLevel::Error);
+    assert_eq!(native_log_level(), NativeLogLevel::Error);
+    set_native_log_level(NativeLogLevel::Debug);
+    assert_eq!(native_log_level(), NativeLogLevel::Debug);
+    set_native_log_level(NativeLogLevel::PerConfig);
+    assert_eq!(native_log_level(), NativeLogLevel::PerConfig);
+}
@@ -0,0 +1,65 @@
+use sherpa_rs::nemo_ctc::{NemoCtcConfig, NemoCtcRecognizer};
+use sherpa_rs::offline_stream::AcceptError;
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+fn recognizer() -> NemoCtcRecognizer {
+    NemoCtcRecognizer::new(NemoCtcConfig {
+        model: required_env("SHERPA_RS_TEST_NEMO_CTC_MODEL"),
+        tokens: required_env("SHERPA_RS_TEST_NEMO_CTC_TOKENS"),
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn chunks_decode_like_one_buffer() {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_WAV")).unwrap();
+    let mut recognizer = recognizer();
+    let whole = recognizer.transcribe(sample_rate, &samples).text;
+
+    let mut stream = recognizer.create_stream().unwrap();
+    for chunk in samples.chunks(sample_rate as usize) {
+        stream.accept_waveform(sample_rate, chunk).unwrap();
+    }
+    assert!(!whole.is_empty());
+    assert_eq!(stream.decode().text, whole);
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn rejects_a_sample_rate_change() {
+    let recognizer = recognizer();
+    let mut stream = recognizer.create_stream().unwrap();
+    stream.accept_waveform(16000, &[0.0; 160]).unwrap();
+    assert_eq!(
+        stream.accept_waveform(8000, &[0.0; 80]),
+        Err(AcceptError::SampleRateChanged {
+            expected: 16000,
+            got: 8000
+        })
+    );
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn decodes_again_after_more_audio() {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_WAV")).unwrap();
+    let mut recognizer = recognizer();
+    let (first, second) = samples.split_at(samples.len() / 2);
+    let first_text = recognizer.transcribe(sample_rate, first).text;
+    let whole = recognizer.transcribe(sample_rate, &samples).text;
+
+    let mut stream = recognizer.create_stream().unwrap();
+    stream.accept_waveform(sample_rate, first).unwrap();
+    assert_eq!(stream.decode().text, first_text);
+    stream.accept_waveform(sample_rate, second).unwrap();
+    assert_eq!(stream.decode().text, whole);
+    // Decoding without new audio gives the same result
+    assert_eq!(stream.decode().text, whole);
+}
@@ -0,0 +1,49 @@
+use sherpa_rs::online::{OnlineModel, OnlineRecognizer, OnlineRecognizerConfig};
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+/// Decode the test recording to the end
+fn transcribe(config: OnlineRecognizerConfig) -> String {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_WAV")).unwrap();
+    let mut recognizer = OnlineRecognizer::new(config).unwrap();
+    recognizer.accept_waveform(sample_rate, &samples).unwrap();
+    recognizer.input_finished();
+    recognizer.get_result()
+}
+
+#[test]
+fn zipformer2_ctc_config_takes_one_model() {
+    let config = OnlineRecognizerConfig::zipformer2_ctc("ctc.onnx", "tokens.txt");
+    assert_eq!(
+        config.model,
+        OnlineModel::Zipformer2Ctc {
+            model: "ctc.onnx".into()
+        }
+    );
+    assert_eq!(config.tokens, "tokens.txt");
+    assert_eq!(config.decoding_method, "greedy_search");
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn decodes_with_zipformer2_ctc() {
+    let text = transcribe(OnlineRecognizerConfig::zipformer2_ctc(
+        required_env("SHERPA_RS_TEST_CTC_MODEL"),
+        required_env("SHERPA_RS_TEST_CTC_TOKENS"),
+    ));
+    assert!(!text.is_empty());
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn decodes_with_streaming_paraformer() {
+    let text = transcribe(OnlineRecognizerConfig::paraformer(
+        required_env("SHERPA_RS_TEST_PARAFORMER_ENCODER"),
+        required_env("SHERPA_RS_TEST_PARAFORMER_DECODER"),
+        required_env("SHERPA_RS_TEST_PARAFORMER_TOKENS"),
+    ));
+    assert!(!text.is_empty());
+}
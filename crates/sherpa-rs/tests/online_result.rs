@@ -0,0 +1,37 @@
+use sherpa_rs::alignment::TokenStyle;
+use sherpa_rs::online::{OnlineModel, OnlineRecognizer, OnlineRecognizerConfig};
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn results_carry_token_timing_and_probs() {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_WAV")).unwrap();
+    let mut recognizer = OnlineRecognizer::new(OnlineRecognizerConfig {
+        model: OnlineModel::Transducer {
+            encoder: required_env("SHERPA_RS_TEST_ENCODER"),
+            decoder: required_env("SHERPA_RS_TEST_DECODER"),
+            joiner: required_env("SHERPA_RS_TEST_JOINER"),
+        },
+        tokens: required_env("SHERPA_RS_TEST_TOKENS"),
+        decoding_method: "modified_beam_search".into(),
+        ..Default::default()
+    })
+    .unwrap();
+    recognizer.accept_waveform(sample_rate, &samples).unwrap();
+    recognizer.input_finished();
+
+    let result = recognizer.get_result_full();
+    assert!(!result.tokens.is_empty());
+    assert_eq!(result.timestamps.len(), result.tokens.len());
+    assert_eq!(result.ys_probs.len(), result.tokens.len());
+    assert!(result.ys_probs.iter().all(|p| *p <= 0.0));
+    assert!(result.timestamps.windows(2).all(|w| w[0] <= w[1]));
+
+    let words = result.words(TokenStyle::detect(&result.tokens));
+    assert!(!words.is_empty());
+    assert!(words.iter().all(|word| word.start <= word.end));
+}
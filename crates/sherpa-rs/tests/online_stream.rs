@@ -0,0 +1,94 @@
+use sherpa_rs::online::{OnlineModel, OnlineRecognizer, OnlineRecognizerConfig};
+use std::sync::mpsc;
+use std::thread;
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+fn recognizer() -> OnlineRecognizer {
+    OnlineRecognizer::new(OnlineRecognizerConfig {
+        model: OnlineModel::Transducer {
+            encoder: required_env("SHERPA_RS_TEST_ENCODER"),
+            decoder: required_env("SHERPA_RS_TEST_DECODER"),
+            joiner: required_env("SHERPA_RS_TEST_JOINER"),
+        },
+        tokens: required_env("SHERPA_RS_TEST_TOKENS"),
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn decodes_streams_side_by_side() {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_WAV")).unwrap();
+    let recognizer = recognizer();
+
+    let texts: Vec<String> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                scope.spawn(|| {
+                    let stream = recognizer.open_stream().unwrap();
+                    stream.accept_waveform(sample_rate, &samples).unwrap();
+                    stream.input_finished();
+                    stream.transcribe()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert!(!texts[0].is_empty());
+    assert_eq!(texts[0], texts[1]);
+
+    let first = recognizer.open_stream().unwrap();
+    let second = recognizer.open_stream().unwrap();
+    for stream in [&first, &second] {
+        stream.accept_waveform(sample_rate, &samples).unwrap();
+        stream.input_finished();
+    }
+    while first.is_ready() || second.is_ready() {
+        recognizer.decode_batch(&[&first, &second]);
+    }
+    assert_eq!(first.result(), texts[0]);
+    assert_eq!(second.result(), texts[0]);
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn dropped_streams_are_finalized() {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_WAV")).unwrap();
+    let (tx, rx) = mpsc::channel();
+    let mut recognizer = recognizer();
+    recognizer.finalize_on_drop(move |result| tx.send(result).unwrap());
+
+    let mut stream = recognizer.open_stream().unwrap();
+    stream.set_tag("session");
+    stream.accept_waveform(sample_rate, &samples).unwrap();
+    drop(stream);
+
+    let result = rx.try_recv().unwrap();
+    assert_eq!(result.tag.as_deref(), Some("session"));
+    assert!(!result.text.is_empty());
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn streams_outlive_their_recognizer() {
+    let recognizer = recognizer();
+    let stream = recognizer.create_stream().unwrap();
+    drop(recognizer);
+    stream.accept_waveform(16000, &[0.0; 1600]).unwrap();
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+#[should_panic = "stream created by another recognizer"]
+fn foreign_streams_are_rejected() {
+    let first = recognizer();
+    let second = recognizer();
+    let stream = first.create_stream().unwrap();
+    second.is_stream_ready(&stream);
+}
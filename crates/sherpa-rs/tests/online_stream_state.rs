@@ -0,0 +1,105 @@
+#![allow(deprecated)]
+
+use sherpa_rs::online::{OnlineModel, OnlineRecognizer, OnlineRecognizerConfig};
+use sherpa_rs::transducer_online::{OnlineTransducerConfig, OnlineTransducerRecognizer};
+use sherpa_rs::zipformer_online::{ZipFormerOnline, ZipFormerOnlineConfig};
+use sherpa_rs::StreamingError;
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+const SILENCE: [f32; 1600] = [0.0; 1600];
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn zipformer_online_accepts_audio_again_after_reset() {
+    let mut recognizer = ZipFormerOnline::new(ZipFormerOnlineConfig {
+        encoder: required_env("SHERPA_RS_TEST_ENCODER"),
+        decoder: required_env("SHERPA_RS_TEST_DECODER"),
+        joiner: required_env("SHERPA_RS_TEST_JOINER"),
+        tokens: required_env("SHERPA_RS_TEST_TOKENS"),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut stream = recognizer.create_stream();
+    stream.set_tag("session");
+
+    recognizer
+        .accept_waveform(&stream, 16000, &SILENCE)
+        .unwrap();
+    recognizer.input_finished(&stream);
+    // Finishing twice is harmless
+    recognizer.input_finished(&stream);
+    assert!(matches!(
+        recognizer.accept_waveform(&stream, 16000, &SILENCE),
+        Err(StreamingError::InvalidState)
+    ));
+    // Reset reopens the stream, like `OnlineTransducerRecognizer::reset`
+    recognizer.reset(&mut stream);
+    recognizer
+        .accept_waveform(&stream, 16000, &SILENCE)
+        .unwrap();
+    assert_eq!(stream.tag(), Some("session"));
+    assert_eq!(stream.stats().samples, 2 * SILENCE.len() as u64);
+    recognizer.destroy_stream(&stream);
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn transducer_online_accepts_audio_again_after_reset() {
+    let mut recognizer = OnlineTransducerRecognizer::new(OnlineTransducerConfig {
+        encoder: required_env("SHERPA_RS_TEST_ENCODER"),
+        decoder: required_env("SHERPA_RS_TEST_DECODER"),
+        joiner: required_env("SHERPA_RS_TEST_JOINER"),
+        tokens: required_env("SHERPA_RS_TEST_TOKENS"),
+        ..Default::default()
+    })
+    .unwrap();
+
+    recognizer.input_finished();
+    assert!(matches!(
+        recognizer.accept_waveform(16000, &SILENCE),
+        Err(StreamingError::InvalidState)
+    ));
+    recognizer.reset();
+    recognizer.accept_waveform(16000, &SILENCE).unwrap();
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn online_recognizer_decodes_extra_streams_alongside_its_own() {
+    let mut recognizer = OnlineRecognizer::new(OnlineRecognizerConfig {
+        model: OnlineModel::Transducer {
+            encoder: required_env("SHERPA_RS_TEST_ENCODER"),
+            decoder: required_env("SHERPA_RS_TEST_DECODER"),
+            joiner: required_env("SHERPA_RS_TEST_JOINER"),
+        },
+        tokens: required_env("SHERPA_RS_TEST_TOKENS"),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut first = recognizer.create_stream().unwrap();
+    first.set_tag("first");
+    let second = recognizer.create_stream().unwrap();
+
+    first.accept_waveform(16000, &SILENCE).unwrap();
+    second.accept_waveform(16000, &SILENCE).unwrap();
+    recognizer.accept_waveform(16000, &SILENCE).unwrap();
+    while recognizer.is_stream_ready(&first) || recognizer.is_stream_ready(&second) {
+        recognizer.decode_streams(&[&first, &second]);
+    }
+    assert_eq!(
+        recognizer.stream_result_full(&first).tag.as_deref(),
+        Some("first")
+    );
+    assert_eq!(recognizer.get_result_full().tag, None);
+
+    first.input_finished();
+    assert!(matches!(
+        first.accept_waveform(16000, &SILENCE),
+        Err(StreamingError::InvalidState)
+    ));
+    recognizer.destroy_stream(first);
+    recognizer.destroy_stream(second);
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "opus")]
+
+use sherpa_rs::opus_input::{decode_opus_bytes, OggOpusReader, WebmOpusReader};
+
+/// 20 ms CELT frame without payload, which decodes to silence
+const SILENT_PACKET: [u8; 1] = [0xf8];
+
+fn opus_head(pre_skip: u16) -> Vec<u8> {
+    let mut head = b"OpusHead".to_vec();
+    head.extend([1, 1]);
+    head.extend(pre_skip.to_le_bytes());
+    head.extend(48000u32.to_le_bytes());
+    head.extend([0, 0, 0]);
+    head
+}
+
+fn ogg_page(packets: &[&[u8]], sequence: u32) -> Vec<u8> {
+    let mut lacing = Vec::new();
+    for packet in packets {
+        lacing.extend(std::iter::repeat_n(255, packet.len() / 255));
+        lacing.push((packet.len() % 255) as u8);
+    }
+    let mut page = b"OggS".to_vec();
+    page.extend([0, 0]);
+    page.extend(0u64.to_le_bytes());
+    page.extend(7u32.to_le_bytes());
+    page.extend(sequence.to_le_bytes());
+    page.extend([0; 4]);
+    page.push(lacing.len() as u8);
+    page.extend(lacing);
+    for packet in packets {
+        page.extend(*packet);
+    }
+    page
+}
+
+fn ogg_file(pre_skip: u16) -> Vec<u8> {
+    let mut file = ogg_page(&[&opus_head(pre_skip)], 0);
+    file.extend(ogg_page(&[b"OpusTags\0\0\0\0\0\0\0\0"], 1));
+    file.extend(ogg_page(
+        &[&SILENT_PACKET, &SILENT_PACKET, &SILENT_PACKET],
+        2,
+    ));
+    file
+}
+
+fn element(id: &[u8], payload: &[u8]) -> Vec<u8> {
+    let mut out = id.to_vec();
+    out.push(0x80 | payload.len() as u8);
+    out.extend(payload);
+    out
+}
+
+fn webm_file() -> Vec<u8> {
+    let mut entry = element(&[0xd7], &[1]);
+    entry.extend(element(&[0x86], b"A_OPUS"));
+    entry.extend(element(&[0x63, 0xa2], &opus_head(0)));
+    let tracks = element(&[0x16, 0x54, 0xae, 0x6b], &element(&[0xae], &entry));
+
+    let mut file = element(&[0x1a, 0x45, 0xdf, 0xa3], &[0x42, 0x86, 0x81, 0x01]);
+    // Segment and cluster of unknown size, as written by MediaRecorder
+    file.extend([
+        0x18, 0x53, 0x80, 0x67, 0x01, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    ]);
+    file.extend(tracks);
+    file.extend([0x1f, 0x43, 0xb6, 0x75, 0xff]);
+    file.extend(element(&[0xe7], &[0]));
+    for _ in 0..2 {
+        let mut block = vec![0x81, 0, 0, 0x80];
+        block.extend(SILENT_PACKET);
+        file.extend(element(&[0xa3], &block));
+    }
+    file
+}
+
+#[test]
+fn decodes_ogg_opus() {
+    assert_eq!(
+        decode_opus_bytes(&ogg_file(0), 16000).unwrap().len(),
+        3 * 320
+    );
+    // 10 ms of pre-skip at 48 kHz is dropped from the start
+    assert_eq!(
+        decode_opus_bytes(&ogg_file(480), 16000).unwrap().len(),
+        3 * 320 - 160
+    );
+}
+
+#[test]
+fn decodes_ogg_opus_incrementally() {
+    let mut reader = OggOpusReader::new(16000);
+    let mut total = 0;
+    for byte in ogg_file(0) {
+        total += reader.push(&[byte]).unwrap().len();
+    }
+    assert_eq!(total, 3 * 320);
+}
+
+#[test]
+fn decodes_webm_opus() {
+    assert_eq!(
+        decode_opus_bytes(&webm_file(), 48000).unwrap().len(),
+        2 * 960
+    );
+
+    let mut reader = WebmOpusReader::new(16000);
+    let mut total = 0;
+    for chunk in webm_file().chunks(5) {
+        total += reader.push(chunk).unwrap().len();
+    }
+    assert_eq!(total, 2 * 320);
+}
+
+#[test]
+fn rejects_other_containers() {
+    assert!(decode_opus_bytes(b"RIFF....WAVE", 16000).is_err());
+    assert!(OggOpusReader::new(11025).push(&ogg_file(0)).is_err());
+}
+
+#[test]
+fn skips_bad_packets_and_blocks() {
+    let mut file = ogg_file(0);
+    file.extend(ogg_page(&[&[], &SILENT_PACKET], 3));
+    let mut reader = OggOpusReader::new(16000);
+    assert!(reader.push(&file).is_err());
+    // The packet after the empty one is still decoded
+    assert_eq!(reader.push(&[]).unwrap().len(), 320);
+
+    let mut file = webm_file();
+    // Laced block
+    file.extend(element(&[0xa3], &[0x81, 0, 0, 0x82, 0xf8]));
+    let mut reader = WebmOpusReader::new(16000);
+    assert!(reader.push(&file).is_err());
+    let mut block = vec![0x81, 0, 0, 0x80];
+    block.extend(SILENT_PACKET);
+    assert_eq!(reader.push(&element(&[0xa3], &block)).unwrap().len(), 320);
+}
+
+#[test]
+fn stops_on_broken_framing() {
+    let mut reader = OggOpusReader::new(16000);
+    assert!(reader.push(&[b'x'; 40]).is_err());
+    assert!(reader.push(&ogg_file(0)).is_err());
+
+    // A zero byte can't start an element id
+    let mut file = webm_file();
+    file.push(0);
+    let mut reader = WebmOpusReader::new(16000);
+    assert!(reader.push(&file).is_err());
+    assert!(reader.push(&webm_file()).is_err());
+}
@@ -0,0 +1,112 @@
+use sherpa_rs::audio_tag::{AudioTag, AudioTagConfig};
+use sherpa_rs::language_id::{SpokenLanguageId, SpokenLanguageIdConfig};
+use sherpa_rs::nemo_ctc::{NemoCtcConfig, NemoCtcRecognizer};
+use sherpa_rs::online::{OnlineModel, OnlineRecognizer, OnlineRecognizerConfig};
+use sherpa_rs::utils::{native_chunks, native_len, MAX_NATIVE_SAMPLES};
+use sherpa_rs::TooManySamples;
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+#[test]
+fn native_len_rejects_lengths_past_i32() {
+    assert_eq!(native_len(0), Ok(0));
+    assert_eq!(native_len(MAX_NATIVE_SAMPLES), Ok(i32::MAX));
+    assert_eq!(
+        native_len(MAX_NATIVE_SAMPLES + 1),
+        Err(TooManySamples {
+            len: MAX_NATIVE_SAMPLES + 1,
+            max: MAX_NATIVE_SAMPLES,
+        })
+    );
+    let err = native_len(usize::MAX).unwrap_err();
+    assert_eq!(err.len, usize::MAX);
+}
+
+#[test]
+fn short_buffers_are_one_chunk() {
+    let samples = vec![0.0; 16000];
+    let chunks: Vec<_> = native_chunks(&samples).collect();
+    assert_eq!(chunks.len(), 1);
+    assert_eq!(chunks[0].len(), 16000);
+}
+
+#[test]
+#[ignore = "allocates over 8 GiB of samples"]
+fn multi_gigabyte_buffers_split_at_the_limit() {
+    let samples = vec![0.0f32; MAX_NATIVE_SAMPLES + 10];
+    let lens: Vec<_> = native_chunks(&samples).map(<[f32]>::len).collect();
+    assert_eq!(lens, [MAX_NATIVE_SAMPLES, 10]);
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries, model files and over 8 GiB of memory"]
+fn online_stream_takes_multi_gigabyte_buffers() {
+    let mut recognizer = OnlineRecognizer::new(OnlineRecognizerConfig {
+        model: OnlineModel::Transducer {
+            encoder: required_env("SHERPA_RS_TEST_ENCODER"),
+            decoder: required_env("SHERPA_RS_TEST_DECODER"),
+            joiner: required_env("SHERPA_RS_TEST_JOINER"),
+        },
+        tokens: required_env("SHERPA_RS_TEST_TOKENS"),
+        ..Default::default()
+    })
+    .unwrap();
+    let samples = vec![0.0f32; MAX_NATIVE_SAMPLES + 16000];
+    recognizer.accept_waveform(16000, &samples).unwrap();
+    recognizer.input_finished();
+    recognizer.decode();
+    assert!(recognizer.get_result().trim().is_empty());
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries, model files and over 8 GiB of memory"]
+fn offline_stream_rejects_multi_gigabyte_buffers() {
+    let mut recognizer = NemoCtcRecognizer::new(NemoCtcConfig {
+        model: required_env("SHERPA_RS_TEST_NEMO_CTC_MODEL"),
+        tokens: required_env("SHERPA_RS_TEST_NEMO_CTC_TOKENS"),
+        ..Default::default()
+    })
+    .unwrap();
+    let samples = vec![0.0f32; MAX_NATIVE_SAMPLES + 1];
+    let mut stream = recognizer.create_stream().unwrap();
+    assert_eq!(
+        stream.accept_waveform(16000, &samples),
+        Err(TooManySamples {
+            len: MAX_NATIVE_SAMPLES + 1,
+            max: MAX_NATIVE_SAMPLES,
+        }
+        .into())
+    );
+    drop(stream);
+    // The infallible helper logs the error and gives an empty result
+    assert!(recognizer.transcribe(16000, &samples).text.is_empty());
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries, model files and over 8 GiB of memory"]
+fn language_id_rejects_multi_gigabyte_buffers() {
+    let mut slid = SpokenLanguageId::new(SpokenLanguageIdConfig {
+        encoder: required_env("SHERPA_RS_TEST_LID_ENCODER"),
+        decoder: required_env("SHERPA_RS_TEST_LID_DECODER"),
+        ..Default::default()
+    });
+    let error = slid
+        .compute(vec![0.0f32; MAX_NATIVE_SAMPLES + 1], 16000)
+        .unwrap_err();
+    assert!(error.downcast_ref::<TooManySamples>().is_some());
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries, model files and over 8 GiB of memory"]
+fn audio_tag_skips_multi_gigabyte_buffers() {
+    let mut tagger = AudioTag::new(AudioTagConfig {
+        model: required_env("SHERPA_RS_TEST_TAG_MODEL"),
+        labels: required_env("SHERPA_RS_TEST_TAG_LABELS"),
+        ..Default::default()
+    })
+    .unwrap();
+    let samples = vec![0.0f32; MAX_NATIVE_SAMPLES + 1];
+    assert!(tagger.compute_with_probs(&samples, 16000).is_empty());
+}
@@ -0,0 +1,154 @@
+use sherpa_rs::energy_vad::{EnergyVad, EnergyVadConfig};
+use sherpa_rs::levels::LevelCheck;
+use sherpa_rs::pipeline::Pipeline;
+use sherpa_rs::preprocess::{HighPass, HighPassConfig};
+use sherpa_rs::{OfflineRecognizerResult, Transcriber};
+use std::sync::{Arc, Mutex};
+
+/// 200 Hz sine at 16 kHz
+fn tone(amplitude: f32, len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| amplitude * (i as f32 * 200.0 * std::f32::consts::TAU / 16000.0).sin())
+        .collect()
+}
+
+/// Transcribes audio as its length in samples, as one token at its start
+#[derive(Default)]
+struct Counter {
+    calls: Vec<usize>,
+}
+
+impl Transcriber for Counter {
+    fn transcribe(&mut self, _sample_rate: u32, samples: &[f32]) -> OfflineRecognizerResult {
+        self.calls.push(samples.len());
+        OfflineRecognizerResult {
+            text: samples.len().to_string(),
+            tokens: vec![samples.len().to_string()],
+            timestamps: vec![0.0],
+            ..Default::default()
+        }
+    }
+}
+
+/// 1 s silence, 1.5 s tone, 1 s silence at 16 kHz
+fn utterance() -> Vec<f32> {
+    let mut samples = vec![0.0; 16000];
+    samples.extend(tone(0.3, 24000));
+    samples.extend(vec![0.0; 16000]);
+    samples
+}
+
+#[test]
+fn without_vad_recognizes_everything_on_flush() {
+    let mut pipeline = Pipeline::builder(Counter::default()).build().unwrap();
+    assert!(pipeline.push(&tone(0.3, 8000)).unwrap().is_empty());
+    assert!(pipeline.push(&tone(0.3, 8000)).unwrap().is_empty());
+
+    let segments = pipeline.flush().unwrap();
+    assert_eq!(segments.len(), 1);
+    assert_eq!((segments[0].start, segments[0].duration), (0.0, 1.0));
+    assert_eq!(segments[0].text, "16000");
+
+    // Timestamps keep counting after a flush
+    let segments = pipeline.process(&tone(0.3, 8000)).unwrap();
+    assert_eq!((segments[0].start, segments[0].duration), (1.0, 0.5));
+}
+
+#[test]
+fn vad_segments_carry_their_position() {
+    let vad = EnergyVad::new(EnergyVadConfig::default()).unwrap();
+    let mut pipeline = Pipeline::builder(Counter::default())
+        .vad(vad)
+        .build()
+        .unwrap();
+    let mut segments = Vec::new();
+    for chunk in utterance().chunks(1600) {
+        segments.extend(pipeline.push(chunk).unwrap());
+    }
+    segments.extend(pipeline.flush().unwrap());
+
+    assert_eq!(segments.len(), 1, "{segments:?}");
+    assert!((0.7..1.0).contains(&segments[0].start), "{segments:?}");
+    let samples: usize = segments[0].text.parse().unwrap();
+    assert!(samples >= 24000, "{samples}");
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries"]
+fn resamples_input_to_the_model_rate() {
+    let mut pipeline = Pipeline::builder(Counter::default())
+        .input_sample_rate(8000)
+        .build()
+        .unwrap();
+    let segments = pipeline.process(&vec![0.1; 8000]).unwrap();
+    let samples: f32 = segments[0].text.parse().unwrap();
+    assert!((samples - 16000.0).abs() <= 32.0, "{samples}");
+    assert!((segments[0].duration - 1.0).abs() < 0.01);
+}
+
+#[test]
+fn post_processors_run_in_order() {
+    let mut pipeline = Pipeline::builder(Counter::default())
+        .post_processor(|text| format!("{text} samples"))
+        .result_post_processor(|result| result.text = result.text.to_uppercase())
+        .post_processor(|text| text.replace(' ', "_"))
+        .build()
+        .unwrap();
+    let segments = pipeline.process(&[0.1; 160]).unwrap();
+    assert_eq!(segments[0].text, "160_SAMPLES");
+    assert_eq!(segments[0].result.text, segments[0].text);
+}
+
+#[test]
+fn level_check_rejects_silence() {
+    let mut pipeline = Pipeline::builder(Counter::default())
+        .level_check(LevelCheck::Error)
+        .build()
+        .unwrap();
+    assert!(pipeline.process(&[0.0; 1600]).is_err());
+    assert!(pipeline.process(&tone(0.3, 1600)).is_ok());
+}
+
+#[test]
+fn level_check_keeps_checking_after_pushes() {
+    let mut pipeline = Pipeline::builder(Counter::default())
+        .level_check(LevelCheck::Error)
+        .build()
+        .unwrap();
+    assert!(pipeline.push(&tone(0.3, 1600)).is_ok());
+    assert!(pipeline.push(&tone(3.0, 1600)).is_err());
+    assert!(pipeline.push(&tone(3.0, 1600)).is_err());
+    assert!(pipeline.process(&[0.0; 1600]).is_err());
+}
+
+#[test]
+fn high_pass_runs_before_other_processors() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = seen.clone();
+    let mut pipeline = Pipeline::builder(Counter::default())
+        .processor(move |samples: &mut Vec<f32>| sink.lock().unwrap().extend_from_slice(samples))
+        .high_pass(HighPass::new(HighPassConfig::default()).unwrap())
+        .build()
+        .unwrap();
+    pipeline.process(&[0.5; 16000]).unwrap();
+
+    // The DC offset is gone by the time the processor sees the audio
+    let seen = seen.lock().unwrap();
+    let tail = &seen[8000..];
+    let mean = tail.iter().sum::<f32>() / tail.len() as f32;
+    assert!(mean.abs() < 0.01, "{mean}");
+}
+
+#[test]
+fn processors_keep_timestamps_in_sync() {
+    let mut pipeline = Pipeline::builder(Counter::default())
+        .processor(|samples: &mut Vec<f32>| samples.truncate(samples.len() / 2))
+        .build()
+        .unwrap();
+    pipeline.push(&tone(0.3, 16000)).unwrap();
+    let segments = pipeline.flush().unwrap();
+    assert_eq!((segments[0].start, segments[0].duration), (0.0, 1.0));
+
+    let segments = pipeline.process(&tone(0.3, 8000)).unwrap();
+    assert_eq!((segments[0].start, segments[0].duration), (1.0, 0.5));
+}
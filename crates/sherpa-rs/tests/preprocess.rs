@@ -0,0 +1,125 @@
+use sherpa_rs::levels::LevelReport;
+use sherpa_rs::preprocess::{
+    Agc, AgcConfig, AudioProcessors, HighPass, HighPassConfig, Preprocessed,
+};
+use sherpa_rs::{AudioProcessor, OfflineRecognizerResult, Transcriber};
+
+/// 200 Hz sine at 16 kHz
+fn tone(amplitude: f32, len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| amplitude * (i as f32 * 200.0 * std::f32::consts::TAU / 16000.0).sin())
+        .collect()
+}
+
+/// Transcribes audio as its RMS level
+struct Level;
+
+impl Transcriber for Level {
+    fn transcribe(&mut self, _sample_rate: u32, samples: &[f32]) -> OfflineRecognizerResult {
+        OfflineRecognizerResult {
+            text: LevelReport::measure(samples).rms.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[test]
+fn agc_brings_quiet_audio_to_target() {
+    let mut agc = Agc::new(AgcConfig::default()).unwrap();
+    // Quiet far-field speech stand-in, RMS about 0.014
+    let mut samples = tone(0.02, 48000);
+    agc.process(&mut samples);
+    let settled = LevelReport::measure(&samples[32000..]);
+    assert!((settled.rms - 0.1).abs() < 0.01, "{settled:?}");
+    assert!(settled.peak <= 1.0);
+}
+
+#[test]
+fn agc_caps_gain_and_ignores_silence() {
+    let mut agc = Agc::new(AgcConfig::default()).unwrap();
+    let mut silence = vec![0.0; 16000];
+    agc.process(&mut silence);
+    assert_eq!(agc.gain(), 1.0);
+
+    let mut faint = tone(0.002, 48000);
+    agc.process(&mut faint);
+    assert!((agc.gain() - 10.0).abs() < 0.1, "{}", agc.gain());
+
+    // Loud input right after is backed off instead of clipping
+    let mut loud = tone(0.9, 1600);
+    agc.process(&mut loud);
+    assert!(agc.gain() <= 1.0 / 0.9 + 1e-3);
+    assert!(LevelReport::measure(&loud).clipped < 0.01);
+
+    assert!(Agc::new(AgcConfig {
+        adaptation: 0.0,
+        ..Default::default()
+    })
+    .is_err());
+}
+
+#[test]
+fn preprocessed_recognizer_sees_processed_audio() {
+    let agc = Agc::new(AgcConfig {
+        adaptation: 1.0,
+        ..Default::default()
+    })
+    .unwrap();
+    let mut recognizer = Preprocessed::new(Level).agc(agc);
+    let rms: f32 = recognizer
+        .transcribe(16000, &tone(0.02, 16000))
+        .text
+        .parse()
+        .unwrap();
+    assert!((rms - 0.1).abs() < 0.01, "{rms}");
+}
+
+#[test]
+fn high_pass_removes_dc_and_rumble_but_keeps_speech_band() {
+    let mut high_pass = HighPass::new(HighPassConfig::default()).unwrap();
+    let mut offset = vec![0.2; 16000];
+    high_pass.process(&mut offset);
+    assert!(offset[8000..].iter().all(|s| s.abs() < 1e-4));
+
+    let hum = |freq: f32| -> Vec<f32> {
+        (0..16000)
+            .map(|i| 0.3 * (i as f32 * freq * std::f32::consts::TAU / 16000.0).sin())
+            .collect()
+    };
+    let mut low = hum(20.0);
+    high_pass.reset();
+    high_pass.process(&mut low);
+    assert!(LevelReport::measure(&low[8000..]).rms < 0.3 / 2f32.sqrt() * 0.1);
+
+    let mut voice = hum(1000.0);
+    high_pass.reset();
+    high_pass.process(&mut voice);
+    let rms = LevelReport::measure(&voice[8000..]).rms;
+    assert!((rms - 0.3 / 2f32.sqrt()).abs() < 0.01, "{rms}");
+
+    assert!(HighPass::new(HighPassConfig {
+        cutoff: 9000.0,
+        ..Default::default()
+    })
+    .is_err());
+}
+
+#[test]
+fn processors_run_in_order() {
+    let mut recognizer = Preprocessed::new(Level)
+        .processor(|samples: &mut Vec<f32>| samples.iter_mut().for_each(|s| *s += 0.5))
+        .processor(|samples: &mut Vec<f32>| samples.iter_mut().for_each(|s| *s *= 2.0));
+    let rms: f32 = recognizer
+        .transcribe(16000, &[0.0; 160])
+        .text
+        .parse()
+        .unwrap();
+    assert_eq!(rms, 1.0);
+
+    // A user stage can change the length, e.g. to drop a channel
+    let mut chain = AudioProcessors::new();
+    chain.push(|samples: &mut Vec<f32>| samples.truncate(80));
+    let mut samples = vec![0.1; 160];
+    chain.process(&mut samples);
+    assert_eq!(samples.len(), 80);
+}
@@ -0,0 +1,134 @@
+use sherpa_rs::realtime::{FeedError, RealtimeSession, SessionEvent};
+use sherpa_rs::{OnlineTranscriber, StreamingError};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+/// Decodes one chunk each time the test allows it, telling the test when it's waiting
+struct Gated {
+    gate: Receiver<()>,
+    waiting: Sender<()>,
+    chunks: usize,
+}
+
+impl OnlineTranscriber for Gated {
+    fn accept_waveform(&mut self, _: u32, _: &[f32]) -> Result<(), StreamingError> {
+        let _ = self.waiting.send(());
+        let _ = self.gate.recv();
+        self.chunks += 1;
+        Ok(())
+    }
+
+    fn transcribe(&mut self) -> String {
+        format!("{} chunks", self.chunks)
+    }
+
+    fn is_endpoint(&self) -> bool {
+        false
+    }
+
+    fn reset(&mut self) {}
+
+    fn input_finished(&mut self) {}
+}
+
+/// Reports the samples of the current utterance. The stream breaks on the third chunk
+/// and stays broken until it's recreated.
+#[derive(Default)]
+struct Flaky {
+    chunks: usize,
+    samples: usize,
+    broken: bool,
+    finished: bool,
+}
+
+impl OnlineTranscriber for Flaky {
+    fn accept_waveform(&mut self, _: u32, samples: &[f32]) -> Result<(), StreamingError> {
+        self.chunks += 1;
+        if self.broken || self.chunks == 3 {
+            self.broken = true;
+            return Err(StreamingError::DecodingFailed(-1));
+        }
+        self.samples += samples.len();
+        Ok(())
+    }
+
+    fn transcribe(&mut self) -> String {
+        format!("{} samples", self.samples)
+    }
+
+    fn is_endpoint(&self) -> bool {
+        false
+    }
+
+    fn reset(&mut self) {
+        self.samples = 0;
+        if self.finished {
+            self.broken = false;
+            self.finished = false;
+        }
+    }
+
+    fn input_finished(&mut self) {
+        self.finished = true;
+    }
+}
+
+#[test]
+fn reports_backlog_and_rejects_when_full() {
+    let (gate, gate_rx) = mpsc::channel();
+    let (waiting, waiting_rx) = mpsc::channel();
+    let recognizer = Gated {
+        gate: gate_rx,
+        waiting,
+        chunks: 0,
+    };
+    let (session, feeder) = RealtimeSession::bounded(recognizer, 16000, 2);
+
+    // One chunk blocks in the decoder and two fill the queue
+    feeder.try_send_audio(vec![0.0; 1600]).unwrap();
+    waiting_rx.recv().unwrap();
+    feeder.try_send_audio(vec![0.0; 1600]).unwrap();
+    let backlog = feeder.try_send_audio(vec![0.0; 1600]).unwrap();
+    assert_eq!(backlog.chunks, 3);
+    assert_eq!(backlog.samples, 4800);
+    assert!((backlog.lag - 0.3).abs() < 1e-6);
+
+    match feeder.try_send_audio(vec![0.0; 800]) {
+        Err(FeedError::Full { chunk, backlog }) => {
+            assert_eq!(chunk.len(), 800);
+            assert_eq!(backlog.chunks, 3);
+        }
+        other => panic!("expected a full queue, got {other:?}"),
+    }
+
+    for _ in 0..3 {
+        gate.send(()).unwrap();
+    }
+    let event = session.events().recv_timeout(Duration::from_secs(5));
+    assert_eq!(event, Ok(SessionEvent::Partial("1 chunks".into())));
+    drop(feeder);
+    drop(gate);
+    session.stop();
+}
+
+#[test]
+fn failed_chunks_leave_the_backlog() {
+    let (session, feeder) = RealtimeSession::bounded(Flaky::default(), 16000, 4);
+    for _ in 0..2 {
+        feeder.try_send_audio(vec![0.0; 1600]).unwrap();
+        assert!(matches!(
+            session.events().recv_timeout(Duration::from_secs(5)),
+            Ok(SessionEvent::Partial(_))
+        ));
+    }
+    // The third chunk fails and ends the session
+    feeder.try_send_audio(vec![0.0; 1600]).unwrap();
+    while session
+        .events()
+        .recv_timeout(Duration::from_secs(5))
+        .is_ok()
+    {}
+    assert_eq!(feeder.backlog().chunks, 0);
+    assert_eq!(feeder.backlog().samples, 0);
+    session.stop();
+}
@@ -0,0 +1,182 @@
+use sherpa_rs::realtime::{RealtimeSession, RecoveryPolicy, SessionEvent};
+use sherpa_rs::{OnlineTranscriber, StreamingError};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Reports the samples of the current utterance. The stream breaks on the third chunk
+/// and stays broken until it's recreated.
+#[derive(Default)]
+struct Flaky {
+    chunks: usize,
+    samples: usize,
+    broken: bool,
+    finished: bool,
+}
+
+impl OnlineTranscriber for Flaky {
+    fn accept_waveform(&mut self, _: u32, samples: &[f32]) -> Result<(), StreamingError> {
+        self.chunks += 1;
+        if self.broken || self.chunks == 3 {
+            self.broken = true;
+            return Err(StreamingError::DecodingFailed(-1));
+        }
+        self.samples += samples.len();
+        Ok(())
+    }
+
+    fn transcribe(&mut self) -> String {
+        format!("{} samples", self.samples)
+    }
+
+    fn is_endpoint(&self) -> bool {
+        false
+    }
+
+    fn reset(&mut self) {
+        self.samples = 0;
+        if self.finished {
+            self.broken = false;
+            self.finished = false;
+        }
+    }
+
+    fn input_finished(&mut self) {
+        self.finished = true;
+    }
+}
+
+fn next(session: &RealtimeSession<Flaky>) -> SessionEvent {
+    session
+        .events()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap()
+}
+
+#[test]
+fn replays_the_utterance_after_a_failure() {
+    let (audio_tx, audio) = mpsc::channel();
+    let session = RealtimeSession::start_with_recovery(
+        Flaky::default(),
+        16000,
+        audio,
+        RecoveryPolicy::Replay { max_attempts: 1 },
+    );
+    for _ in 0..3 {
+        audio_tx.send(vec![0.0; 100]).unwrap();
+    }
+
+    assert_eq!(next(&session), SessionEvent::Partial("100 samples".into()));
+    assert_eq!(next(&session), SessionEvent::Partial("200 samples".into()));
+    assert_eq!(
+        next(&session),
+        SessionEvent::Recovered {
+            error: "Decoding failed with code: -1".into()
+        }
+    );
+    assert_eq!(next(&session), SessionEvent::Partial("300 samples".into()));
+
+    audio_tx.send(vec![0.0; 100]).unwrap();
+    assert_eq!(next(&session), SessionEvent::Partial("400 samples".into()));
+    drop(audio_tx);
+    assert_eq!(next(&session), SessionEvent::Final("400 samples".into()));
+}
+
+#[test]
+fn stops_by_default() {
+    let (audio_tx, audio) = mpsc::channel();
+    let session = RealtimeSession::start(Flaky::default(), 16000, audio);
+    for _ in 0..3 {
+        audio_tx.send(vec![0.0; 100]).unwrap();
+    }
+
+    assert_eq!(next(&session), SessionEvent::Partial("100 samples".into()));
+    assert_eq!(next(&session), SessionEvent::Partial("200 samples".into()));
+    assert_eq!(next(&session), SessionEvent::Final("200 samples".into()));
+    // The session ended, so the audio channel is closed
+    assert!(session
+        .events()
+        .recv_timeout(Duration::from_secs(5))
+        .is_err());
+    assert!(audio_tx.send(vec![0.0; 100]).is_err());
+}
+
+/// Panics when the session finishes
+struct Panicky;
+
+impl OnlineTranscriber for Panicky {
+    fn accept_waveform(&mut self, _: u32, _: &[f32]) -> Result<(), StreamingError> {
+        Ok(())
+    }
+
+    fn transcribe(&mut self) -> String {
+        String::new()
+    }
+
+    fn is_endpoint(&self) -> bool {
+        false
+    }
+
+    fn reset(&mut self) {}
+
+    fn input_finished(&mut self) {
+        panic!("input_finished failed");
+    }
+}
+
+#[test]
+fn dropping_a_session_does_not_resume_worker_panics() {
+    let (_audio_tx, audio) = mpsc::channel();
+    drop(RealtimeSession::start(Panicky, 16000, audio));
+
+    let (_audio_tx, audio) = mpsc::channel();
+    let session = RealtimeSession::start(Panicky, 16000, audio);
+    let stopped = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| session.stop()));
+    assert!(stopped.is_err());
+}
+
+/// Panics while decoding its second chunk
+#[derive(Default)]
+struct PanicsWhileDecoding {
+    chunks: usize,
+}
+
+impl OnlineTranscriber for PanicsWhileDecoding {
+    fn accept_waveform(&mut self, _: u32, _: &[f32]) -> Result<(), StreamingError> {
+        self.chunks += 1;
+        if self.chunks == 2 {
+            panic!("decoder crashed");
+        }
+        Ok(())
+    }
+
+    fn transcribe(&mut self) -> String {
+        format!("{} chunks", self.chunks)
+    }
+
+    fn is_endpoint(&self) -> bool {
+        false
+    }
+
+    fn reset(&mut self) {}
+
+    fn input_finished(&mut self) {}
+}
+
+#[test]
+fn panics_are_not_replayed() {
+    let (audio_tx, audio) = mpsc::channel();
+    let session = RealtimeSession::start_with_recovery(
+        PanicsWhileDecoding::default(),
+        16000,
+        audio,
+        RecoveryPolicy::Replay { max_attempts: 3 },
+    );
+    audio_tx.send(vec![0.0; 100]).unwrap();
+    audio_tx.send(vec![0.0; 100]).unwrap();
+
+    // The worker unwound, closing the event channel
+    let events: Vec<_> = session.events().iter().collect();
+    assert_eq!(events, [SessionEvent::Partial("1 chunks".into())]);
+    let stopped = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| session.stop()));
+    assert!(stopped.is_err());
+}
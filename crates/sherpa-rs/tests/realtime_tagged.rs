@@ -0,0 +1,88 @@
+use sherpa_rs::realtime::{RealtimeSession, SessionEvent};
+use sherpa_rs::{OnlineTranscriber, StreamingError};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Hears the number of chunks it was fed, ending an utterance every two
+struct ChunkCounter {
+    name: &'static str,
+    chunks: usize,
+}
+
+impl ChunkCounter {
+    fn new(name: &'static str) -> Self {
+        Self { name, chunks: 0 }
+    }
+}
+
+impl OnlineTranscriber for ChunkCounter {
+    fn accept_waveform(&mut self, _: u32, _: &[f32]) -> Result<(), StreamingError> {
+        self.chunks += 1;
+        Ok(())
+    }
+
+    fn transcribe(&mut self) -> String {
+        match self.chunks {
+            0 => String::new(),
+            n => format!("{} {}", self.name, n),
+        }
+    }
+
+    fn is_endpoint(&self) -> bool {
+        self.chunks == 2
+    }
+
+    fn reset(&mut self) {
+        self.chunks = 0;
+    }
+
+    fn input_finished(&mut self) {}
+}
+
+#[test]
+fn multiplexes_tagged_events() {
+    let (events_tx, events) = mpsc::channel();
+    let mut sessions = Vec::new();
+    for (id, name) in [(1u32, "a"), (2, "b")] {
+        let (audio_tx, audio) = mpsc::channel();
+        let recognizer = ChunkCounter::new(name);
+        let session =
+            RealtimeSession::start_tagged(recognizer, 16000, audio, id, events_tx.clone());
+        audio_tx.send(vec![0.0; 160]).unwrap();
+        audio_tx.send(vec![0.0; 160]).unwrap();
+        sessions.push(session);
+    }
+    drop(events_tx);
+
+    let mut received: Vec<(u32, SessionEvent)> = Vec::new();
+    while received.len() < 4 {
+        received.push(events.recv_timeout(Duration::from_secs(5)).unwrap());
+    }
+    received.sort_by_key(|(id, _)| *id);
+    assert_eq!(
+        received,
+        vec![
+            (1, SessionEvent::Partial("a 1".into())),
+            (1, SessionEvent::Final("a 2".into())),
+            (2, SessionEvent::Partial("b 1".into())),
+            (2, SessionEvent::Final("b 2".into())),
+        ]
+    );
+    assert!(sessions[0].events().try_recv().is_err());
+}
+
+#[test]
+fn shared_sessions_report_their_id() {
+    let (events_tx, events) = mpsc::channel();
+    let (audio_tx, audio) = mpsc::channel();
+    let recognizer = ChunkCounter::new("a");
+    let session = RealtimeSession::start_shared(recognizer, 16000, audio, events_tx);
+    audio_tx.send(vec![0.0; 160]).unwrap();
+    let (id, event) = events.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(id, session.id());
+    assert_eq!(event, SessionEvent::Partial("a 1".into()));
+
+    let (_other_tx, other_audio) = mpsc::channel();
+    let other = RealtimeSession::start(ChunkCounter::new("b"), 16000, other_audio);
+    assert_ne!(other.id(), session.id());
+}
@@ -0,0 +1,44 @@
+use sherpa_rs::recorder::UtteranceRecorder;
+
+fn path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("sherpa-rs-{}-{}.wav", name, std::process::id()))
+}
+
+#[test]
+fn appends_chunks_to_the_file() {
+    let path = path("recorder-append");
+    let mut recorder = UtteranceRecorder::new(&path, 100, 1.0).unwrap();
+    recorder.push(&[0.1; 30]).unwrap();
+    recorder.push(&[0.2; 30]).unwrap();
+
+    // Readable while the recorder is still writing
+    let (samples, sample_rate) = UtteranceRecorder::load(&path).unwrap();
+    assert_eq!(sample_rate, 100);
+    assert_eq!(samples.len(), 60);
+    assert_eq!((samples[0], samples[59]), (0.1, 0.2));
+    assert_eq!(recorder.samples(), samples);
+
+    recorder.clear().unwrap();
+    assert!(UtteranceRecorder::load(&path).unwrap().0.is_empty());
+    drop(recorder);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn keeps_the_newest_audio_within_the_limit() {
+    let path = path("recorder-limit");
+    let mut recorder = UtteranceRecorder::new(&path, 100, 1.0).unwrap();
+    let samples: Vec<f32> = (0..1000).map(|i| i as f32 / 1000.0).collect();
+    for chunk in samples.chunks(30) {
+        recorder.push(chunk).unwrap();
+        let (on_disk, _) = UtteranceRecorder::load(&path).unwrap();
+        assert!(on_disk.len() <= 200, "{}", on_disk.len());
+        // The file always ends with the newest audio
+        assert!(on_disk.ends_with(&recorder.samples()));
+    }
+    assert_eq!(recorder.samples(), &samples[900..]);
+
+    assert!(UtteranceRecorder::new(&path, 100, 0.0).is_err());
+    drop(recorder);
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,58 @@
+use sherpa_rs::registry::RecognizerRegistry;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn loads_lazily_and_evicts_least_recently_used() {
+    let loads = Arc::new(AtomicUsize::new(0));
+    let registry = {
+        let loads = loads.clone();
+        RecognizerRegistry::new(2, move |key: &str| {
+            if key == "xx" {
+                eyre::bail!("no model for {key}");
+            }
+            loads.fetch_add(1, Ordering::SeqCst);
+            Ok(format!("model-{key}"))
+        })
+        .unwrap()
+    };
+
+    assert_eq!(*registry.get("en").unwrap().lock().unwrap(), "model-en");
+    let zh = registry.get("zh").unwrap();
+    registry.get("en").unwrap();
+    assert_eq!(loads.load(Ordering::SeqCst), 2);
+
+    // "zh" is the least recently used
+    registry.get("ja").unwrap();
+    assert_eq!(registry.loaded(), vec!["en", "ja"]);
+    assert!(!registry.is_loaded("zh"));
+    // Callers keep evicted recognizers alive
+    assert_eq!(*zh.lock().unwrap(), "model-zh");
+
+    // The least recently used is evicted before loading, even if the load fails
+    assert!(registry.get("xx").is_err());
+    assert_eq!(registry.loaded(), vec!["ja"]);
+
+    registry.get("en").unwrap();
+    assert!(registry.evict("en"));
+    assert!(!registry.evict("en"));
+    registry.get("zh").unwrap();
+    assert_eq!(loads.load(Ordering::SeqCst), 5);
+    assert_eq!(registry.loaded(), vec!["ja", "zh"]);
+}
+
+#[test]
+fn survives_a_panicking_loader() {
+    let registry = RecognizerRegistry::new(2, |key: &str| {
+        assert_ne!(key, "boom", "loader bug");
+        Ok(key.len())
+    })
+    .unwrap();
+    registry.get("en").unwrap();
+    let loaded = panic::catch_unwind(AssertUnwindSafe(|| registry.get("boom")));
+    assert!(loaded.is_err());
+
+    assert_eq!(*registry.get("zh").unwrap().lock().unwrap(), 2);
+    assert_eq!(registry.loaded(), vec!["en", "zh"]);
+}
@@ -0,0 +1,74 @@
+use sherpa_rs::retry::{ensure_readable, is_transient, RetryPolicy};
+use std::io::{self, ErrorKind};
+use std::time::Duration;
+
+fn fast(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        initial_backoff: Duration::ZERO,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn retries_transient_failures() {
+    let mut attempts = 0;
+    let loaded = fast(3).run(|| {
+        attempts += 1;
+        if attempts < 3 {
+            return Err(io::Error::new(ErrorKind::TimedOut, "nfs timeout").into());
+        }
+        Ok("model")
+    });
+    assert_eq!(loaded.unwrap(), "model");
+    assert_eq!(attempts, 3);
+
+    let mut attempts = 0;
+    let loaded: eyre::Result<()> = fast(2).run(|| {
+        attempts += 1;
+        Err(io::Error::from(ErrorKind::NotFound).into())
+    });
+    assert!(loaded.is_err());
+    assert_eq!(attempts, 2);
+}
+
+#[test]
+fn gives_up_on_permanent_failures() {
+    let mut attempts = 0;
+    let loaded: eyre::Result<()> = fast(5).run(|| {
+        attempts += 1;
+        eyre::bail!("Failed to create recognizer")
+    });
+    assert!(loaded.is_err());
+    assert_eq!(attempts, 1);
+}
+
+#[test]
+fn classifies_missing_files_as_transient() {
+    let dir = std::env::temp_dir().join(format!("sherpa-rs-retry-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let model = dir.join("model.onnx");
+
+    let missing = ensure_readable([&model]).unwrap_err();
+    assert!(is_transient(&missing));
+    std::fs::write(&model, b"").unwrap();
+    assert!(is_transient(&ensure_readable([&model]).unwrap_err()));
+    std::fs::write(&model, b"onnx").unwrap();
+    ensure_readable([model.as_path(), "".as_ref()]).unwrap();
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn backoff_grows_up_to_the_limit() {
+    let policy = RetryPolicy {
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(300),
+        multiplier: 2.0,
+        ..Default::default()
+    };
+    assert_eq!(policy.backoff(1), Duration::from_millis(100));
+    assert_eq!(policy.backoff(2), Duration::from_millis(200));
+    assert_eq!(policy.backoff(3), Duration::from_millis(300));
+    assert_eq!(policy.backoff(50), Duration::from_millis(300));
+}
@@ -0,0 +1,80 @@
+use sherpa_rs::rtp::{RtpCodec, RtpConfig, RtpDepacketizer, RtpPacket};
+
+fn packet(payload_type: u8, sequence: u16, timestamp: u32, ssrc: u32, payload: &[u8]) -> Vec<u8> {
+    let mut data = vec![0x80, payload_type];
+    data.extend(sequence.to_be_bytes());
+    data.extend(timestamp.to_be_bytes());
+    data.extend(ssrc.to_be_bytes());
+    data.extend(payload);
+    data
+}
+
+#[test]
+fn parses_header_extension_and_padding() {
+    let mut data = packet(0x80 | 8, 7, 160, 0xabcd, &[]);
+    data[0] |= 0x30;
+    // Extension of one word, then a 2-byte payload and 2 bytes of padding
+    data.extend([0xbe, 0xde, 0, 1, 1, 2, 3, 4, 0xd5, 0xd5, 0, 2]);
+    let packet = RtpPacket::parse(&data).unwrap();
+    assert_eq!(packet.payload_type, 8);
+    assert!(packet.marker);
+    assert_eq!(packet.sequence, 7);
+    assert_eq!(packet.timestamp, 160);
+    assert_eq!(packet.ssrc, 0xabcd);
+    assert_eq!(packet.payload, &[0xd5, 0xd5]);
+
+    assert!(RtpPacket::parse(&[0x80, 0]).is_err());
+}
+
+#[test]
+fn fills_timestamp_gaps_with_silence() {
+    let mut rtp = RtpDepacketizer::new(RtpConfig::default()).unwrap();
+    // 0x80 decodes to full scale
+    let audio = [0x80; 160];
+    assert_eq!(rtp.push(&packet(0, 1, 1000, 1, &audio)).unwrap().len(), 160);
+    // Packet 2 was lost
+    let samples = rtp.push(&packet(0, 3, 1320, 1, &audio)).unwrap();
+    assert_eq!(samples.len(), 320);
+    assert!(samples[..160].iter().all(|&s| s == 0.0));
+    assert!(samples[160..].iter().all(|&s| s > 0.9));
+
+    // Late, duplicate and DTMF packets are dropped
+    assert!(rtp.push(&packet(0, 2, 1160, 1, &audio)).unwrap().is_empty());
+    assert!(rtp.push(&packet(0, 3, 1320, 1, &audio)).unwrap().is_empty());
+    assert!(rtp
+        .push(&packet(101, 4, 1480, 1, &[1, 0, 0, 160]))
+        .unwrap()
+        .is_empty());
+    assert_eq!(rtp.push(&packet(0, 4, 1480, 1, &audio)).unwrap().len(), 160);
+}
+
+#[test]
+fn resynchronizes_on_jumps_and_new_ssrc() {
+    let mut rtp = RtpDepacketizer::new(RtpConfig::default()).unwrap();
+    let audio = [0xff; 160];
+    rtp.push(&packet(0, 1, u32::MAX - 159, 1, &audio)).unwrap();
+    // Timestamps wrap around
+    assert_eq!(rtp.push(&packet(0, 2, 0, 1, &audio)).unwrap().len(), 160);
+    // Beyond max_gap
+    assert_eq!(
+        rtp.push(&packet(0, 3, 8000 * 60, 1, &audio)).unwrap().len(),
+        160
+    );
+    // Another sender starts anywhere
+    assert_eq!(rtp.push(&packet(0, 900, 5, 2, &audio)).unwrap().len(), 160);
+}
+
+#[test]
+fn decodes_big_endian_l16() {
+    let config = RtpConfig {
+        codec: RtpCodec::L16 { sample_rate: 16000 },
+        payload_type: 96,
+        ..Default::default()
+    };
+    let mut rtp = RtpDepacketizer::new(config).unwrap();
+    assert_eq!(rtp.sample_rate(), 16000);
+    let samples = rtp
+        .push(&packet(96, 1, 0, 1, &[0x40, 0x00, 0xc0, 0x00]))
+        .unwrap();
+    assert_eq!(samples, vec![0.5, -0.5]);
+}
@@ -0,0 +1,21 @@
+use sherpa_rs::sense_voice::SenseVoiceTags;
+
+#[test]
+fn parses_tag_markers() {
+    assert_eq!(
+        SenseVoiceTags::parse("<|zh|><|NEUTRAL|><|Speech|>"),
+        SenseVoiceTags {
+            lang: "zh".into(),
+            emotion: "NEUTRAL".into(),
+            event: "Speech".into(),
+        }
+    );
+    // Markers after the event, e.g. `<|withitn|>`, are ignored
+    assert_eq!(
+        SenseVoiceTags::parse(" <|en|><|HAPPY|><|Laughter|><|withitn|>").event,
+        "Laughter"
+    );
+    let partial = SenseVoiceTags::parse("<|ja|>");
+    assert_eq!(partial.lang, "ja");
+    assert!(partial.emotion.is_empty() && partial.event.is_empty());
+}
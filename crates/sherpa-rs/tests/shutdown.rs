@@ -0,0 +1,61 @@
+use sherpa_rs::realtime::RealtimeSession;
+use sherpa_rs::shutdown::running_workers;
+use sherpa_rs::{OnlineTranscriber, StreamingError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+/// Never hears anything and records when it's dropped
+struct Silent {
+    dropped: Arc<AtomicBool>,
+}
+
+impl Silent {
+    fn new() -> Self {
+        Self {
+            dropped: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+impl OnlineTranscriber for Silent {
+    fn accept_waveform(&mut self, _: u32, _: &[f32]) -> Result<(), StreamingError> {
+        Ok(())
+    }
+
+    fn transcribe(&mut self) -> String {
+        String::new()
+    }
+
+    fn is_endpoint(&self) -> bool {
+        false
+    }
+
+    fn reset(&mut self) {}
+
+    fn input_finished(&mut self) {}
+}
+
+impl Drop for Silent {
+    fn drop(&mut self) {
+        self.dropped.store(true, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn shutdown_all_stops_workers() {
+    let recognizer = Silent::new();
+    let dropped = recognizer.dropped.clone();
+    // The sender stays alive, so only shutdown ends the session
+    let (_audio_tx, audio) = mpsc::channel();
+    let session = RealtimeSession::start(recognizer, 16000, audio);
+    let (other, _feeder) = RealtimeSession::bounded(Silent::new(), 16000, 4);
+    assert_eq!(running_workers(), 2);
+
+    assert!(sherpa_rs::shutdown_all(Duration::from_secs(5)));
+    assert_eq!(running_workers(), 0);
+    // The workers destroyed their recognizers before exiting
+    assert!(dropped.load(Ordering::SeqCst));
+    assert!(session.stop().is_none());
+    assert!(other.stop().is_none());
+}
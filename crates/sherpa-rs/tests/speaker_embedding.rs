@@ -0,0 +1,34 @@
+use sherpa_rs::speaker_id::{cosine_similarity, EmbeddingExtractor, ExtractorConfig};
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn streamed_utterance_matches_one_shot_embedding() {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_WAV")).unwrap();
+    let mut extractor = EmbeddingExtractor::new(ExtractorConfig {
+        model: required_env("SHERPA_RS_TEST_SPEAKER_MODEL"),
+        ..Default::default()
+    })
+    .unwrap();
+    let expected = extractor
+        .compute_speaker_embedding(samples.clone(), sample_rate)
+        .unwrap();
+
+    let mut stream = extractor.create_stream().unwrap();
+    for chunk in samples.chunks(1600) {
+        stream.accept_waveform(sample_rate, chunk);
+    }
+    stream.input_finished();
+    assert!(stream.is_ready());
+    let embedding = stream.compute().unwrap();
+    assert_eq!(embedding.len(), extractor.dim());
+    assert!(cosine_similarity(&embedding, &expected) > 0.99);
+
+    let empty = extractor.create_stream().unwrap();
+    assert!(!empty.is_ready());
+    assert!(empty.compute().is_err());
+}
@@ -0,0 +1,106 @@
+use sherpa_rs::online::{OnlineModel, OnlineRecognizer, OnlineRecognizerConfig};
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+const SILENCE: [f32; 1600] = [0.0; 1600];
+
+fn recognizer() -> OnlineRecognizer {
+    OnlineRecognizer::new(OnlineRecognizerConfig {
+        model: OnlineModel::Transducer {
+            encoder: required_env("SHERPA_RS_TEST_ENCODER"),
+            decoder: required_env("SHERPA_RS_TEST_DECODER"),
+            joiner: required_env("SHERPA_RS_TEST_JOINER"),
+        },
+        tokens: required_env("SHERPA_RS_TEST_TOKENS"),
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn counts_audio_and_decode_calls_per_stream() {
+    let mut recognizer = recognizer();
+    let stream = recognizer.create_stream().unwrap();
+
+    for _ in 0..10 {
+        recognizer.accept_waveform(16000, &SILENCE).unwrap();
+    }
+    stream.accept_waveform(8000, &SILENCE).unwrap();
+    while recognizer.is_ready() {
+        recognizer.decode();
+    }
+
+    let stats = recognizer.stats();
+    assert_eq!(stats.samples, 16000);
+    assert!((stats.audio_seconds - 1.0).abs() < 1e-9);
+    assert!(stats.decode_calls > 0);
+    assert!(!stats.decode_time.is_zero());
+
+    // Stats survive finishing and resetting the built-in stream
+    recognizer.input_finished();
+    recognizer.reset();
+    assert_eq!(recognizer.stats().samples, 16000);
+    recognizer.reset_stats();
+    assert_eq!(recognizer.stats().samples, 0);
+
+    let extra = stream.stats();
+    assert_eq!(extra.samples, 1600);
+    assert!((extra.audio_seconds - 0.2).abs() < 1e-9);
+    assert_eq!(extra.decode_calls, 0);
+    recognizer.destroy_stream(stream);
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn batches_split_decode_time() {
+    let mut recognizer = recognizer();
+    let streams = [
+        recognizer.create_stream().unwrap(),
+        recognizer.create_stream().unwrap(),
+    ];
+    for stream in &streams {
+        for _ in 0..10 {
+            stream.accept_waveform(16000, &SILENCE).unwrap();
+        }
+    }
+    let refs: Vec<_> = streams.iter().collect();
+    let mut batches = 0;
+    let started = std::time::Instant::now();
+    while streams
+        .iter()
+        .all(|stream| recognizer.is_stream_ready(stream))
+    {
+        recognizer.decode_streams(&refs);
+        batches += 1;
+    }
+    let wall = started.elapsed();
+
+    let [first, second] = streams.map(|stream| stream.stats());
+    assert!(batches > 0);
+    assert_eq!(first.decode_calls, batches);
+    assert_eq!(first.decode_time, second.decode_time);
+    assert!(first.decode_time + second.decode_time <= wall);
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn built_in_stream_is_shared_across_threads() {
+    let mut recognizer = recognizer();
+    recognizer.accept_waveform(16000, &SILENCE).unwrap();
+    let recognizer = &recognizer;
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..100 {
+                    recognizer.is_endpoint();
+                    recognizer.stats();
+                }
+            });
+        }
+        scope.spawn(|| recognizer.reset_stats());
+    });
+    assert_eq!(recognizer.stats().samples, 0);
+}
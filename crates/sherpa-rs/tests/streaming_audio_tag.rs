@@ -0,0 +1,59 @@
+use sherpa_rs::audio_tag::{AudioTag, AudioTagConfig, StreamingAudioTag, StreamingTagConfig};
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn tags_sliding_windows_of_a_feed() {
+    let tagger = AudioTag::new(AudioTagConfig {
+        model: required_env("SHERPA_RS_TEST_TAG_MODEL"),
+        labels: required_env("SHERPA_RS_TEST_TAG_LABELS"),
+        top_k: 3,
+        ..Default::default()
+    })
+    .unwrap();
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_TAG_WAV")).unwrap();
+    let config = StreamingTagConfig {
+        window: 1.0,
+        hop: 0.5,
+        min_prob: 0.0,
+    };
+    let mut tagger = StreamingAudioTag::new(tagger, sample_rate, config).unwrap();
+
+    let mut events = Vec::new();
+    for chunk in samples.chunks(sample_rate as usize / 10) {
+        events.extend(tagger.accept_waveform(chunk));
+    }
+    events.extend(tagger.flush());
+
+    assert!(!events.is_empty());
+    let duration = samples.len() as f32 / sample_rate as f32;
+    for event in &events {
+        assert!(event.end - event.start <= 1.0 + 1e-3);
+        assert!((event.start / 0.5 - (event.start / 0.5).round()).abs() < 1e-3);
+        assert!(event.end <= duration + 1e-3);
+    }
+    let last = events.iter().map(|e| e.end).fold(0.0, f32::max);
+    assert!((last - duration).abs() < 1e-3);
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn rejects_hops_longer_than_the_window() {
+    let tagger = AudioTag::new(AudioTagConfig {
+        model: required_env("SHERPA_RS_TEST_TAG_MODEL"),
+        labels: required_env("SHERPA_RS_TEST_TAG_LABELS"),
+        top_k: 3,
+        ..Default::default()
+    })
+    .unwrap();
+    let config = StreamingTagConfig {
+        window: 1.0,
+        hop: 2.0,
+        ..Default::default()
+    };
+    assert!(StreamingAudioTag::new(tagger, 16000, config).is_err());
+}
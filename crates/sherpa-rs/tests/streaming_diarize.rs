@@ -0,0 +1,150 @@
+use sherpa_rs::speaker_id::{EmbeddingExtractor, ExtractorConfig};
+use sherpa_rs::streaming_diarize::{
+    DiarizedTranscriber, StreamingDiarizer, StreamingDiarizerConfig,
+};
+use sherpa_rs::{OnlineTranscriber, StreamingError};
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+/// Hears the number of chunks it was fed, ending an utterance every two
+struct ChunkCounter {
+    name: &'static str,
+    chunks: usize,
+}
+
+impl ChunkCounter {
+    fn new(name: &'static str) -> Self {
+        Self { name, chunks: 0 }
+    }
+}
+
+impl OnlineTranscriber for ChunkCounter {
+    fn accept_waveform(&mut self, _: u32, _: &[f32]) -> Result<(), StreamingError> {
+        self.chunks += 1;
+        Ok(())
+    }
+
+    fn transcribe(&mut self) -> String {
+        match self.chunks {
+            0 => String::new(),
+            n => format!("{} {}", self.name, n),
+        }
+    }
+
+    fn is_endpoint(&self) -> bool {
+        self.chunks == 2
+    }
+
+    fn reset(&mut self) {
+        self.chunks = 0;
+    }
+
+    fn input_finished(&mut self) {}
+}
+
+fn extractor() -> EmbeddingExtractor {
+    EmbeddingExtractor::new(ExtractorConfig {
+        model: required_env("SHERPA_RS_TEST_SPEAKER_MODEL"),
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+fn speech() -> Vec<f32> {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_WAV")).unwrap();
+    assert_eq!(sample_rate, 16000);
+    samples
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn hop_longer_than_window_is_rejected() {
+    let config = StreamingDiarizerConfig {
+        window: 1.0,
+        hop: 2.0,
+        ..Default::default()
+    };
+    assert!(StreamingDiarizer::new(extractor(), config).is_err());
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn silence_is_left_unlabeled() {
+    let mut diarizer = StreamingDiarizer::new(extractor(), Default::default()).unwrap();
+    for chunk in vec![0.0; 16000 * 5].chunks(1600) {
+        assert!(diarizer.accept_waveform(chunk).unwrap().is_empty());
+    }
+    assert!(diarizer.turns().is_empty());
+    assert_eq!(diarizer.num_speakers(), 0);
+    assert_eq!(diarizer.speaker_for(0.0, 5.0), None);
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn speech_yields_ordered_turns() {
+    let samples = speech();
+    let duration = samples.len() as f32 / 16000.0;
+    let mut diarizer = StreamingDiarizer::new(extractor(), Default::default()).unwrap();
+    let mut returned = Vec::new();
+    for chunk in samples.chunks(1600) {
+        returned.extend(diarizer.accept_waveform(chunk).unwrap());
+    }
+
+    let turns = diarizer.turns();
+    assert!(!turns.is_empty());
+    assert_eq!(returned.last(), turns.last());
+    for pair in turns.windows(2) {
+        assert!(pair[0].end <= pair[1].start + 1e-3);
+    }
+    for turn in turns {
+        assert!(turn.start < turn.end && turn.end <= duration + 1e-3);
+        assert!((turn.speaker as usize) < diarizer.num_speakers());
+    }
+    assert!(diarizer.speaker_for(0.0, duration).is_some());
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn max_speakers_caps_new_speakers() {
+    let config = StreamingDiarizerConfig {
+        threshold: 0.99,
+        max_speakers: Some(1),
+        ..Default::default()
+    };
+    let mut diarizer = StreamingDiarizer::new(extractor(), config).unwrap();
+    for chunk in speech().chunks(1600) {
+        diarizer.accept_waveform(chunk).unwrap();
+    }
+    assert_eq!(diarizer.num_speakers(), 1);
+    assert!(diarizer.turns().iter().all(|turn| turn.speaker == 0));
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn utterances_are_tagged_with_their_speaker() {
+    let samples = speech();
+    let diarizer = StreamingDiarizer::new(extractor(), Default::default()).unwrap();
+    let mut transcriber = DiarizedTranscriber::new(ChunkCounter::new("a"), diarizer);
+
+    // The fake ends an utterance every two chunks
+    let chunk_len = samples.len() / 4;
+    let mut utterances = Vec::new();
+    for chunk in samples.chunks(chunk_len).take(4) {
+        utterances.extend(transcriber.accept_waveform(chunk).unwrap());
+    }
+    assert!(transcriber.flush().is_none());
+
+    assert_eq!(utterances.len(), 2);
+    assert_eq!(utterances[0].text, "a 2");
+    assert_eq!(utterances[0].start, 0.0);
+    assert_eq!(utterances[0].end, utterances[1].start);
+    for utterance in &utterances {
+        let speaker = transcriber
+            .diarizer()
+            .speaker_for(utterance.start, utterance.end);
+        assert_eq!(utterance.speaker, speaker);
+    }
+}
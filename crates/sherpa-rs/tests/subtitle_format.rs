@@ -0,0 +1,36 @@
+use sherpa_rs::subtitle::{to_srt, to_vtt, Cue};
+
+fn cues() -> Vec<Cue> {
+    vec![
+        Cue {
+            start: 0.5,
+            end: 2.25,
+            speaker: Some(0),
+            text: "Hello there.".into(),
+        },
+        Cue {
+            start: 3661.0,
+            end: 3662.001,
+            speaker: None,
+            text: "Bye.".into(),
+        },
+    ]
+}
+
+#[test]
+fn formats_srt_with_speaker_labels() {
+    assert_eq!(
+        to_srt(&cues()),
+        "1\n00:00:00,500 --> 00:00:02,250\n[Speaker 0] Hello there.\n\n\
+         2\n01:01:01,000 --> 01:01:02,001\nBye.\n\n"
+    );
+}
+
+#[test]
+fn formats_vtt_with_voice_tags() {
+    assert_eq!(
+        to_vtt(&cues()),
+        "WEBVTT\n\n00:00:00.500 --> 00:00:02.250\n<v Speaker 0>Hello there.\n\n\
+         01:01:01.000 --> 01:01:02.001\nBye.\n\n"
+    );
+}
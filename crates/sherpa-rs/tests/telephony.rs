@@ -0,0 +1,43 @@
+use sherpa_rs::telephony::{
+    alaw_to_i16, decode_mulaw, mulaw_to_i16, upsample_8k_to_16k, Upsampler,
+};
+
+#[test]
+fn decodes_g711() {
+    assert_eq!(mulaw_to_i16(0xff), 0);
+    assert_eq!(mulaw_to_i16(0x80), 32124);
+    assert_eq!(mulaw_to_i16(0x00), -32124);
+    assert_eq!(alaw_to_i16(0xd5), 8);
+    assert_eq!(alaw_to_i16(0x55), -8);
+    assert_eq!(alaw_to_i16(0xaa), 32256);
+    assert_eq!(alaw_to_i16(0x2a), -32256);
+    assert_eq!(decode_mulaw(&[0xff, 0x80]), vec![0.0, 32124.0 / 32768.0]);
+}
+
+#[test]
+fn upsamples_sine_accurately() {
+    let input: Vec<f32> = (0..800)
+        .map(|n| (2.0 * std::f32::consts::PI * 440.0 * n as f32 / 8000.0).sin())
+        .collect();
+    let output = upsample_8k_to_16k(&input);
+    assert_eq!(output.len(), 2 * input.len());
+    // Skip the edges, where the filter sees zeros
+    for (n, &y) in output.iter().enumerate().skip(64).take(1400) {
+        let expected = (2.0 * std::f32::consts::PI * 440.0 * n as f32 / 16000.0).sin();
+        assert!((y - expected).abs() < 0.02, "sample {n}: {y} vs {expected}");
+    }
+}
+
+#[test]
+fn streaming_matches_one_shot() {
+    let input: Vec<f32> = (0..333)
+        .map(|n| ((n * 7919) % 200) as f32 / 100.0 - 1.0)
+        .collect();
+    let mut upsampler = Upsampler::new();
+    let mut streamed = Vec::new();
+    for chunk in input.chunks(37) {
+        streamed.extend(upsampler.process(chunk));
+    }
+    streamed.extend(upsampler.flush());
+    assert_eq!(streamed, upsample_8k_to_16k(&input));
+}
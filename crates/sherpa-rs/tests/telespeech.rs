@@ -0,0 +1,19 @@
+use sherpa_rs::telespeech::{TeleSpeechConfig, TeleSpeechRecognizer};
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn transcribes_with_telespeech_model() {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_WAV")).unwrap();
+    let mut recognizer = TeleSpeechRecognizer::new(TeleSpeechConfig {
+        model: required_env("SHERPA_RS_TEST_TELESPEECH_MODEL"),
+        tokens: required_env("SHERPA_RS_TEST_TELESPEECH_TOKENS"),
+        ..Default::default()
+    })
+    .unwrap();
+    assert!(!recognizer.transcribe(sample_rate, &samples).text.is_empty());
+}
@@ -0,0 +1,95 @@
+use sherpa_rs::{
+    diarize::Segment,
+    silero_vad::SpeechSegment,
+    timeline::{
+        parse_rttm, rttm_spans, to_audacity_labels, to_csv, to_json, to_rttm, TimelineSpan,
+    },
+};
+
+fn spans() -> Vec<TimelineSpan> {
+    vec![
+        TimelineSpan::from_speech_segment(
+            &SpeechSegment {
+                start: 8000,
+                samples: vec![0.0; 16000],
+            },
+            16000,
+        ),
+        TimelineSpan::from(&Segment {
+            start: 2.0,
+            end: 3.25,
+            speaker: 1,
+        }),
+    ]
+}
+
+#[test]
+fn speech_segment_times() {
+    let segment = SpeechSegment {
+        start: 24000,
+        samples: vec![0.0; 8000],
+    };
+    assert_eq!(segment.start_time(16000), 1.5);
+    assert_eq!(segment.duration(16000), 0.5);
+}
+
+#[test]
+fn exports_audacity_labels() {
+    assert_eq!(
+        to_audacity_labels(&spans()),
+        "0.500000\t1.500000\tspeech\n2.000000\t3.250000\tspeaker 1\n"
+    );
+}
+
+#[test]
+fn exports_json() {
+    assert_eq!(
+        to_json(&spans()),
+        r#"[{"end":1.5,"speaker":null,"start":0.5},{"end":3.25,"speaker":1,"start":2.0}]"#
+    );
+}
+
+#[test]
+fn exports_csv() {
+    assert_eq!(
+        to_csv(&spans()),
+        "start,end,speaker\n0.500,1.500,\n2.000,3.250,1\n"
+    );
+}
+
+#[test]
+fn exports_rttm() {
+    assert_eq!(
+        to_rttm(&spans(), "meeting"),
+        "SPEAKER meeting 1 0.500 1.000 <NA> <NA> speech <NA> <NA>\n\
+         SPEAKER meeting 1 2.000 1.250 <NA> <NA> speaker_1 <NA> <NA>\n"
+    );
+}
+
+#[test]
+fn parses_rttm() {
+    let turns = parse_rttm(&to_rttm(&spans(), "meeting")).unwrap();
+    let parsed = rttm_spans(&turns);
+    assert_eq!(parsed[0], spans()[0]);
+    // Speakers are renumbered in order of appearance
+    assert_eq!(parsed[1].speaker, Some(0));
+    assert_eq!(turns[1].file_id, "meeting");
+    assert_eq!(turns[1].speaker, "speaker_1");
+
+    let reference = ";; reference\n\
+                     SPKR-INFO ES2008a 1 <NA> <NA> <NA> unknown spk03 <NA>\n\
+                     \n\
+                     SPEAKER ES2008a 1 10.5 2.5 <NA> <NA> spk03 <NA> <NA>\n\
+                     SPEAKER ES2008a 1 14 1 <NA> <NA> MEE068 <NA> <NA>\n\
+                     SPEAKER ES2008a 1 15 1 <NA> <NA> FEE068 <NA> <NA>\n\
+                     SPEAKER ES2008a 1 16 1 <NA> <NA> spk03 <NA> <NA>\n\
+                     SPEAKER ES2008a 1 17 1 <NA> <NA> alice <NA> <NA>\n";
+    let turns = parse_rttm(reference).unwrap();
+    assert_eq!(turns.len(), 5);
+    assert_eq!(turns[0].end, 13.0);
+    let speakers: Vec<_> = rttm_spans(&turns).iter().map(|span| span.speaker).collect();
+    assert_eq!(speakers, [Some(0), Some(1), Some(2), Some(0), Some(3)]);
+
+    assert!(parse_rttm("SPEAKER x 1 abc 1 <NA> <NA> a <NA> <NA>").is_err());
+    assert!(parse_rttm("SPEAKER x 1 0.5").is_err());
+}
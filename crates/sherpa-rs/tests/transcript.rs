@@ -0,0 +1,94 @@
+use sherpa_rs::alignment::TokenStyle;
+use sherpa_rs::hypothesis::Hypothesis;
+use sherpa_rs::streaming_diarize::TaggedUtterance;
+use sherpa_rs::transcript::{Transcript, Turn};
+use sherpa_rs::OfflineRecognizerResult;
+
+fn result() -> OfflineRecognizerResult {
+    OfflineRecognizerResult {
+        lang: "<|en|>".into(),
+        text: " hello world".into(),
+        timestamps: vec![0.0, 0.2, 0.5],
+        tokens: vec!["▁hel".into(), "lo".into(), "▁world".into()],
+        is_translation: false,
+        emotion: String::new(),
+        event: String::new(),
+    }
+}
+
+#[test]
+fn builds_turns_from_results() {
+    let turn = Turn {
+        speaker: Some(1),
+        ..Turn::from_result(&result(), 10.0)
+    };
+    assert_eq!(turn.text, "hello world");
+    assert_eq!(turn.language.as_deref(), Some("en"));
+    assert_eq!(turn.start, 10.0);
+    let words: Vec<_> = turn
+        .words
+        .iter()
+        .map(|w| (w.text.as_str(), w.start))
+        .collect();
+    assert_eq!(words, vec![("hello", 10.0), ("world", 10.5)]);
+    assert_eq!(turn.end, turn.words[1].end);
+
+    let mut transcript = Transcript::new();
+    transcript.push(turn);
+    transcript.push(Turn {
+        speaker: Some(0),
+        start: 12.0,
+        end: 13.0,
+        text: "hi".into(),
+        ..Default::default()
+    });
+    transcript.push(Turn::default());
+    assert_eq!(transcript.language.as_deref(), Some("en"));
+    assert_eq!(transcript.turns.len(), 2);
+    assert!(transcript.rename_speaker(0, "Alice"));
+    assert!(!transcript.rename_speaker(7, "Bob"));
+    assert_eq!(transcript.text(), "speaker 1: hello world\nAlice: hi\n");
+    assert_eq!(transcript.spans()[1].speaker, Some(0));
+}
+
+#[test]
+fn word_confidence_comes_from_token_probabilities() {
+    let hypothesis = Hypothesis::from_json(
+        r#"{"text": "hello world", "tokens": ["▁hel", "lo", "▁world"],
+            "timestamps": [0.0, 0.2, 0.5], "ys_log_probs": [-0.1, -0.3, -2.0]}"#,
+    )
+    .unwrap();
+    let turn = Turn::from_hypothesis(&hypothesis, 0.0, TokenStyle::SentencePiece);
+    let hello = turn.words[0].confidence.unwrap();
+    assert!((hello - ((-0.1f32).exp() + (-0.3f32).exp()) / 2.0).abs() < 1e-6);
+    assert!((turn.words[1].confidence.unwrap() - (-2.0f32).exp()).abs() < 1e-6);
+}
+
+#[test]
+fn round_trips_through_json() {
+    let transcript = Transcript::from_utterances(&[
+        TaggedUtterance {
+            start: 0.0,
+            end: 1.5,
+            speaker: Some(0),
+            text: "good morning".into(),
+        },
+        TaggedUtterance {
+            start: 2.0,
+            end: 2.5,
+            speaker: None,
+            text: "yes".into(),
+        },
+    ]);
+    assert_eq!(transcript.speakers.len(), 1);
+    let json = transcript.to_json();
+    assert_eq!(
+        json,
+        r#"{"speakers":[{"id":0,"label":"speaker 0"}],"turns":[{"speaker":0,"start":0.0,"end":1.5,"text":"good morning","words":[]},{"speaker":null,"start":2.0,"end":2.5,"text":"yes","words":[]}]}"#
+    );
+    assert_eq!(Transcript::from_json(&json).unwrap(), transcript);
+    assert_eq!(
+        Transcript::from_json(r#"{"turns": []}"#).unwrap(),
+        Transcript::new()
+    );
+}
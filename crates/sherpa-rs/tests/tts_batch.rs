@@ -0,0 +1,82 @@
+#![cfg(feature = "tts")]
+
+use eyre::{bail, Result};
+use sherpa_rs::tts::{synthesize_batch, TextToSpeech, TtsAudio};
+
+/// Produces one full-scale sample per character at 10 Hz
+struct FakeTts;
+
+impl TextToSpeech for FakeTts {
+    fn create(&mut self, text: &str, _sid: i32, _speed: f32) -> Result<TtsAudio> {
+        if text.is_empty() {
+            bail!("empty text");
+        }
+        Ok(TtsAudio {
+            samples: vec![1.0; text.chars().count()],
+            sample_rate: 10,
+            duration: 0,
+        })
+    }
+
+    fn sample_rate(&self) -> u32 {
+        10
+    }
+}
+
+/// Panics on texts containing `!`
+struct Fragile;
+
+impl TextToSpeech for Fragile {
+    fn create(&mut self, text: &str, sid: i32, speed: f32) -> eyre::Result<TtsAudio> {
+        assert!(!text.contains('!'), "can't say {text}");
+        FakeTts.create(text, sid, speed)
+    }
+
+    fn sample_rate(&self) -> u32 {
+        10
+    }
+}
+
+#[test]
+fn keeps_input_order_across_engines() {
+    let texts: Vec<String> = (1..=20).map(|n| "a".repeat(n)).collect();
+    let mut engines = [FakeTts, FakeTts, FakeTts];
+    let results = synthesize_batch(&mut engines, &texts, 0, 1.0);
+    let lengths: Vec<usize> = results
+        .into_iter()
+        .map(|r| r.unwrap().samples.len())
+        .collect();
+    assert_eq!(lengths, (1..=20).collect::<Vec<_>>());
+}
+
+#[test]
+fn reports_failures_per_text() {
+    let results = synthesize_batch(&mut [FakeTts], &["hi", "", "there"], 0, 1.0);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+    assert!(results[2].is_ok());
+    assert!(synthesize_batch(&mut Vec::<FakeTts>::new(), &["hi"], 0, 1.0)[0].is_err());
+}
+
+#[test]
+fn boxed_engines_mix_in_one_batch() {
+    let mut engines: Vec<Box<dyn TextToSpeech + Send>> = vec![Box::new(FakeTts), Box::new(FakeTts)];
+    let results = synthesize_batch(&mut engines, &["one", "three"], 0, 1.0);
+    assert_eq!(results[1].as_ref().unwrap().samples.len(), 5);
+    assert_eq!(engines[0].sample_rate(), 10);
+}
+
+#[test]
+fn panicking_engine_fails_its_text_only() {
+    let results = synthesize_batch(&mut [Fragile], &["a", "b!", "cc"], 0, 1.0);
+    assert!(results[0].is_ok());
+    let error = results[1].as_ref().unwrap_err().to_string();
+    assert!(error.contains("can't say b!"), "{error}");
+    // Nothing is left to take the rest once the only engine has panicked
+    assert!(results[2].is_err());
+
+    let texts = ["x!"; 10];
+    let mut engines: Vec<Box<dyn TextToSpeech + Send>> = vec![Box::new(Fragile), Box::new(FakeTts)];
+    let results = synthesize_batch(&mut engines, &texts, 0, 1.0);
+    assert!(results.iter().filter(|r| r.is_ok()).count() >= 9);
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "tts")]
+
+use sherpa_rs::tts::TtsAudio;
+
+fn audio() -> TtsAudio {
+    TtsAudio {
+        samples: vec![0.0, 0.5, -1.0, 2.0],
+        sample_rate: 16000,
+        duration: 0,
+    }
+}
+
+#[test]
+fn converts_to_clamped_pcm() {
+    assert_eq!(audio().to_i16(), vec![0, 16383, -32767, 32767]);
+    assert_eq!(
+        audio().to_pcm_bytes(),
+        vec![0, 0, 0xff, 0x3f, 0x01, 0x80, 0xff, 0x7f]
+    );
+}
+
+#[test]
+fn writes_wav_in_memory() {
+    let wav = audio().to_wav_bytes().unwrap();
+    let reader = hound::WavReader::new(std::io::Cursor::new(wav)).unwrap();
+    assert_eq!(reader.spec().sample_rate, 16000);
+    assert_eq!(reader.spec().bits_per_sample, 16);
+    let samples: Vec<i16> = reader.into_samples().map(|s| s.unwrap()).collect();
+    assert_eq!(samples, audio().to_i16());
+}
+
+#[cfg(feature = "opus")]
+#[test]
+fn wraps_opus_in_ogg() {
+    let audio = TtsAudio {
+        samples: vec![0.1; 24100],
+        sample_rate: 24000,
+        duration: 1,
+    };
+    let ogg = audio.to_ogg_opus(32000).unwrap();
+    assert!(ogg.starts_with(b"OggS"));
+    let head = ogg.windows(8).position(|w| w == b"OpusHead").unwrap();
+    // Original sample rate is kept in the header
+    assert_eq!(&ogg[head + 12..head + 16], &24000u32.to_le_bytes());
+    assert!(ogg.windows(8).any(|w| w == b"OpusTags"));
+}
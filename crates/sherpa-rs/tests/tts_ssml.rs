@@ -0,0 +1,98 @@
+#![cfg(feature = "tts")]
+
+use eyre::{bail, Result};
+use sherpa_rs::tts::{
+    parse_ssml, synthesize_ssml, SsmlSegment, TextToSpeech, TtsAudio, MAX_BREAK_SECONDS,
+};
+
+/// Produces one full-scale sample per character at 10 Hz
+struct FakeTts;
+
+impl TextToSpeech for FakeTts {
+    fn create(&mut self, text: &str, _sid: i32, _speed: f32) -> Result<TtsAudio> {
+        if text.is_empty() {
+            bail!("empty text");
+        }
+        Ok(TtsAudio {
+            samples: vec![1.0; text.chars().count()],
+            sample_rate: 10,
+            duration: 0,
+        })
+    }
+
+    fn sample_rate(&self) -> u32 {
+        10
+    }
+}
+
+#[test]
+fn compiles_breaks_prosody_and_voices() {
+    let ssml = r#"<speak>Hello &amp; welcome.<break time="300ms"/>
+        <prosody rate="150%">Quick <voice name="2">part</voice></prosody>
+        <break strength="strong"/>Done</speak>"#;
+    assert_eq!(
+        parse_ssml(ssml, 0, 1.0).unwrap(),
+        vec![
+            SsmlSegment::Text {
+                text: "Hello & welcome.".into(),
+                sid: 0,
+                speed: 1.0
+            },
+            SsmlSegment::Break(0.3),
+            SsmlSegment::Text {
+                text: "Quick".into(),
+                sid: 0,
+                speed: 1.5
+            },
+            SsmlSegment::Text {
+                text: "part".into(),
+                sid: 2,
+                speed: 1.5
+            },
+            SsmlSegment::Break(0.75),
+            SsmlSegment::Text {
+                text: "Done".into(),
+                sid: 0,
+                speed: 1.0
+            },
+        ]
+    );
+}
+
+#[test]
+fn rejects_invalid_markup() {
+    assert!(parse_ssml("a</prosody>", 0, 1.0).is_err());
+    assert!(parse_ssml(r#"<break time="soon"/>"#, 0, 1.0).is_err());
+    assert!(parse_ssml(r#"<prosody rate="-5%">a</prosody>"#, 0, 1.0).is_err());
+    assert!(parse_ssml(r#"<voice name="alice">a</voice>"#, 0, 1.0).is_err());
+    assert!(parse_ssml(r#"<break time="inf"/>"#, 0, 1.0).is_err());
+    assert!(parse_ssml(r#"<break time="NaNms"/>"#, 0, 1.0).is_err());
+    assert!(parse_ssml(r#"<prosody rate="inf">a</prosody>"#, 0, 1.0).is_err());
+}
+
+#[test]
+fn caps_long_breaks() {
+    assert_eq!(
+        parse_ssml(r#"<break time="1e30s"/><break TIME='2s'/>"#, 0, 1.0).unwrap(),
+        vec![
+            SsmlSegment::Break(MAX_BREAK_SECONDS),
+            SsmlSegment::Break(2.0)
+        ]
+    );
+}
+
+#[test]
+fn inserts_silence_for_breaks() {
+    let audio = synthesize_ssml(
+        &mut FakeTts,
+        r#"<break time="0.5s"/>ab<break time="200ms"/>c"#,
+        0,
+        1.0,
+    )
+    .unwrap();
+    assert_eq!(
+        audio.samples,
+        vec![0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0]
+    );
+    assert!(synthesize_ssml(&mut FakeTts, r#"<break time="1s"/>"#, 0, 1.0).is_err());
+}
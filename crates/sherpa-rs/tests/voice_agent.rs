@@ -0,0 +1,156 @@
+#![cfg(feature = "playback")]
+
+use eyre::{bail, Result};
+use sherpa_rs::energy_vad::EnergyVad;
+use sherpa_rs::tts::{TextToSpeech, TtsAudio};
+use sherpa_rs::voice_agent::{AgentEvent, VoiceAgent, VoiceAgentConfig};
+use sherpa_rs::{OnlineTranscriber, StreamingError};
+use std::future::Future;
+use std::sync::mpsc::{self, Sender};
+use std::time::{Duration, Instant};
+
+/// Hears the number of chunks it was fed, ending an utterance every two
+struct ChunkCounter {
+    name: &'static str,
+    chunks: usize,
+}
+
+impl ChunkCounter {
+    fn new(name: &'static str) -> Self {
+        Self { name, chunks: 0 }
+    }
+}
+
+impl OnlineTranscriber for ChunkCounter {
+    fn accept_waveform(&mut self, _: u32, _: &[f32]) -> Result<(), StreamingError> {
+        self.chunks += 1;
+        Ok(())
+    }
+
+    fn transcribe(&mut self) -> String {
+        match self.chunks {
+            0 => String::new(),
+            n => format!("{} {}", self.name, n),
+        }
+    }
+
+    fn is_endpoint(&self) -> bool {
+        self.chunks == 2
+    }
+
+    fn reset(&mut self) {
+        self.chunks = 0;
+    }
+
+    fn input_finished(&mut self) {}
+}
+
+/// Produces one full-scale sample per character at 10 Hz
+struct FakeTts;
+
+impl TextToSpeech for FakeTts {
+    fn create(&mut self, text: &str, _sid: i32, _speed: f32) -> Result<TtsAudio> {
+        if text.is_empty() {
+            bail!("empty text");
+        }
+        Ok(TtsAudio {
+            samples: vec![1.0; text.chars().count()],
+            sample_rate: 10,
+            duration: 0,
+        })
+    }
+
+    fn sample_rate(&self) -> u32 {
+        10
+    }
+}
+
+fn start<H, F>(handler: H) -> (VoiceAgent, Sender<Vec<f32>>)
+where
+    H: FnMut(String) -> F + Send + 'static,
+    F: Future<Output = eyre::Result<String>>,
+{
+    let (audio, audio_rx) = mpsc::channel();
+    let agent = VoiceAgent::start(
+        ChunkCounter::new("a"),
+        EnergyVad::new(Default::default()).unwrap(),
+        FakeTts,
+        handler,
+        audio_rx,
+        VoiceAgentConfig::default(),
+    )
+    .unwrap();
+    (agent, audio)
+}
+
+fn next_event(agent: &VoiceAgent) -> AgentEvent {
+    agent.events().recv_timeout(Duration::from_secs(5)).unwrap()
+}
+
+#[test]
+#[ignore = "requires an audio output device"]
+fn replies_to_each_utterance() {
+    let (agent, audio) = start(|text| async move { eyre::Ok(format!("echo {text}")) });
+    audio.send(vec![0.0; 1600]).unwrap();
+    audio.send(vec![0.0; 1600]).unwrap();
+    assert_eq!(next_event(&agent), AgentEvent::Partial("a 1".into()));
+    assert_eq!(next_event(&agent), AgentEvent::Heard("a 2".into()));
+    assert_eq!(next_event(&agent), AgentEvent::Reply("echo a 2".into()));
+    agent.stop();
+}
+
+#[test]
+#[ignore = "requires an audio output device"]
+fn failed_handler_calls_are_skipped() {
+    let mut calls = 0;
+    let (agent, audio) = start(move |text| {
+        calls += 1;
+        let reply = match calls {
+            1 => Err(eyre::eyre!("offline")),
+            _ => Ok(text),
+        };
+        async move { reply }
+    });
+    for _ in 0..4 {
+        audio.send(vec![0.0; 1600]).unwrap();
+    }
+    let replies: Vec<AgentEvent> = (0..5)
+        .map(|_| next_event(&agent))
+        .filter(|event| matches!(event, AgentEvent::Reply(_)))
+        .collect();
+    assert_eq!(replies, vec![AgentEvent::Reply("a 2".into())]);
+    agent.stop();
+}
+
+#[test]
+#[ignore = "requires an audio output device"]
+fn stopping_drops_a_pending_reply() {
+    let (agent, audio) = start(|_| std::future::pending::<eyre::Result<String>>());
+    audio.send(vec![0.0; 1600]).unwrap();
+    audio.send(vec![0.0; 1600]).unwrap();
+    assert_eq!(next_event(&agent), AgentEvent::Partial("a 1".into()));
+    assert_eq!(next_event(&agent), AgentEvent::Heard("a 2".into()));
+
+    let started = Instant::now();
+    agent.stop();
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+#[ignore = "requires an audio output device"]
+fn handler_panics_are_logged_on_drop() {
+    let (agent, audio) = start(|_| async { panic!("handler bug") });
+    audio.send(vec![0.0; 1600]).unwrap();
+    audio.send(vec![0.0; 1600]).unwrap();
+    assert_eq!(next_event(&agent), AgentEvent::Partial("a 1".into()));
+    assert_eq!(next_event(&agent), AgentEvent::Heard("a 2".into()));
+    drop(agent);
+
+    let (agent, audio) = start(|_| async { panic!("handler bug") });
+    audio.send(vec![0.0; 1600]).unwrap();
+    audio.send(vec![0.0; 1600]).unwrap();
+    assert_eq!(next_event(&agent), AgentEvent::Partial("a 1".into()));
+    assert_eq!(next_event(&agent), AgentEvent::Heard("a 2".into()));
+    let stopped = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| agent.stop()));
+    assert!(stopped.is_err());
+}
@@ -0,0 +1,80 @@
+use sherpa_rs::keyword_spot::{KeywordSpot, KeywordSpotConfig};
+use sherpa_rs::wake_word::{WakeWordEvent, WakeWordSession};
+use sherpa_rs::{OnlineTranscriber, StreamingError};
+
+fn required_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("missing required env var {name}"))
+}
+
+/// Hears the number of samples it was fed
+#[derive(Default)]
+struct Samples(usize);
+
+impl OnlineTranscriber for Samples {
+    fn accept_waveform(&mut self, _: u32, samples: &[f32]) -> Result<(), StreamingError> {
+        self.0 += samples.len();
+        Ok(())
+    }
+
+    fn transcribe(&mut self) -> String {
+        self.0.to_string()
+    }
+
+    fn is_endpoint(&self) -> bool {
+        false
+    }
+
+    fn reset(&mut self) {
+        self.0 = 0;
+    }
+
+    fn input_finished(&mut self) {}
+}
+
+fn spotter() -> KeywordSpot {
+    KeywordSpot::new(KeywordSpotConfig {
+        zipformer_encoder: required_env("SHERPA_RS_TEST_KWS_ENCODER"),
+        zipformer_decoder: required_env("SHERPA_RS_TEST_KWS_DECODER"),
+        zipformer_joiner: required_env("SHERPA_RS_TEST_KWS_JOINER"),
+        tokens: required_env("SHERPA_RS_TEST_KWS_TOKENS"),
+        keywords: required_env("SHERPA_RS_TEST_KWS_KEYWORDS"),
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn transcribes_the_rest_of_the_chunk_after_the_wake_word() {
+    let (samples, sample_rate) =
+        sherpa_rs::read_audio_file(&required_env("SHERPA_RS_TEST_KWS_WAV")).unwrap();
+    let mut session = WakeWordSession::new(spotter(), Samples::default());
+    assert!(session.is_listening());
+
+    let events = session.accept_waveform(sample_rate, &samples).unwrap();
+    assert!(matches!(&events[0], WakeWordEvent::Detected(keyword) if !keyword.is_empty()));
+    assert!(!session.is_listening());
+    let WakeWordEvent::Partial(heard) = &events[1] else {
+        panic!("expected the audio after the keyword, got {events:?}");
+    };
+    let heard: usize = heard.parse().unwrap();
+    assert!(heard > 0 && heard < samples.len(), "{heard}");
+
+    // Everything after the detection goes to the recognizer
+    let events = session.accept_waveform(sample_rate, &[0.0; 1600]).unwrap();
+    assert_eq!(events, [WakeWordEvent::Partial((heard + 1600).to_string())]);
+}
+
+#[test]
+#[ignore = "requires local sherpa-onnx libraries and model files"]
+fn silence_keeps_listening() {
+    let mut session = WakeWordSession::new(spotter(), Samples::default());
+    for _ in 0..20 {
+        assert!(session
+            .accept_waveform(16000, &[0.0; 1600])
+            .unwrap()
+            .is_empty());
+    }
+    assert!(session.is_listening());
+    assert_eq!(session.into_inner().1 .0, 0);
+}
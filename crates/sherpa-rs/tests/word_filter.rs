@@ -0,0 +1,59 @@
+use sherpa_rs::post_process::{collapse_repetitions, WordFilter};
+use sherpa_rs::OfflineRecognizerResult;
+
+#[test]
+fn replaces_and_masks_whole_words() {
+    let filter = WordFilter::new()
+        .replace("gonna", "going to")
+        .mask("darn")
+        .replace_regex(r"(\d+) percent", "$1%")
+        .unwrap();
+
+    assert_eq!(
+        filter.apply("Darn it, we're gonna hit 50 percent, darning aside"),
+        "**** it, we're going to hit 50%, darning aside"
+    );
+}
+
+#[test]
+fn plain_replacements_are_literal() {
+    let filter = WordFilter::new()
+        .replace("five dollars", "$5")
+        .replace("c++", "C++");
+    assert_eq!(
+        filter.apply("five dollars for c++ books"),
+        "$5 for C++ books"
+    );
+}
+
+#[test]
+fn matches_words_in_scripts_without_spaces() {
+    let filter = WordFilter::new().replace("你好", "您好").mask("笨蛋");
+    assert_eq!(
+        filter.apply("我说你好吗，你这个笨蛋"),
+        "我说您好吗，你这个**"
+    );
+    // Latin words inside CJK text still need their boundaries
+    let filter = WordFilter::new().replace("ok", "OK");
+    assert_eq!(filter.apply("好 ok 吗 token"), "好 OK 吗 token");
+}
+
+#[test]
+fn collapses_looping_phrases() {
+    let tokens = [
+        " Thank", " you", ".", " Thank", " you", ".", " Thank", " you", ".", " Bye",
+    ];
+    let mut result = OfflineRecognizerResult {
+        text: tokens.concat(),
+        tokens: tokens.iter().map(|t| t.to_string()).collect(),
+        timestamps: (0..tokens.len()).map(|i| i as f32).collect(),
+        ..Default::default()
+    };
+
+    collapse_repetitions(&mut result, 3);
+    assert_eq!(result.tokens.len(), tokens.len());
+
+    collapse_repetitions(&mut result, 1);
+    assert_eq!(result.text, "Thank you. Bye");
+    assert_eq!(result.timestamps, [0.0, 1.0, 2.0, 9.0]);
+}
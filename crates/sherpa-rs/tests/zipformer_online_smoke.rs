@@ -1,3 +1,5 @@
+#![allow(deprecated)]
+
 use sherpa_rs::zipformer_online::{ZipFormerOnline, ZipFormerOnlineConfig};
 
 fn required_env(name: &str) -> String {
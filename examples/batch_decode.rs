@@ -0,0 +1,39 @@
+/*
+Decode several files in one batch with a single SenseVoice model
+
+wget https://github.com/k2-fsa/sherpa-onnx/releases/download/asr-models/sherpa-onnx-sense-voice-zh-en-ja-ko-yue-2024-07-17.tar.bz2
+tar xvf sherpa-onnx-sense-voice-zh-en-ja-ko-yue-2024-07-17.tar.bz2
+cargo run --example batch_decode a.wav b.wav c.wav
+*/
+use sherpa_rs::{
+    offline_stream::OfflineStream,
+    read_audio_file,
+    sense_voice::{SenseVoiceConfig, SenseVoiceRecognizer},
+};
+
+fn main() {
+    let paths: Vec<String> = std::env::args().skip(1).collect();
+    let dir = "sherpa-onnx-sense-voice-zh-en-ja-ko-yue-2024-07-17";
+    let recognizer = SenseVoiceRecognizer::new(SenseVoiceConfig {
+        model: format!("{dir}/model.int8.onnx"),
+        tokens: format!("{dir}/tokens.txt"),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut streams = Vec::new();
+    for path in &paths {
+        let (samples, sample_rate) = read_audio_file(path).unwrap();
+        let mut stream = recognizer.create_stream().unwrap();
+        // Feed in 1 second chunks, e.g. as an upload arrives; all are decoded together
+        for chunk in samples.chunks(sample_rate as usize) {
+            stream.accept_waveform(sample_rate, chunk).unwrap();
+        }
+        streams.push(stream);
+    }
+
+    let results = OfflineStream::decode_batch(&mut streams).unwrap();
+    for (path, result) in paths.iter().zip(results) {
+        println!("{path}: {}", result.text);
+    }
+}
@@ -0,0 +1,22 @@
+/*
+Remove background noise from speech
+
+wget https://github.com/k2-fsa/sherpa-onnx/releases/download/speech-enhancement-models/gtcrn_simple.onnx
+wget https://github.com/k2-fsa/sherpa-onnx/releases/download/speech-enhancement-models/inp_16k.wav
+cargo run --example denoise inp_16k.wav
+*/
+use sherpa_rs::denoise::{Denoiser, DenoiserConfig};
+
+fn main() {
+    let path = std::env::args().nth(1).expect("Missing file path argument");
+    let (samples, sample_rate) = sherpa_rs::read_audio_file(&path).unwrap();
+
+    let config = DenoiserConfig {
+        model: "gtcrn_simple.onnx".into(),
+        ..Default::default()
+    };
+    let mut denoiser = Denoiser::new(config).unwrap();
+    let audio = denoiser.denoise(&samples, sample_rate).unwrap();
+    sherpa_rs::write_audio_file("denoised.wav", &audio.samples, audio.sample_rate).unwrap();
+    println!("Created denoised.wav");
+}
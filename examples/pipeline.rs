@@ -0,0 +1,62 @@
+/*
+Transcribe a file with VAD, Whisper and punctuation in one pipeline
+
+wget https://github.com/k2-fsa/sherpa-onnx/releases/download/asr-models/sherpa-onnx-whisper-tiny.tar.bz2
+wget https://github.com/snakers4/silero-vad/raw/master/files/silero_vad.onnx
+wget https://github.com/k2-fsa/sherpa-onnx/releases/download/punctuation-models/sherpa-onnx-punct-ct-transformer-zh-en-vocab272727-2024-04-12.tar.bz2
+tar xvf sherpa-onnx-whisper-tiny.tar.bz2
+tar xvf sherpa-onnx-punct-ct-transformer-zh-en-vocab272727-2024-04-12.tar.bz2
+wget https://github.com/thewh1teagle/sherpa-rs/releases/download/v0.1.0/sam_altman.wav -O sam_altman.wav
+cargo run --example pipeline sam_altman.wav
+*/
+use sherpa_rs::{
+    pipeline::Pipeline,
+    punctuate::{Punctuation, PunctuationConfig},
+    read_audio_file,
+    silero_vad::{SileroVad, SileroVadConfig},
+    whisper::{WhisperConfig, WhisperRecognizer},
+};
+
+fn main() {
+    let path = std::env::args().nth(1).expect("Missing file path argument");
+    let (samples, sample_rate) = read_audio_file(&path).unwrap();
+
+    let recognizer = WhisperRecognizer::new(WhisperConfig {
+        decoder: "sherpa-onnx-whisper-tiny/tiny-decoder.onnx".into(),
+        encoder: "sherpa-onnx-whisper-tiny/tiny-encoder.onnx".into(),
+        tokens: "sherpa-onnx-whisper-tiny/tiny-tokens.txt".into(),
+        language: "en".into(),
+        ..Default::default()
+    })
+    .unwrap();
+    let vad = SileroVad::new(
+        SileroVadConfig {
+            model: "silero_vad.onnx".into(),
+            ..Default::default()
+        },
+        60.0 * 10.0,
+    )
+    .unwrap();
+    let punctuation = Punctuation::new(PunctuationConfig {
+        model: "sherpa-onnx-punct-ct-transformer-zh-en-vocab272727-2024-04-12/model.onnx".into(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut pipeline = Pipeline::builder(recognizer)
+        .input_sample_rate(sample_rate)
+        .vad(vad)
+        .punctuation(punctuation)
+        .post_processor(|text| text.replace("Sam Altman", "Sam"))
+        .build()
+        .unwrap();
+
+    for segment in pipeline.process(&samples).unwrap() {
+        println!(
+            "({:.2}s - {:.2}s): {}",
+            segment.start,
+            segment.start + segment.duration,
+            segment.text
+        );
+    }
+}
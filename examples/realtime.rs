@@ -0,0 +1,34 @@
+/*
+Live transcription from the default microphone
+
+wget https://github.com/k2-fsa/sherpa-onnx/releases/download/asr-models/sherpa-onnx-streaming-zipformer-en-2023-06-26.tar.bz2
+tar xvf sherpa-onnx-streaming-zipformer-en-2023-06-26.tar.bz2
+cargo run --example realtime --features capture
+*/
+use sherpa_rs::online::{OnlineModel, OnlineRecognizer, OnlineRecognizerConfig};
+use sherpa_rs::realtime::{RealtimeSession, SessionEvent};
+
+fn main() {
+    let dir = "sherpa-onnx-streaming-zipformer-en-2023-06-26";
+    let config = OnlineRecognizerConfig {
+        model: OnlineModel::Transducer {
+            encoder: format!("{dir}/encoder-epoch-99-avg-1-chunk-16-left-128.onnx"),
+            decoder: format!("{dir}/decoder-epoch-99-avg-1-chunk-16-left-128.onnx"),
+            joiner: format!("{dir}/joiner-epoch-99-avg-1-chunk-16-left-128.onnx"),
+        },
+        tokens: format!("{dir}/tokens.txt"),
+        ..Default::default()
+    };
+    let recognizer = OnlineRecognizer::new(config).unwrap();
+
+    let session = RealtimeSession::from_microphone(recognizer).unwrap();
+    println!("Listening... press Ctrl+C to stop");
+    for event in session.events() {
+        match event {
+            SessionEvent::Partial(text) => eprint!("\r{text}"),
+            SessionEvent::Final(text) => eprintln!("\r{text}"),
+            SessionEvent::Recovered { error } => eprintln!("\rrecovered from: {error}"),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,31 @@
+/*
+Speak text on the default output device while it's being synthesized
+
+wget https://huggingface.co/csukuangfj/vits-ljs/resolve/main/vits-ljs.onnx
+wget https://huggingface.co/csukuangfj/vits-ljs/resolve/main/lexicon.txt
+wget https://huggingface.co/csukuangfj/vits-ljs/resolve/main/tokens.txt
+cargo run --example speak --features playback
+*/
+use sherpa_rs::playback::AudioPlayer;
+use sherpa_rs::tts::{CommonTtsConfig, VitsTts, VitsTtsConfig};
+
+fn main() {
+    let config = VitsTtsConfig {
+        model: "./vits-ljs.onnx".into(),
+        lexicon: "./lexicon.txt".into(),
+        tokens: "./tokens.txt".into(),
+        length_scale: 1.0,
+        // One sentence at a time, so playback starts after the first one
+        tts_config: CommonTtsConfig {
+            max_num_sentences: 1,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut tts = VitsTts::new(config);
+    let player = AudioPlayer::open(2.0).unwrap();
+
+    let text =
+        "Hello! This is the first sentence. Playback already started while this one is generated.";
+    player.speak(&mut tts, text, 0, 1.0).unwrap();
+}
@@ -32,7 +32,7 @@ fn main() {
 
     // Create the embedding manager
     let mut embedding_manager =
-        embedding_manager::EmbeddingManager::new(extractor.embedding_size.try_into().unwrap()); // Assuming dimension 512 for embeddings
+        embedding_manager::EmbeddingManager::new(extractor.dim().try_into().unwrap());
 
     // Map to store speakers and their corresponding files
     let mut speaker_map: HashMap<String, Vec<String>> = HashMap::new();
@@ -40,7 +40,7 @@ fn main() {
 
     // Process each embedding and identify speakers
     for (file, embedding) in &embeddings {
-        if let Some(speaker_name) = embedding_manager.search(embedding, 0.5) {
+        if let Some(speaker_name) = embedding_manager.search(embedding, 0.5).unwrap() {
             // Add file to existing speaker
             speaker_map
                 .entry(speaker_name)
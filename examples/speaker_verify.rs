@@ -0,0 +1,30 @@
+/*
+Check whether two recordings are of the same speaker
+
+wget https://github.com/k2-fsa/sherpa-onnx/releases/download/speaker-recongition-models/nemo_en_speakerverification_speakernet.onnx
+wget https://github.com/thewh1teagle/sherpa-rs/releases/download/v0.1.0/biden.wav -O biden.wav
+wget https://github.com/thewh1teagle/sherpa-rs/releases/download/v0.1.0/obama.wav -O obama.wav
+cargo run --example speaker_verify biden.wav obama.wav
+*/
+use sherpa_rs::speaker_id;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let (a, b) = (&args[1], &args[2]);
+
+    let config = speaker_id::ExtractorConfig {
+        model: "nemo_en_speakerverification_speakernet.onnx".into(),
+        ..Default::default()
+    };
+    let mut extractor = speaker_id::EmbeddingExtractor::new(config).unwrap();
+
+    let (samples_a, sample_rate) = sherpa_rs::read_audio_file(a).unwrap();
+    let (samples_b, _) = sherpa_rs::read_audio_file(b).unwrap();
+    let result = extractor
+        .verify(&samples_a, &samples_b, sample_rate)
+        .unwrap();
+    println!(
+        "score: {:.3}, same speaker: {}",
+        result.score, result.same_speaker
+    );
+}
@@ -0,0 +1,64 @@
+/*
+Live captions tagged with speaker ids
+
+wget https://github.com/k2-fsa/sherpa-onnx/releases/download/speaker-recongition-models/3dspeaker_speech_eres2net_base_sv_zh-cn_3dspeaker_16k.onnx
+wget https://github.com/k2-fsa/sherpa-onnx/releases/download/asr-models/sherpa-onnx-streaming-zipformer-en-2023-06-26.tar.bz2
+tar xvf sherpa-onnx-streaming-zipformer-en-2023-06-26.tar.bz2
+wget https://github.com/thewh1teagle/sherpa-rs/releases/download/v0.1.0/motivation.wav -O motivation.wav
+cargo run --example streaming_diarize motivation.wav
+*/
+use sherpa_rs::{
+    online::{OnlineModel, OnlineRecognizer, OnlineRecognizerConfig},
+    speaker_id::{EmbeddingExtractor, ExtractorConfig},
+    streaming_diarize::{DiarizedTranscriber, StreamingDiarizer, StreamingDiarizerConfig},
+};
+
+fn main() {
+    let path = std::env::args().nth(1).expect("Missing file path argument");
+    let (samples, sample_rate) = sherpa_rs::read_audio_file(&path).unwrap();
+
+    let extractor = EmbeddingExtractor::new(ExtractorConfig {
+        model: "3dspeaker_speech_eres2net_base_sv_zh-cn_3dspeaker_16k.onnx".into(),
+        ..Default::default()
+    })
+    .unwrap();
+    let diarizer = StreamingDiarizer::new(
+        extractor,
+        StreamingDiarizerConfig {
+            sample_rate,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let asr = "sherpa-onnx-streaming-zipformer-en-2023-06-26";
+    let recognizer = OnlineRecognizer::new(OnlineRecognizerConfig {
+        model: OnlineModel::Transducer {
+            encoder: format!("{asr}/encoder-epoch-99-avg-1-chunk-16-left-128.onnx"),
+            decoder: format!("{asr}/decoder-epoch-99-avg-1-chunk-16-left-128.onnx"),
+            joiner: format!("{asr}/joiner-epoch-99-avg-1-chunk-16-left-128.onnx"),
+        },
+        tokens: format!("{asr}/tokens.txt"),
+        enable_endpoint: true,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut transcriber = DiarizedTranscriber::new(recognizer, diarizer);
+    let print = |u: sherpa_rs::streaming_diarize::TaggedUtterance| {
+        let speaker = u.speaker.map_or("?".to_string(), |s| s.to_string());
+        println!(
+            "[{:.1}s - {:.1}s] Speaker {}: {}",
+            u.start, u.end, speaker, u.text
+        );
+    };
+    // Feed 100ms chunks like a microphone would
+    for chunk in samples.chunks(sample_rate as usize / 10) {
+        for utterance in transcriber.accept_waveform(chunk).unwrap() {
+            print(utterance);
+        }
+    }
+    if let Some(utterance) = transcriber.flush() {
+        print(utterance);
+    }
+}
@@ -0,0 +1,43 @@
+/*
+Create SRT subtitles with speaker labels
+
+wget https://github.com/k2-fsa/sherpa-onnx/releases/download/asr-models/sherpa-onnx-whisper-tiny.tar.bz2
+wget https://github.com/k2-fsa/sherpa-onnx/releases/download/speaker-segmentation-models/sherpa-onnx-pyannote-segmentation-3-0.tar.bz2
+wget https://github.com/k2-fsa/sherpa-onnx/releases/download/speaker-recongition-models/nemo_en_speakerverification_speakernet.onnx
+tar xvf sherpa-onnx-whisper-tiny.tar.bz2
+tar xvf sherpa-onnx-pyannote-segmentation-3-0.tar.bz2
+wget https://github.com/thewh1teagle/sherpa-rs/releases/download/v0.1.0/sam_altman.wav -O sam_altman.wav
+cargo run --example subtitle sam_altman.wav > sam_altman.srt
+*/
+use sherpa_rs::{
+    diarize::{Diarize, DiarizeConfig},
+    subtitle::{subtitle_file, to_srt, SubtitleOptions},
+    whisper::{WhisperConfig, WhisperRecognizer},
+};
+
+fn main() {
+    let path = std::env::args().nth(1).expect("Missing file path argument");
+
+    let mut recognizer = WhisperRecognizer::new(WhisperConfig {
+        decoder: "sherpa-onnx-whisper-tiny/tiny-decoder.onnx".into(),
+        encoder: "sherpa-onnx-whisper-tiny/tiny-encoder.onnx".into(),
+        tokens: "sherpa-onnx-whisper-tiny/tiny-tokens.txt".into(),
+        language: "en".into(),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut diarize = Diarize::new(
+        "sherpa-onnx-pyannote-segmentation-3-0/model.onnx",
+        "nemo_en_speakerverification_speakernet.onnx",
+        DiarizeConfig {
+            num_clusters: None,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let mut options = SubtitleOptions::new(&mut recognizer);
+    options.diarize = Some(&mut diarize);
+    let cues = subtitle_file(&path, options).unwrap();
+    print!("{}", to_srt(&cues));
+}
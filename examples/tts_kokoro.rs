@@ -17,9 +17,10 @@ fn main() {
         tokens: "./kokoro-multi-lang-v1_0/tokens.txt".into(),
         data_dir: "./kokoro-multi-lang-v1_0/espeak-ng-data".into(),
         dict_dir: "./kokoro-multi-lang-v1_0/dict".into(),
-        lexicon:
-            "./kokoro-multi-lang-v1_0/lexicon-us-en.txt,./kokoro-multi-lang-v1_0/lexicon-zh.txt"
-                .into(),
+        lexicons: vec![
+            "./kokoro-multi-lang-v1_0/lexicon-us-en.txt".into(),
+            "./kokoro-multi-lang-v1_0/lexicon-zh.txt".into(),
+        ],
         length_scale: 1.0,
         ..Default::default()
     };
@@ -27,7 +28,7 @@ fn main() {
 
     let sid = 0;
     let text = "This is generated by next generation Kaldi using Kokoro without Misaki.";
-    let audio = tts.create(&text, sid, 1.0).unwrap();
+    let audio = tts.create(text, sid, 1.0).unwrap();
     sherpa_rs::write_audio_file("audio.wav", &audio.samples, audio.sample_rate).unwrap();
     println!("Created audio.wav")
 }
@@ -25,7 +25,7 @@ fn get_speaker_name(
         embedding_manager.add(name.clone(), embedding).unwrap();
         *speaker_counter += 1;
     } else if *speaker_counter <= max_speakers {
-        if let Some(search_result) = embedding_manager.search(embedding, 0.5) {
+        if let Some(search_result) = embedding_manager.search(embedding, 0.5).unwrap() {
             name = search_result;
         } else {
             name = format!("speaker {}", speaker_counter);
@@ -33,7 +33,9 @@ fn get_speaker_name(
             *speaker_counter += 1;
         }
     } else {
-        let matches = embedding_manager.get_best_matches(embedding, 0.2, *speaker_counter);
+        let matches = embedding_manager
+            .get_best_matches(embedding, 0.2, *speaker_counter)
+            .unwrap();
         if let Some(name_match) = matches.first().map(|m| m.name.clone()) {
             name = name_match;
         }
@@ -92,7 +94,7 @@ fn main() {
     };
     let mut extractor = speaker_id::EmbeddingExtractor::new(extractor_config).unwrap();
     let mut embedding_manager =
-        embedding_manager::EmbeddingManager::new(extractor.embedding_size.try_into().unwrap()); // Assuming dimension 512 for embeddings
+        embedding_manager::EmbeddingManager::new(extractor.dim().try_into().unwrap());
 
     let mut speaker_counter = 1;
 
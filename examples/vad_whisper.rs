@@ -30,7 +30,7 @@ fn main() {
     };
     let mut extractor = speaker_id::EmbeddingExtractor::new(extractor_config).unwrap();
     let mut embedding_manager =
-        embedding_manager::EmbeddingManager::new(extractor.embedding_size.try_into().unwrap()); // Assuming dimension 512 for embeddings
+        embedding_manager::EmbeddingManager::new(extractor.dim().try_into().unwrap());
 
     let config = WhisperConfig {
         decoder: "sherpa-onnx-whisper-tiny/tiny-decoder.onnx".into(),
@@ -67,7 +67,9 @@ fn main() {
                 let mut embedding = extractor
                     .compute_speaker_embedding(segment.samples, sample_rate)
                     .unwrap();
-                let name = if let Some(speaker_name) = embedding_manager.search(&embedding, 0.4) {
+                let name = if let Some(speaker_name) =
+                    embedding_manager.search(&embedding, 0.4).unwrap()
+                {
                     speaker_name
                 } else {
                     // Register a new speaker and add the embedding
@@ -104,16 +106,17 @@ fn main() {
                 .compute_speaker_embedding(segment.samples, sample_rate)
                 .unwrap();
 
-            let name = if let Some(speaker_name) = embedding_manager.search(&embedding, 0.4) {
-                speaker_name
-            } else {
-                // Register a new speaker and add the embedding
-                let name = format!("speaker {}", speaker_counter);
-                embedding_manager.add(name.clone(), &mut embedding).unwrap();
+            let name =
+                if let Some(speaker_name) = embedding_manager.search(&embedding, 0.4).unwrap() {
+                    speaker_name
+                } else {
+                    // Register a new speaker and add the embedding
+                    let name = format!("speaker {}", speaker_counter);
+                    embedding_manager.add(name.clone(), &mut embedding).unwrap();
 
-                speaker_counter += 1;
-                name
-            };
+                    speaker_counter += 1;
+                    name
+                };
             println!(
                 "({}) {} | {}s - {}s",
                 name,
@@ -0,0 +1,64 @@
+/*
+Voice agent that repeats what it hears. Replace the handler with an LLM call.
+Use headphones, otherwise the agent hears itself and keeps interrupting.
+
+wget https://github.com/k2-fsa/sherpa-onnx/releases/download/asr-models/sherpa-onnx-streaming-zipformer-en-2023-06-26.tar.bz2
+tar xvf sherpa-onnx-streaming-zipformer-en-2023-06-26.tar.bz2
+wget https://github.com/k2-fsa/sherpa-onnx/releases/download/asr-models/silero_vad.onnx
+wget https://huggingface.co/csukuangfj/vits-ljs/resolve/main/vits-ljs.onnx
+wget https://huggingface.co/csukuangfj/vits-ljs/resolve/main/lexicon.txt
+wget https://huggingface.co/csukuangfj/vits-ljs/resolve/main/tokens.txt
+cargo run --example voice_agent --features "capture playback"
+*/
+use sherpa_rs::online::{OnlineModel, OnlineRecognizer, OnlineRecognizerConfig};
+use sherpa_rs::silero_vad::{SileroVad, SileroVadConfig};
+use sherpa_rs::tts::{CommonTtsConfig, VitsTts, VitsTtsConfig};
+use sherpa_rs::voice_agent::{AgentEvent, VoiceAgent, VoiceAgentConfig};
+
+fn main() {
+    let dir = "sherpa-onnx-streaming-zipformer-en-2023-06-26";
+    let recognizer = OnlineRecognizer::new(OnlineRecognizerConfig {
+        model: OnlineModel::Transducer {
+            encoder: format!("{dir}/encoder-epoch-99-avg-1-chunk-16-left-128.onnx"),
+            decoder: format!("{dir}/decoder-epoch-99-avg-1-chunk-16-left-128.onnx"),
+            joiner: format!("{dir}/joiner-epoch-99-avg-1-chunk-16-left-128.onnx"),
+        },
+        tokens: format!("{dir}/tokens.txt"),
+        ..Default::default()
+    })
+    .unwrap();
+    let vad = SileroVad::new(
+        SileroVadConfig {
+            model: "silero_vad.onnx".into(),
+            ..Default::default()
+        },
+        30.0,
+    )
+    .unwrap();
+    let tts = VitsTts::new(VitsTtsConfig {
+        model: "./vits-ljs.onnx".into(),
+        lexicon: "./lexicon.txt".into(),
+        tokens: "./tokens.txt".into(),
+        length_scale: 1.0,
+        tts_config: CommonTtsConfig {
+            max_num_sentences: 1,
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+
+    let handler =
+        |text: String| async move { eyre::Ok(format!("You said: {}", text.to_lowercase())) };
+    let agent =
+        VoiceAgent::from_microphone(recognizer, vad, tts, handler, VoiceAgentConfig::default())
+            .unwrap();
+    println!("Listening... press Ctrl+C to stop");
+    for event in agent.events() {
+        match event {
+            AgentEvent::Partial(text) => eprint!("\r{text}"),
+            AgentEvent::Heard(text) => eprintln!("\rYou: {text}"),
+            AgentEvent::Reply(text) => eprintln!("Agent: {text}"),
+            AgentEvent::BargeIn => eprintln!("(interrupted)"),
+        }
+    }
+}
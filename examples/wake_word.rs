@@ -0,0 +1,53 @@
+/*
+Transcribe what follows a wake word
+
+wget https://github.com/k2-fsa/sherpa-onnx/releases/download/kws-models/sherpa-onnx-kws-zipformer-gigaspeech-3.3M-2024-01-01.tar.bz2
+wget https://github.com/k2-fsa/sherpa-onnx/releases/download/asr-models/sherpa-onnx-streaming-zipformer-en-2023-06-26.tar.bz2
+tar xvf sherpa-onnx-kws-zipformer-gigaspeech-3.3M-2024-01-01.tar.bz2
+tar xvf sherpa-onnx-streaming-zipformer-en-2023-06-26.tar.bz2
+cargo run --example wake_word audio.wav
+*/
+use sherpa_rs::keyword_spot::{KeywordSpot, KeywordSpotConfig};
+use sherpa_rs::online::{OnlineModel, OnlineRecognizer, OnlineRecognizerConfig};
+use sherpa_rs::read_audio_file;
+use sherpa_rs::wake_word::{WakeWordEvent, WakeWordSession};
+
+fn main() {
+    let path = std::env::args().nth(1).expect("Missing file path argument");
+    let (samples, sample_rate) = read_audio_file(&path).unwrap();
+
+    let kws = "sherpa-onnx-kws-zipformer-gigaspeech-3.3M-2024-01-01";
+    let spotter = KeywordSpot::new(KeywordSpotConfig {
+        zipformer_encoder: format!("{kws}/encoder-epoch-12-avg-2-chunk-16-left-64.int8.onnx"),
+        zipformer_decoder: format!("{kws}/decoder-epoch-12-avg-2-chunk-16-left-64.int8.onnx"),
+        zipformer_joiner: format!("{kws}/joiner-epoch-12-avg-2-chunk-16-left-64.int8.onnx"),
+        tokens: format!("{kws}/tokens.txt"),
+        keywords: format!("{kws}/test_wavs/test_keywords.txt"),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let asr = "sherpa-onnx-streaming-zipformer-en-2023-06-26";
+    let recognizer = OnlineRecognizer::new(OnlineRecognizerConfig {
+        model: OnlineModel::Transducer {
+            encoder: format!("{asr}/encoder-epoch-99-avg-1-chunk-16-left-128.onnx"),
+            decoder: format!("{asr}/decoder-epoch-99-avg-1-chunk-16-left-128.onnx"),
+            joiner: format!("{asr}/joiner-epoch-99-avg-1-chunk-16-left-128.onnx"),
+        },
+        tokens: format!("{asr}/tokens.txt"),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut session = WakeWordSession::new(spotter, recognizer);
+    // Feed 100ms chunks like a microphone would
+    for chunk in samples.chunks(sample_rate as usize / 10) {
+        for event in session.accept_waveform(sample_rate, chunk).unwrap() {
+            match event {
+                WakeWordEvent::Detected(keyword) => println!("Wake word: {keyword}"),
+                WakeWordEvent::Partial(_) => {}
+                WakeWordEvent::Utterance { text, .. } => println!("Command: {text}"),
+            }
+        }
+    }
+}